@@ -0,0 +1,138 @@
+// src/bin/batch.rs
+//
+// headless batch processing: runs a JSON/TOML recipe over an image or a folder
+//
+//   batch <recipe.json|recipe.toml> <input file or directory> [-o <output dir>] [-r]
+
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use youtube_image_viewer::utils::batch::{BatchPipeline, BatchRecipe};
+
+const USAGE: &str = "usage: batch <recipe.json|recipe.toml> <input file or directory> [-o <output dir>] [-r]";
+
+struct Args {
+    recipe: PathBuf,
+    input: PathBuf,
+    output: Option<PathBuf>,
+    recursive: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut output = None;
+    let mut recursive = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => {
+                output = Some(PathBuf::from(args.next().ok_or("-o needs a directory")?));
+            }
+            "-r" | "--recursive" => recursive = true,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}\n{}", arg, USAGE)),
+            _ => positional.push(PathBuf::from(arg)),
+        }
+    }
+
+    match <[PathBuf; 2]>::try_from(positional) {
+        Ok([recipe, input]) => Ok(Args { recipe, input, output, recursive }),
+        Err(_) => Err(USAGE.to_string()),
+    }
+}
+
+fn is_image(path: &Path) -> bool {
+    path.is_file() && image::ImageFormat::from_path(path).is_ok()
+}
+
+fn collect_inputs(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if recursive {
+                collect_inputs(&path, recursive, files)?;
+            }
+        } else if is_image(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn run(args: &Args) -> Result<usize, String> {
+    let recipe = BatchRecipe::load(&args.recipe).map_err(|e| e.to_string())?;
+    let pipeline = BatchPipeline::new(&recipe).map_err(|e| e.to_string())?;
+
+    let mut inputs = Vec::new();
+    if args.input.is_dir() {
+        collect_inputs(&args.input, args.recursive, &mut inputs)
+            .map_err(|e| format!("Failed to read {}: {}", args.input.display(), e))?;
+        inputs.sort();
+    } else if args.input.is_file() {
+        inputs.push(args.input.clone());
+    } else {
+        return Err(format!("{} does not exist", args.input.display()));
+    }
+    if inputs.is_empty() {
+        return Err(format!("No images found in {}", args.input.display()));
+    }
+
+    // -o wins over the recipe, otherwise results land next to the inputs
+    let out_root = args.output.clone().or_else(|| pipeline.output_directory().map(Path::to_path_buf));
+
+    let progress = ProgressBar::new(inputs.len() as u64);
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner} [{elapsed_precise}] [{bar:40}] {pos}/{len} {msg}")
+            .progress_chars("=> "),
+    );
+
+    let mut failures = 0;
+    for input in &inputs {
+        let name = input.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        progress.set_message(name);
+
+        // keep the folder layout when recursing into an output directory
+        let out_dir = match &out_root {
+            Some(root) => {
+                let relative = input.parent().and_then(|p| p.strip_prefix(&args.input).ok());
+                relative.map(|r| root.join(r)).unwrap_or_else(|| root.clone())
+            }
+            None => input.parent().map(Path::to_path_buf).unwrap_or_default(),
+        };
+
+        let result = std::fs::create_dir_all(&out_dir)
+            .map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))
+            .and_then(|_| pipeline.process(input, &out_dir).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            progress.println(format!("FAILED {}: {}", input.display(), e));
+            failures += 1;
+        }
+        progress.inc(1);
+    }
+
+    progress.finish_with_message(format!("{} done, {} failed", inputs.len() - failures, failures));
+    Ok(failures)
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            process::exit(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(0) => {}
+        Ok(_) => process::exit(1),
+        Err(msg) => {
+            eprintln!("{}", msg);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod menu;
+pub mod state;
+pub mod utils;
+pub mod scientific;
@@ -1,22 +1,22 @@
-mod menu;
-mod state;
-mod utils;
-mod scientific;
+use youtube_image_viewer::{menu, state, utils, scientific};
 
 use fltk::{
-    app,
+    app::{self, WidgetId},
     frame::Frame,
     menu::{MenuBar, MenuFlag},
     prelude::*,
     window::Window,
-    enums::{Shortcut, Event, CallbackTrigger},
+    enums::{Shortcut, Event, CallbackTrigger, Key},
 };
 use fltk_theme::{ColorTheme, color_themes, WidgetTheme, ThemeType};
 use std::{cell::RefCell, rc::Rc};
 use state::ImageState;
-use utils::MENU_HEIGHT;
-use crate::menu::scientific::analysis::cell::setup_cell_analysis_menu;
-use crate::scientific::tools::interactive::cell_analysis_tool::CellAnalysisState;
+use state::folder_listing::FolderSort;
+use menu::view::zoom::ZoomPreset;
+use utils::transform::Transform;
+use utils::{MENU_HEIGHT, STATUS_BAR_HEIGHT};
+use menu::scientific::analysis::cell::setup_cell_analysis_menu;
+use scientific::tools::interactive::cell_analysis_tool::CellAnalysisState;
 
 
 fn main() {
@@ -25,11 +25,12 @@ fn main() {
     theme.apply();
 
     let mut wind = Window::new(100, 100, 800, 600, "Image Viewer");
-    let mut menu = MenuBar::new(0, 0, 800, MENU_HEIGHT, "");
+    let mut menu = MenuBar::new(0, 0, 800, MENU_HEIGHT, "").with_id(menu::file::recent::MAIN_MENU_ID);
 
-    let frame = Rc::new(RefCell::new(Frame::new(0, MENU_HEIGHT, 800, 600 - MENU_HEIGHT, "")));
+    let frame = Rc::new(RefCell::new(Frame::new(0, MENU_HEIGHT, 800, 600 - MENU_HEIGHT - STATUS_BAR_HEIGHT, "")));
     frame.borrow_mut().set_frame(fltk::enums::FrameType::FlatBox);
     let state = Rc::new(RefCell::new(ImageState::new()));
+    state.borrow_mut().scientific_state.load_flat_field_registry();
 
     // Set up frame event handling
     frame.borrow_mut().set_trigger(CallbackTrigger::Release);
@@ -54,12 +55,65 @@ fn main() {
         menu::file::save::handle_save_as(&frame_save_as, &state_save_as);
     });
 
+    let state_export = state.clone();
+    menu.add("&File/&Export Options...", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::file::export_dialog::show_export_options_dialog(&state_export, None);
+    });
+
+    let state_sidecar = state.clone();
+    menu.add("&File/Write Metadata Sidecar", Shortcut::None, MenuFlag::Toggle, move |m| {
+        let enabled = m.mvalue().map(|item| item.value()).unwrap_or(false);
+        if let Ok(mut state_ref) = state_sidecar.try_borrow_mut() {
+            state_ref.write_metadata_sidecar = enabled;
+        }
+    });
+
+    let frame_open_project = frame.clone();
+    let state_open_project = state.clone();
+    menu.add("&File/Open &Project...", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::file::project::handle_open_project(&frame_open_project, &state_open_project);
+    });
+
+    let frame_save_project = frame.clone();
+    let state_save_project = state.clone();
+    menu.add("&File/Save P&roject...", Shortcut::None, MenuFlag::MenuDivider, move |_| {
+        menu::file::project::handle_save_project(&frame_save_project, &state_save_project);
+    });
+
     menu.add("&File/Recent Files/", Shortcut::None, MenuFlag::Submenu, |_| {});
     menu.add("&File/Exit", Shortcut::None, MenuFlag::Normal, |_| {
         menu::file::exit::handle_exit();
     });
 
     // Edit Menu
+    let frame_undo = frame.clone();
+    let state_undo = state.clone();
+    menu.add("&Edit/&Undo", Shortcut::Ctrl | 'z', MenuFlag::Normal, move |_| {
+        menu::edit::history::handle_undo(&frame_undo, &state_undo);
+    });
+
+    let frame_redo = frame.clone();
+    let state_redo = state.clone();
+    menu.add("&Edit/&Redo", Shortcut::Ctrl | 'y', MenuFlag::Normal, move |_| {
+        menu::edit::history::handle_redo(&frame_redo, &state_redo);
+    });
+
+    // other programs receive the copy as the path of a temporary PNG, not as pixels
+    let state_copy = state.clone();
+    menu.add("&Edit/&Copy as File Path", Shortcut::Ctrl | 'c', MenuFlag::Normal, move |_| {
+        menu::edit::clipboard::handle_copy(&state_copy);
+    });
+
+    let wind_paste = wind.clone();
+    menu.add("&Edit/&Paste", Shortcut::Ctrl | 'v', MenuFlag::MenuDivider, move |_| {
+        menu::edit::clipboard::handle_paste(&wind_paste);
+    });
+
+    let state_history = state.clone();
+    menu.add("&Edit/History Settings...", Shortcut::None, MenuFlag::MenuDivider, move |_| {
+        menu::edit::history::show_history_settings_dialog(&state_history);
+    });
+
     let frame_crop = frame.clone();
     let state_crop = state.clone();
     menu.add("&Edit/&Cropping Images", Shortcut::None, MenuFlag::Normal, move |_| {
@@ -177,6 +231,45 @@ fn main() {
         menu::edit::filters::show_filter_dialog(&frame_advanced_motion, &state_advanced_motion, "motion_blur");
     });
 
+    // Edge-preserving denoise
+    for (label, spec) in [
+        ("&Edit/&Filters/De&noise/&Median...", menu::edit::filters::FilterSpec::Median { radius: 2 }),
+        ("&Edit/&Filters/De&noise/&Bilateral...", menu::edit::filters::FilterSpec::Bilateral { spatial_sigma: 3.0, range_sigma: 25.0 }),
+        ("&Edit/&Filters/De&noise/Non-&Local Means...", menu::edit::filters::FilterSpec::NonLocalMeans { strength: 10.0, search_radius: 5, patch_radius: 1 }),
+    ] {
+        let frame_denoise = frame.clone();
+        let state_denoise = state.clone();
+        menu.add(label, Shortcut::None, MenuFlag::Normal, move |_| {
+            menu::edit::filters::show_denoise_dialog(&frame_denoise, &state_denoise, spec.clone());
+        });
+    }
+
+    // Tonal adjustments
+    let frame_levels = frame.clone();
+    let state_levels = state.clone();
+    menu.add("&Edit/&Filters/A&djust/&Levels...", Shortcut::Ctrl | 'l', MenuFlag::Normal, move |_| {
+        menu::edit::filters::show_levels_dialog(&frame_levels, &state_levels);
+    });
+
+    let frame_curves = frame.clone();
+    let state_curves = state.clone();
+    menu.add("&Edit/&Filters/A&djust/&Curves...", Shortcut::Ctrl | 'm', MenuFlag::MenuDivider, move |_| {
+        menu::edit::filters::show_curves_dialog(&frame_curves, &state_curves);
+    });
+
+    // Automatic contrast
+    for (label, spec) in [
+        ("&Edit/&Filters/A&djust/&Equalize Histogram...", menu::edit::filters::FilterSpec::Equalize {}),
+        ("&Edit/&Filters/A&djust/&Auto Stretch...", menu::edit::filters::FilterSpec::AutoStretch { saturated: 0.35 }),
+        ("&Edit/&Filters/A&djust/C&LAHE...", menu::edit::filters::FilterSpec::Clahe { tile_size: 64, clip_limit: 3.0 }),
+    ] {
+        let frame_auto = frame.clone();
+        let state_auto = state.clone();
+        menu.add(label, Shortcut::None, MenuFlag::Normal, move |_| {
+            menu::edit::filters::show_auto_contrast_dialog(&frame_auto, &state_auto, spec.clone());
+        });
+    }
+
     // Layers Menu
     let frame_layer = frame.clone();
     let state_layer = state.clone();
@@ -280,6 +373,68 @@ menu.add("&Scientific/&Measurements/ROI", Shortcut::None, MenuFlag::Normal, move
         menu::edit::filters::handle_toggle_preview(&frame_preview, &state_preview);
     });
 
+    let frame_stack = frame.clone();
+    let state_stack = state.clone();
+    menu.add("&Edit/&Filters/Filter &Stack...", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::edit::filters::show_filter_stack_dialog(&frame_stack, &state_stack);
+    });
+
+    // Folder navigation
+    let frame_next = frame.clone();
+    let state_next = state.clone();
+    menu.add("&View/&Next Image", Shortcut::None | Key::PageDown, MenuFlag::Normal, move |_| {
+        menu::view::navigation::handle_next_image(&frame_next, &state_next);
+    });
+
+    let frame_prev = frame.clone();
+    let state_prev = state.clone();
+    menu.add("&View/&Previous Image", Shortcut::None | Key::PageUp, MenuFlag::Normal, move |_| {
+        menu::view::navigation::handle_previous_image(&frame_prev, &state_prev);
+    });
+
+    for sort in FolderSort::ALL {
+        let frame_sort = frame.clone();
+        let state_sort = state.clone();
+        menu.add(&format!("&View/Sort Folder By/{}", sort.label()), Shortcut::None, MenuFlag::Radio, move |_| {
+            menu::view::navigation::handle_sort_folder(&frame_sort, &state_sort, sort);
+        });
+    }
+    if let Some(mut item) = menu.find_item(&format!("&View/Sort Folder By/{}", FolderSort::Name.label())) {
+        item.set();
+    }
+
+    let frame_zoom_in = frame.clone();
+    let state_zoom_in = state.clone();
+    menu.add("&View/Zoom &In", Shortcut::Ctrl | '=', MenuFlag::Normal, move |_| {
+        menu::view::zoom::handle_zoom_in(&frame_zoom_in, &state_zoom_in);
+    });
+
+    let frame_zoom_out = frame.clone();
+    let state_zoom_out = state.clone();
+    menu.add("&View/Zoom &Out", Shortcut::Ctrl | '-', MenuFlag::Normal, move |_| {
+        menu::view::zoom::handle_zoom_out(&frame_zoom_out, &state_zoom_out);
+    });
+
+    for (label, shortcut, preset) in [
+        ("&View/Fit to &Window", Shortcut::Ctrl | '0', ZoomPreset::Fit),
+        ("&View/Fi&ll Window", Shortcut::Ctrl | '9', ZoomPreset::Fill),
+        ("&View/&Actual Size (1:1)", Shortcut::Ctrl | '1', ZoomPreset::Actual),
+    ] {
+        let frame_preset = frame.clone();
+        let state_preset = state.clone();
+        let flag = if preset == ZoomPreset::Actual { MenuFlag::MenuDivider } else { MenuFlag::Normal };
+        menu.add(label, shortcut, flag, move |_| {
+            menu::view::zoom::handle_zoom_preset(&frame_preset, &state_preset, preset);
+        });
+    }
+
+    let frame_strip = frame.clone();
+    let state_strip = state.clone();
+    menu.add("&View/Show &Filmstrip", Shortcut::None, MenuFlag::Toggle | MenuFlag::MenuDivider, move |m| {
+        let visible = m.mvalue().map(|i| i.value()).unwrap_or(false);
+        menu::view::filmstrip::handle_toggle_filmstrip(&frame_strip, &state_strip, visible);
+    });
+
     // Theme Options
     menu.add("&View/&Themes/Color Themes/Dark", Shortcut::None, MenuFlag::Normal, |_| {
         let theme = ColorTheme::new(color_themes::DARK_THEME);
@@ -306,12 +461,111 @@ menu.add("&Scientific/&Measurements/ROI", Shortcut::None, MenuFlag::Normal, move
         widget_theme.apply();
     });
 
-    menu.add("&Image/", Shortcut::None, MenuFlag::Normal, |_| {});
-    menu.add("&Info/", Shortcut::None, MenuFlag::Normal, |_| {});
+    // Image Menu
+    let frame_range = frame.clone();
+    let state_range = state.clone();
+    let state_histogram = state.clone();
+    menu.add("&Image/&Histogram...", Shortcut::Ctrl | 'h', MenuFlag::Normal, move |_| {
+        menu::image::show_histogram_window(&state_histogram);
+    });
+
+    menu.add("&Image/&Display Range...", Shortcut::None, MenuFlag::MenuDivider, move |_| {
+        menu::image::show_display_range_dialog(&frame_range, &state_range);
+    });
+
+    for (label, transform) in [
+        ("&Image/&Rotate/90° &Right", Transform::Rotate90),
+        ("&Image/&Rotate/&180°", Transform::Rotate180),
+        ("&Image/&Rotate/90° &Left", Transform::Rotate270),
+        ("&Image/&Rotate/Flip &Horizontal", Transform::FlipHorizontal),
+        ("&Image/&Rotate/Flip &Vertical", Transform::FlipVertical),
+    ] {
+        let frame_transform = frame.clone();
+        let state_transform = state.clone();
+        let flag = if transform == Transform::Rotate270 || transform == Transform::FlipVertical {
+            MenuFlag::MenuDivider
+        } else {
+            MenuFlag::Normal
+        };
+        menu.add(label, Shortcut::None, flag, move |_| {
+            menu::image::handle_transform(&frame_transform, &state_transform, transform);
+        });
+    }
+
+    let frame_rotate = frame.clone();
+    let state_rotate = state.clone();
+    menu.add("&Image/&Rotate/&Arbitrary...", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::image::show_rotate_dialog(&frame_rotate, &state_rotate);
+    });
+
+    let frame_resize = frame.clone();
+    let state_resize = state.clone();
+    menu.add("&Image/Re&size...", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::image::show_resize_dialog(&frame_resize, &state_resize);
+    });
+
+    let frame_calculator = frame.clone();
+    let state_calculator = state.clone();
+    menu.add("&Process/Image &Calculator...", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::process::show_image_calculator(&frame_calculator, &state_calculator);
+    });
+
+    let frame_background = frame.clone();
+    let state_background = state.clone();
+    menu.add("&Process/Subtract &Background...", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::process::show_subtract_background_dialog(&frame_background, &state_background);
+    });
+
+    let frame_flat_field = frame.clone();
+    let state_flat_field = state.clone();
+    menu.add("&Process/&Flat-Field Correction...", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::process::show_flat_field_dialog(&frame_flat_field, &state_flat_field);
+    });
+
+    // Binary morphology on thresholded images
+    for operation in menu::process::BinaryOperation::ALL {
+        let frame_binary = frame.clone();
+        let state_binary = state.clone();
+        menu.add(operation.menu_path(), Shortcut::None, MenuFlag::Normal, move |_| {
+            menu::process::show_binary_dialog(&frame_binary, &state_binary, operation);
+        });
+    }
+
+    let state_particles = state.clone();
+    menu.add("&Process/Bi&nary/&Analyze Particles", Shortcut::None, MenuFlag::Normal, move |_| {
+        menu::process::analyze_particles(&state_particles);
+    });
+
+    let state_info = state.clone();
+    menu.add("&Info/&Image Info...", Shortcut::Ctrl | 'i', MenuFlag::Normal, move |_| {
+        menu::info::show_image_info_window(&state_info);
+    });
+
+    menu::file::recent::rebuild_recent_menu(&frame, &state);
+
+    menu::view::filmstrip::create_filmstrip(0, 600 - STATUS_BAR_HEIGHT - menu::view::filmstrip::FILMSTRIP_HEIGHT, 800);
+    menu::view::status_bar::create_status_bar(0, 600 - STATUS_BAR_HEIGHT, 800);
+
+    // drops, pastes, wheel zoom, panning and the pixel readout arrive at the window,
+    // the frame's handler belongs to whichever tool is active
+    let frame_drop = frame.clone();
+    let state_drop = state.clone();
+    let mut pan_drag = None;
+    let mut inspector = menu::view::status_bar::PixelInspector::default();
+    wind.handle(move |_, ev| {
+        let handled = match ev {
+            Event::DndEnter | Event::DndDrag | Event::DndRelease => true,
+            Event::Paste => menu::edit::clipboard::handle_paste_event(&frame_drop, &state_drop),
+            _ => menu::view::zoom::handle_viewport_event(&frame_drop, &state_drop, ev, &mut pan_drag),
+        };
+        inspector.handle_event(&frame_drop, &state_drop, ev);
+        handled
+    });
 
     wind.end();
     wind.show();
     app.run().unwrap();
+    menu::edit::clipboard::remove_clipboard_file();
 }
 
 
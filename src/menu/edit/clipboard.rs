@@ -0,0 +1,133 @@
+// src/menu/edit/clipboard.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell, path::PathBuf};
+
+// FLTK imports
+use fltk::{
+    app::{self, ClipboardContent, ClipboardEvent},
+    dialog::alert,
+    enums::ColorDepth,
+    frame::Frame,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::state::export_options::ExportOptions;
+use crate::menu::file::open::{open_dropped_files, open_untitled_image};
+use crate::menu::view::status_bar::show_status;
+use crate::scientific::io::write_image;
+use crate::utils::raw_image::RawImage;
+
+// fltk can only put text on the system clipboard, so Copy writes the pixels
+// here and hands other programs the path. one file per running viewer
+fn clipboard_file() -> PathBuf {
+    std::env::temp_dir().join(format!("image-viewer-clipboard-{}.png", std::process::id()))
+}
+
+/// deletes the file the last copy wrote, called before the next copy and on exit
+pub fn remove_clipboard_file() {
+    let path = clipboard_file();
+    if path.exists() {
+        if let Err(e) = std::fs::remove_file(&path) {
+            println!("Failed to remove {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Edit→Copy as File Path: copies the current ROI's bounding box, the crop selection
+/// or the whole image. pasting here gives the image back at full bit depth, other
+/// programs get the path of a PNG in the temp directory as text, not pixels
+pub fn handle_copy(state: &Rc<RefCell<ImageState>>) {
+    let mut state_ref = match state.try_borrow_mut() {
+        Ok(state_ref) => state_ref,
+        Err(_) => return,
+    };
+
+    let source = match (&state_ref.raw_image, &state_ref.image) {
+        (Some(raw), _) => raw.clone(),
+        (None, Some(image)) => RawImage::from_rgb_image(image),
+        (None, None) => return,
+    };
+    let copied = match copy_region(&state_ref) {
+        Some((x, y, w, h)) => match source.crop(x, y, w, h) {
+            Some(cropped) => cropped,
+            None => return,
+        },
+        None => source,
+    };
+
+    remove_clipboard_file();
+    state_ref.clipboard = None;
+    let path = clipboard_file();
+    if let Err(e) = write_image(&path, &copied, None, &ExportOptions::default()) {
+        drop(state_ref);
+        alert(200, 200, &format!("Failed to copy the image: {}", e));
+        return;
+    }
+    app::copy(&path.to_string_lossy());
+    let message = format!("Copied {}x{} image as file path {}", copied.width, copied.height, path.display());
+    println!("{}", message);
+    show_status(&message);
+    state_ref.clipboard = Some(copied);
+}
+
+// image-space rectangle to copy, None for the whole image
+fn copy_region(state_ref: &ImageState) -> Option<(u32, u32, u32, u32)> {
+    let points = &state_ref.scientific_state.current_roi_points;
+    if points.len() >= 2 {
+        let min_x = points.iter().map(|p| p.0).min()?.max(0);
+        let min_y = points.iter().map(|p| p.1).min()?.max(0);
+        let max_x = points.iter().map(|p| p.0).max()?;
+        let max_y = points.iter().map(|p| p.1).max()?;
+        if max_x > min_x && max_y > min_y {
+            return Some((min_x as u32, min_y as u32, (max_x - min_x + 1) as u32, (max_y - min_y + 1) as u32));
+        }
+    }
+
+    let selection = state_ref.crop_selection.as_ref()?;
+    let (x, y, w, h) = selection.get_image_dimensions();
+    if w > 0 && h > 0 {
+        Some((x.max(0) as u32, y.max(0) as u32, w as u32, h as u32))
+    } else {
+        None
+    }
+}
+
+/// asks fltk for the clipboard contents, they arrive as an Event::Paste on `target`
+pub fn handle_paste<W: WidgetExt>(target: &W) {
+    if app::clipboard_contains(ClipboardContent::Image) {
+        app::paste_image(target);
+    } else if app::clipboard_contains(ClipboardContent::Text) {
+        app::paste_text(target);
+    }
+}
+
+/// Event::Paste on the main window, from Edit→Paste or a file dropped on it.
+/// images become an untitled document, text is treated as a list of files
+pub fn handle_paste_event(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) -> bool {
+    match app::event_clipboard() {
+        Some(ClipboardEvent::Image(Some(image))) => {
+            let raw = match image.convert(ColorDepth::Rgb8) {
+                Ok(rgb) => RawImage::from_rgb_image(&rgb),
+                Err(e) => {
+                    alert(200, 200, &format!("Failed to paste image: {}", e));
+                    return false;
+                }
+            };
+            open_untitled_image(frame, state, raw)
+        }
+        Some(ClipboardEvent::Text(text)) => {
+            // our own copy, the in-memory version still has the full bit depth
+            if text.trim() == clipboard_file().to_string_lossy() {
+                let copied = state.try_borrow().ok().and_then(|state_ref| state_ref.clipboard.clone());
+                if let Some(raw) = copied {
+                    return open_untitled_image(frame, state, raw);
+                }
+            }
+            open_dropped_files(frame, state, &text)
+        }
+        _ => false,
+    }
+}
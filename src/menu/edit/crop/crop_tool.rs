@@ -1,4 +1,5 @@
 use crate::state::ImageState;
+use super::CropSelection;
 use fltk::{
     dialog::{alert, choice2},
     frame::Frame,
@@ -10,83 +11,8 @@ use fltk::{
 };
 use std::{cell::RefCell, rc::Rc};
 use crate::menu::file::save::handle_save_as;
-use crate::utils::display_image;
-
-#[derive(Clone)]
-pub struct CropSelection {
-    pub start_x: i32,
-    pub start_y: i32,
-    pub end_x: i32,
-    pub end_y: i32,
-    pub is_selecting: bool,
-    pub image_w: i32,
-    pub image_h: i32,
-    pub frame_w: i32,
-    pub frame_h: i32,
-}
-
-impl CropSelection {
-    pub fn new(image_w: i32, image_h: i32, frame_w: i32, frame_h: i32) -> Self {
-        Self {
-            start_x: 0,
-            start_y: 0,
-            end_x: 0,
-            end_y: 0,
-            is_selecting: false,
-            image_w,
-            image_h,
-            frame_w,
-            frame_h,
-        }
-    }
-
-    pub fn get_dimensions(&self) -> (i32, i32, i32, i32) {
-        let x = self.start_x.min(self.end_x);
-        let y = self.start_y.min(self.end_y);
-        let w = (self.start_x - self.end_x).abs();
-        let h = (self.start_y - self.end_y).abs();
-        (x, y, w, h)
-    }
-
-    pub fn get_image_dimensions(&self) -> (i32, i32, i32, i32) {
-        let (x, y, w, h) = self.get_dimensions();
-        
-        // Calculate FLTK's display scale
-        let scale_x = self.frame_w as f64 / self.image_w as f64;
-        let scale_y = self.frame_h as f64 / self.image_h as f64;
-        let scale = scale_x.min(scale_y);  // FLTK uses the smaller scale to preserve aspect ratio
-
-        // Calculate display size
-        let displayed_w = (self.image_w as f64 * scale) as i32;
-        let displayed_h = (self.image_h as f64 * scale) as i32;
-
-        // Calculate offsets for centered image
-        let offset_x = (self.frame_w - displayed_w) / 2;
-        let offset_y = (self.frame_h - displayed_h) / 2;
-
-        // Convert screen coordinates back to image coordinates
-        let image_x = ((x as f64 - offset_x as f64) / scale) as i32;
-        let image_y = ((y as f64 - offset_y as f64) / scale) as i32;
-        let image_w = (w as f64 / scale) as i32;
-        let image_h = (h as f64 / scale) as i32;
-
-        // Ensure coordinates are within bounds
-        let image_x = image_x.max(0);
-        let image_y = image_y.max(0);
-        let image_w = image_w.min(self.image_w - image_x);
-        let image_h = image_h.min(self.image_h - image_y);
-
-        (image_x, image_y, image_w, image_h)
-    }
-
-    pub fn reset(&mut self) {
-        self.start_x = 0;
-        self.start_y = 0;
-        self.end_x = 0;
-        self.end_y = 0;
-        self.is_selecting = false;
-    }
-}
+use crate::utils::{display_image_with_zoom, draw_current_image, Viewport};
+use crate::menu::view::zoom::is_pan_button;
 
 fn handle_crop_with_selection(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) -> bool {
     let cropped_img = {
@@ -131,13 +57,15 @@ fn handle_crop_with_selection(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Ima
 
     if let Some(mut fltk_image) = cropped_img {
         let mut state_ref = state.borrow_mut();
+        state_ref.record_image_edit("Crop");
         state_ref.image = Some(fltk_image.clone());
         state_ref.path = None;
         state_ref.crop_selection = None;
+        state_ref.zoom = 1.0;
+        state_ref.pan = (0.0, 0.0);
         drop(state_ref);
-        
-        
-        display_image(frame, &fltk_image, 1.0);
+
+        display_image_with_zoom(frame, &mut fltk_image, 1.0, state);
         true
     } else {
         alert(300, 300, "Failed to create cropped image");
@@ -153,6 +81,8 @@ pub fn start_interactive_crop(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Ima
     }
     
     let original_image = state_ref.image.clone();
+    // what a cancelled crop goes back to
+    let original_view = (state_ref.path.clone(), state_ref.zoom, state_ref.pan);
     
     // Initialize crop selection with image and frame dimensions
     if let Some(img) = &original_image {
@@ -172,25 +102,23 @@ pub fn start_interactive_crop(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Ima
 
     let draw_callback = {
         let state_clone = state_clone.clone();
-        let original_image = original_image.clone();
         move |f: &mut Frame| {
-            if let Some(img) = &original_image {
-                f.set_image(Some(img.clone()));
-
-                let dimensions = state_clone
-                    .try_borrow()
-                    .ok()
-                    .and_then(|state_ref| state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions())));
-                
-                if let Some((true, (x, y, w, h))) = dimensions {
-                    draw::set_draw_color(Color::White);
-                    draw::set_line_style(draw::LineStyle::Solid, 2);
-                    draw::draw_rect(x, y, w, h);
-                    
-                    draw::set_draw_color(Color::Black);
-                    draw::set_line_style(draw::LineStyle::Dash, 1);
-                    draw::draw_rect(x-1, y-1, w+2, h+2);
+            let dimensions = match state_clone.try_borrow() {
+                Ok(state_ref) => {
+                    draw_current_image(f, &state_ref);
+                    state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions()))
                 }
+                Err(_) => None,
+            };
+
+            if let Some((true, (x, y, w, h))) = dimensions {
+                draw::set_draw_color(Color::White);
+                draw::set_line_style(draw::LineStyle::Solid, 2);
+                draw::draw_rect(x, y, w, h);
+
+                draw::set_draw_color(Color::Black);
+                draw::set_line_style(draw::LineStyle::Dash, 1);
+                draw::draw_rect(x-1, y-1, w+2, h+2);
             }
         }
     };
@@ -201,12 +129,17 @@ pub fn start_interactive_crop(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Ima
         let state_clone = state_clone.clone();
         let frame_clone = frame_clone.clone();
         move |f: &mut Frame, ev: Event| -> bool {
+            if is_pan_button() {
+                return false;
+            }
             match ev {
                 Event::Push => {
                     let mut handled = false;
                     if let Ok(mut state) = state_clone.try_borrow_mut() {
+                        let viewport = Viewport::current(f, &state);
                         if let Some(selection) = &mut state.crop_selection {
                             selection.reset();  // Reset previous selection state
+                            selection.set_viewport(viewport);
                             selection.start_x = app::event_x();
                             selection.start_y = app::event_y();
                             selection.is_selecting = true;
@@ -262,15 +195,27 @@ pub fn start_interactive_crop(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Ima
                                 },
                                 _ => {
                                     // User chose not to save, restore original image
-                                    if let Ok(mut state) = state_clone.try_borrow_mut() {
-                                        state.image = original_image.clone();
-                                        state.crop_selection = None;
-                                        
-                                        // Display original image
-                                        if let Some(img) = &original_image {
-                                            
-                                            display_image(&frame_clone, img, 1.0);
+                                    let restored = match state_clone.try_borrow_mut() {
+                                        Ok(mut state) => {
+                                            // the crop was rolled back, so its undo step goes too,
+                                            // giving back the raw data it dropped
+                                            let raw = state.history.pop_undo()
+                                                .and_then(|entry| entry.restore_image())
+                                                .and_then(|(_, raw)| raw);
+                                            let (path, zoom, pan) = original_view.clone();
+                                            state.image = original_image.clone();
+                                            state.raw_image = raw;
+                                            state.crop_selection = None;
+                                            state.path = path;
+                                            state.pan = pan;
+                                            original_image.clone().map(|img| (img, zoom))
                                         }
+                                        Err(_) => None,
+                                    };
+
+                                    // Display original image at the zoom it had before
+                                    if let Some((mut img, zoom)) = restored {
+                                        display_image_with_zoom(&frame_clone, &mut img, zoom, &state_clone);
                                     }
                                 }
                             }
@@ -1,6 +1,9 @@
 // src/menu/edit/crop/mod.rs
+pub mod selection;
+#[cfg(feature = "gui")]
 pub mod crop_tool;
-pub use crop_tool::CropSelection;
+pub use selection::CropSelection;
+#[cfg(feature = "gui")]
 pub use crop_tool::start_interactive_crop;
 
 
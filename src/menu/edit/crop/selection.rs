@@ -0,0 +1,65 @@
+// src/menu/edit/crop/selection.rs
+use serde::{Serialize, Deserialize};
+use crate::utils::Viewport;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CropSelection {
+    pub start_x: i32,
+    pub start_y: i32,
+    pub end_x: i32,
+    pub end_y: i32,
+    pub is_selecting: bool,
+    pub image_w: i32,
+    pub image_h: i32,
+    pub frame_w: i32,
+    pub frame_h: i32,
+    /// where the image was on screen when the selection was drawn,
+    /// None for a selection over the image fitted in a frame at the origin
+    #[serde(default)]
+    pub viewport: Option<Viewport>,
+}
+
+impl CropSelection {
+    pub fn new(image_w: i32, image_h: i32, frame_w: i32, frame_h: i32) -> Self {
+        Self {
+            start_x: 0,
+            start_y: 0,
+            end_x: 0,
+            end_y: 0,
+            is_selecting: false,
+            image_w,
+            image_h,
+            frame_w,
+            frame_h,
+            viewport: None,
+        }
+    }
+
+    /// records where the image was on screen, usually `Viewport::current`
+    pub fn set_viewport(&mut self, viewport: Option<Viewport>) {
+        self.viewport = viewport;
+    }
+
+    pub fn get_dimensions(&self) -> (i32, i32, i32, i32) {
+        let x = self.start_x.min(self.end_x);
+        let y = self.start_y.min(self.end_y);
+        let w = (self.start_x - self.end_x).abs();
+        let h = (self.start_y - self.end_y).abs();
+        (x, y, w, h)
+    }
+
+    pub fn get_image_dimensions(&self) -> (i32, i32, i32, i32) {
+        let (x, y, w, h) = self.get_dimensions();
+        self.viewport
+            .unwrap_or_else(|| Viewport::new((0, 0, self.frame_w, self.frame_h), self.image_w, self.image_h, 1.0, (0.0, 0.0)))
+            .rect_to_image(x, y, w, h)
+    }
+
+    pub fn reset(&mut self) {
+        self.start_x = 0;
+        self.start_y = 0;
+        self.end_x = 0;
+        self.end_y = 0;
+        self.is_selecting = false;
+    }
+}
@@ -0,0 +1,35 @@
+// src/menu/edit/filters/advanced/background.rs
+use image::{ImageBuffer, Rgba};
+use crate::state::FilterError;
+use crate::menu::edit::filters::ImageFilter;
+use crate::scientific::analysis::background::{estimate_background, BackgroundMethod};
+
+/// subtracts an estimate of the uneven background from each colour channel
+pub struct SubtractBackgroundFilter {
+    method: BackgroundMethod,
+}
+
+impl SubtractBackgroundFilter {
+    pub fn new(method: BackgroundMethod) -> Self {
+        Self { method }
+    }
+
+    /// the background of each of the red, green and blue channels, row-major
+    pub fn estimate(&self, image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> [Vec<f32>; 3] {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let plane = |channel: usize| -> Vec<f32> { image.pixels().map(|p| p[channel] as f32).collect() };
+        [0, 1, 2].map(|channel| estimate_background(&plane(channel), width, height, &self.method))
+    }
+}
+
+impl ImageFilter for SubtractBackgroundFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        let backgrounds = self.estimate(image);
+        for (i, pixel) in image.pixels_mut().enumerate() {
+            for (value, background) in pixel.0.iter_mut().zip(backgrounds.iter()) {
+                *value = (*value as f32 - background[i]).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+        Ok(())
+    }
+}
@@ -2,19 +2,22 @@
 use image::{ImageBuffer, Rgba};
 use crate::state::FilterError;
 use crate::menu::edit::filters::ImageFilter;
-use crate::menu::edit::crop::crop_tool::CropSelection;
+use crate::menu::edit::crop::CropSelection;
 
 #[derive(Clone)]
 pub enum FilterType {
     BoxBlur,
     GaussianBlur,
     Sharpen,
+    /// adds back `amount` times the difference to a Gaussian blur where it reaches `threshold`
+    UnsharpMask { amount: f32, threshold: f32, luminance_only: bool },
 }
 #[derive(Clone)]
  pub enum ConvolutionType {
      GaussianBlur { radius: f32, sigma: f32 },
      BoxBlur { radius: f32 },
      Sharpen { intensity: f32 },
+     UnsharpMask { radius: f32, amount: f32, threshold: f32, luminance_only: bool },
  }
 
 pub struct ConvolutionFilter {
@@ -64,6 +67,33 @@ impl ConvolutionFilter {
         }
     }
 
+    /// `radius` is the sigma of the Gaussian blur in pixels, `amount` how much of the detail
+    /// is added back and `threshold` the smallest difference (0 - 255) that gets sharpened,
+    /// which keeps low-contrast noise from being amplified
+    pub fn new_unsharp_mask(radius: f32, amount: f32, threshold: f32) -> Self {
+        let sigma = radius.clamp(0.1, 50.0);
+        Self {
+            radius: (sigma * 3.0).ceil(),
+            sigma: Some(sigma),
+            intensity: Some(1.0),
+            filter_type: FilterType::UnsharpMask {
+                amount: amount.clamp(0.0, 10.0),
+                threshold: threshold.clamp(0.0, 255.0),
+                luminance_only: false,
+            },
+            selection: None,
+            feather_radius: 0,
+        }
+    }
+
+    /// sharpens the luminance only, so edges between colours get no coloured fringes
+    pub fn with_luminance_only(mut self, luminance_only: bool) -> Self {
+        if let FilterType::UnsharpMask { luminance_only: ref mut l, .. } = self.filter_type {
+            *l = luminance_only;
+        }
+        self
+    }
+
     pub fn with_selection(mut self, selection: CropSelection) -> Self {
         self.selection = Some(selection);
         self
@@ -155,6 +185,71 @@ impl ConvolutionFilter {
         ]
     }
 
+    /// RGB of the image blurred with a separable Gaussian of `self.sigma`, row by row
+    fn separable_gaussian(&self, image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Vec<[f32; 3]> {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let sigma = self.sigma.unwrap_or(1.0);
+        let half = self.radius as i32;
+        let mut kernel: Vec<f32> = (-half..=half)
+            .map(|d| (-(d * d) as f32 / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let sum: f32 = kernel.iter().sum();
+        kernel.iter_mut().for_each(|k| *k /= sum);
+
+        let pass = |source: &[[f32; 3]], horizontal: bool| -> Vec<[f32; 3]> {
+            let mut output = vec![[0.0; 3]; width * height];
+            for y in 0..height {
+                for x in 0..width {
+                    let mut total = [0.0; 3];
+                    for (i, k) in kernel.iter().enumerate() {
+                        let d = i as i32 - half;
+                        let (sx, sy) = if horizontal {
+                            ((x as i32 + d).clamp(0, width as i32 - 1) as usize, y)
+                        } else {
+                            (x, (y as i32 + d).clamp(0, height as i32 - 1) as usize)
+                        };
+                        let value = source[sy * width + sx];
+                        for c in 0..3 {
+                            total[c] += value[c] * k;
+                        }
+                    }
+                    output[y * width + x] = total;
+                }
+            }
+            output
+        };
+
+        let pixels: Vec<[f32; 3]> = image.pixels()
+            .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+            .collect();
+        pass(&pass(&pixels, true), false)
+    }
+
+    fn apply_unsharp_mask(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, amount: f32, threshold: f32, luminance_only: bool) {
+        let width = image.width();
+        let blurred = self.separable_gaussian(image);
+        let luma = |c: [f32; 3]| 0.299 * c[0] + 0.587 * c[1] + 0.114 * c[2];
+
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            let factor = self.calculate_feather_factor(x as i32, y as i32);
+            if factor == 0.0 {
+                continue;
+            }
+            let original = [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32];
+            let blur = blurred[(y * width + x) as usize];
+
+            // the detail the blur took away, left alone where it is below the threshold
+            let detail = |difference: f32| if difference.abs() < threshold { 0.0 } else { amount * difference };
+            let luma_detail = detail(luma(original) - luma(blur));
+
+            for c in 0..3 {
+                let added = if luminance_only { luma_detail } else { detail(original[c] - blur[c]) };
+                let sharpened = (original[c] + added).clamp(0.0, 255.0);
+                pixel[c] = (sharpened * factor + original[c] * (1.0 - factor)).round() as u8;
+            }
+        }
+    }
+
     fn apply_kernel(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, kernel: &[f32], kernel_size: usize) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
         let width = image.width() as usize;
         let height = image.height() as usize;
@@ -231,9 +326,59 @@ impl ImageFilter for ConvolutionFilter {
                 let kernel = self.create_sharpen_kernel();
                 (kernel, 3)
             },
+            FilterType::UnsharpMask { amount, threshold, luminance_only } => {
+                self.apply_unsharp_mask(image, amount, threshold, luminance_only);
+                return Ok(());
+            },
         };
 
         *image = self.apply_kernel(image, &kernel, size);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a dark left half and a light right half
+    fn step(dark: u8, light: u8) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(12, 4, |x, _| {
+            let v = if x < 6 { dark } else { light };
+            Rgba([v, v, v, 255])
+        })
+    }
+
+    #[test]
+    fn unsharp_mask_steepens_edges_above_the_threshold() {
+        let mut image = step(100, 150);
+        ConvolutionFilter::new_unsharp_mask(1.0, 1.0, 0.0).apply(&mut image).unwrap();
+        assert!(image.get_pixel(5, 0)[0] < 100);
+        assert!(image.get_pixel(6, 0)[0] > 150);
+        // flat areas far from the edge stay as they were
+        assert_eq!(image.get_pixel(0, 0)[0], 100);
+        assert_eq!(image.get_pixel(11, 0)[0], 150);
+
+        let mut faint = step(100, 104);
+        ConvolutionFilter::new_unsharp_mask(1.0, 1.0, 10.0).apply(&mut faint).unwrap();
+        assert_eq!(faint, step(100, 104));
+    }
+
+    #[test]
+    fn luminance_only_adds_the_same_detail_to_every_channel() {
+        let mut image: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::from_fn(12, 4, |x, _| {
+            if x < 6 { Rgba([200, 40, 40, 255]) } else { Rgba([40, 40, 200, 255]) }
+        });
+        let original = image.clone();
+        ConvolutionFilter::new_unsharp_mask(1.0, 1.0, 0.0)
+            .with_luminance_only(true)
+            .apply(&mut image)
+            .unwrap();
+        // the same amount is added to every channel, so the colour differences are untouched
+        for (before, after) in original.pixels().zip(image.pixels()) {
+            let shift = after[1] as i32 - before[1] as i32;
+            assert!((after[0] as i32 - before[0] as i32 - shift).abs() <= 1);
+            assert!((after[2] as i32 - before[2] as i32 - shift).abs() <= 1);
+        }
+    }
+}
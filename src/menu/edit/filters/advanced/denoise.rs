@@ -0,0 +1,375 @@
+// src/menu/edit/filters/advanced/denoise.rs
+use image::{ImageBuffer, Rgba};
+use crate::state::FilterError;
+use crate::menu::edit::filters::{ImageFilter, Region};
+use crate::menu::edit::crop::CropSelection;
+
+/// (left, top, right, bottom) of every pixel the filter may change, None if nothing does
+fn pixel_bounds(region: &Region, width: u32, height: u32) -> Option<(usize, usize, usize, usize)> {
+    let (x, y, w, h) = region.affected(width, height)?;
+    Some((x as usize, y as usize, (x + w) as usize, (y + h) as usize))
+}
+
+/// blends `result`, row-major over `bounds`, into `image`. alpha is left alone
+fn blend(region: &Region, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, bounds: (usize, usize, usize, usize), result: &[[f32; 3]]) {
+    let (left, top, right, _) = bounds;
+    let row_len = right - left;
+    for (i, filtered) in result.iter().enumerate() {
+        let (x, y) = ((left + i % row_len) as u32, (top + i / row_len) as u32);
+        let factor = region.factor(x, y);
+        if factor == 0.0 {
+            continue;
+        }
+        let pixel = image.get_pixel_mut(x, y);
+        for (value, &new) in pixel.0.iter_mut().zip(filtered.iter()) {
+            let blended = new * factor + *value as f32 * (1.0 - factor);
+            *value = blended.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// the RGB of every pixel as floats, read with clamped (edge-repeating) coordinates
+struct Pixels {
+    width: i32,
+    height: i32,
+    values: Vec<[f32; 3]>,
+}
+
+impl Pixels {
+    fn new(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Self {
+        Self {
+            width: image.width() as i32,
+            height: image.height() as i32,
+            values: image.pixels().map(|p| [p[0] as f32, p[1] as f32, p[2] as f32]).collect(),
+        }
+    }
+
+    fn get(&self, x: i32, y: i32) -> [f32; 3] {
+        let x = x.clamp(0, self.width - 1);
+        let y = y.clamp(0, self.height - 1);
+        self.values[(y * self.width + x) as usize]
+    }
+}
+
+fn squared_distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    a.iter().zip(b.iter()).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// median of each colour channel over a (2 × radius + 1)² window. removes salt and
+/// pepper noise and keeps step edges sharp
+pub struct MedianFilter {
+    radius: u32,
+    region: Region,
+}
+
+impl MedianFilter {
+    pub fn new(radius: u32) -> Self {
+        Self { radius: radius.clamp(1, 20), region: Region::default() }
+    }
+
+    pub fn with_selection(mut self, selection: CropSelection) -> Self {
+        self.region.rect = Some(selection.get_image_dimensions());
+        self
+    }
+
+    pub fn with_feather(mut self, radius: u32) -> Self {
+        self.region.feather_radius = radius;
+        self
+    }
+}
+
+impl ImageFilter for MedianFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        let bounds = match pixel_bounds(&self.region, image.width(), image.height()) {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+        let (left, top, right, bottom) = bounds;
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        let r = self.radius as i32;
+        let half = ((2 * r + 1) * (2 * r + 1) / 2) as u32;
+        let channel = |x: i32, y: i32, c: usize| image.get_pixel(x.clamp(0, width - 1) as u32, y.clamp(0, height - 1) as u32)[c] as usize;
+
+        // Huang's sliding histogram: moving one pixel right swaps one column of the window
+        let mut result = Vec::with_capacity((right - left) * (bottom - top));
+        for y in top as i32..bottom as i32 {
+            let mut histograms = [[0u32; 256]; 3];
+            for wy in y - r..=y + r {
+                for wx in left as i32 - r..=left as i32 + r {
+                    for (c, histogram) in histograms.iter_mut().enumerate() {
+                        histogram[channel(wx, wy, c)] += 1;
+                    }
+                }
+            }
+            for x in left as i32..right as i32 {
+                let mut median = [0f32; 3];
+                for (value, histogram) in median.iter_mut().zip(histograms.iter()) {
+                    let mut count = 0;
+                    for (level, &n) in histogram.iter().enumerate() {
+                        count += n;
+                        if count > half {
+                            *value = level as f32;
+                            break;
+                        }
+                    }
+                }
+                result.push(median);
+
+                for wy in y - r..=y + r {
+                    for (c, histogram) in histograms.iter_mut().enumerate() {
+                        histogram[channel(x - r, wy, c)] -= 1;
+                        histogram[channel(x + r + 1, wy, c)] += 1;
+                    }
+                }
+            }
+        }
+
+        blend(&self.region, image, bounds, &result);
+        Ok(())
+    }
+}
+
+/// averages neighbours weighted by distance (`spatial_sigma`, pixels) and by colour
+/// difference (`range_sigma`, 0-255), so pixels across an edge barely contribute
+pub struct BilateralFilter {
+    spatial_sigma: f32,
+    range_sigma: f32,
+    region: Region,
+}
+
+impl BilateralFilter {
+    pub fn new(spatial_sigma: f32, range_sigma: f32) -> Self {
+        Self {
+            spatial_sigma: spatial_sigma.clamp(0.5, 10.0),
+            range_sigma: range_sigma.clamp(1.0, 255.0),
+            region: Region::default(),
+        }
+    }
+
+    pub fn with_selection(mut self, selection: CropSelection) -> Self {
+        self.region.rect = Some(selection.get_image_dimensions());
+        self
+    }
+
+    pub fn with_feather(mut self, radius: u32) -> Self {
+        self.region.feather_radius = radius;
+        self
+    }
+}
+
+impl ImageFilter for BilateralFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        let bounds = match pixel_bounds(&self.region, image.width(), image.height()) {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+        let (left, top, right, bottom) = bounds;
+        let pixels = Pixels::new(image);
+
+        let r = (2.0 * self.spatial_sigma).ceil() as i32;
+        let size = (2 * r + 1) as usize;
+        let mut spatial = vec![0f32; size * size];
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let d2 = (dx * dx + dy * dy) as f32;
+                spatial[(dy + r) as usize * size + (dx + r) as usize] = (-d2 / (2.0 * self.spatial_sigma.powi(2))).exp();
+            }
+        }
+        let range_scale = -1.0 / (2.0 * self.range_sigma.powi(2));
+
+        let mut result = Vec::with_capacity((right - left) * (bottom - top));
+        for y in top as i32..bottom as i32 {
+            for x in left as i32..right as i32 {
+                let center = pixels.get(x, y);
+                let mut sum = [0f32; 3];
+                let mut weights = 0f32;
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        let neighbour = pixels.get(x + dx, y + dy);
+                        let weight = spatial[(dy + r) as usize * size + (dx + r) as usize]
+                            * (squared_distance(&center, &neighbour) * range_scale).exp();
+                        for (s, v) in sum.iter_mut().zip(neighbour.iter()) {
+                            *s += weight * v;
+                        }
+                        weights += weight;
+                    }
+                }
+                // the centre pixel always has weight 1, weights is never 0
+                result.push(sum.map(|s| s / weights));
+            }
+        }
+
+        blend(&self.region, image, bounds, &result);
+        Ok(())
+    }
+}
+
+/// non-local means: averages pixels within `search_radius` whose surrounding patches
+/// (of `patch_radius`) look alike. `strength` is the noise level in 0-255 units
+pub struct NonLocalMeansFilter {
+    strength: f32,
+    search_radius: u32,
+    patch_radius: u32,
+    region: Region,
+}
+
+impl NonLocalMeansFilter {
+    pub fn new(strength: f32, search_radius: u32, patch_radius: u32) -> Self {
+        Self {
+            strength: strength.clamp(1.0, 100.0),
+            search_radius: search_radius.clamp(1, 15),
+            patch_radius: patch_radius.clamp(1, 3),
+            region: Region::default(),
+        }
+    }
+
+    pub fn with_selection(mut self, selection: CropSelection) -> Self {
+        self.region.rect = Some(selection.get_image_dimensions());
+        self
+    }
+
+    pub fn with_feather(mut self, radius: u32) -> Self {
+        self.region.feather_radius = radius;
+        self
+    }
+}
+
+impl ImageFilter for NonLocalMeansFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        let bounds = match pixel_bounds(&self.region, image.width(), image.height()) {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+        let (left, top, right, bottom) = bounds;
+        let pixels = Pixels::new(image);
+        let (bw, bh) = (right - left, bottom - top);
+        let p = self.patch_radius as i32;
+        let s = self.search_radius as i32;
+        let patch_area = ((2 * p + 1) * (2 * p + 1)) as f32;
+        let h2 = self.strength * self.strength;
+
+        // the patch distances for one offset come from a summed-area table of the
+        // per pixel differences, so the cost doesn't grow with the patch size
+        let (pw, ph) = (bw + 2 * p as usize, bh + 2 * p as usize);
+        let mut table = vec![0f64; (pw + 1) * (ph + 1)];
+        let mut sum = vec![[0f32; 3]; bw * bh];
+        let mut weights = vec![0f32; bw * bh];
+        let mut max_weight = vec![0f32; bw * bh];
+
+        for oy in -s..=s {
+            for ox in -s..=s {
+                if ox == 0 && oy == 0 {
+                    continue;
+                }
+                for j in 0..ph {
+                    let mut row = 0f64;
+                    for i in 0..pw {
+                        let (x, y) = (left as i32 + i as i32 - p, top as i32 + j as i32 - p);
+                        // mean over the colour channels
+                        row += (squared_distance(&pixels.get(x, y), &pixels.get(x + ox, y + oy)) / 3.0) as f64;
+                        table[(j + 1) * (pw + 1) + i + 1] = table[j * (pw + 1) + i + 1] + row;
+                    }
+                }
+
+                for j in 0..bh {
+                    for i in 0..bw {
+                        let (i1, j1, i2, j2) = (i, j, i + 2 * p as usize + 1, j + 2 * p as usize + 1);
+                        let patch = table[j2 * (pw + 1) + i2] - table[j1 * (pw + 1) + i2]
+                            - table[j2 * (pw + 1) + i1] + table[j1 * (pw + 1) + i1];
+                        let distance = (patch as f32 / patch_area).max(0.0);
+                        let weight = (-distance / h2).exp();
+                        let k = j * bw + i;
+                        let neighbour = pixels.get((left + i) as i32 + ox, (top + j) as i32 + oy);
+                        for (total, v) in sum[k].iter_mut().zip(neighbour.iter()) {
+                            *total += weight * v;
+                        }
+                        weights[k] += weight;
+                        max_weight[k] = max_weight[k].max(weight);
+                    }
+                }
+            }
+        }
+
+        // the pixel itself counts as much as its best match, not more
+        let mut result = Vec::with_capacity(bw * bh);
+        for j in 0..bh {
+            for i in 0..bw {
+                let k = j * bw + i;
+                let center = pixels.get((left + i) as i32, (top + j) as i32);
+                let self_weight = if max_weight[k] > 0.0 { max_weight[k] } else { 1.0 };
+                let total = weights[k] + self_weight;
+                result.push(std::array::from_fn(|c| (sum[k][c] + self_weight * center[c]) / total));
+            }
+        }
+
+        blend(&self.region, image, bounds, &result);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(width: u32, height: u32, value: impl Fn(u32, u32) -> u8) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(width, height, |x, y| {
+            let v = value(x, y);
+            Rgba([v, v, v, 255])
+        })
+    }
+
+    #[test]
+    fn median_removes_impulses_and_keeps_edges() {
+        // a step edge at x = 6 with one hot pixel on each side
+        let mut image = gray(12, 8, |x, y| match (x, y) {
+            (2, 3) => 255,
+            (9, 4) => 0,
+            (x, _) if x < 6 => 40,
+            _ => 200,
+        });
+        MedianFilter::new(1).apply(&mut image).unwrap();
+        assert_eq!(image.get_pixel(2, 3)[0], 40);
+        assert_eq!(image.get_pixel(9, 4)[0], 200);
+        assert_eq!(image.get_pixel(5, 2)[0], 40);
+        assert_eq!(image.get_pixel(6, 2)[0], 200);
+        assert_eq!(image.get_pixel(0, 0)[3], 255);
+    }
+
+    #[test]
+    fn edge_preserving_filters_keep_a_step_and_smooth_noise() {
+        // alternating ±10 noise on both sides of a 60 → 180 step
+        let noisy = gray(16, 16, |x, y| {
+            let base: i32 = if x < 8 { 60 } else { 180 };
+            (base + if (x + y) % 2 == 0 { 10 } else { -10 }) as u8
+        });
+        let filters: [Box<dyn ImageFilter>; 2] = [
+            Box::new(BilateralFilter::new(2.0, 30.0)),
+            Box::new(NonLocalMeansFilter::new(20.0, 3, 1)),
+        ];
+        for filter in filters.iter() {
+            let mut image = noisy.clone();
+            filter.apply(&mut image).unwrap();
+            for y in 2..14 {
+                assert!((image.get_pixel(3, y)[0] as i32 - 60).abs() <= 4);
+                assert!((image.get_pixel(12, y)[0] as i32 - 180).abs() <= 4);
+                // the edge stays within one pixel
+                assert!(image.get_pixel(7, y)[0] < 90);
+                assert!(image.get_pixel(8, y)[0] > 150);
+            }
+        }
+    }
+
+    #[test]
+    fn only_the_selection_changes_without_feather() {
+        let noisy = gray(20, 20, |x, y| if (x + 2 * y) % 5 == 0 { 250 } else { 100 });
+        let mut image = noisy.clone();
+        let mut filter = MedianFilter::new(1);
+        filter.region.rect = Some((5, 5, 5, 5));
+        filter.apply(&mut image).unwrap();
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let inside = (5..10).contains(&x) && (5..10).contains(&y);
+            let expected = if inside { 100 } else { noisy.get_pixel(x, y)[0] };
+            assert_eq!(pixel[0], expected);
+        }
+    }
+}
@@ -2,9 +2,10 @@
 use image::{ImageBuffer, Rgba};
 use crate::state::FilterError;
 use super::super::ImageFilter;
-use crate::menu::edit::crop::crop_tool::CropSelection;
+use crate::menu::edit::crop::CropSelection;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EdgeDetectionMethod {
     Sobel,
     Canny,
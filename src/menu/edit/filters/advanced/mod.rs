@@ -6,6 +6,8 @@ mod vignette;
 mod posterize;
 mod pixelate;
 mod motion_blur;
+mod background;
+mod denoise;
 
 pub use edge_detection::{EdgeDetectionFilter, EdgeDetectionMethod};
 pub use noise::NoiseFilter;
@@ -13,5 +15,7 @@ pub use vignette::VignetteFilter;
 pub use posterize::PosterizeFilter;
 pub use pixelate::PixelateFilter;
 pub use motion_blur::MotionBlurFilter;
+pub use background::SubtractBackgroundFilter;
+pub use denoise::{BilateralFilter, MedianFilter, NonLocalMeansFilter};
 
 pub use convolution::{ConvolutionFilter, ConvolutionType};
\ No newline at end of file
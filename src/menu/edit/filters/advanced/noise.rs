@@ -3,7 +3,7 @@ use image::{ImageBuffer, Rgba};
 use rand::Rng;
 use crate::state::FilterError;
 use crate::menu::edit::filters::ImageFilter;
-use crate::menu::edit::crop::crop_tool::CropSelection;
+use crate::menu::edit::crop::CropSelection;
 
 pub struct NoiseFilter {
     amount: f32,
@@ -2,7 +2,7 @@
 use image::{ImageBuffer, Rgba};
 use crate::state::FilterError;
 use crate::menu::edit::filters::ImageFilter;
-use crate::menu::edit::crop::crop_tool::CropSelection;
+use crate::menu::edit::crop::CropSelection;
 
 pub struct VignetteFilter {
     intensity: f32,
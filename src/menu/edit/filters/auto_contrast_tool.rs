@@ -0,0 +1,266 @@
+// src/menu/edit/filters/auto_contrast_tool.rs
+use fltk::{
+    window::Window,
+    button::Button,
+    dialog::{alert, choice2},
+    frame::Frame,
+    group::{Pack, PackType},
+    input::FloatInput,
+    menu::Choice,
+    prelude::*,
+    enums::{Color, Event},
+    app,
+    draw,
+};
+use std::{cell::RefCell, rc::Rc};
+use crate::state::ImageState;
+use crate::menu::edit::crop::CropSelection;
+use crate::menu::view::zoom::is_pan_button;
+use crate::utils::{draw_current_image, Viewport};
+use super::tonal::{AutoStretchFilter, ClaheFilter, EqualizeFilter};
+use super::{FilterSpec, ImageFilter};
+
+/// asks for the parameters of an equalize, auto stretch or CLAHE `spec` and whether to
+/// adjust the whole image or a dragged selection
+pub fn show_auto_contrast_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, spec: FilterSpec) {
+    if state.borrow().image.is_none() {
+        alert(300, 300, "Please open an image first");
+        return;
+    }
+
+    let params = spec.params();
+    let mut dialog = Window::default()
+        .with_size(320, 130 + 55 * params.len() as i32)
+        .with_label(spec.name());
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 300, 110 + 55 * params.len() as i32, "");
+    pack.set_spacing(5);
+
+    let mut inputs = Vec::with_capacity(params.len());
+    for (label, value) in params.iter() {
+        Frame::default().with_size(300, 20).with_label(label);
+        let mut input = FloatInput::default().with_size(300, 25);
+        input.set_value(&format!("{}", value));
+        inputs.push(input);
+    }
+
+    Frame::default().with_size(300, 20).with_label("Apply to:");
+    let mut target = Choice::default().with_size(300, 25);
+    target.add_choice("Whole image|Drag a selection on the image");
+    target.set_value(0);
+
+    let mut button_pack = Pack::default().with_size(300, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut ok = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+
+    let result: Rc<RefCell<Option<(FilterSpec, bool)>>> = Rc::new(RefCell::new(None));
+
+    let result_ok = result.clone();
+    let mut dialog_ok = dialog.clone();
+    ok.set_callback(move |_| {
+        let mut values = Vec::with_capacity(inputs.len());
+        for input in inputs.iter() {
+            match input.value().parse::<f32>() {
+                Ok(value) => values.push(value),
+                Err(_) => {
+                    alert(300, 300, "Please enter valid numbers");
+                    return;
+                }
+            }
+        }
+        *result_ok.borrow_mut() = Some((spec.with_params(&values), target.value() == 1));
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    let chosen = result.take();
+    match chosen {
+        Some((spec, true)) => start_interactive_auto_contrast(frame, state, spec),
+        Some((spec, false)) => apply_auto_contrast(frame, state, &spec),
+        None => {}
+    }
+}
+
+/// the equalization, auto stretch or CLAHE filter for `spec`, measured and applied
+/// inside `selection` only
+fn selected_filter(spec: &FilterSpec, selection: CropSelection) -> Option<Box<dyn ImageFilter>> {
+    match *spec {
+        FilterSpec::Equalize {} => Some(Box::new(EqualizeFilter::new().with_selection(selection).with_feather(5))),
+        FilterSpec::AutoStretch { saturated } => {
+            Some(Box::new(AutoStretchFilter::new(saturated).with_selection(selection).with_feather(5)))
+        }
+        FilterSpec::Clahe { tile_size, clip_limit } => {
+            Some(Box::new(ClaheFilter::new(tile_size, clip_limit).with_selection(selection).with_feather(5)))
+        }
+        _ => None,
+    }
+}
+
+/// applies `spec` to the whole image as one undo step
+pub fn apply_auto_contrast(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, spec: &FilterSpec) {
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        let current_image = match &state_ref.image {
+            Some(img) => img.clone(),
+            None => {
+                alert(300, 300, "Please open an image first");
+                return;
+            }
+        };
+
+        let filter = spec.build();
+        match state_ref.filter_state.apply_filter(&current_image, &filter) {
+            Ok(Some(new_image)) => {
+                frame.borrow_mut().set_image(Some(new_image.clone()));
+                frame.borrow_mut().redraw();
+                state_ref.record_image_edit(spec.name());
+                state_ref.image = Some(new_image);
+                println!("Applied {}", spec.describe());
+            }
+            Ok(None) => {}
+            Err(e) => alert(300, 300, &format!("Failed to apply {}: {}", spec.name(), e)),
+        }
+    }
+}
+
+/// lets the user drag the area to measure and adjust, the statistics come from that area only
+pub fn start_interactive_auto_contrast(
+    frame: &Rc<RefCell<Frame>>,
+    state: &Rc<RefCell<ImageState>>,
+    spec: FilterSpec
+) {
+    let mut state_ref = state.borrow_mut();
+    if state_ref.image.is_none() {
+        alert(300, 300, "Please open an image first");
+        return;
+    }
+
+    let original_image = state_ref.image.clone();
+
+    // initialize the crop selection functionality for the adjusted area
+    if let Some(img) = &original_image {
+        let frame_ref = frame.borrow();
+        state_ref.crop_selection = Some(CropSelection::new(
+            img.data_w(),
+            img.data_h(),
+            frame_ref.w(),
+            frame_ref.h()
+        ));
+    }
+    drop(state_ref);
+
+    let frame_clone = frame.clone();
+    let state_clone = state.clone();
+    let mut frame = frame.borrow_mut();
+
+    let draw_callback = {
+        let state_clone = state_clone.clone();
+        move |f: &mut Frame| {
+            let dimensions = match state_clone.try_borrow() {
+                Ok(state_ref) => {
+                    draw_current_image(f, &state_ref);
+                    state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions()))
+                }
+                Err(_) => None,
+            };
+
+            if let Some((true, (x, y, w, h))) = dimensions {
+                draw::set_draw_color(Color::Yellow);
+                draw::set_line_style(draw::LineStyle::Solid, 2);
+                draw::draw_rect(x, y, w, h);
+                draw::set_line_style(draw::LineStyle::Solid, 0);
+            }
+        }
+    };
+
+    frame.draw(draw_callback);
+
+    let handle_callback = {
+        let state_clone = state_clone.clone();
+        let frame_clone = frame_clone.clone();
+
+        move |f: &mut Frame, ev: Event| -> bool {
+            match ev {
+                Event::Push | Event::Drag | Event::Released if is_pan_button() => false,
+                Event::Push => {
+                    if let Ok(mut state) = state_clone.try_borrow_mut() {
+                        let viewport = Viewport::current(f, &state);
+                        if let Some(selection) = &mut state.crop_selection {
+                            selection.reset();
+                            selection.set_viewport(viewport);
+                            selection.start_x = app::event_x();
+                            selection.start_y = app::event_y();
+                            selection.is_selecting = true;
+                            f.redraw();
+                        }
+                    }
+                    true
+                },
+                Event::Drag => {
+                    if let Ok(mut state) = state_clone.try_borrow_mut() {
+                        if let Some(selection) = &mut state.crop_selection {
+                            selection.end_x = app::event_x();
+                            selection.end_y = app::event_y();
+                            f.redraw();
+                        }
+                    }
+                    true
+                },
+                Event::Released => {
+                    let should_apply = {
+                        let mut dimensions = None;
+                        if let Ok(mut state) = state_clone.try_borrow_mut() {
+                            if let Some(selection) = &mut state.crop_selection {
+                                selection.is_selecting = false;
+                                selection.end_x = app::event_x();
+                                selection.end_y = app::event_y();
+                                dimensions = Some(selection.get_dimensions());
+                            }
+                        }
+
+                        if let Some((_, _, w, h)) = dimensions {
+                            w > 5 && h > 5 && choice2(300, 300, &format!("Apply {} to selected area?", spec.name()), "Yes", "No", "") == Some(0)
+                        } else {
+                            false
+                        }
+                    };
+
+                    if let Ok(mut state) = state_clone.try_borrow_mut() {
+                        if should_apply {
+                            let filter = state.crop_selection.clone().and_then(|s| selected_filter(&spec, s));
+                            if let (Some(filter), Some(current_image)) = (filter, state.image.clone()) {
+                                if let Ok(Some(new_image)) = state.filter_state.apply_filter(&current_image, &filter) {
+                                    state.record_image_edit(spec.name());
+                                    state.image = Some(new_image.clone());
+                                    frame_clone.borrow_mut().set_image(state.image.clone());
+                                }
+                            }
+                        }
+                        state.crop_selection = None;
+                    }
+
+                    f.redraw();
+                    true
+                },
+                _ => false,
+            }
+        }
+    };
+
+    frame.handle(handle_callback);
+}
@@ -0,0 +1,273 @@
+// src/menu/edit/filters/curves_dialog.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::{Cell, RefCell}};
+
+// FLTK imports
+use fltk::{
+    app::{self, MouseButton},
+    window::Window,
+    button::Button,
+    frame::Frame,
+    group::{Pack, PackType},
+    image::RgbImage,
+    menu::Choice,
+    enums::{Align, Color, Event},
+    draw,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::utils::histogram::{Histogram, HistogramChannel};
+use super::preview::{edit_current_image, Preview};
+use super::tonal::Curve;
+use super::FilterSpec;
+
+/// master first, then red, green and blue, the order of `HistogramChannel::ALL`
+const CHANNEL_LABELS: [&str; 4] = ["RGB", "Red", "Green", "Blue"];
+
+/// the editor is one screen pixel per value
+const EDITOR_SIZE: i32 = 256;
+
+/// how close (in pixels) a click has to be to grab a control point
+const GRAB_DISTANCE: i32 = 5;
+
+pub fn show_curves_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    edit_current_image(frame, state, |image, preview| {
+        let identity = FilterSpec::Curves {
+            master: Curve::identity(),
+            red: Curve::identity(),
+            green: Curve::identity(),
+            blue: Curve::identity(),
+        };
+        edit_curves(&identity, &Histogram::of_image(image), preview)
+    });
+}
+
+fn curves_spec(points: &[Vec<(u8, u8)>; 4]) -> FilterSpec {
+    let curve = |i: usize| Curve { points: points[i].clone() };
+    FilterSpec::Curves {
+        master: curve(0),
+        red: curve(1),
+        green: curve(2),
+        blue: curve(3),
+    }
+}
+
+fn channel_color(channel: usize) -> Color {
+    match channel {
+        1 => Color::from_rgb(200, 0, 0),
+        2 => Color::from_rgb(0, 150, 0),
+        3 => Color::from_rgb(0, 0, 200),
+        _ => Color::Black,
+    }
+}
+
+/// edits a `FilterSpec::Curves` over the histogram of the image it will be applied to.
+/// click to add a point, drag to move it, right click to remove it. returns None when cancelled
+pub fn edit_curves(initial: &FilterSpec, histogram: &Histogram, preview: Preview) -> Option<FilterSpec> {
+    let start = match initial {
+        FilterSpec::Curves { master, red, green, blue } => [
+            master.control_points(),
+            red.control_points(),
+            green.control_points(),
+            blue.control_points(),
+        ],
+        _ => std::array::from_fn(|_| Curve::identity().control_points()),
+    };
+    let points = Rc::new(RefCell::new(start));
+    let current = Rc::new(Cell::new(0usize));
+    let dragging: Rc<Cell<Option<usize>>> = Rc::new(Cell::new(None));
+
+    let mut dialog = Window::default()
+        .with_size(276, 400)
+        .with_label("Curves");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, EDITOR_SIZE, 380, "");
+    pack.set_spacing(6);
+
+    let mut channel_choice = Choice::default().with_size(EDITOR_SIZE, 25);
+    for label in CHANNEL_LABELS {
+        channel_choice.add_choice(label);
+    }
+    channel_choice.set_value(0);
+
+    let plots: Vec<Option<RgbImage>> = HistogramChannel::ALL
+        .iter()
+        .map(|&channel| histogram.render(&[channel], EDITOR_SIZE as u32, EDITOR_SIZE as u32, true, false).ok())
+        .collect();
+
+    let mut editor = Frame::default().with_size(EDITOR_SIZE, EDITOR_SIZE);
+    {
+        let points = points.clone();
+        let current = current.clone();
+        editor.draw(move |f| {
+            let (x0, y0) = (f.x(), f.y());
+            draw::draw_rect_fill(x0, y0, EDITOR_SIZE, EDITOR_SIZE, Color::White);
+            if let Some(Some(image)) = plots.get(current.get()) {
+                let mut image = image.clone();
+                image.draw(x0, y0, EDITOR_SIZE, EDITOR_SIZE);
+            }
+
+            // quarter grid and the identity diagonal
+            draw::set_draw_color(Color::from_rgb(210, 210, 210));
+            draw::set_line_style(draw::LineStyle::Dot, 1);
+            for i in 1..4 {
+                let offset = i * EDITOR_SIZE / 4;
+                draw::draw_line(x0 + offset, y0, x0 + offset, y0 + EDITOR_SIZE - 1);
+                draw::draw_line(x0, y0 + offset, x0 + EDITOR_SIZE - 1, y0 + offset);
+            }
+            draw::draw_line(x0, y0 + EDITOR_SIZE - 1, x0 + EDITOR_SIZE - 1, y0);
+
+            let channel = current.get();
+            let list = points.borrow()[channel].clone();
+            let lut = Curve { points: list.clone() }.lut();
+            draw::set_draw_color(channel_color(channel));
+            draw::set_line_style(draw::LineStyle::Solid, 2);
+            for (value, pair) in lut.windows(2).enumerate() {
+                let x = x0 + value as i32;
+                draw::draw_line(x, y0 + 255 - pair[0] as i32, x + 1, y0 + 255 - pair[1] as i32);
+            }
+            draw::set_line_style(draw::LineStyle::Solid, 0);
+            for (x, y) in list {
+                draw::draw_rect_fill(x0 + x as i32 - 3, y0 + 255 - y as i32 - 3, 7, 7, channel_color(channel));
+            }
+
+            draw::set_draw_color(Color::Black);
+            draw::draw_rect(x0, y0, EDITOR_SIZE, EDITOR_SIZE);
+        });
+    }
+
+    let mut readout = Frame::default().with_size(EDITOR_SIZE, 20);
+    readout.set_align(Align::Left | Align::Inside);
+
+    let mut button_pack = Pack::default().with_size(EDITOR_SIZE, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut reset_btn = Button::default().with_size(78, 30).with_label("Reset");
+    let mut ok_btn = Button::default().with_size(78, 30).with_label("OK");
+    let mut cancel_btn = Button::default().with_size(78, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+
+    let changed = {
+        let points = points.clone();
+        let editor = editor.clone();
+        Rc::new(move || {
+            editor.clone().redraw();
+            preview(Some(&curves_spec(&points.borrow())));
+        })
+    };
+
+    {
+        let points = points.clone();
+        let current = current.clone();
+        let dragging = dragging.clone();
+        let changed = changed.clone();
+        editor.handle(move |f, ev| {
+            // position in curve space, output counts up from the bottom
+            let x = (app::event_x() - f.x()).clamp(0, 255);
+            let y = (255 - (app::event_y() - f.y())).clamp(0, 255);
+            match ev {
+                Event::Push => {
+                    let channel = current.get();
+                    let hit = points.borrow()[channel].iter().position(|&(px, py)| {
+                        (px as i32 - x).abs() <= GRAB_DISTANCE && (py as i32 - y).abs() <= GRAB_DISTANCE
+                    });
+                    if app::event_mouse_button() == MouseButton::Right {
+                        let mut all = points.borrow_mut();
+                        match hit {
+                            Some(index) if all[channel].len() > 2 => {
+                                all[channel].remove(index);
+                            }
+                            _ => return true,
+                        }
+                        drop(all);
+                        changed();
+                        return true;
+                    }
+                    let index = match hit {
+                        Some(index) => index,
+                        None => {
+                            let mut all = points.borrow_mut();
+                            let list = &mut all[channel];
+                            let index = list.partition_point(|&(px, _)| (px as i32) < x);
+                            if list.get(index).map(|&(px, _)| px as i32) == Some(x) {
+                                list[index].1 = y as u8;
+                            } else {
+                                list.insert(index, (x as u8, y as u8));
+                            }
+                            index
+                        }
+                    };
+                    dragging.set(Some(index));
+                    readout.set_label(&format!("Input: {}   Output: {}", x, y));
+                    changed();
+                    true
+                }
+                Event::Drag => {
+                    if let Some(index) = dragging.get() {
+                        {
+                            let mut all = points.borrow_mut();
+                            let list = &mut all[current.get()];
+                            // a point can't pass its neighbours
+                            let low = if index > 0 { list[index - 1].0 as i32 + 1 } else { 0 };
+                            let high = list.get(index + 1).map_or(255, |&(px, _)| px as i32 - 1);
+                            let x = x.clamp(low, high.max(low));
+                            list[index] = (x as u8, y as u8);
+                            readout.set_label(&format!("Input: {}   Output: {}", x, y));
+                        }
+                        changed();
+                    }
+                    true
+                }
+                Event::Released => {
+                    dragging.set(None);
+                    true
+                }
+                _ => false,
+            }
+        });
+    }
+
+    let current_choice = current.clone();
+    let mut editor_choice = editor.clone();
+    channel_choice.set_callback(move |choice| {
+        current_choice.set((choice.value().max(0) as usize).min(CHANNEL_LABELS.len() - 1));
+        editor_choice.redraw();
+    });
+
+    let points_reset = points.clone();
+    let current_reset = current.clone();
+    let changed_reset = changed.clone();
+    reset_btn.set_callback(move |_| {
+        points_reset.borrow_mut()[current_reset.get()] = Curve::identity().control_points();
+        changed_reset();
+    });
+
+    let result: Rc<RefCell<Option<FilterSpec>>> = Rc::new(RefCell::new(None));
+
+    let result_ok = result.clone();
+    let points_ok = points.clone();
+    let mut dialog_ok = dialog.clone();
+    ok_btn.set_callback(move |_| {
+        *result_ok.borrow_mut() = Some(curves_spec(&points_ok.borrow()));
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel_btn.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    result.take()
+}
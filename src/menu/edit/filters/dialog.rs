@@ -6,7 +6,7 @@ use std::{rc::Rc, cell::RefCell};
 // FLTK imports
 use fltk::{
     window::Window,
-    button::Button,
+    button::{Button, CheckButton},
     input::FloatInput,
     frame::Frame,
     group::Pack,
@@ -40,6 +40,7 @@ use super::handlers::{
     handle_apply_box_blur,
     handle_apply_gaussian_blur,
     handle_apply_sharpen,
+    handle_apply_unsharp_mask,
     //handle_apply_vignette,
     handle_apply_posterize,
     handle_apply_motion_blur,
@@ -50,13 +51,18 @@ pub fn show_filter_dialog(
     state: &Rc<RefCell<ImageState>>,
     filter_type: &str
 ) -> bool {
+    // the convolution filters share one dialog with a choice between them
+    let convolution_types = ["gaussian_blur", "box_blur", "sharpen", "unsharp_mask"];
+    let is_convolution = convolution_types.contains(&filter_type);
+    let height = if is_convolution { 520 } else { 380 };
+
     // Create a taller window to accommodate instructions
     let mut dialog = Window::default()
-        .with_size(400, 380)  // Increased height
+        .with_size(400, height)  // Increased height
         .with_label(&format!("Apply {} Filter", filter_type));
     dialog.make_modal(true);
 
-    let mut pack = Pack::new(10, 10, 380, 360, "");  // Increased height
+    let mut pack = Pack::new(10, 10, 380, height - 20, "");  // Increased height
     pack.set_spacing(10);
 
     // Title frame
@@ -91,6 +97,21 @@ pub fn show_filter_dialog(
             .with_pos(0, 145);  // Adjusted position
     }
 
+    // Add type choice for the convolution filters
+    let mut convolution_choice = None;
+    if is_convolution {
+        Frame::default()
+            .with_size(380, 25)
+            .with_label("Convolution Type:")
+            .with_pos(0, 85);
+
+        let mut choice = Choice::new(20, 110, 180, 25, "");
+        choice.add_choice("Gaussian Blur|Box Blur|Sharpen|Unsharp Mask");
+        let index = convolution_types.iter().position(|t| *t == filter_type).unwrap_or(0);
+        choice.set_value(index as i32);
+        convolution_choice = Some(choice);
+    }
+
     let param_label = match filter_type {
         "grayscale" | "sepia" | "invert" => "Intensity (0.0 - 1.0):",
         "brightness" => "Level (-1.0 to 1.0):",
@@ -99,6 +120,7 @@ pub fn show_filter_dialog(
         "hue" => "Angle (0 - 360):",
         "box_blur" | "gaussian_blur" => "Radius (1.0 - 10.0):",
         "sharpen" => "Intensity (0.0 - 5.0):",
+        "unsharp_mask" => "Radius (0.1 - 50 pixels):",
         "edge_detection" => "",  // Already handled above
         "noise" => "Amount (0.0 - 1.0):",
         "vignette" => "Intensity (0.0 - 1.0):",
@@ -108,16 +130,18 @@ pub fn show_filter_dialog(
         _ => "Value:",
     };
     
+    let mut param_frame = None;
     if filter_type != "edge_detection" {
-        Frame::default()
+        let label_frame = Frame::default()
             .with_size(380, 25)
             .with_label(param_label)
-            .with_pos(0, 85);  // Adjusted position
+            .with_pos(0, if is_convolution { 145 } else { 85 });  // Adjusted position
+        param_frame = Some(label_frame);
     }
 
     let mut value_input = FloatInput::new(
         20, 
-        if filter_type == "edge_detection" { 170 } else { 110 },  // Adjusted position
+        if filter_type == "edge_detection" || is_convolution { 170 } else { 110 },  // Adjusted position
         180, 
         25, 
         ""
@@ -129,6 +153,7 @@ pub fn show_filter_dialog(
         "hue" => "180.0",
         "box_blur" | "gaussian_blur" => "3.0",
         "sharpen" => "1.0",
+        "unsharp_mask" => "2.0",
         "edge_detection" | "noise" | "vignette" => "0.5",
         "posterize" => "4",
         "pixelate" => "8",
@@ -137,11 +162,70 @@ pub fn show_filter_dialog(
     };
     value_input.set_value(default_value);
 
+    // amount, threshold and luminance mode of the unsharp mask
+    let mut unsharp_widgets = None;
+    if is_convolution {
+        Frame::default()
+            .with_size(380, 25)
+            .with_label("Amount (0.0 - 10.0):")
+            .with_pos(0, 205);
+        let mut amount_input = FloatInput::new(20, 230, 180, 25, "");
+        amount_input.set_value("0.6");
+
+        Frame::default()
+            .with_size(380, 25)
+            .with_label("Threshold (0 - 255):")
+            .with_pos(0, 265);
+        let mut threshold_input = FloatInput::new(20, 290, 180, 25, "");
+        threshold_input.set_value("3");
+
+        let mut luminance_check = CheckButton::new(20, 325, 180, 25, "Luminance only (no colour fringes)");
+        luminance_check.set_checked(false);
+
+        if filter_type != "unsharp_mask" {
+            amount_input.deactivate();
+            threshold_input.deactivate();
+            luminance_check.deactivate();
+        }
+        unsharp_widgets = Some((amount_input, threshold_input, luminance_check));
+    }
+
+    // switching the convolution type updates the parameter label and the unsharp inputs
+    if let (Some(choice), Some(label_frame), Some((amount_input, threshold_input, luminance_check))) =
+        (&mut convolution_choice, &param_frame, &unsharp_widgets)
+    {
+        let mut label_frame = label_frame.clone();
+        let mut value_input = value_input.clone();
+        let mut amount_input = amount_input.clone();
+        let mut threshold_input = threshold_input.clone();
+        let mut luminance_check = luminance_check.clone();
+        choice.set_callback(move |c| {
+            let (label, value) = match c.value() {
+                2 => ("Intensity (0.0 - 5.0):", "1.0"),
+                3 => ("Radius (0.1 - 50 pixels):", "2.0"),
+                _ => ("Radius (1.0 - 10.0):", "3.0"),
+            };
+            label_frame.set_label(label);
+            value_input.set_value(value);
+            if c.value() == 3 {
+                amount_input.activate();
+                threshold_input.activate();
+                luminance_check.activate();
+            } else {
+                amount_input.deactivate();
+                threshold_input.deactivate();
+                luminance_check.deactivate();
+            }
+        });
+    }
+
+    let button_y = if is_convolution { 460 } else { 320 };
+
     let dialog_rc = Rc::new(RefCell::new(dialog));
     let result = Rc::new(RefCell::new(false));
 
-    let mut cancel = Button::new(190, 320, 85, 25, "Cancel");  // Adjusted position
-    let mut ok = Button::new(285, 320, 85, 25, "Apply");      // Adjusted position
+    let mut cancel = Button::new(190, button_y, 85, 25, "Cancel");  // Adjusted position
+    let mut ok = Button::new(285, button_y, 85, 25, "Apply");      // Adjusted position
 
     pack.end();
     dialog_rc.borrow_mut().end();
@@ -155,9 +239,14 @@ pub fn show_filter_dialog(
     let frame_rc = frame.clone();
     let state_rc = state.clone();
     let result_rc = result.clone();
-    let filter_type = filter_type.to_string();
+    let mut filter_type = filter_type.to_string();
 
     ok.set_callback(move |_| {
+        if let Some(ref choice) = convolution_choice {
+            let index = (choice.value().max(0) as usize).min(convolution_types.len() - 1);
+            filter_type = convolution_types[index].to_string();
+        }
+
         if let Ok(value) = value_input.value().parse::<f32>() {
             let adjusted_value = match filter_type.as_str() {
                 "brightness" => value.clamp(-1.0, 1.0),
@@ -165,6 +254,7 @@ pub fn show_filter_dialog(
                 "hue" => value % 360.0,
                 "box_blur" | "gaussian_blur" => value.clamp(1.0, 10.0),
                 "sharpen" => value.clamp(0.0, 5.0),
+                "unsharp_mask" => value.clamp(0.1, 50.0),
                 "edge_detection" | "noise" | "vignette" => value.clamp(0.0, 1.0),
                 "posterize" => value.clamp(2.0, 8.0),
                 "pixelate" => value.clamp(2.0, 32.0),
@@ -183,6 +273,24 @@ pub fn show_filter_dialog(
                 "box_blur" => handle_apply_box_blur(&frame_rc, &state_rc, adjusted_value),
                 "gaussian_blur" => handle_apply_gaussian_blur(&frame_rc, &state_rc, adjusted_value),
                 "sharpen" => handle_apply_sharpen(&frame_rc, &state_rc, adjusted_value),
+                "unsharp_mask" => {
+                    if let Some((ref amount_input, ref threshold_input, ref luminance_check)) = unsharp_widgets {
+                        match (amount_input.value().parse::<f32>(), threshold_input.value().parse::<f32>()) {
+                            (Ok(amount), Ok(threshold)) => handle_apply_unsharp_mask(
+                                &frame_rc,
+                                &state_rc,
+                                adjusted_value,
+                                amount.clamp(0.0, 10.0),
+                                threshold.clamp(0.0, 255.0),
+                                luminance_check.is_checked(),
+                            ),
+                            _ => {
+                                println!("Invalid input value");
+                                return;
+                            }
+                        }
+                    }
+                },
                 "edge_detection" => {
                     let method = if let Some(ref choice) = method_choice {
                         match choice.value() {
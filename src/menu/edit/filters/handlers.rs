@@ -67,6 +67,7 @@ pub fn handle_apply_grayscale(
             println!("Successfully applied grayscale filter");
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Grayscale");
             state_ref.image = Some(new_image);
         }
     }
@@ -89,6 +90,7 @@ pub fn handle_apply_sepia(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Sepia");
             state_ref.image = Some(new_image);
         }
     }
@@ -111,6 +113,7 @@ pub fn handle_apply_brightness(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Brightness");
             state_ref.image = Some(new_image);
         }
     }
@@ -133,6 +136,7 @@ pub fn handle_apply_contrast(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Contrast");
             state_ref.image = Some(new_image);
         }
     }
@@ -155,6 +159,7 @@ pub fn handle_apply_saturation(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Saturation");
             state_ref.image = Some(new_image);
         }
     }
@@ -179,6 +184,7 @@ pub fn handle_apply_threshold(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Threshold");
             state_ref.image = Some(new_image);
         }
     }
@@ -201,6 +207,7 @@ pub fn handle_apply_hue(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Hue");
             state_ref.image = Some(new_image);
         }
     }
@@ -238,6 +245,22 @@ pub fn handle_apply_sharpen(
     });
 }
 
+pub fn handle_apply_unsharp_mask(
+    frame: &Rc<RefCell<Frame>>,
+    state: &Rc<RefCell<ImageState>>,
+    radius: f32,
+    amount: f32,
+    threshold: f32,
+    luminance_only: bool
+) {
+    start_interactive_convolution(frame, state, ConvolutionType::UnsharpMask {
+        radius,
+        amount,
+        threshold,
+        luminance_only,
+    });
+}
+
 pub fn handle_apply_edge_detection(
     frame: &Rc<RefCell<Frame>>, 
     state: &Rc<RefCell<ImageState>>,
@@ -280,6 +303,7 @@ pub fn handle_apply_vignette(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Vignette");
             state_ref.image = Some(new_image);
         }
     }
@@ -302,6 +326,7 @@ pub fn handle_apply_posterize(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Posterize");
             state_ref.image = Some(new_image);
         }
     }
@@ -324,6 +349,7 @@ pub fn handle_apply_pixelate(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Pixelate");
             state_ref.image = Some(new_image);
         }
     }
@@ -348,36 +374,47 @@ pub fn handle_apply_motion_blur(
         if let Ok(Some(new_image)) = state_ref.filter_state.apply_filter(&current_image, &filter) {
             frame.borrow_mut().set_image(Some(new_image.clone()));
             frame.borrow_mut().redraw();
+            state_ref.record_image_edit("Motion Blur");
             state_ref.image = Some(new_image);
         }
     }
 }
 
 pub fn handle_toggle_preview(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
-    if let Ok(mut state_ref) = state.try_borrow_mut() {
+    let (mut shown, zoom) = {
+        let mut state_ref = match state.try_borrow_mut() {
+            Ok(state_ref) => state_ref,
+            Err(_) => return,
+        };
         let preview_active = state_ref.filter_state.is_preview_active();
-        let current_zoom = state_ref.zoom;
         state_ref.filter_state.toggle_preview();
-        
-        if let Some(ref image) = state_ref.image.clone() {
-            if preview_active {
-                // Reload original image when disabling preview
-                if let Some(path) = &state_ref.path {
-                    if let Ok(img) = image::open(path) {
-                        let fltk_image = fltk::image::RgbImage::new(
-                            &img.to_rgb8().into_raw(),
-                            img.width() as i32,
-                            img.height() as i32,
-                            fltk::enums::ColorDepth::Rgb8
-                        ).unwrap();
-                        
-                        crate::utils::image::display_image(frame, &fltk_image, current_zoom.into());
-                        state_ref.image = Some(fltk_image);
-                        return;
-                    }
+
+        let image = match state_ref.image.clone() {
+            Some(image) => image,
+            None => return,
+        };
+        let shown = if preview_active {
+            // Reload original image when disabling preview
+            let original = state_ref.path.as_ref()
+                .and_then(|path| image::open(path).ok())
+                .and_then(|img| fltk::image::RgbImage::new(
+                    &img.to_rgb8().into_raw(),
+                    img.width() as i32,
+                    img.height() as i32,
+                    fltk::enums::ColorDepth::Rgb8
+                ).ok());
+            match original {
+                Some(fltk_image) => {
+                    state_ref.record_image_edit("Revert Filter Preview");
+                    state_ref.image = Some(fltk_image.clone());
+                    fltk_image
                 }
-                crate::utils::image::display_image(frame, image, current_zoom.into());
+                None => image,
             }
-        }
-    }
-}
\ No newline at end of file
+        } else {
+            return;
+        };
+        (shown, state_ref.zoom)
+    };
+    crate::utils::image::display_image_with_zoom(frame, &mut shown, zoom, state);
+}
@@ -0,0 +1,211 @@
+// src/menu/edit/filters/levels_dialog.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::{Cell, RefCell}};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::Button,
+    frame::Frame,
+    group::{Pack, PackType},
+    image::RgbImage,
+    menu::Choice,
+    valuator::HorValueSlider,
+    enums::Color,
+    draw,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::utils::histogram::{Histogram, HistogramChannel};
+use super::preview::{edit_current_image, Preview};
+use super::tonal::ChannelLevels;
+use super::FilterSpec;
+
+/// master first, then red, green and blue, the order of `HistogramChannel::ALL`
+const CHANNEL_LABELS: [&str; 4] = ["RGB", "Red", "Green", "Blue"];
+
+pub fn show_levels_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    edit_current_image(frame, state, |image, preview| {
+        edit_levels(&levels_spec(&[ChannelLevels::IDENTITY; 4]), &Histogram::of_image(image), preview)
+    });
+}
+
+fn levels_spec(channels: &[ChannelLevels; 4]) -> FilterSpec {
+    FilterSpec::Levels {
+        master: channels[0],
+        red: channels[1],
+        green: channels[2],
+        blue: channels[3],
+    }
+}
+
+/// edits a `FilterSpec::Levels` over the histogram of the image it will be applied to.
+/// returns None when cancelled
+pub fn edit_levels(initial: &FilterSpec, histogram: &Histogram, preview: Preview) -> Option<FilterSpec> {
+    let start = match initial {
+        FilterSpec::Levels { master, red, green, blue } => [*master, *red, *green, *blue],
+        _ => [ChannelLevels::IDENTITY; 4],
+    };
+    let channels = Rc::new(RefCell::new(start));
+    let current = Rc::new(Cell::new(0usize));
+
+    let mut dialog = Window::default()
+        .with_size(420, 420)
+        .with_label("Levels");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 400, 400, "");
+    pack.set_spacing(6);
+
+    let mut channel_choice = Choice::default().with_size(400, 25);
+    for label in CHANNEL_LABELS {
+        channel_choice.add_choice(label);
+    }
+    channel_choice.set_value(0);
+
+    // one histogram per channel, drawn without axes so x maps straight to the value
+    let plots: Vec<Option<RgbImage>> = HistogramChannel::ALL
+        .iter()
+        .map(|&channel| histogram.render(&[channel], 400, 140, false, false).ok())
+        .collect();
+    let mut plot = Frame::default().with_size(400, 140);
+    {
+        let channels = channels.clone();
+        let current = current.clone();
+        plot.draw(move |f| {
+            draw::draw_rect_fill(f.x(), f.y(), f.w(), f.h(), Color::White);
+            if let Some(Some(image)) = plots.get(current.get()) {
+                let mut image = image.clone();
+                image.draw(f.x(), f.y(), f.w(), f.h());
+            }
+            let levels = channels.borrow()[current.get()];
+            let to_x = |value: f32| f.x() + (value * f.w() as f32 / 256.0) as i32;
+            // the gamma marker sits where the input maps to mid gray
+            let mid = levels.black as f32 + (levels.white as f32 - levels.black as f32) * 0.5f32.powf(levels.gamma);
+            draw::set_line_style(draw::LineStyle::Solid, 2);
+            for (value, color) in [
+                (levels.black as f32, Color::Black),
+                (mid, Color::from_rgb(128, 128, 128)),
+                (levels.white as f32, Color::from_rgb(200, 200, 200)),
+            ] {
+                let x = to_x(value + 0.5);
+                draw::set_draw_color(color);
+                draw::draw_line(x, f.y(), x, f.y() + f.h());
+            }
+            draw::set_line_style(draw::LineStyle::Solid, 0);
+            draw::set_draw_color(Color::Black);
+            draw::draw_rect(f.x(), f.y(), f.w(), f.h());
+        });
+    }
+
+    Frame::default().with_size(400, 20).with_label("Black point:");
+    let mut black = HorValueSlider::default().with_size(400, 25);
+    black.set_range(0.0, 255.0);
+    black.set_step(1.0, 1);
+
+    Frame::default().with_size(400, 20).with_label("Gamma (midtones):");
+    let mut gamma = HorValueSlider::default().with_size(400, 25);
+    gamma.set_range(0.1, 5.0);
+    gamma.set_step(0.01, 1);
+
+    Frame::default().with_size(400, 20).with_label("White point:");
+    let mut white = HorValueSlider::default().with_size(400, 25);
+    white.set_range(0.0, 255.0);
+    white.set_step(1.0, 1);
+
+    let mut button_pack = Pack::default().with_size(400, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut reset_btn = Button::default().with_size(90, 30).with_label("Reset");
+    let mut ok_btn = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel_btn = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+
+    // puts the levels of the current channel into the sliders
+    let mut show_channel = {
+        let channels = channels.clone();
+        let current = current.clone();
+        let (mut black, mut gamma, mut white) = (black.clone(), gamma.clone(), white.clone());
+        let mut plot = plot.clone();
+        move || {
+            let levels = channels.borrow()[current.get()];
+            black.set_value(levels.black as f64);
+            gamma.set_value(levels.gamma as f64);
+            white.set_value(levels.white as f64);
+            plot.redraw();
+        }
+    };
+    let mut show_channel_choice = show_channel.clone();
+    let mut show_channel_reset = show_channel.clone();
+    show_channel();
+
+    // reads the sliders back into the current channel and previews the result
+    let update = {
+        let channels = channels.clone();
+        let current = current.clone();
+        let (black, gamma, white) = (black.clone(), gamma.clone(), white.clone());
+        let plot = plot.clone();
+        let preview = preview.clone();
+        Rc::new(move || {
+            let spec = {
+                let mut channels = channels.borrow_mut();
+                channels[current.get()] = ChannelLevels::new(
+                    black.value().round() as u8,
+                    white.value().round() as u8,
+                    gamma.value() as f32,
+                );
+                levels_spec(&channels)
+            };
+            plot.clone().redraw();
+            preview(Some(&spec));
+        })
+    };
+    for slider in [&mut black, &mut gamma, &mut white] {
+        let update = update.clone();
+        slider.set_callback(move |_| update());
+    }
+
+    let current_choice = current.clone();
+    channel_choice.set_callback(move |choice| {
+        current_choice.set((choice.value().max(0) as usize).min(CHANNEL_LABELS.len() - 1));
+        show_channel_choice();
+    });
+
+    let channels_reset = channels.clone();
+    let current_reset = current.clone();
+    let update_reset = update.clone();
+    reset_btn.set_callback(move |_| {
+        channels_reset.borrow_mut()[current_reset.get()] = ChannelLevels::IDENTITY;
+        show_channel_reset();
+        update_reset();
+    });
+
+    let result: Rc<RefCell<Option<FilterSpec>>> = Rc::new(RefCell::new(None));
+
+    let result_ok = result.clone();
+    let channels_ok = channels.clone();
+    let mut dialog_ok = dialog.clone();
+    ok_btn.set_callback(move |_| {
+        *result_ok.borrow_mut() = Some(levels_spec(&channels_ok.borrow()));
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel_btn.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    result.take()
+}
@@ -1,6 +1,8 @@
 // menu/edit/filters/mod.rs
 use image::{ImageBuffer, Rgba};
+#[cfg(feature = "gui")]
 use fltk::image::RgbImage;
+#[cfg(feature = "gui")]
 use fltk::prelude::*;
 
 // Internal imports
@@ -9,30 +11,75 @@ use crate::state::FilterError;
 // Module declarations - public modules
 pub mod basic;
 pub mod advanced;
+pub mod tonal;
+#[cfg(feature = "gui")]
 pub mod handlers;
+#[cfg(feature = "gui")]
 pub mod dialog;
+pub mod spec;
+mod region;
+#[cfg(feature = "gui")]
+pub mod stack_dialog;
 
 // Module declarations - private modules
+#[cfg(feature = "gui")]
 mod pixelate_tool;
+#[cfg(feature = "gui")]
 mod convolution_tool;
+#[cfg(feature = "gui")]
 mod edge_detection_tool;
+#[cfg(feature = "gui")]
 mod noise_tool;
+#[cfg(feature = "gui")]
 mod vignette_tool;
+#[cfg(feature = "gui")]
 mod posterize_tool;
+#[cfg(feature = "gui")]
 mod motion_blur_tool;
+#[cfg(feature = "gui")]
+mod preview;
+#[cfg(feature = "gui")]
+mod levels_dialog;
+#[cfg(feature = "gui")]
+mod curves_dialog;
+#[cfg(feature = "gui")]
+mod auto_contrast_tool;
+#[cfg(feature = "gui")]
+mod denoise_tool;
 
 // Public re-exports
+pub(crate) use region::Region;
+#[cfg(feature = "gui")]
 pub use dialog::show_filter_dialog;
+#[cfg(feature = "gui")]
+pub use stack_dialog::show_filter_stack_dialog;
+#[cfg(feature = "gui")]
 pub use handlers::*;
+#[cfg(feature = "gui")]
 pub use pixelate_tool::start_interactive_pixelate;
+#[cfg(feature = "gui")]
 pub use convolution_tool::start_interactive_convolution;
+#[cfg(feature = "gui")]
 pub use edge_detection_tool::start_interactive_edge_detection;
+#[cfg(feature = "gui")]
 pub use noise_tool::start_interactive_noise;
+#[cfg(feature = "gui")]
 pub use vignette_tool::start_interactive_vignette;
+#[cfg(feature = "gui")]
 pub use posterize_tool::start_interactive_posterize;
+#[cfg(feature = "gui")]
 pub use motion_blur_tool::start_interactive_motion_blur;
+#[cfg(feature = "gui")]
+pub use levels_dialog::show_levels_dialog;
+#[cfg(feature = "gui")]
+pub use curves_dialog::show_curves_dialog;
+#[cfg(feature = "gui")]
+pub use auto_contrast_tool::show_auto_contrast_dialog;
+#[cfg(feature = "gui")]
+pub use denoise_tool::{show_denoise_dialog, start_interactive_denoise};
 
 pub use advanced::{ConvolutionType, EdgeDetectionMethod};  // Re-export from advanced module
+pub use spec::FilterSpec;
 
 
 
@@ -40,7 +87,15 @@ pub trait ImageFilter {
     fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError>;
 }
 
+// lets a filter built from a FilterSpec go through FilterState::apply_filter
+impl ImageFilter for Box<dyn ImageFilter> {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        (**self).apply(image)
+    }
+}
+
 // Helper function to convert between FLTK and image crate formats
+#[cfg(feature = "gui")]
 pub(crate) fn fltk_to_image_buffer(fltk_image: &RgbImage) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
     let data = fltk_image.to_rgb_data();
     let width = fltk_image.data_w() as u32;
@@ -64,6 +119,7 @@ pub(crate) fn fltk_to_image_buffer(fltk_image: &RgbImage) -> ImageBuffer<Rgba<u8
         .unwrap_or_else(|| ImageBuffer::new(width, height))
 }
 
+#[cfg(feature = "gui")]
 pub(crate) fn image_buffer_to_fltk(buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Option<RgbImage> {
     let width = buffer.width() as i32;
     let height = buffer.height() as i32;
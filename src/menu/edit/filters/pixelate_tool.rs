@@ -9,7 +9,9 @@ use fltk::{
 };
 use std::{cell::RefCell, rc::Rc};
 use crate::state::ImageState;
-use crate::menu::edit::crop::crop_tool::CropSelection;
+use crate::menu::edit::crop::CropSelection;
+use crate::menu::view::zoom::is_pan_button;
+use crate::utils::{draw_current_image, Viewport};
 use super::ImageFilter;
 use super::advanced::PixelateFilter;
 
@@ -44,30 +46,28 @@ pub fn start_interactive_pixelate(
 
     let draw_callback = {
         let state_clone = state_clone.clone();
-        let original_image = original_image.clone();
         move |f: &mut Frame| {
-            if let Some(img) = &original_image {
-                f.set_image(Some(img.clone()));
-
-                let dimensions = state_clone
-                    .try_borrow()
-                    .ok()
-                    .and_then(|state_ref| state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions())));
+            let dimensions = match state_clone.try_borrow() {
+                Ok(state_ref) => {
+                    draw_current_image(f, &state_ref);
+                    state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions()))
+                }
+                Err(_) => None,
+            };
+            
+            if let Some((true, (x, y, w, h))) = dimensions {
+                draw::set_draw_color(Color::Yellow);
+                draw::set_line_style(draw::LineStyle::Solid, 2);
+                draw::draw_rect(x, y, w, h);
                 
-                if let Some((true, (x, y, w, h))) = dimensions {
-                    draw::set_draw_color(Color::Yellow);
-                    draw::set_line_style(draw::LineStyle::Solid, 2);
-                    draw::draw_rect(x, y, w, h);
-                    
-                    let grid_size = block_size.min(20) as i32;
-                    draw::set_line_style(draw::LineStyle::Dot, 1);
-                    
-                    for i in (x..x + w).step_by(grid_size as usize) {
-                        draw::draw_line(i, y, i, y + h);
-                    }
-                    for i in (y..y + h).step_by(grid_size as usize) {
-                        draw::draw_line(x, i, x + w, i);
-                    }
+                let grid_size = block_size.min(20) as i32;
+                draw::set_line_style(draw::LineStyle::Dot, 1);
+                
+                for i in (x..x + w).step_by(grid_size as usize) {
+                    draw::draw_line(i, y, i, y + h);
+                }
+                for i in (y..y + h).step_by(grid_size as usize) {
+                    draw::draw_line(x, i, x + w, i);
                 }
             }
         }
@@ -81,10 +81,13 @@ pub fn start_interactive_pixelate(
         let block_size = block_size;
         move |f: &mut Frame, ev: Event| -> bool {
             match ev {
+                Event::Push | Event::Drag | Event::Released if is_pan_button() => false,
                 Event::Push => {
                     if let Ok(mut state) = state_clone.try_borrow_mut() {
+                        let viewport = Viewport::current(f, &state);
                         if let Some(selection) = &mut state.crop_selection {
                             selection.reset();
+                            selection.set_viewport(viewport);
                             selection.start_x = app::event_x();
                             selection.start_y = app::event_y();
                             selection.is_selecting = true;
@@ -131,6 +134,7 @@ pub fn start_interactive_pixelate(
                                     .with_intensity(1.0);
                                 
                                 if let Ok(Some(new_image)) = state.filter_state.apply_filter(current_image, &filter) {
+                                    state.record_image_edit("Pixelate");
                                     state.image = Some(new_image.clone());
                                     frame_clone.borrow_mut().set_image(state.image.clone());
                                 }
@@ -0,0 +1,89 @@
+// src/menu/edit/filters/preview.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    dialog::alert,
+    frame::Frame,
+    image::RgbImage,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::{FilterError, ImageState};
+use super::{fltk_to_image_buffer, image_buffer_to_fltk, FilterSpec};
+
+/// called by the levels and curves editors with the spec to show after every change,
+/// and with None when the edit is cancelled
+pub type Preview = Rc<dyn Fn(Option<&FilterSpec>)>;
+
+/// runs `spec` on a copy of `image`
+pub fn apply_spec(image: &RgbImage, spec: &FilterSpec) -> Result<Option<RgbImage>, FilterError> {
+    let mut buffer = fltk_to_image_buffer(image);
+    spec.build().apply(&mut buffer)?;
+    Ok(image_buffer_to_fltk(&buffer))
+}
+
+/// opens `editor` on the current image. changes are only shown on the frame while
+/// it is open; the image itself (and the undo history) changes once it returns a spec
+pub fn edit_current_image<E>(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, editor: E)
+where
+    E: FnOnce(&RgbImage, Preview) -> Option<FilterSpec>,
+{
+    let original = match state.try_borrow() {
+        Ok(state_ref) => match &state_ref.image {
+            Some(image) => image.clone(),
+            None => {
+                alert(300, 300, "Please open an image first");
+                return;
+            }
+        },
+        Err(_) => return,
+    };
+
+    let preview: Preview = {
+        let frame = frame.clone();
+        let original = original.clone();
+        Rc::new(move |spec: Option<&FilterSpec>| {
+            let shown = match spec {
+                Some(spec) => match apply_spec(&original, spec) {
+                    Ok(Some(image)) => image,
+                    _ => return,
+                },
+                None => original.clone(),
+            };
+            if let Ok(mut frame_ref) = frame.try_borrow_mut() {
+                frame_ref.set_image(Some(shown));
+                frame_ref.redraw();
+            }
+        })
+    };
+
+    let spec = match editor(&original, preview.clone()) {
+        Some(spec) => spec,
+        None => {
+            preview(None);
+            return;
+        }
+    };
+
+    match apply_spec(&original, &spec) {
+        Ok(Some(new_image)) => {
+            if let Ok(mut state_ref) = state.try_borrow_mut() {
+                state_ref.record_image_edit(spec.name());
+                state_ref.image = Some(new_image.clone());
+            }
+            let mut frame_ref = frame.borrow_mut();
+            frame_ref.set_image(Some(new_image));
+            frame_ref.redraw();
+            println!("Applied {}", spec.describe());
+        }
+        Ok(None) => preview(None),
+        Err(e) => {
+            preview(None);
+            alert(300, 300, &format!("Failed to apply {}: {}", spec.name(), e));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// menu/edit/filters/region.rs
+
+/// the part of the image a filter measures and changes: the whole image, or a selection
+/// whose result fades out over `feather_radius` pixels around it
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Region {
+    /// (x, y, w, h) in image pixels, None for the whole image
+    pub(crate) rect: Option<(i32, i32, i32, i32)>,
+    pub(crate) feather_radius: u32,
+}
+
+impl Region {
+    /// the selected rectangle clipped to the image as (x, y, w, h), None if nothing is left
+    pub(crate) fn bounds(&self, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        let (x, y, w, h) = self.rect.unwrap_or((0, 0, width as i32, height as i32));
+        let (left, top) = (x.clamp(0, width as i32), y.clamp(0, height as i32));
+        let (right, bottom) = ((x + w).clamp(0, width as i32), (y + h).clamp(0, height as i32));
+        if right <= left || bottom <= top {
+            return None;
+        }
+        Some((left as u32, top as u32, (right - left) as u32, (bottom - top) as u32))
+    }
+
+    /// the bounds grown by the feather, i.e. every pixel that may change
+    pub(crate) fn affected(&self, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        let (x, y, w, h) = self.bounds(width, height)?;
+        let feather = if self.rect.is_some() { self.feather_radius } else { 0 };
+        let (left, top) = (x.saturating_sub(feather), y.saturating_sub(feather));
+        let right = (x + w + feather).min(width);
+        let bottom = (y + h + feather).min(height);
+        Some((left, top, right - left, bottom - top))
+    }
+
+    /// 1 inside the selection, falling to 0 across the feather, the curve the other tools use
+    pub(crate) fn factor(&self, x: u32, y: u32) -> f32 {
+        let (sx, sy, sw, sh) = match self.rect {
+            Some(rect) => rect,
+            None => return 1.0,
+        };
+        let (x, y) = (x as i32, y as i32);
+        let dx = if x < sx { sx - x } else if x >= sx + sw { x - (sx + sw - 1) } else { 0 };
+        let dy = if y < sy { sy - y } else if y >= sy + sh { y - (sy + sh - 1) } else { 0 };
+        if dx == 0 && dy == 0 {
+            return 1.0;
+        }
+        let distance = ((dx * dx + dy * dy) as f32).sqrt();
+        if distance >= self.feather_radius as f32 {
+            0.0
+        } else {
+            (1.0 - distance / self.feather_radius as f32).powf(0.75)
+        }
+    }
+}
@@ -0,0 +1,278 @@
+// src/menu/edit/filters/spec.rs
+use serde::{Deserialize, Serialize};
+use super::ImageFilter;
+use super::basic::{
+    BrightnessFilter, ContrastFilter, GrayscaleFilter, HueFilter, SaturationFilter, SepiaFilter, ThresholdFilter,
+};
+use super::advanced::{
+    BilateralFilter, ConvolutionFilter, EdgeDetectionFilter, EdgeDetectionMethod, MedianFilter, MotionBlurFilter,
+    NoiseFilter, NonLocalMeansFilter, PixelateFilter, PosterizeFilter, VignetteFilter,
+};
+use super::tonal::{
+    AutoStretchFilter, ChannelLevels, ClaheFilter, Curve, CurvesFilter, EqualizeFilter, LevelsFilter,
+};
+
+/// a filter and its parameters in a form that can be written to a recipe file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "filter", rename_all = "snake_case")]
+pub enum FilterSpec {
+    Grayscale { intensity: f32 },
+    Sepia { intensity: f32 },
+    Brightness { intensity: f32 },
+    Contrast { intensity: f32 },
+    Saturation { intensity: f32 },
+    Threshold { threshold: f32 },
+    Hue { angle: f32 },
+    EdgeDetection { threshold: f32, method: EdgeDetectionMethod },
+    Noise { amount: f32 },
+    Vignette { intensity: f32 },
+    Posterize { levels: u8 },
+    Pixelate { block_size: u32 },
+    MotionBlur { angle: f32 },
+    BoxBlur { radius: f32 },
+    GaussianBlur { radius: f32, sigma: f32 },
+    Sharpen { intensity: f32 },
+    UnsharpMask { radius: f32, amount: f32, threshold: f32, luminance_only: bool },
+    Median { radius: u32 },
+    Bilateral { spatial_sigma: f32, range_sigma: f32 },
+    NonLocalMeans { strength: f32, search_radius: u32, patch_radius: u32 },
+    Levels { master: ChannelLevels, red: ChannelLevels, green: ChannelLevels, blue: ChannelLevels },
+    Curves { master: Curve, red: Curve, green: Curve, blue: Curve },
+    Equalize {},
+    AutoStretch { saturated: f32 },
+    Clahe { tile_size: u32, clip_limit: f32 },
+}
+
+impl FilterSpec {
+    /// one of each with a reasonable starting value, in menu order
+    pub const ALL: [FilterSpec; 25] = [
+        FilterSpec::Grayscale { intensity: 1.0 },
+        FilterSpec::Sepia { intensity: 1.0 },
+        FilterSpec::Brightness { intensity: 0.2 },
+        FilterSpec::Contrast { intensity: 1.2 },
+        FilterSpec::Saturation { intensity: 1.2 },
+        FilterSpec::Threshold { threshold: 0.5 },
+        FilterSpec::Hue { angle: 180.0 },
+        FilterSpec::EdgeDetection { threshold: 0.5, method: EdgeDetectionMethod::Sobel },
+        FilterSpec::Noise { amount: 0.5 },
+        FilterSpec::Vignette { intensity: 0.5 },
+        FilterSpec::Posterize { levels: 4 },
+        FilterSpec::Pixelate { block_size: 8 },
+        FilterSpec::MotionBlur { angle: 45.0 },
+        FilterSpec::BoxBlur { radius: 3.0 },
+        FilterSpec::GaussianBlur { radius: 3.0, sigma: 1.5 },
+        FilterSpec::Sharpen { intensity: 1.0 },
+        FilterSpec::UnsharpMask { radius: 2.0, amount: 0.6, threshold: 3.0, luminance_only: false },
+        FilterSpec::Median { radius: 2 },
+        FilterSpec::Bilateral { spatial_sigma: 3.0, range_sigma: 25.0 },
+        FilterSpec::NonLocalMeans { strength: 10.0, search_radius: 5, patch_radius: 1 },
+        FilterSpec::Levels {
+            master: ChannelLevels::IDENTITY,
+            red: ChannelLevels::IDENTITY,
+            green: ChannelLevels::IDENTITY,
+            blue: ChannelLevels::IDENTITY,
+        },
+        FilterSpec::Curves {
+            master: Curve::identity(),
+            red: Curve::identity(),
+            green: Curve::identity(),
+            blue: Curve::identity(),
+        },
+        FilterSpec::Equalize {},
+        FilterSpec::AutoStretch { saturated: 0.35 },
+        FilterSpec::Clahe { tile_size: 64, clip_limit: 3.0 },
+    ];
+
+    pub fn build(&self) -> Box<dyn ImageFilter> {
+        match *self {
+            FilterSpec::Grayscale { intensity } => Box::new(GrayscaleFilter::new(intensity)),
+            FilterSpec::Sepia { intensity } => Box::new(SepiaFilter::new(intensity)),
+            FilterSpec::Brightness { intensity } => Box::new(BrightnessFilter::new(intensity)),
+            FilterSpec::Contrast { intensity } => Box::new(ContrastFilter::new(intensity)),
+            FilterSpec::Saturation { intensity } => Box::new(SaturationFilter::new(intensity)),
+            FilterSpec::Threshold { threshold } => Box::new(ThresholdFilter::new(threshold)),
+            FilterSpec::Hue { angle } => Box::new(HueFilter::new(angle)),
+            FilterSpec::EdgeDetection { threshold, method } => Box::new(EdgeDetectionFilter::new(threshold, method)),
+            FilterSpec::Noise { amount } => Box::new(NoiseFilter::new(amount)),
+            FilterSpec::Vignette { intensity } => Box::new(VignetteFilter::new(intensity)),
+            FilterSpec::Posterize { levels } => Box::new(PosterizeFilter::new(levels)),
+            FilterSpec::Pixelate { block_size } => Box::new(PixelateFilter::new(block_size)),
+            FilterSpec::MotionBlur { angle } => Box::new(MotionBlurFilter::new(angle)),
+            FilterSpec::BoxBlur { radius } => Box::new(ConvolutionFilter::new_box_blur(radius)),
+            FilterSpec::GaussianBlur { radius, sigma } => Box::new(ConvolutionFilter::new_gaussian_blur(radius, sigma)),
+            FilterSpec::Sharpen { intensity } => Box::new(ConvolutionFilter::new_sharpen(intensity)),
+            FilterSpec::UnsharpMask { radius, amount, threshold, luminance_only } => Box::new(
+                ConvolutionFilter::new_unsharp_mask(radius, amount, threshold).with_luminance_only(luminance_only),
+            ),
+            FilterSpec::Median { radius } => Box::new(MedianFilter::new(radius)),
+            FilterSpec::Bilateral { spatial_sigma, range_sigma } => Box::new(BilateralFilter::new(spatial_sigma, range_sigma)),
+            FilterSpec::NonLocalMeans { strength, search_radius, patch_radius } => {
+                Box::new(NonLocalMeansFilter::new(strength, search_radius, patch_radius))
+            }
+            FilterSpec::Levels { master, red, green, blue } => Box::new(LevelsFilter::new(master, red, green, blue)),
+            FilterSpec::Curves { ref master, ref red, ref green, ref blue } => {
+                Box::new(CurvesFilter::new(master, red, green, blue))
+            }
+            FilterSpec::Equalize {} => Box::new(EqualizeFilter::new()),
+            FilterSpec::AutoStretch { saturated } => Box::new(AutoStretchFilter::new(saturated)),
+            FilterSpec::Clahe { tile_size, clip_limit } => Box::new(ClaheFilter::new(tile_size, clip_limit)),
+        }
+    }
+
+    /// short name for progress output and history labels
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterSpec::Grayscale { .. } => "Grayscale",
+            FilterSpec::Sepia { .. } => "Sepia",
+            FilterSpec::Brightness { .. } => "Brightness",
+            FilterSpec::Contrast { .. } => "Contrast",
+            FilterSpec::Saturation { .. } => "Saturation",
+            FilterSpec::Threshold { .. } => "Threshold",
+            FilterSpec::Hue { .. } => "Hue",
+            FilterSpec::EdgeDetection { .. } => "Edge Detection",
+            FilterSpec::Noise { .. } => "Noise",
+            FilterSpec::Vignette { .. } => "Vignette",
+            FilterSpec::Posterize { .. } => "Posterize",
+            FilterSpec::Pixelate { .. } => "Pixelate",
+            FilterSpec::MotionBlur { .. } => "Motion Blur",
+            FilterSpec::BoxBlur { .. } => "Box Blur",
+            FilterSpec::GaussianBlur { .. } => "Gaussian Blur",
+            FilterSpec::Sharpen { .. } => "Sharpen",
+            FilterSpec::UnsharpMask { .. } => "Unsharp Mask",
+            FilterSpec::Median { .. } => "Median",
+            FilterSpec::Bilateral { .. } => "Bilateral",
+            FilterSpec::NonLocalMeans { .. } => "Non-Local Means",
+            FilterSpec::Levels { .. } => "Levels",
+            FilterSpec::Curves { .. } => "Curves",
+            FilterSpec::Equalize { .. } => "Equalize Histogram",
+            FilterSpec::AutoStretch { .. } => "Auto Stretch",
+            FilterSpec::Clahe { .. } => "CLAHE",
+        }
+    }
+
+    /// the numeric parameters as (label, value), the edge detection method and the unsharp
+    /// mask's luminance mode are kept separately.
+    /// levels and curves have their own editors and list none
+    pub fn params(&self) -> Vec<(&'static str, f32)> {
+        match *self {
+            FilterSpec::Grayscale { intensity }
+            | FilterSpec::Sepia { intensity }
+            | FilterSpec::Vignette { intensity } => vec![("Intensity (0.0 - 1.0)", intensity)],
+            FilterSpec::Brightness { intensity } => vec![("Level (-1.0 to 1.0)", intensity)],
+            FilterSpec::Contrast { intensity } | FilterSpec::Saturation { intensity } => {
+                vec![("Amount (0.0 - 2.0)", intensity)]
+            }
+            FilterSpec::Threshold { threshold } | FilterSpec::EdgeDetection { threshold, .. } => {
+                vec![("Threshold (0.0 - 1.0)", threshold)]
+            }
+            FilterSpec::Hue { angle } | FilterSpec::MotionBlur { angle } => vec![("Angle (0 - 360)", angle)],
+            FilterSpec::Noise { amount } => vec![("Amount (0.0 - 1.0)", amount)],
+            FilterSpec::Posterize { levels } => vec![("Levels (2 - 8)", levels as f32)],
+            FilterSpec::Pixelate { block_size } => vec![("Block Size (2 - 32)", block_size as f32)],
+            FilterSpec::BoxBlur { radius } => vec![("Radius (1.0 - 10.0)", radius)],
+            FilterSpec::GaussianBlur { radius, sigma } => {
+                vec![("Radius (1.0 - 10.0)", radius), ("Sigma", sigma)]
+            }
+            FilterSpec::Sharpen { intensity } => vec![("Intensity (0.0 - 5.0)", intensity)],
+            FilterSpec::UnsharpMask { radius, amount, threshold, .. } => vec![
+                ("Radius (0.1 - 50 pixels)", radius),
+                ("Amount (0.0 - 10.0)", amount),
+                ("Threshold (0 - 255)", threshold),
+            ],
+            FilterSpec::Median { radius } => vec![("Radius (1 - 20 pixels)", radius as f32)],
+            FilterSpec::Bilateral { spatial_sigma, range_sigma } => {
+                vec![("Spatial sigma (0.5 - 10 pixels)", spatial_sigma), ("Range sigma (1 - 255)", range_sigma)]
+            }
+            FilterSpec::NonLocalMeans { strength, search_radius, patch_radius } => vec![
+                ("Strength (1 - 100)", strength),
+                ("Search radius (1 - 15 pixels)", search_radius as f32),
+                ("Patch radius (1 - 3 pixels)", patch_radius as f32),
+            ],
+            FilterSpec::Levels { .. } | FilterSpec::Curves { .. } | FilterSpec::Equalize { .. } => Vec::new(),
+            FilterSpec::AutoStretch { saturated } => vec![("Saturated pixels (0 - 50 %)", saturated)],
+            FilterSpec::Clahe { tile_size, clip_limit } => {
+                vec![("Tile size (8 - 1024 pixels)", tile_size as f32), ("Clip limit (1.0 - 100.0)", clip_limit)]
+            }
+        }
+    }
+
+    /// same filter with new parameter values, in the order `params` lists them
+    pub fn with_params(&self, values: &[f32]) -> FilterSpec {
+        let mut spec = self.clone();
+        let first = values.first().copied();
+        match &mut spec {
+            FilterSpec::Grayscale { intensity }
+            | FilterSpec::Sepia { intensity }
+            | FilterSpec::Brightness { intensity }
+            | FilterSpec::Contrast { intensity }
+            | FilterSpec::Saturation { intensity }
+            | FilterSpec::Vignette { intensity }
+            | FilterSpec::Sharpen { intensity } => *intensity = first.unwrap_or(*intensity),
+            FilterSpec::Threshold { threshold } | FilterSpec::EdgeDetection { threshold, .. } => {
+                *threshold = first.unwrap_or(*threshold)
+            }
+            FilterSpec::Hue { angle } | FilterSpec::MotionBlur { angle } => *angle = first.unwrap_or(*angle),
+            FilterSpec::Noise { amount } => *amount = first.unwrap_or(*amount),
+            FilterSpec::Posterize { levels } => *levels = first.map_or(*levels, |v| v.round().clamp(2.0, 8.0) as u8),
+            FilterSpec::Pixelate { block_size } => {
+                *block_size = first.map_or(*block_size, |v| v.round().clamp(2.0, 32.0) as u32)
+            }
+            FilterSpec::BoxBlur { radius } => *radius = first.unwrap_or(*radius),
+            FilterSpec::GaussianBlur { radius, sigma } => {
+                *radius = first.unwrap_or(*radius);
+                *sigma = values.get(1).copied().unwrap_or(*sigma);
+            }
+            FilterSpec::UnsharpMask { radius, amount, threshold, .. } => {
+                *radius = first.map_or(*radius, |v| v.clamp(0.1, 50.0));
+                *amount = values.get(1).map_or(*amount, |v| v.clamp(0.0, 10.0));
+                *threshold = values.get(2).map_or(*threshold, |v| v.clamp(0.0, 255.0));
+            }
+            FilterSpec::Median { radius } => *radius = first.map_or(*radius, |v| v.round().clamp(1.0, 20.0) as u32),
+            FilterSpec::Bilateral { spatial_sigma, range_sigma } => {
+                *spatial_sigma = first.map_or(*spatial_sigma, |v| v.clamp(0.5, 10.0));
+                *range_sigma = values.get(1).map_or(*range_sigma, |v| v.clamp(1.0, 255.0));
+            }
+            FilterSpec::NonLocalMeans { strength, search_radius, patch_radius } => {
+                *strength = first.map_or(*strength, |v| v.clamp(1.0, 100.0));
+                *search_radius = values.get(1).map_or(*search_radius, |v| v.round().clamp(1.0, 15.0) as u32);
+                *patch_radius = values.get(2).map_or(*patch_radius, |v| v.round().clamp(1.0, 3.0) as u32);
+            }
+            FilterSpec::AutoStretch { saturated } => *saturated = first.map_or(*saturated, |v| v.clamp(0.0, 50.0)),
+            FilterSpec::Clahe { tile_size, clip_limit } => {
+                *tile_size = first.map_or(*tile_size, |v| v.round().clamp(8.0, 1024.0) as u32);
+                *clip_limit = values.get(1).map_or(*clip_limit, |v| v.clamp(1.0, 100.0));
+            }
+            FilterSpec::Levels { .. } | FilterSpec::Curves { .. } | FilterSpec::Equalize { .. } => {}
+        }
+        spec
+    }
+
+    /// e.g. "Gaussian Blur (3, 1.5)"
+    pub fn describe(&self) -> String {
+        let values: Vec<String> = self.params().iter().map(|(_, v)| format!("{}", v)).collect();
+        match self {
+            FilterSpec::EdgeDetection { method, .. } => format!("{} ({:?}, {})", self.name(), method, values.join(", ")),
+            FilterSpec::UnsharpMask { luminance_only: true, .. } => {
+                format!("{} ({}, luminance)", self.name(), values.join(", "))
+            }
+            FilterSpec::Levels { master, red, green, blue } => {
+                let per_channel = [red, green, blue].iter().any(|c| !c.is_identity());
+                format!(
+                    "{} ({} - {}, gamma {:.2}{})",
+                    self.name(), master.black, master.white, master.gamma,
+                    if per_channel { ", per channel" } else { "" },
+                )
+            }
+            FilterSpec::Curves { master, red, green, blue } => {
+                let per_channel = [red, green, blue].iter().any(|c| !c.is_identity());
+                format!(
+                    "{} ({} points{})",
+                    self.name(), master.control_points().len(),
+                    if per_channel { ", per channel" } else { "" },
+                )
+            }
+            _ if values.is_empty() => self.name().to_string(),
+            _ => format!("{} ({})", self.name(), values.join(", ")),
+        }
+    }
+}
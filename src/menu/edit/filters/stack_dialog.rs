@@ -0,0 +1,472 @@
+// src/menu/edit/filters/stack_dialog.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::{Cell, RefCell}, path::PathBuf};
+
+// FLTK imports
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    button::Button,
+    dialog::{alert, choice2, FileDialog, FileDialogType},
+    frame::Frame,
+    input::{FloatInput, Input},
+    menu::Choice,
+    window::Window,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::state::filter_recipe::{FilterRecipe, FilterStep, RECIPE_EXTENSION};
+use crate::utils::image::display_image_with_zoom;
+use crate::utils::histogram::Histogram;
+use super::advanced::EdgeDetectionMethod;
+use super::curves_dialog::edit_curves;
+use super::levels_dialog::edit_levels;
+use super::preview::Preview;
+use super::FilterSpec;
+
+/// editor for the non-destructive filter stack. every change re-renders the
+/// stack from the image it was started on
+pub fn show_filter_stack_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    {
+        let mut state_ref = match state.try_borrow_mut() {
+            Ok(state_ref) => state_ref,
+            Err(_) => return,
+        };
+        let image = match &state_ref.image {
+            Some(image) => image.clone(),
+            None => {
+                alert(200, 200, "Open an image first");
+                return;
+            }
+        };
+        let continued = state_ref.filter_state.attach_stack(&image);
+        if !continued && !state_ref.filter_state.stack.is_empty() {
+            let keep = choice2(
+                200, 200,
+                "The image changed since the filter stack was last rendered.\nApply the existing steps to it?",
+                "Apply", "Start empty", "",
+            ) == Some(0);
+            if !keep {
+                state_ref.filter_state.stack = FilterRecipe::default();
+            }
+        }
+    }
+
+    let mut dialog = Window::default()
+        .with_size(470, 400)
+        .with_label("Filter Stack");
+    dialog.make_modal(true);
+
+    let mut browser = HoldBrowser::new(10, 10, 310, 300, "");
+
+    let mut add_btn = Button::new(330, 10, 130, 28, "Add...");
+    let mut edit_btn = Button::new(330, 44, 130, 28, "Edit...");
+    let mut remove_btn = Button::new(330, 78, 130, 28, "Remove");
+    let mut up_btn = Button::new(330, 122, 130, 28, "Move Up");
+    let mut down_btn = Button::new(330, 156, 130, 28, "Move Down");
+    let mut toggle_btn = Button::new(330, 200, 130, 28, "Enable/Disable");
+    let mut clear_btn = Button::new(330, 244, 130, 28, "Clear");
+
+    let mut name_input = Input::new(110, 322, 210, 25, "Recipe name:");
+    let mut load_btn = Button::new(10, 360, 110, 28, "Load Recipe...");
+    let mut save_btn = Button::new(130, 360, 110, 28, "Save Recipe...");
+    let mut close_btn = Button::new(360, 360, 100, 28, "Close");
+
+    dialog.end();
+
+    if let Ok(state_ref) = state.try_borrow() {
+        name_input.set_value(&state_ref.filter_state.stack.name);
+        fill_browser(&mut browser, &state_ref.filter_state.stack, 0);
+    }
+
+    // the first change in this session goes on the undo stack, later ones just re-render
+    let recorded = Rc::new(Cell::new(false));
+    let rerender = {
+        let frame = frame.clone();
+        let state = state.clone();
+        let recorded = recorded.clone();
+        let browser = browser.clone();
+        Rc::new(move |selected: usize| {
+            let mut browser = browser.clone();
+            let result = {
+                let mut state_ref = match state.try_borrow_mut() {
+                    Ok(state_ref) => state_ref,
+                    Err(_) => return,
+                };
+                fill_browser(&mut browser, &state_ref.filter_state.stack, selected);
+                match state_ref.filter_state.render_stack() {
+                    Ok(Some(image)) => {
+                        if !recorded.get() {
+                            state_ref.record_image_edit("Filter Stack");
+                            recorded.set(true);
+                        }
+                        state_ref.image = Some(image.clone());
+                        Ok((image, state_ref.zoom))
+                    }
+                    Ok(None) => return,
+                    Err(e) => Err(e),
+                }
+            };
+            match result {
+                Ok((mut image, zoom)) => display_image_with_zoom(&frame, &mut image, zoom, &state),
+                Err(e) => alert(200, 200, &format!("Failed to render filter stack: {}", e)),
+            }
+        })
+    };
+
+    let selected_index = |browser: &HoldBrowser| -> Option<usize> {
+        match browser.value() {
+            0 => None,
+            line => Some(line as usize - 1),
+        }
+    };
+
+    let frame_add = frame.clone();
+    let state_add = state.clone();
+    let rerender_add = rerender.clone();
+    add_btn.set_callback(move |_| {
+        if let Some(spec) = edit_filter_spec(&FilterSpec::ALL[0]) {
+            let tonal = matches!(spec, FilterSpec::Levels { .. } | FilterSpec::Curves { .. });
+            let index = match state_add.try_borrow_mut() {
+                Ok(mut state_ref) => {
+                    state_ref.filter_state.stack.steps.push(FilterStep::new(spec));
+                    state_ref.filter_state.stack.steps.len() - 1
+                }
+                Err(_) => return,
+            };
+            // a new levels or curves step starts as the identity, so it goes straight to its editor
+            if tonal {
+                let edited = edit_tonal_step(&frame_add, &state_add, index);
+                if let Ok(mut state_ref) = state_add.try_borrow_mut() {
+                    match edited {
+                        Some(spec) => state_ref.filter_state.stack.steps[index].spec = spec,
+                        None => {
+                            state_ref.filter_state.stack.steps.pop();
+                            return;
+                        }
+                    }
+                }
+            }
+            rerender_add(index);
+        }
+    });
+
+    let frame_edit = frame.clone();
+    let state_edit = state.clone();
+    let rerender_edit = rerender.clone();
+    let browser_edit = browser.clone();
+    edit_btn.set_callback(move |_| {
+        let index = match selected_index(&browser_edit) {
+            Some(index) => index,
+            None => return,
+        };
+        let current = match state_edit.try_borrow() {
+            Ok(state_ref) => state_ref.filter_state.stack.steps.get(index).map(|s| s.spec.clone()),
+            Err(_) => return,
+        };
+        let edited = match current {
+            Some(FilterSpec::Levels { .. }) | Some(FilterSpec::Curves { .. }) => {
+                edit_tonal_step(&frame_edit, &state_edit, index)
+            }
+            current => current.and_then(|spec| edit_filter_spec(&spec)),
+        };
+        if let Some(spec) = edited {
+            if let Ok(mut state_ref) = state_edit.try_borrow_mut() {
+                state_ref.filter_state.stack.steps[index].spec = spec;
+            }
+            rerender_edit(index);
+        }
+    });
+
+    let state_remove = state.clone();
+    let rerender_remove = rerender.clone();
+    let browser_remove = browser.clone();
+    remove_btn.set_callback(move |_| {
+        if let Some(index) = selected_index(&browser_remove) {
+            if let Ok(mut state_ref) = state_remove.try_borrow_mut() {
+                if index < state_ref.filter_state.stack.steps.len() {
+                    state_ref.filter_state.stack.steps.remove(index);
+                }
+            }
+            rerender_remove(index.saturating_sub(1));
+        }
+    });
+
+    for (button, up) in [(&mut up_btn, true), (&mut down_btn, false)] {
+        let state_move = state.clone();
+        let rerender_move = rerender.clone();
+        let browser_move = browser.clone();
+        button.set_callback(move |_| {
+            if let Some(index) = selected_index(&browser_move) {
+                let new_index = match state_move.try_borrow_mut() {
+                    Ok(mut state_ref) => state_ref.filter_state.stack.move_step(index, up),
+                    Err(_) => return,
+                };
+                if new_index != index {
+                    rerender_move(new_index);
+                }
+            }
+        });
+    }
+
+    let state_toggle = state.clone();
+    let rerender_toggle = rerender.clone();
+    let browser_toggle = browser.clone();
+    toggle_btn.set_callback(move |_| {
+        if let Some(index) = selected_index(&browser_toggle) {
+            if let Ok(mut state_ref) = state_toggle.try_borrow_mut() {
+                if let Some(step) = state_ref.filter_state.stack.steps.get_mut(index) {
+                    step.enabled = !step.enabled;
+                }
+            }
+            rerender_toggle(index);
+        }
+    });
+
+    let state_clear = state.clone();
+    let rerender_clear = rerender.clone();
+    clear_btn.set_callback(move |_| {
+        if let Ok(mut state_ref) = state_clear.try_borrow_mut() {
+            state_ref.filter_state.stack.steps.clear();
+        }
+        rerender_clear(0);
+    });
+
+    let state_name = state.clone();
+    name_input.set_callback(move |input| {
+        if let Ok(mut state_ref) = state_name.try_borrow_mut() {
+            state_ref.filter_state.stack.name = input.value();
+        }
+    });
+    name_input.set_trigger(fltk::enums::CallbackTrigger::Changed);
+
+    let state_load = state.clone();
+    let rerender_load = rerender.clone();
+    let mut name_load = name_input.clone();
+    load_btn.set_callback(move |_| {
+        let mut chooser = FileDialog::new(FileDialogType::BrowseFile);
+        chooser.set_filter(&format!("Filter Recipes\t*.{}", RECIPE_EXTENSION));
+        chooser.show();
+        let path = chooser.filename();
+        if path.as_os_str().is_empty() {
+            return;
+        }
+        match FilterRecipe::load(&path) {
+            Ok(recipe) => {
+                name_load.set_value(&recipe.name);
+                if let Ok(mut state_ref) = state_load.try_borrow_mut() {
+                    state_ref.filter_state.stack = recipe;
+                }
+                rerender_load(0);
+            }
+            Err(e) => alert(200, 200, &format!("Failed to load recipe: {}", e)),
+        }
+    });
+
+    let state_save = state.clone();
+    save_btn.set_callback(move |_| {
+        let recipe = match state_save.try_borrow() {
+            Ok(state_ref) => state_ref.filter_state.stack.clone(),
+            Err(_) => return,
+        };
+        let mut chooser = FileDialog::new(FileDialogType::BrowseSaveFile);
+        chooser.set_filter(&format!("Filter Recipes\t*.{}", RECIPE_EXTENSION));
+        if !recipe.name.is_empty() {
+            chooser.set_preset_file(&format!("{}.{}", recipe.name, RECIPE_EXTENSION));
+        }
+        chooser.show();
+        let filename = chooser.filename();
+        if filename.as_os_str().is_empty() {
+            return;
+        }
+        let mut path = PathBuf::from(filename);
+        if path.extension().and_then(|e| e.to_str()) != Some(RECIPE_EXTENSION) {
+            path.set_extension(RECIPE_EXTENSION);
+        }
+        if let Err(e) = recipe.save(&path) {
+            alert(200, 200, &format!("Failed to save recipe: {}", e));
+        }
+    });
+
+    let mut dialog_close = dialog.clone();
+    close_btn.set_callback(move |_| {
+        dialog_close.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+}
+
+fn fill_browser(browser: &mut HoldBrowser, recipe: &FilterRecipe, selected: usize) {
+    browser.clear();
+    for (i, step) in recipe.steps.iter().enumerate() {
+        let mark = if step.enabled { "[x]" } else { "[ ]" };
+        browser.add(&format!("{}. {} {}", i + 1, mark, step.spec.describe()));
+    }
+    if selected < recipe.steps.len() {
+        browser.select(selected as i32 + 1);
+    }
+}
+
+/// opens the levels or curves editor on step `index`, over the histogram of what the step
+/// gets as input. the preview re-renders the stack onto the frame only; when the editor
+/// is cancelled the step is put back as it was
+fn edit_tonal_step(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, index: usize) -> Option<FilterSpec> {
+    let (initial, input) = match state.try_borrow() {
+        Ok(state_ref) => (
+            state_ref.filter_state.stack.steps.get(index)?.spec.clone(),
+            state_ref.filter_state.render_stack_input(index).ok().flatten()?,
+        ),
+        Err(_) => return None,
+    };
+    let histogram = Histogram::of_image(&input);
+
+    let preview: Preview = {
+        let frame = frame.clone();
+        let state = state.clone();
+        let initial = initial.clone();
+        Rc::new(move |spec: Option<&FilterSpec>| {
+            let rendered = match state.try_borrow_mut() {
+                Ok(mut state_ref) => {
+                    state_ref.filter_state.stack.steps[index].spec = spec.unwrap_or(&initial).clone();
+                    state_ref.filter_state.render_stack()
+                }
+                Err(_) => return,
+            };
+            if let (Ok(Some(image)), Ok(mut frame_ref)) = (rendered, frame.try_borrow_mut()) {
+                frame_ref.set_image(Some(image));
+                frame_ref.redraw();
+            }
+        })
+    };
+
+    let edited = match initial {
+        FilterSpec::Curves { .. } => edit_curves(&initial, &histogram, preview.clone()),
+        _ => edit_levels(&initial, &histogram, preview.clone()),
+    };
+    if edited.is_none() {
+        preview(None);
+    }
+    edited
+}
+
+/// asks for a filter type and its parameters, starting from `initial`
+fn edit_filter_spec(initial: &FilterSpec) -> Option<FilterSpec> {
+    let mut dialog = Window::default()
+        .with_size(320, 315)
+        .with_label("Filter Step");
+    dialog.make_modal(true);
+
+    let mut kind = Choice::new(90, 10, 220, 25, "Filter:");
+    for spec in FilterSpec::ALL.iter() {
+        kind.add_choice(spec.name());
+    }
+    let mut method = Choice::new(90, 45, 220, 25, "Method:");
+
+    let labels = [
+        Frame::new(10, 80, 300, 20, ""),
+        Frame::new(10, 135, 300, 20, ""),
+        Frame::new(10, 190, 300, 20, ""),
+    ];
+    let inputs = [
+        FloatInput::new(10, 102, 300, 25, ""),
+        FloatInput::new(10, 157, 300, 25, ""),
+        FloatInput::new(10, 212, 300, 25, ""),
+    ];
+
+    let mut ok = Button::new(120, 275, 90, 28, "OK");
+    let mut cancel = Button::new(220, 275, 90, 28, "Cancel");
+    dialog.end();
+
+    // shows the parameters of `spec` in the widgets
+    let mut show_spec = {
+        let mut method = method.clone();
+        let mut labels = labels.clone();
+        let mut inputs = inputs.clone();
+        move |spec: &FilterSpec| {
+            method.clear();
+            match spec {
+                FilterSpec::EdgeDetection { method: m, .. } => {
+                    method.add_choice("Sobel|Canny");
+                    method.activate();
+                    method.set_value(if *m == EdgeDetectionMethod::Canny { 1 } else { 0 });
+                }
+                FilterSpec::UnsharpMask { luminance_only, .. } => {
+                    method.add_choice("All channels|Luminance only");
+                    method.activate();
+                    method.set_value(if *luminance_only { 1 } else { 0 });
+                }
+                _ => method.deactivate(),
+            }
+            let params = spec.params();
+            for i in 0..3 {
+                match params.get(i) {
+                    Some((label, value)) => {
+                        labels[i].set_label(label);
+                        inputs[i].set_value(&format!("{}", value));
+                        inputs[i].activate();
+                    }
+                    None => {
+                        labels[i].set_label("");
+                        inputs[i].set_value("");
+                        inputs[i].deactivate();
+                    }
+                }
+            }
+        }
+    };
+
+    let kind_index = FilterSpec::ALL.iter().position(|s| s.name() == initial.name()).unwrap_or(0);
+    kind.set_value(kind_index as i32);
+    show_spec(initial);
+
+    kind.set_callback(move |choice| {
+        let index = (choice.value().max(0) as usize).min(FilterSpec::ALL.len() - 1);
+        show_spec(&FilterSpec::ALL[index]);
+    });
+
+    let result: Rc<RefCell<Option<FilterSpec>>> = Rc::new(RefCell::new(None));
+
+    let result_ok = result.clone();
+    let mut dialog_ok = dialog.clone();
+    ok.set_callback(move |_| {
+        let index = (kind.value().max(0) as usize).min(FilterSpec::ALL.len() - 1);
+        let mut values = Vec::new();
+        for input in inputs.iter().filter(|i| i.active()) {
+            match input.value().parse::<f32>() {
+                Ok(value) => values.push(value),
+                Err(_) => {
+                    alert(200, 200, "Please enter valid numbers");
+                    return;
+                }
+            }
+        }
+        let mut spec = FilterSpec::ALL[index].with_params(&values);
+        match &mut spec {
+            FilterSpec::EdgeDetection { method: m, .. } => {
+                *m = if method.value() == 1 { EdgeDetectionMethod::Canny } else { EdgeDetectionMethod::Sobel };
+            }
+            FilterSpec::UnsharpMask { luminance_only, .. } => *luminance_only = method.value() == 1,
+            _ => {}
+        }
+        *result_ok.borrow_mut() = Some(spec);
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    result.take()
+}
@@ -0,0 +1,197 @@
+// src/menu/edit/filters/tonal/auto_contrast.rs
+use image::{ImageBuffer, Rgba};
+use crate::state::FilterError;
+use crate::menu::edit::filters::ImageFilter;
+use crate::menu::edit::crop::CropSelection;
+use super::{equalization_lut, luma, luma_histogram, set_luma};
+use crate::menu::edit::filters::Region;
+
+/// global histogram equalization of the luma, measured over the selection if there is one
+#[derive(Default)]
+pub struct EqualizeFilter {
+    region: Region,
+}
+
+impl EqualizeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_selection(mut self, selection: CropSelection) -> Self {
+        self.region.rect = Some(selection.get_image_dimensions());
+        self
+    }
+
+    pub fn with_feather(mut self, radius: u32) -> Self {
+        self.region.feather_radius = radius;
+        self
+    }
+}
+
+impl ImageFilter for EqualizeFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        let (width, height) = image.dimensions();
+        let (bounds, affected) = match (self.region.bounds(width, height), self.region.affected(width, height)) {
+            (Some(bounds), Some(affected)) => (bounds, affected),
+            _ => return Ok(()),
+        };
+        let lut = equalization_lut(&luma_histogram(image, bounds));
+
+        let (x, y, w, h) = affected;
+        for py in y..y + h {
+            for px in x..x + w {
+                let factor = self.region.factor(px, py);
+                if factor == 0.0 {
+                    continue;
+                }
+                let pixel = image.get_pixel_mut(px, py);
+                let target = lut[luma(pixel).round().min(255.0) as usize];
+                set_luma(pixel, target, factor);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// linear stretch so that `saturated` percent of the values end up clipped, half at
+/// each end, like ImageJ's Enhance Contrast. one mapping for all three channels keeps colors
+pub struct AutoStretchFilter {
+    saturated: f32,
+    region: Region,
+}
+
+impl AutoStretchFilter {
+    pub fn new(saturated: f32) -> Self {
+        Self {
+            saturated: saturated.clamp(0.0, 50.0),
+            region: Region::default(),
+        }
+    }
+
+    pub fn with_selection(mut self, selection: CropSelection) -> Self {
+        self.region.rect = Some(selection.get_image_dimensions());
+        self
+    }
+
+    pub fn with_feather(mut self, radius: u32) -> Self {
+        self.region.feather_radius = radius;
+        self
+    }
+
+    /// the (low, high) input values that become 0 and 255
+    fn limits(&self, histogram: &[u64; 256]) -> Option<(u8, u8)> {
+        let total: u64 = histogram.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let clipped = (total as f64 * self.saturated as f64 / 200.0) as u64;
+
+        let mut count = 0;
+        let mut low = 0;
+        for (value, &c) in histogram.iter().enumerate() {
+            count += c;
+            if count > clipped {
+                low = value;
+                break;
+            }
+        }
+        count = 0;
+        let mut high = 255;
+        for (value, &c) in histogram.iter().enumerate().rev() {
+            count += c;
+            if count > clipped {
+                high = value;
+                break;
+            }
+        }
+        (high > low).then_some((low as u8, high as u8))
+    }
+}
+
+impl ImageFilter for AutoStretchFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        let (width, height) = image.dimensions();
+        let (bounds, affected) = match (self.region.bounds(width, height), self.region.affected(width, height)) {
+            (Some(bounds), Some(affected)) => (bounds, affected),
+            _ => return Ok(()),
+        };
+
+        // red, green and blue values pooled into one histogram
+        let mut histogram = [0u64; 256];
+        let (x, y, w, h) = bounds;
+        for py in y..y + h {
+            for px in x..x + w {
+                for &value in image.get_pixel(px, py).0.iter().take(3) {
+                    histogram[value as usize] += 1;
+                }
+            }
+        }
+        let (low, high) = match self.limits(&histogram) {
+            Some(limits) => limits,
+            None => return Ok(()),
+        };
+        let scale = 255.0 / (high as f32 - low as f32);
+
+        let (x, y, w, h) = affected;
+        for py in y..y + h {
+            for px in x..x + w {
+                let factor = self.region.factor(px, py);
+                if factor == 0.0 {
+                    continue;
+                }
+                let pixel = image.get_pixel_mut(px, py);
+                for value in pixel.0.iter_mut().take(3) {
+                    let stretched = ((*value as f32 - low as f32) * scale).clamp(0.0, 255.0);
+                    *value = (stretched * factor + *value as f32 * (1.0 - factor)).round() as u8;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(values: &[u8], width: u32) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        let data = values.iter().flat_map(|&v| [v, v, v, 255]).collect();
+        ImageBuffer::from_raw(width, values.len() as u32 / width, data).unwrap()
+    }
+
+    #[test]
+    fn equalization_spreads_a_dim_image_over_the_full_range() {
+        let mut image = gray(&[10, 20, 30, 40], 2);
+        EqualizeFilter::new().apply(&mut image).unwrap();
+        let values: Vec<u8> = image.pixels().map(|p| p[0]).collect();
+        assert_eq!(values, vec![0, 85, 170, 255]);
+    }
+
+    #[test]
+    fn auto_stretch_clips_the_requested_share() {
+        let mut values: Vec<u8> = (0..100).map(|i| 50 + i as u8).collect();
+        let mut image = gray(&values, 10);
+        AutoStretchFilter::new(0.0).apply(&mut image).unwrap();
+        assert_eq!(image.get_pixel(0, 0)[0], 0);
+        assert_eq!(image.get_pixel(9, 9)[0], 255);
+
+        // 10% saturated: the 5 lowest values clip to 0 together with the new black point,
+        // the same at the top
+        values.reverse();
+        let mut image = gray(&values, 10);
+        AutoStretchFilter::new(10.0).apply(&mut image).unwrap();
+        let stretched: Vec<u8> = image.pixels().map(|p| p[0]).collect();
+        assert_eq!(stretched.iter().filter(|&&v| v == 255).count(), 6);
+        assert_eq!(stretched.iter().filter(|&&v| v == 0).count(), 6);
+    }
+
+    #[test]
+    fn only_the_selection_changes_without_feather() {
+        let mut image = gray(&[10, 20, 30, 40], 4);
+        let mut filter = EqualizeFilter::new();
+        filter.region.rect = Some((2, 0, 2, 1));
+        filter.apply(&mut image).unwrap();
+        let values: Vec<u8> = image.pixels().map(|p| p[0]).collect();
+        assert_eq!(values, vec![10, 20, 0, 255]);
+    }
+}
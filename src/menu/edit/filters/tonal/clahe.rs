@@ -0,0 +1,157 @@
+// src/menu/edit/filters/tonal/clahe.rs
+use image::{ImageBuffer, Rgba};
+use crate::state::FilterError;
+use crate::menu::edit::filters::ImageFilter;
+use crate::menu::edit::crop::CropSelection;
+use super::{equalization_lut, luma, luma_histogram, set_luma};
+use crate::menu::edit::filters::Region;
+
+/// contrast-limited adaptive histogram equalization: every `tile_size` square gets its own
+/// equalization, with no bin allowed more than `clip_limit` times the average count so
+/// noise in flat areas isn't blown up. the mappings are blended between tile centres
+pub struct ClaheFilter {
+    tile_size: u32,
+    clip_limit: f32,
+    region: Region,
+}
+
+impl ClaheFilter {
+    pub fn new(tile_size: u32, clip_limit: f32) -> Self {
+        Self {
+            tile_size: tile_size.clamp(8, 1024),
+            clip_limit: clip_limit.clamp(1.0, 100.0),
+            region: Region::default(),
+        }
+    }
+
+    pub fn with_selection(mut self, selection: CropSelection) -> Self {
+        self.region.rect = Some(selection.get_image_dimensions());
+        self
+    }
+
+    pub fn with_feather(mut self, radius: u32) -> Self {
+        self.region.feather_radius = radius;
+        self
+    }
+
+    /// cuts every bin down to the limit and spreads what was cut evenly over all bins
+    fn clip(&self, histogram: &mut [u32; 256]) {
+        let total: u32 = histogram.iter().sum();
+        let limit = ((self.clip_limit * total as f32 / 256.0).ceil() as u32).max(1);
+        let mut excess = 0;
+        for count in histogram.iter_mut() {
+            if *count > limit {
+                excess += *count - limit;
+                *count = limit;
+            }
+        }
+        let (share, remainder) = (excess / 256, (excess % 256) as usize);
+        for (value, count) in histogram.iter_mut().enumerate() {
+            *count += share + u32::from(value < remainder);
+        }
+    }
+}
+
+/// start, length and number of tiles along one side of `length` pixels
+fn tiles(start: u32, length: u32, tile_size: u32) -> (f32, f32, usize) {
+    let count = length.div_ceil(tile_size).max(1);
+    (start as f32, length as f32 / count as f32, count as usize)
+}
+
+/// the two tiles around `position` and the weight of the second, clamped at the edges
+fn neighbours(position: u32, (start, size, count): (f32, f32, usize)) -> (usize, usize, f32) {
+    let t = (position as f32 + 0.5 - start) / size - 0.5;
+    if t <= 0.0 {
+        return (0, 0, 0.0);
+    }
+    let first = t.floor() as usize;
+    if first >= count - 1 {
+        return (count - 1, count - 1, 0.0);
+    }
+    (first, first + 1, t - first as f32)
+}
+
+impl ImageFilter for ClaheFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        let (width, height) = image.dimensions();
+        let (bounds, affected) = match (self.region.bounds(width, height), self.region.affected(width, height)) {
+            (Some(bounds), Some(affected)) => (bounds, affected),
+            _ => return Ok(()),
+        };
+
+        let (bx, by, bw, bh) = bounds;
+        let columns = tiles(bx, bw, self.tile_size);
+        let rows = tiles(by, bh, self.tile_size);
+
+        let mut luts = Vec::with_capacity(columns.2 * rows.2);
+        for row in 0..rows.2 {
+            let top = by + (row as f32 * rows.1) as u32;
+            let bottom = (by + ((row + 1) as f32 * rows.1) as u32).min(by + bh);
+            for column in 0..columns.2 {
+                let left = bx + (column as f32 * columns.1) as u32;
+                let right = (bx + ((column + 1) as f32 * columns.1) as u32).min(bx + bw);
+                let mut histogram = luma_histogram(image, (left, top, right - left, bottom - top));
+                self.clip(&mut histogram);
+                luts.push(equalization_lut(&histogram));
+            }
+        }
+        let lut = |row: usize, column: usize| &luts[row * columns.2 + column];
+
+        let (x, y, w, h) = affected;
+        for py in y..y + h {
+            let (r0, r1, wy) = neighbours(py, rows);
+            for px in x..x + w {
+                let factor = self.region.factor(px, py);
+                if factor == 0.0 {
+                    continue;
+                }
+                let (c0, c1, wx) = neighbours(px, columns);
+                let pixel = image.get_pixel_mut(px, py);
+                let value = luma(pixel).round().min(255.0) as usize;
+                let top = lut(r0, c0)[value] * (1.0 - wx) + lut(r0, c1)[value] * wx;
+                let bottom = lut(r1, c0)[value] * (1.0 - wx) + lut(r1, c1)[value] * wx;
+                set_luma(pixel, top * (1.0 - wy) + bottom * wy, factor);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clipping_keeps_the_pixel_count() {
+        let filter = ClaheFilter::new(8, 2.0);
+        let mut histogram = [0u32; 256];
+        histogram[100] = 1000;
+        histogram[101] = 24;
+        filter.clip(&mut histogram);
+        assert_eq!(histogram.iter().sum::<u32>(), 1024);
+        assert!(histogram[100] <= 8 + 4);
+    }
+
+    #[test]
+    fn tiles_blend_towards_their_neighbours() {
+        // two 8 pixel tiles centred at 4 and 12, pixel 7 covers 7.0 - 8.0
+        let columns = tiles(0, 16, 8);
+        assert_eq!(columns.2, 2);
+        assert_eq!(neighbours(0, columns), (0, 0, 0.0));
+        assert_eq!(neighbours(7, columns), (0, 1, 0.4375));
+        assert_eq!(neighbours(15, columns), (1, 1, 0.0));
+    }
+
+    #[test]
+    fn a_dim_gradient_gains_contrast() {
+        let data: Vec<u8> = (0..32 * 32).flat_map(|i| {
+            let v = 40 + (i % 32) as u8;
+            [v, v, v, 255]
+        }).collect();
+        let mut image: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::from_raw(32, 32, data).unwrap();
+        ClaheFilter::new(16, 4.0).apply(&mut image).unwrap();
+        let (min, max) = image.pixels().fold((255, 0), |(lo, hi), p| (p[0].min(lo), p[0].max(hi)));
+        // at least doubles the 31 levels it started with
+        assert!(max - min > 60, "range {} - {}", min, max);
+    }
+}
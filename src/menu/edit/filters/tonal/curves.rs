@@ -0,0 +1,168 @@
+// src/menu/edit/filters/tonal/curves.rs
+use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use crate::state::FilterError;
+use crate::menu::edit::filters::ImageFilter;
+use super::{apply_luts, combine};
+
+/// a tone curve through (input, output) control points. no points is the identity,
+/// inputs left of the first or right of the last point keep that point's output
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Curve {
+    pub points: Vec<(u8, u8)>,
+}
+
+impl Curve {
+    pub const fn identity() -> Self {
+        Self { points: Vec::new() }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.lut().iter().enumerate().all(|(value, &out)| value == out as usize)
+    }
+
+    /// the points the editor shows: sorted by input, one per input, and the two
+    /// end points of the identity when there are none
+    pub fn control_points(&self) -> Vec<(u8, u8)> {
+        if self.points.is_empty() {
+            return vec![(0, 0), (255, 255)];
+        }
+        let mut points = self.points.clone();
+        // stable, so for a repeated input the last point added wins
+        points.sort_by_key(|&(x, _)| x);
+        let mut unique: Vec<(u8, u8)> = Vec::with_capacity(points.len());
+        for point in points {
+            match unique.last_mut() {
+                Some(last) if last.0 == point.0 => *last = point,
+                _ => unique.push(point),
+            }
+        }
+        unique
+    }
+
+    /// monotone cubic (Fritsch-Carlson) through the control points, so the curve
+    /// never overshoots between two points the way a natural spline can
+    pub fn lut(&self) -> [u8; 256] {
+        let mut lut = [0u8; 256];
+        if self.points.is_empty() {
+            for (value, out) in lut.iter_mut().enumerate() {
+                *out = value as u8;
+            }
+            return lut;
+        }
+
+        let points: Vec<(f64, f64)> = self
+            .control_points()
+            .iter()
+            .map(|&(x, y)| (x as f64, y as f64))
+            .collect();
+        let n = points.len();
+        if n == 1 {
+            return [points[0].1 as u8; 256];
+        }
+
+        let secants: Vec<f64> = points
+            .windows(2)
+            .map(|pair| (pair[1].1 - pair[0].1) / (pair[1].0 - pair[0].0))
+            .collect();
+        let mut tangents = vec![0.0; n];
+        tangents[0] = secants[0];
+        tangents[n - 1] = secants[n - 2];
+        for i in 1..n - 1 {
+            if secants[i - 1] * secants[i] > 0.0 {
+                tangents[i] = (secants[i - 1] + secants[i]) / 2.0;
+            }
+        }
+        for i in 0..n - 1 {
+            if secants[i] == 0.0 {
+                tangents[i] = 0.0;
+                tangents[i + 1] = 0.0;
+                continue;
+            }
+            let a = tangents[i] / secants[i];
+            let b = tangents[i + 1] / secants[i];
+            let length = a * a + b * b;
+            if length > 9.0 {
+                let t = 3.0 / length.sqrt();
+                tangents[i] = t * a * secants[i];
+                tangents[i + 1] = t * b * secants[i];
+            }
+        }
+
+        let mut segment = 0;
+        for (value, out) in lut.iter_mut().enumerate() {
+            let x = value as f64;
+            let y = if x <= points[0].0 {
+                points[0].1
+            } else if x >= points[n - 1].0 {
+                points[n - 1].1
+            } else {
+                while x > points[segment + 1].0 {
+                    segment += 1;
+                }
+                let ((x0, y0), (x1, y1)) = (points[segment], points[segment + 1]);
+                let h = x1 - x0;
+                let t = (x - x0) / h;
+                let (t2, t3) = (t * t, t * t * t);
+                (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                    + (t3 - 2.0 * t2 + t) * h * tangents[segment]
+                    + (-2.0 * t3 + 3.0 * t2) * y1
+                    + (t3 - t2) * h * tangents[segment + 1]
+            };
+            *out = y.round().clamp(0.0, 255.0) as u8;
+        }
+        lut
+    }
+}
+
+pub struct CurvesFilter {
+    luts: [[u8; 256]; 3],
+}
+
+impl CurvesFilter {
+    pub fn new(master: &Curve, red: &Curve, green: &Curve, blue: &Curve) -> Self {
+        let master = master.lut();
+        Self {
+            luts: [
+                combine(&red.lut(), &master),
+                combine(&green.lut(), &master),
+                combine(&blue.lut(), &master),
+            ],
+        }
+    }
+}
+
+impl ImageFilter for CurvesFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        apply_luts(image, &self.luts);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_pass_through_their_points_without_overshooting() {
+        let curve = Curve { points: vec![(0, 0), (64, 128), (255, 255)] };
+        let lut = curve.lut();
+        assert_eq!(lut[0], 0);
+        assert_eq!(lut[64], 128);
+        assert_eq!(lut[255], 255);
+        assert!(lut.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        assert!(Curve::identity().is_identity());
+        assert!(Curve { points: vec![(0, 0), (255, 255)] }.is_identity());
+        assert!(!curve.is_identity());
+    }
+
+    #[test]
+    fn curves_hold_their_end_values_and_keep_the_last_duplicate() {
+        let curve = Curve { points: vec![(200, 40), (50, 10), (200, 60)] };
+        assert_eq!(curve.control_points(), vec![(50, 10), (200, 60)]);
+        let lut = curve.lut();
+        assert_eq!(lut[0], 10);
+        assert_eq!(lut[255], 60);
+    }
+}
@@ -0,0 +1,94 @@
+// src/menu/edit/filters/tonal/levels.rs
+use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use crate::state::FilterError;
+use crate::menu::edit::filters::ImageFilter;
+use super::{apply_luts, combine};
+
+/// input black point, white point and midtone gamma of one channel.
+/// values at or below `black` become 0, at or above `white` 255, gamma > 1 lightens the midtones
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChannelLevels {
+    pub black: u8,
+    pub white: u8,
+    pub gamma: f32,
+}
+
+impl ChannelLevels {
+    pub const IDENTITY: ChannelLevels = ChannelLevels { black: 0, white: 255, gamma: 1.0 };
+
+    pub fn new(black: u8, white: u8, gamma: f32) -> Self {
+        Self {
+            black,
+            white,
+            gamma: gamma.clamp(0.1, 10.0),
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.black == 0 && self.white == 255 && self.gamma == 1.0
+    }
+
+    pub fn lut(&self) -> [u8; 256] {
+        let black = self.black as f32;
+        // a white point at or below the black point turns the channel into a hard threshold
+        let range = (self.white as f32 - black).max(1.0);
+        let exponent = 1.0 / self.gamma.clamp(0.1, 10.0);
+
+        let mut lut = [0u8; 256];
+        for (value, out) in lut.iter_mut().enumerate() {
+            let t = ((value as f32 - black) / range).clamp(0.0, 1.0);
+            *out = (t.powf(exponent) * 255.0).round() as u8;
+        }
+        lut
+    }
+}
+
+impl Default for ChannelLevels {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+pub struct LevelsFilter {
+    luts: [[u8; 256]; 3],
+}
+
+impl LevelsFilter {
+    pub fn new(master: ChannelLevels, red: ChannelLevels, green: ChannelLevels, blue: ChannelLevels) -> Self {
+        let master = master.lut();
+        Self {
+            luts: [
+                combine(&red.lut(), &master),
+                combine(&green.lut(), &master),
+                combine(&blue.lut(), &master),
+            ],
+        }
+    }
+}
+
+impl ImageFilter for LevelsFilter {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        apply_luts(image, &self.luts);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_stretch_between_black_and_white_points() {
+        let lut = ChannelLevels::new(50, 150, 1.0).lut();
+        assert_eq!(lut[0], 0);
+        assert_eq!(lut[50], 0);
+        assert_eq!(lut[100], 128);
+        assert_eq!(lut[150], 255);
+        assert_eq!(lut[255], 255);
+
+        // gamma 2 lifts the midpoint to sqrt(0.5)
+        assert_eq!(ChannelLevels::new(0, 255, 2.0).lut()[128], 181);
+        assert!(ChannelLevels::IDENTITY.lut().iter().enumerate().all(|(v, &o)| v == o as usize));
+    }
+}
@@ -0,0 +1,77 @@
+// menu/edit/filters/tonal/mod.rs
+use image::{ImageBuffer, Rgba};
+
+mod levels;
+mod curves;
+mod auto_contrast;
+mod clahe;
+
+pub use levels::{ChannelLevels, LevelsFilter};
+pub use curves::{Curve, CurvesFilter};
+pub use auto_contrast::{AutoStretchFilter, EqualizeFilter};
+pub use clahe::ClaheFilter;
+
+/// a per channel adjustment is run first, the master (RGB) one on its result
+fn combine(channel: &[u8; 256], master: &[u8; 256]) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    for (out, &value) in lut.iter_mut().zip(channel.iter()) {
+        *out = master[value as usize];
+    }
+    lut
+}
+
+fn apply_luts(image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, luts: &[[u8; 256]; 3]) {
+    for pixel in image.pixels_mut() {
+        for (value, lut) in pixel.0.iter_mut().zip(luts.iter()) {
+            *value = lut[*value as usize];
+        }
+        // alpha is left alone
+    }
+}
+
+/// floating point Rec. 601 luma
+fn luma(pixel: &Rgba<u8>) -> f32 {
+    0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32
+}
+
+/// gives `pixel` the luma `target` by scaling its channels, which keeps the hue of
+/// colored (e.g. fluorescence) images, then blends by `factor`
+fn set_luma(pixel: &mut Rgba<u8>, target: f32, factor: f32) {
+    let current = luma(pixel);
+    for value in pixel.0.iter_mut().take(3) {
+        let adjusted = if current < 0.5 { target } else { *value as f32 * target / current };
+        let adjusted = adjusted.clamp(0.0, 255.0);
+        *value = (adjusted * factor + *value as f32 * (1.0 - factor)).round() as u8;
+    }
+}
+
+/// the 256-bin luma histogram of the (x, y, w, h) block
+fn luma_histogram(image: &ImageBuffer<Rgba<u8>, Vec<u8>>, (x, y, w, h): (u32, u32, u32, u32)) -> [u32; 256] {
+    let mut histogram = [0u32; 256];
+    for py in y..y + h {
+        for px in x..x + w {
+            histogram[luma(image.get_pixel(px, py)).round().min(255.0) as usize] += 1;
+        }
+    }
+    histogram
+}
+
+/// maps each value to its share of the cumulative histogram, the classic equalization
+fn equalization_lut(histogram: &[u32; 256]) -> [f32; 256] {
+    let total: u64 = histogram.iter().map(|&c| c as u64).sum();
+    let first = histogram.iter().find(|&&c| c > 0).copied().unwrap_or(0) as u64;
+    let mut lut = [0f32; 256];
+    if total <= first {
+        // a single value has nothing to spread
+        for (value, out) in lut.iter_mut().enumerate() {
+            *out = value as f32;
+        }
+        return lut;
+    }
+    let mut cumulative = 0u64;
+    for (out, &count) in lut.iter_mut().zip(histogram.iter()) {
+        cumulative += count as u64;
+        *out = cumulative.saturating_sub(first) as f32 * 255.0 / (total - first) as f32;
+    }
+    lut
+}
@@ -0,0 +1,94 @@
+// src/menu/edit/history/dialog.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    window::Window,
+    button::Button,
+    input::IntInput,
+    frame::Frame,
+    group::Pack,
+    prelude::*,
+};
+
+// Internal state imports
+use crate::state::ImageState;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+pub fn show_history_settings_dialog(state: &Rc<RefCell<ImageState>>) {
+    let (budget_mb, usage_mb, undo_label, redo_label) = match state.try_borrow() {
+        Ok(state_ref) => (
+            state_ref.history.memory_budget() / BYTES_PER_MB,
+            state_ref.history.memory_usage() as f64 / BYTES_PER_MB as f64,
+            state_ref.history.undo_label().unwrap_or("-").to_string(),
+            state_ref.history.redo_label().unwrap_or("-").to_string(),
+        ),
+        Err(_) => return,
+    };
+
+    let mut dialog = Window::default()
+        .with_size(320, 220)
+        .with_label("Undo History Settings");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 300, 200, "");
+    pack.set_spacing(10);
+
+    Frame::default()
+        .with_size(300, 25)
+        .with_label(&format!("History memory in use: {:.1} MB", usage_mb));
+    Frame::default()
+        .with_size(300, 25)
+        .with_label(&format!("Next undo: {}   Next redo: {}", undo_label, redo_label));
+
+    Frame::default()
+        .with_size(300, 25)
+        .with_label("Memory budget (MB):");
+    let mut budget_input = IntInput::default().with_size(300, 25);
+    budget_input.set_value(&budget_mb.to_string());
+
+    let mut button_pack = Pack::default().with_size(300, 30);
+    button_pack.set_type(fltk::group::PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut clear_btn = Button::default().with_size(90, 30).with_label("Clear");
+    let mut ok_btn = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel_btn = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+    dialog.show();
+
+    let state_clear = state.clone();
+    clear_btn.set_callback(move |_| {
+        if let Ok(mut state_ref) = state_clear.try_borrow_mut() {
+            state_ref.history.clear();
+            println!("Undo history cleared");
+        }
+    });
+
+    let state_ok = state.clone();
+    let mut dialog_ok = dialog.clone();
+    ok_btn.set_callback(move |_| {
+        if let Ok(value) = budget_input.value().parse::<usize>() {
+            if let Ok(mut state_ref) = state_ok.try_borrow_mut() {
+                // keep at least 1 MB so the last step can still be stored
+                state_ref.history.set_memory_budget(value.max(1) * BYTES_PER_MB);
+                println!("History memory budget set to {} MB", value.max(1));
+            }
+        }
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel_btn.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    while dialog.shown() {
+        fltk::app::wait();
+    }
+}
@@ -0,0 +1,45 @@
+// src/menu/edit/history/handlers.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{frame::Frame, prelude::*};
+
+// Internal state imports
+use crate::state::ImageState;
+use crate::state::history_state::HistoryKind;
+
+pub fn handle_undo(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let kind = match state.try_borrow_mut() {
+        Ok(mut state_ref) => state_ref.undo(),
+        Err(_) => return,
+    };
+
+    match kind {
+        Some(kind) => refresh_after_history(frame, state, kind),
+        None => println!("Nothing to undo"),
+    }
+}
+
+pub fn handle_redo(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let kind = match state.try_borrow_mut() {
+        Ok(mut state_ref) => state_ref.redo(),
+        Err(_) => return,
+    };
+
+    match kind {
+        Some(kind) => refresh_after_history(frame, state, kind),
+        None => println!("Nothing to redo"),
+    }
+}
+
+fn refresh_after_history(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, kind: HistoryKind) {
+    if kind == HistoryKind::Image || kind == HistoryKind::Geometry {
+        if let Ok(state_ref) = state.try_borrow() {
+            frame.borrow_mut().set_image(state_ref.image.clone());
+        }
+    }
+    // annotations are drawn from scientific_state, so a redraw is enough
+    frame.borrow_mut().redraw();
+}
@@ -0,0 +1,6 @@
+// src/menu/edit/history/mod.rs
+pub mod handlers;
+mod dialog;
+
+pub use handlers::{handle_undo, handle_redo};
+pub use dialog::show_history_settings_dialog;
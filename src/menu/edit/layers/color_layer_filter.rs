@@ -2,7 +2,7 @@
 use image::{ImageBuffer, Rgba};
 use crate::menu::edit::filters::ImageFilter;
 use crate::state::FilterError;  // Import FilterError from state module
-use crate::menu::edit::crop::crop_tool::CropSelection;
+use crate::menu::edit::crop::CropSelection;
 
 pub struct ColorLayerFilter {
     color: (u8, u8, u8),    // RGB color for the layer
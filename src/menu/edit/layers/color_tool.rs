@@ -8,8 +8,9 @@ use fltk::{
 };
 use std::{cell::RefCell, rc::Rc};
 use crate::state::ImageState;
-use crate::menu::edit::crop::crop_tool::CropSelection;
-use crate::utils::{scale_image_dimensions, MENU_HEIGHT};
+use crate::menu::edit::crop::CropSelection;
+use crate::menu::view::zoom::is_pan_button;
+use crate::utils::{draw_current_image, draw_image_in_frame, Viewport};
 use super::color_filter::ColorFilter;
 
 pub fn start_interactive_color(
@@ -29,19 +30,11 @@ pub fn start_interactive_color(
     
     if let Some(img) = &original_image {
         let frame_ref = frame.borrow();
-        let (displayed_w, displayed_h) = scale_image_dimensions(
+        state_ref.crop_selection = Some(CropSelection::new(
             img.data_w(),
             img.data_h(),
             frame_ref.w(),
-            frame_ref.h() - MENU_HEIGHT,
-            1.0
-        );
-        
-        state_ref.crop_selection = Some(CropSelection::new(
-            displayed_w,
-            displayed_h,
-            displayed_w,
-            displayed_h
+            frame_ref.h()
         ));
     }
     drop(state_ref);
@@ -53,41 +46,33 @@ pub fn start_interactive_color(
 
     let draw_callback = {
         let state_clone = state_clone.clone();
-        let original_image = original_image.clone();
-        let frame_clone = frame_clone.clone();
         move |f: &mut Frame| {
-            if let Some(img) = &original_image {
-                if let Ok(state_ref) = state_clone.try_borrow() {
-                    if let Some(composite) = state_ref.layer_state.get_composite_image() {
-                        if state_ref.layer_state.is_preview_active() {
-                            f.set_image(Some(composite.clone()));
-                        } else {
-                            f.set_image(Some(img.clone()));
+            let dimensions = match state_clone.try_borrow() {
+                Ok(state_ref) => {
+                    match state_ref.layer_state.get_composite_image() {
+                        Some(composite) if state_ref.layer_state.is_preview_active() => {
+                            draw_image_in_frame(f, &composite, state_ref.zoom, state_ref.pan);
                         }
-                    } else {
-                        f.set_image(Some(img.clone()));
+                        _ => draw_current_image(f, &state_ref),
                     }
+                    state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions()))
                 }
-
-                let dimensions = state_clone
-                    .try_borrow()
-                    .ok()
-                    .and_then(|state_ref| state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions())));
+                Err(_) => None,
+            };
+            
+            if let Some((true, (x, y, w, h))) = dimensions {
+                draw::set_draw_color(Color::Yellow);
+                draw::set_line_style(draw::LineStyle::Solid, 2);
+                draw::draw_rect(x, y, w, h);
                 
-                if let Some((true, (x, y, w, h))) = dimensions {
-                    draw::set_draw_color(Color::Yellow);
-                    draw::set_line_style(draw::LineStyle::Solid, 2);
-                    draw::draw_rect(x, y, w, h);
-                    
-                    let grid_size = 20;
-                    draw::set_line_style(draw::LineStyle::Dot, 1);
-                    
-                    for i in (x..x + w).step_by(grid_size as usize) {
-                        draw::draw_line(i, y, i, y + h);
-                    }
-                    for i in (y..y + h).step_by(grid_size as usize) {
-                        draw::draw_line(x, i, x + w, i);
-                    }
+                let grid_size = 20;
+                draw::set_line_style(draw::LineStyle::Dot, 1);
+                
+                for i in (x..x + w).step_by(grid_size as usize) {
+                    draw::draw_line(i, y, i, y + h);
+                }
+                for i in (y..y + h).step_by(grid_size as usize) {
+                    draw::draw_line(x, i, x + w, i);
                 }
             }
         }
@@ -119,10 +104,13 @@ pub fn start_interactive_color(
                     }
                     false
                 },
+                Event::Push | Event::Drag | Event::Released if is_pan_button() => false,
                 Event::Push => {
                     if let Ok(mut state) = state_clone.try_borrow_mut() {
+                        let viewport = Viewport::current(f, &state);
                         if let Some(selection) = &mut state.crop_selection {
                             selection.reset();
+                            selection.set_viewport(viewport);
                             selection.start_x = app::event_x();
                             selection.start_y = app::event_y();
                             selection.is_selecting = true;
@@ -151,37 +139,8 @@ pub fn start_interactive_color(
                     };
                 
                     if let (Some(selection), Some(current_image)) = (selection_data, current_image) {
-                        let (sx, sy, sw, sh) = selection.get_dimensions();
-                        let frame_w = frame_clone.borrow().w();
-                        let frame_h = frame_clone.borrow().h();
                         let image_dims = (current_image.data_w(), current_image.data_h());
-
-                        let (displayed_w, displayed_h) = scale_image_dimensions(
-                            image_dims.0,
-                            image_dims.1,
-                            frame_w,
-                            frame_h - MENU_HEIGHT,
-                            1.0
-                        );
-
-                        let offset_x = (frame_w - displayed_w) / 2;
-                        let offset_y = MENU_HEIGHT + (frame_h - MENU_HEIGHT - displayed_h) / 2;
-
-                        let adjusted_x = sx - offset_x;
-                        let adjusted_y = sy - offset_y;
-
-                        let scale_x = image_dims.0 as f64 / displayed_w as f64;
-                        let scale_y = image_dims.1 as f64 / displayed_h as f64;
-
-                        let img_x = (adjusted_x as f64 * scale_x).round() as i32;
-                        let img_y = (adjusted_y as f64 * scale_y).round() as i32;
-                        let img_w = (sw as f64 * scale_x).round() as i32;
-                        let img_h = (sh as f64 * scale_y).round() as i32;
-
-                        let img_x = img_x.max(0).min(image_dims.0 as i32 - 1);
-                        let img_y = img_y.max(0).min(image_dims.1 as i32 - 1);
-                        let img_w = img_w.min(image_dims.0 as i32 - img_x);
-                        let img_h = img_h.min(image_dims.1 as i32 - img_y);
+                        let (img_x, img_y, img_w, img_h) = selection.get_image_dimensions();
 
                         let should_apply = img_w > 5 && img_h > 5 && 
                             choice2(300, 300, "Apply color to selected area?", "Yes", "No", "") == Some(0);
@@ -218,10 +177,10 @@ pub fn start_interactive_color(
                                     
                                     // here it keeps the selection active for next area
                                     state.crop_selection = Some(CropSelection::new(
-                                        displayed_w,
-                                        displayed_h,
-                                        displayed_w,
-                                        displayed_h
+                                        image_dims.0,
+                                        image_dims.1,
+                                        selection.frame_w,
+                                        selection.frame_h
                                     ));
                                 }
                             }
@@ -12,6 +12,7 @@ pub fn handle_toggle_preview(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Imag
         // Update the displayed image based on preview state
         if state_ref.layer_state.is_preview_active() {
             if let Some(composite) = state_ref.layer_state.get_composite_image() {
+                state_ref.record_image_edit("Flatten Layers");
                 state_ref.image = Some(composite.clone());
                 frame.borrow_mut().set_image(Some(composite));
                 frame.borrow_mut().redraw();
@@ -19,6 +20,7 @@ pub fn handle_toggle_preview(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Imag
         } else {
             if let Some(original) = state_ref.layer_state.get_original_image() {
                 let original_image = original.clone();
+                state_ref.record_image_edit("Unflatten Layers");
                 state_ref.image = Some(original_image.clone());
                 frame.borrow_mut().set_image(Some(original_image));
                 frame.borrow_mut().redraw();
@@ -9,7 +9,9 @@ use fltk::{
 };
 use std::{cell::RefCell, rc::Rc};
 use crate::state::ImageState;
-use crate::menu::edit::crop::crop_tool::CropSelection;
+use crate::menu::edit::crop::CropSelection;
+use crate::menu::view::zoom::is_pan_button;
+use crate::utils::{draw_current_image, Viewport};
 use super::color_filter::ColorFilter;
 
 
@@ -44,32 +46,30 @@ pub fn start_interactive_layer(
 
     let draw_callback = {
         let state_clone = state_clone.clone();
-        let original_image = original_image.clone();
         let outline_color = Color::from_rgb(color.0, color.1, color.2);
         
         move |f: &mut Frame| {
-            if let Some(img) = &original_image {
-                f.set_image(Some(img.clone()));
-
-                let dimensions = state_clone
-                    .try_borrow()
-                    .ok()
-                    .and_then(|state_ref| state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions())));
+            let dimensions = match state_clone.try_borrow() {
+                Ok(state_ref) => {
+                    draw_current_image(f, &state_ref);
+                    state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions()))
+                }
+                Err(_) => None,
+            };
+            
+            if let Some((true, (x, y, w, h))) = dimensions {
+                draw::set_draw_color(outline_color);
+                draw::set_line_style(draw::LineStyle::Solid, 2);
+                draw::draw_rect(x, y, w, h);
                 
-                if let Some((true, (x, y, w, h))) = dimensions {
-                    draw::set_draw_color(outline_color);
-                    draw::set_line_style(draw::LineStyle::Solid, 2);
-                    draw::draw_rect(x, y, w, h);
-                    
-                    draw::set_line_style(draw::LineStyle::Dot, 1);
-                    let grid_size = 20;
-                    
-                    for i in (x..x + w).step_by(grid_size as usize) {
-                        draw::draw_line(i, y, i, y + h);
-                    }
-                    for i in (y..y + h).step_by(grid_size as usize) {
-                        draw::draw_line(x, i, x + w, i);
-                    }
+                draw::set_line_style(draw::LineStyle::Dot, 1);
+                let grid_size = 20;
+                
+                for i in (x..x + w).step_by(grid_size as usize) {
+                    draw::draw_line(i, y, i, y + h);
+                }
+                for i in (y..y + h).step_by(grid_size as usize) {
+                    draw::draw_line(x, i, x + w, i);
                 }
             }
         }
@@ -84,10 +84,13 @@ pub fn start_interactive_layer(
         
         move |f: &mut Frame, ev: Event| -> bool {
             match ev {
+                Event::Push | Event::Drag | Event::Released if is_pan_button() => false,
                 Event::Push => {
                     if let Ok(mut state) = state_clone.try_borrow_mut() {
+                        let viewport = Viewport::current(f, &state);
                         if let Some(selection) = &mut state.crop_selection {
                             selection.reset();
+                            selection.set_viewport(viewport);
                             selection.start_x = app::event_x();
                             selection.start_y = app::event_y();
                             selection.is_selecting = true;
@@ -138,6 +141,7 @@ pub fn start_interactive_layer(
                                 
                                 if let Ok(Some(new_image)) = 
                                     state.filter_state.apply_filter(current_image, &filter) {
+                                    state.record_image_edit("Color Layer");
                                     state.image = Some(new_image.clone());
                                     frame_clone.borrow_mut().set_image(state.image.clone());
                                 }
@@ -2,6 +2,11 @@
 pub mod crop;
 pub mod watermark;
 pub mod filters;
+#[cfg(feature = "gui")]
 pub mod layers;
+#[cfg(feature = "gui")]
+pub mod history;
+#[cfg(feature = "gui")]
+pub mod clipboard;
 
 
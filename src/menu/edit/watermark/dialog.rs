@@ -247,6 +247,7 @@ pub fn show_edit_watermark_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell
                     if let Ok(Some(new_image)) = state_ref.watermark_state.apply_watermark(&image) {
                         frame_rc.borrow_mut().set_image(Some(new_image.clone()));
                         frame_rc.borrow_mut().redraw();
+                        state_ref.record_image_edit("Edit Watermark");
                         state_ref.image = Some(new_image);
                     }
                 }
@@ -53,6 +53,7 @@ pub fn handle_add_watermark_from_path(
                     println!("Successfully applied watermark");
                     frame.borrow_mut().set_image(Some(new_image.clone()));
                     frame.borrow_mut().redraw();
+                    state_ref.record_image_edit("Apply Watermark");
                     state_ref.image = Some(new_image);
                 } else {
                     println!("Failed to apply watermark");
@@ -104,6 +105,7 @@ pub fn handle_add_text_watermark(
                     println!("Successfully applied text watermark");
                     frame.borrow_mut().set_image(Some(new_image.clone()));
                     frame.borrow_mut().redraw();
+                    state_ref.record_image_edit("Apply Watermark");
                     state_ref.image = Some(new_image);
                 }
             },
@@ -136,35 +138,43 @@ pub fn handle_remove_watermark(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Im
 }
 
 pub fn handle_toggle_preview(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
-    if let Ok(mut state_ref) = state.try_borrow_mut() {
+    let (mut shown, zoom) = {
+        let mut state_ref = match state.try_borrow_mut() {
+            Ok(state_ref) => state_ref,
+            Err(_) => return,
+        };
         let preview_active = state_ref.watermark_state.is_preview_active();
-        let current_zoom = state_ref.zoom;
         state_ref.watermark_state.toggle_preview();
-        
-        if let Some(ref image) = state_ref.image.clone() {
-            if preview_active {
-                // Reload original image when disabling preview
-                if let Some(path) = &state_ref.path {
-                    if let Ok(img) = image::open(path) {
-                        let fltk_image = fltk::image::RgbImage::new(
-                            &img.to_rgb8().into_raw(),
-                            img.width() as i32,
-                            img.height() as i32,
-                            fltk::enums::ColorDepth::Rgb8
-                        ).unwrap();
-                        
-                        // Use display_image to maintain proper scaling
-                        crate::utils::image::display_image(frame, &fltk_image, current_zoom.into());
-                        state_ref.image = Some(fltk_image);
-                        return;
-                    }
-                }
-                crate::utils::image::display_image(frame, image, current_zoom.into());
-            } else {
-                if let Ok(Some(preview_image)) = state_ref.watermark_state.apply_watermark(image) {
-                    crate::utils::image::display_image(frame, &preview_image, current_zoom.into());
+
+        let image = match state_ref.image.clone() {
+            Some(image) => image,
+            None => return,
+        };
+        let shown = if preview_active {
+            // Reload original image when disabling preview
+            let original = state_ref.path.as_ref()
+                .and_then(|path| image::open(path).ok())
+                .and_then(|img| fltk::image::RgbImage::new(
+                    &img.to_rgb8().into_raw(),
+                    img.width() as i32,
+                    img.height() as i32,
+                    fltk::enums::ColorDepth::Rgb8
+                ).ok());
+            match original {
+                Some(fltk_image) => {
+                    state_ref.record_image_edit("Revert Watermark Preview");
+                    state_ref.image = Some(fltk_image.clone());
+                    fltk_image
                 }
+                None => image,
             }
-        }
-    }
-}
\ No newline at end of file
+        } else {
+            match state_ref.watermark_state.apply_watermark(&image) {
+                Ok(Some(preview_image)) => preview_image,
+                _ => return,
+            }
+        };
+        (shown, state_ref.zoom)
+    };
+    crate::utils::image::display_image_with_zoom(frame, &mut shown, zoom, state);
+}
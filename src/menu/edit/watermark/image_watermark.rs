@@ -32,6 +32,10 @@ impl ImageWatermark {
             .map(Self::new)
     }
 
+    pub fn image(&self) -> &DynamicImage {
+        &self.watermark_image
+    }
+
     fn resize_watermark(
         &self,
         target_width: u32,
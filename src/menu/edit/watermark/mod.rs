@@ -15,19 +15,23 @@ pub struct RemovalArea {
 
 
 mod blend;
+#[cfg(feature = "gui")]
 pub mod dialog;
 mod fonts;
+#[cfg(feature = "gui")]
 mod handlers;
 pub mod image_watermark;
 mod position;
 mod templates;
 pub mod text_watermark;
+#[cfg(feature = "gui")]
 pub mod removal_tool;
 
 
 // Re-export everything needed externally
 pub use blend::{BlendMode};
 pub use position::{Position, WatermarkPosition};
+#[cfg(feature = "gui")]
 pub use handlers::*;
 
 
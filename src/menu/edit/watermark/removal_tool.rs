@@ -7,6 +7,8 @@ use fltk::{
 };
 use std::{rc::Rc, cell::RefCell};
 use crate::state::ImageState;
+use crate::utils::Viewport;
+use crate::menu::view::zoom::is_pan_button;
 use super::RemovalArea;
 
 pub struct WatermarkRemovalTool {
@@ -15,9 +17,7 @@ pub struct WatermarkRemovalTool {
     end_x: i32,
     end_y: i32,
     is_selecting: bool,
-    image_offset_x: i32,
-    image_offset_y: i32,
-    image_scale: f64,
+    viewport: Option<Viewport>,
 }
 
 impl WatermarkRemovalTool {
@@ -28,25 +28,12 @@ impl WatermarkRemovalTool {
             end_x: 0,
             end_y: 0,
             is_selecting: false,
-            image_offset_x: 0,
-            image_offset_y: 0,
-            image_scale: 1.0,
+            viewport: None,
         }
     }
 
-    pub fn calculate_image_position(&mut self, frame: &Frame, image_w: i32, image_h: i32, zoom: f64) {
-        let frame_w = frame.w();
-        let frame_h = frame.h();
-        let menu_height = crate::utils::MENU_HEIGHT;
-
-        // Calculate scaled dimensions
-        let scaled_w = (image_w as f64 * zoom) as i32;
-        let scaled_h = (image_h as f64 * zoom) as i32;
-
-        // Center the image in the frame
-        self.image_offset_x = (frame_w - scaled_w) / 2;
-        self.image_offset_y = ((frame_h - menu_height) - scaled_h) / 2 + menu_height;
-        self.image_scale = zoom;
+    pub fn calculate_image_position(&mut self, frame: &Frame, state: &ImageState) {
+        self.viewport = Viewport::current(frame, state);
     }
 
     pub fn start_selection(&mut self, x: i32, y: i32) {
@@ -70,23 +57,13 @@ impl WatermarkRemovalTool {
         }
         self.is_selecting = false;
 
-        // Convert screen coordinates to image coordinates
-        let start_x = ((self.start_x - self.image_offset_x) as f64 / self.image_scale) as i32;
-        let start_y = ((self.start_y - self.image_offset_y) as f64 / self.image_scale) as i32;
-        let end_x = ((self.end_x - self.image_offset_x) as f64 / self.image_scale) as i32;
-        let end_y = ((self.end_y - self.image_offset_y) as f64 / self.image_scale) as i32;
-
-        let (x, width) = if end_x >= start_x {
-            (start_x, end_x - start_x)
-        } else {
-            (end_x, start_x - end_x)
-        };
-        
-        let (y, height) = if end_y >= start_y {
-            (start_y, end_y - start_y)
-        } else {
-            (end_y, start_y - end_y)
-        };
+        let viewport = self.viewport?;
+        let (x, y, width, height) = viewport.rect_to_image(
+            self.start_x.min(self.end_x),
+            self.start_y.min(self.end_y),
+            (self.end_x - self.start_x).abs(),
+            (self.end_y - self.start_y).abs(),
+        );
 
         Some(RemovalArea { x, y, width, height })
     }
@@ -108,23 +85,18 @@ impl WatermarkRemovalTool {
 pub fn start_watermark_removal(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
     let state = state.clone();
     let mut removal_tool = WatermarkRemovalTool::new();
-    
-    // Initialize tool with current image position
-    if let Ok(state_ref) = state.try_borrow() {
-        if let Some(ref image) = state_ref.image {
-            removal_tool.calculate_image_position(
-                &frame.borrow(),
-                image.data_w(),
-                image.data_h(),
-                state_ref.zoom.into()
-            );
-        }
-    }
 
     let mut frame = frame.borrow_mut();
     frame.handle(move |f, ev| {
+        if is_pan_button() {
+            return false;
+        }
         match ev {
             Event::Push => {
+                // the view may have been zoomed or panned since the last selection
+                if let Ok(state_ref) = state.try_borrow() {
+                    removal_tool.calculate_image_position(f, &state_ref);
+                }
                 let coords = fltk::app::event_coords();
                 removal_tool.start_selection(coords.0, coords.1);
                 f.redraw();
@@ -143,6 +115,7 @@ pub fn start_watermark_removal(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Im
                             if let Ok(Some(new_image)) = state_ref.watermark_state.remove_watermark_area(current_image, &area) {
                                 f.set_image(Some(new_image.clone()));
                                 f.redraw();
+                                state_ref.record_image_edit("Erase Watermark");
                                 state_ref.image = Some(new_image);
                             }
                         }
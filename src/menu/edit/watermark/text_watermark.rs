@@ -32,6 +32,18 @@ impl TextWatermark {
         })
     }
 
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
     fn render_text(
         &self,
         _width: u32,
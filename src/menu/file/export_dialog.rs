@@ -0,0 +1,136 @@
+// src/menu/file/export_dialog.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::{Cell, RefCell}};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::Button,
+    frame::Frame,
+    group::{Pack, PackType},
+    menu::Choice,
+    valuator::HorValueSlider,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::state::export_options::{ChromaSubsampling, ExportOptions, PngCompression, TiffCompression};
+use crate::scientific::io::ExportFormat;
+
+/// edits the encoder settings kept in the state. when `format` is given only
+/// that format's section is active. returns false if the dialog was cancelled
+pub fn show_export_options_dialog(state: &Rc<RefCell<ImageState>>, format: Option<ExportFormat>) -> bool {
+    let current = match state.try_borrow() {
+        Ok(state_ref) => state_ref.export_options.clone(),
+        Err(_) => return false,
+    };
+
+    let mut dialog = Window::default()
+        .with_size(320, 330)
+        .with_label("Export Options");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 300, 310, "");
+    pack.set_spacing(6);
+
+    // JPEG
+    let mut jpeg_label = Frame::default().with_size(300, 20).with_label("JPEG quality:");
+    let mut quality = HorValueSlider::default().with_size(300, 25);
+    quality.set_range(1.0, 100.0);
+    quality.set_step(1.0, 1);
+    quality.set_value(current.jpeg_quality as f64);
+
+    let mut subsampling_label = Frame::default().with_size(300, 20).with_label("JPEG chroma subsampling:");
+    let mut subsampling = Choice::default().with_size(300, 25);
+    for option in ChromaSubsampling::ALL {
+        subsampling.add_choice(option.label());
+    }
+    subsampling.set_value(index_of(&ChromaSubsampling::ALL, &current.jpeg_subsampling));
+
+    // PNG
+    let mut png_label = Frame::default().with_size(300, 20).with_label("PNG compression:");
+    let mut png_compression = Choice::default().with_size(300, 25);
+    for option in PngCompression::ALL {
+        png_compression.add_choice(option.label());
+    }
+    png_compression.set_value(index_of(&PngCompression::ALL, &current.png_compression));
+
+    // TIFF
+    let mut tiff_label = Frame::default().with_size(300, 20).with_label("TIFF compression:");
+    let mut tiff_compression = Choice::default().with_size(300, 25);
+    for option in TiffCompression::ALL {
+        tiff_compression.add_choice(option.label());
+    }
+    tiff_compression.set_value(index_of(&TiffCompression::ALL, &current.tiff_compression));
+
+    Frame::default().with_size(300, 10);
+    let mut button_pack = Pack::default().with_size(300, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut ok_btn = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel_btn = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+
+    if let Some(format) = format {
+        if format != ExportFormat::Jpeg {
+            jpeg_label.deactivate();
+            quality.deactivate();
+            subsampling_label.deactivate();
+            subsampling.deactivate();
+        }
+        if format != ExportFormat::Png {
+            png_label.deactivate();
+            png_compression.deactivate();
+        }
+        if format != ExportFormat::Tiff {
+            tiff_label.deactivate();
+            tiff_compression.deactivate();
+        }
+    }
+
+    dialog.show();
+
+    let accepted = Rc::new(Cell::new(false));
+
+    let accepted_ok = accepted.clone();
+    let state_ok = state.clone();
+    let mut dialog_ok = dialog.clone();
+    ok_btn.set_callback(move |_| {
+        let options = ExportOptions {
+            jpeg_quality: quality.value().round().clamp(1.0, 100.0) as u8,
+            jpeg_subsampling: pick(&ChromaSubsampling::ALL, subsampling.value()),
+            png_compression: pick(&PngCompression::ALL, png_compression.value()),
+            tiff_compression: pick(&TiffCompression::ALL, tiff_compression.value()),
+        };
+        if let Ok(mut state_ref) = state_ok.try_borrow_mut() {
+            state_ref.export_options = options;
+        }
+        accepted_ok.set(true);
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel_btn.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    while dialog.shown() {
+        app::wait();
+    }
+
+    accepted.get()
+}
+
+fn index_of<T: PartialEq>(options: &[T], value: &T) -> i32 {
+    options.iter().position(|o| o == value).unwrap_or(0) as i32
+}
+
+fn pick<T: Copy>(options: &[T], index: i32) -> T {
+    options[(index.max(0) as usize).min(options.len() - 1)]
+}
@@ -2,3 +2,6 @@ pub mod open;
 pub mod exit;
 pub mod save;
 
+pub mod project;
+pub mod recent;
+pub mod export_dialog;
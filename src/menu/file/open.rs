@@ -1,6 +1,15 @@
-//sr
+//src/menu/file/open.rs
 use crate::state::ImageState;
 use crate::utils::image::display_image_with_zoom;
+use crate::utils::raw_image::RawImage;
+use crate::scientific::io::{count_tiff_pages, is_tiff, read_tiff_pages, read_embedded_metadata, read_foreign_metadata};
+use crate::scientific::state::ImageStack;
+use crate::scientific::ui::stack_dialog::{choose_stack_kind, show_stack_navigator};
+use crate::state::recent_files::RecentKind;
+use crate::menu::file::recent::remember_recent;
+use crate::menu::file::project::open_project_path;
+use crate::state::project::PROJECT_EXTENSION;
+use crate::menu::view::filmstrip::update_filmstrip;
 use fltk::{
     prelude::*,
     dialog::{FileDialog, FileDialogType},
@@ -8,26 +17,212 @@ use fltk::{
     image::RgbImage,
     enums::ColorDepth,
 };
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{cell::RefCell, path::{Path, PathBuf}, rc::Rc};
 
 pub fn handle_open(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
     let mut dialog = FileDialog::new(FileDialogType::BrowseFile);
-    dialog.set_filter("Image Files\t*.{jpg,jpeg,png,gif,bmp,tif,tiff}");
+    dialog.set_filter("Image Files\t*.{jpg,jpeg,png,gif,bmp,tif,tiff,webp,pnm,pgm,ppm,pam}");
     dialog.show();
-    
+
     if let Some(filename) = dialog.filename().to_str() {
-        if let Ok(img) = image::open(&filename) {
-            let rgb_img = img.to_rgb8();
-            let (width, height) = rgb_img.dimensions();
-            
-            if let Ok(mut fltk_image) = RgbImage::new(&rgb_img, width as i32, height as i32, ColorDepth::Rgb8) {
-                if let Ok(mut state_ref) = state.try_borrow_mut() {
-                    state_ref.path = Some(PathBuf::from(filename));
-                    state_ref.zoom = 1.0;
-                    state_ref.image = Some(fltk_image.clone());
-                    display_image_with_zoom(frame, &mut fltk_image, 1.0, state);
+        if !filename.is_empty() && open_image_path(frame, state, Path::new(filename)).is_opened() {
+            remember_recent(frame, state, Path::new(filename), RecentKind::Image);
+        }
+    }
+}
+
+/// decodes a file into the 8-bit display image plus the full bit-depth data
+pub fn load_image_file(path: &Path) -> Option<(RgbImage, RawImage)> {
+    let img = match image::open(path) {
+        Ok(img) => img,
+        Err(e) => {
+            println!("Failed to open {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    let raw = RawImage::from_dynamic(&img);
+    println!("Loaded {:?} at {}-bit, {} channel(s)", path, raw.bit_depth(), raw.channels);
+
+    // high bit-depth images are windowed down for display, 8-bit ones go straight through
+    let fltk_image = if raw.is_high_bit_depth() {
+        raw.to_rgb_image()?
+    } else {
+        let rgb_img = img.to_rgb8();
+        let (width, height) = rgb_img.dimensions();
+        RgbImage::new(&rgb_img, width as i32, height as i32, ColorDepth::Rgb8).ok()?
+    };
+
+    Some((fltk_image, raw))
+}
+
+/// how an attempt to open a file ended
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OpenOutcome {
+    Opened,
+    /// the user backed out of a dialog, e.g. the stack kind of a multi-page TIFF
+    Cancelled,
+    /// the file could not be read
+    Failed,
+}
+
+impl OpenOutcome {
+    pub fn is_opened(self) -> bool {
+        self == OpenOutcome::Opened
+    }
+}
+
+/// loads `path` into the state and displays it; shared by every way of opening a file
+pub fn open_image_path(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, path: &Path) -> OpenOutcome {
+    let outcome = load_image_path(frame, state, path);
+    if outcome.is_opened() {
+        if let Ok(mut state_ref) = state.try_borrow_mut() {
+            state_ref.folder.open(path);
+        }
+        update_filmstrip(frame, state);
+    }
+    outcome
+}
+
+fn load_image_path(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, path: &Path) -> OpenOutcome {
+    if is_tiff(path) {
+        if let Ok(page_count) = count_tiff_pages(path) {
+            if page_count > 1 {
+                return open_tiff_stack(frame, state, path, page_count);
+            }
+        }
+    }
+
+    if let Some((mut fltk_image, mut raw)) = load_image_file(path) {
+        if let Ok(mut state_ref) = state.try_borrow_mut() {
+            state_ref.path = Some(PathBuf::from(path));
+            state_ref.zoom = 1.0;
+            state_ref.pan = (0.0, 0.0);
+            state_ref.history.clear();
+            state_ref.scientific_state.clear_stack();
+            load_file_metadata(&mut state_ref, path);
+            // the objective is only known once the metadata is read
+            if let Some(corrected) = state_ref.scientific_state.flat_field_on_open(&raw) {
+                if let Some(image) = corrected.to_rgb_image() {
+                    fltk_image = image;
+                    raw = corrected;
                 }
             }
+            state_ref.raw_image = Some(raw);
+            state_ref.image = Some(fltk_image.clone());
+            display_image_with_zoom(frame, &mut fltk_image, 1.0, state);
+            return OpenOutcome::Opened;
+        }
+    }
+    OpenOutcome::Failed
+}
+
+/// shows `raw` as a new document with no file behind it (e.g. a pasted image)
+pub fn open_untitled_image(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, raw: RawImage) -> bool {
+    let mut fltk_image = match raw.to_rgb_image() {
+        Some(image) => image,
+        None => return false,
+    };
+
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        state_ref.path = None;
+        state_ref.zoom = 1.0;
+        state_ref.pan = (0.0, 0.0);
+        state_ref.history.clear();
+        state_ref.scientific_state.clear_stack();
+        state_ref.scientific_state.reset_calibration();
+        state_ref.scientific_state.set_image_metadata(Default::default());
+        state_ref.scientific_state.measurement_background = None;
+        state_ref.raw_image = Some(raw);
+        state_ref.image = Some(fltk_image.clone());
+        state_ref.folder.clear_current();
+        display_image_with_zoom(frame, &mut fltk_image, 1.0, state);
+    } else {
+        return false;
+    }
+    update_filmstrip(frame, state);
+    true
+}
+
+/// opens the files in a drop (newline separated paths or file:// URIs).
+/// only the first file that loads is kept, the rest would just replace it
+pub fn open_dropped_files(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, text: &str) -> bool {
+    for path in dropped_paths(text) {
+        let is_project = path.extension().and_then(|e| e.to_str()) == Some(PROJECT_EXTENSION);
+        let (opened, kind) = if is_project {
+            (open_project_path(frame, state, &path), RecentKind::Project)
+        } else {
+            (open_image_path(frame, state, &path).is_opened(), RecentKind::Image)
+        };
+        if opened {
+            remember_recent(frame, state, &path, kind);
+            return true;
         }
     }
+    false
+}
+
+pub fn dropped_paths(text: &str) -> Vec<PathBuf> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            if line.starts_with("file:") {
+                url::Url::parse(line).ok()?.to_file_path().ok()
+            } else {
+                Some(PathBuf::from(line))
+            }
+        })
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+// metadata we saved ourselves wins over the camera's EXIF tags
+fn load_file_metadata(state_ref: &mut ImageState, path: &Path) {
+    state_ref.scientific_state.measurement_background = None;
+    // a file without resolution tags must not inherit the last image's scale
+    state_ref.scientific_state.reset_calibration();
+    match read_embedded_metadata(path) {
+        Some(embedded) => state_ref.scientific_state.apply_embedded_metadata(embedded),
+        None => state_ref.scientific_state.set_image_metadata(read_foreign_metadata(path)),
+    }
+}
+
+fn open_tiff_stack(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, path: &Path, page_count: usize) -> OpenOutcome {
+    let kind = match choose_stack_kind(page_count) {
+        Some(kind) => kind,
+        None => return OpenOutcome::Cancelled,
+    };
+
+    let pages = match read_tiff_pages(path) {
+        Ok(pages) => pages,
+        Err(e) => {
+            fltk::dialog::alert(300, 300, &format!("Failed to read TIFF stack: {}", e));
+            return OpenOutcome::Failed;
+        }
+    };
+
+    let stack = ImageStack::new(pages, kind);
+    let mut fltk_image = match stack.current_page().and_then(|page| page.to_rgb_image()) {
+        Some(image) => image,
+        None => return OpenOutcome::Failed,
+    };
+
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        state_ref.path = Some(PathBuf::from(path));
+        state_ref.zoom = 1.0;
+        state_ref.pan = (0.0, 0.0);
+        state_ref.history.clear();
+        state_ref.raw_image = stack.current_page().cloned();
+        state_ref.image = Some(fltk_image.clone());
+        load_file_metadata(&mut state_ref, path);
+        state_ref.scientific_state.clear_stack();
+        state_ref.scientific_state.load_stack(stack);
+        display_image_with_zoom(frame, &mut fltk_image, 1.0, state);
+    } else {
+        return OpenOutcome::Failed;
+    }
+
+    show_stack_navigator(frame, state);
+    OpenOutcome::Opened
 }
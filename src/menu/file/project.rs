@@ -0,0 +1,88 @@
+//src/menu/file/project.rs
+use crate::state::ImageState;
+use crate::state::project::{load_project, save_project, PROJECT_EXTENSION};
+use crate::state::recent_files::RecentKind;
+use crate::menu::file::recent::remember_recent;
+use crate::utils::image::display_image_with_zoom;
+use crate::scientific::ui::stack_dialog::show_stack_navigator;
+use fltk::{
+    dialog::{FileDialog, FileDialogType, alert, message},
+    frame::Frame,
+    prelude::*,
+};
+use std::{cell::RefCell, path::{Path, PathBuf}, rc::Rc};
+
+pub fn handle_save_project(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let mut dialog = FileDialog::new(FileDialogType::BrowseSaveFile);
+    dialog.set_filter(&format!("Project Files\t*.{}", PROJECT_EXTENSION));
+    dialog.show();
+
+    let filename = dialog.filename();
+    if filename.as_os_str().is_empty() {
+        return;
+    }
+
+    let mut path = PathBuf::from(filename);
+    if path.extension().and_then(|e| e.to_str()) != Some(PROJECT_EXTENSION) {
+        path.set_extension(PROJECT_EXTENSION);
+    }
+
+    let result = match state.try_borrow() {
+        Ok(state_ref) => save_project(&state_ref, &path),
+        Err(_) => return,
+    };
+    match result {
+        Ok(()) => {
+            remember_recent(frame, state, &path, RecentKind::Project);
+            message(200, 200, "Project saved successfully!");
+        }
+        Err(e) => alert(200, 200, &format!("Failed to save project: {}", e)),
+    }
+}
+
+pub fn handle_open_project(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let mut dialog = FileDialog::new(FileDialogType::BrowseFile);
+    dialog.set_filter(&format!("Project Files\t*.{}", PROJECT_EXTENSION));
+    dialog.show();
+
+    let path = dialog.filename();
+    if path.as_os_str().is_empty() {
+        return;
+    }
+
+    if open_project_path(frame, state, &path) {
+        remember_recent(frame, state, &path, RecentKind::Project);
+    }
+}
+
+/// loads the project at `path` and displays it
+pub fn open_project_path(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, path: &Path) -> bool {
+    let (image, zoom, has_stack) = {
+        let mut state_ref = match state.try_borrow_mut() {
+            Ok(state_ref) => state_ref,
+            Err(_) => return false,
+        };
+        if let Err(e) = load_project(&mut state_ref, path) {
+            alert(200, 200, &format!("Failed to open project: {}", e));
+            return false;
+        }
+        (
+            state_ref.image.clone(),
+            state_ref.zoom,
+            state_ref.scientific_state.image_stack.is_some(),
+        )
+    };
+
+    match image {
+        Some(mut image) => display_image_with_zoom(frame, &mut image, zoom, state),
+        None => {
+            frame.borrow_mut().set_image(None::<fltk::image::RgbImage>);
+            frame.borrow_mut().redraw();
+        }
+    }
+
+    if has_stack {
+        show_stack_navigator(frame, state);
+    }
+    true
+}
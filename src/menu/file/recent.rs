@@ -0,0 +1,111 @@
+//src/menu/file/recent.rs
+use crate::state::ImageState;
+use crate::state::recent_files::{RecentEntry, RecentKind};
+use crate::menu::file::{open::open_image_path, project::open_project_path};
+use fltk::{
+    app,
+    dialog::alert,
+    enums::Shortcut,
+    frame::Frame,
+    menu::{MenuBar, MenuFlag},
+    prelude::*,
+};
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+/// id the main menu bar is registered under so handlers can reach it
+pub const MAIN_MENU_ID: &str = "main_menu";
+const RECENT_MENU_PATH: &str = "&File/Recent Files";
+
+/// puts `path` at the top of the recent list, saves it and refreshes the submenu
+pub fn remember_recent(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, path: &Path, kind: RecentKind) {
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        state_ref.recent_files.add(path, kind);
+        state_ref.recent_files.save();
+    }
+    rebuild_recent_menu(frame, state);
+}
+
+/// clears and refills the Recent Files submenu from the state's list
+pub fn rebuild_recent_menu(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let mut menu = match app::widget_from_id::<MenuBar>(MAIN_MENU_ID) {
+        Some(menu) => menu,
+        None => return,
+    };
+    let index = menu.find_index(RECENT_MENU_PATH);
+    if index < 0 {
+        return;
+    }
+    if let Err(e) = menu.clear_submenu(index) {
+        println!("Failed to clear recent files menu: {}", e);
+        return;
+    }
+
+    let entries: Vec<RecentEntry> = match state.try_borrow() {
+        Ok(state_ref) => state_ref.recent_files.entries().to_vec(),
+        Err(_) => return,
+    };
+
+    if entries.is_empty() {
+        menu.add(&format!("{}/(empty)", RECENT_MENU_PATH), Shortcut::None, MenuFlag::Inactive, |_| {});
+        return;
+    }
+
+    for (i, entry) in entries.iter().enumerate() {
+        let label = format!("{}/{}. {}", RECENT_MENU_PATH, i + 1, menu_label(entry));
+        // the last file entry draws the divider above "Clear recent"
+        let flag = if i + 1 == entries.len() { MenuFlag::MenuDivider } else { MenuFlag::Normal };
+        let frame = frame.clone();
+        let state = state.clone();
+        let entry = entry.clone();
+        menu.add(&label, Shortcut::None, flag, move |_| {
+            open_recent(&frame, &state, &entry);
+        });
+    }
+
+    let frame = frame.clone();
+    let state = state.clone();
+    menu.add(&format!("{}/Clear recent", RECENT_MENU_PATH), Shortcut::None, MenuFlag::Normal, move |_| {
+        if let Ok(mut state_ref) = state.try_borrow_mut() {
+            state_ref.recent_files.clear();
+            state_ref.recent_files.save();
+        }
+        // the menu can't be rebuilt from inside one of its own callbacks
+        let frame = frame.clone();
+        let state = state.clone();
+        app::add_timeout3(0.0, move |_| rebuild_recent_menu(&frame, &state));
+    });
+}
+
+fn open_recent(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, entry: &RecentEntry) {
+    let exists = entry.path.exists();
+    let opened = exists && match entry.kind {
+        RecentKind::Image => open_image_path(frame, state, &entry.path).is_opened(),
+        RecentKind::Project => open_project_path(frame, state, &entry.path),
+    };
+    if !exists {
+        alert(200, 200, &format!("{} no longer exists", entry.path.display()));
+    }
+
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        if opened {
+            state_ref.recent_files.add(&entry.path, entry.kind);
+        } else if !exists {
+            state_ref.recent_files.remove(&entry.path);
+        }
+        state_ref.recent_files.save();
+    }
+
+    let frame = frame.clone();
+    let state = state.clone();
+    app::add_timeout3(0.0, move |_| rebuild_recent_menu(&frame, &state));
+}
+
+// '/' separates submenus and '&' marks a shortcut in menu labels, so both get escaped
+fn menu_label(entry: &RecentEntry) -> String {
+    let path = entry.path.display().to_string();
+    let label = match entry.kind {
+        RecentKind::Image => path,
+        RecentKind::Project => format!("{} (project)", path),
+    };
+    label.replace('\\', "\\\\").replace('/', "\\/").replace('&', "&&")
+}
@@ -1,18 +1,40 @@
 use crate::state::ImageState;
+use crate::state::recent_files::RecentKind;
+use crate::menu::file::recent::remember_recent;
+use crate::utils::raw_image::{RawImage, RawPixels};
+use crate::scientific::state::ImageStack;
+use crate::scientific::io::{is_tiff, write_image, write_sidecar, write_tiff_pages, EmbeddedMetadata, ExportFormat};
+use crate::state::export_options::ExportOptions;
+use crate::menu::file::export_dialog::show_export_options_dialog;
 use fltk::{
     dialog::{FileDialog, FileDialogType, alert, message},
     frame::Frame,
     prelude::*,
 };
 use std::{cell::RefCell, path::PathBuf, rc::Rc};
-use image::ImageFormat;
 
 pub fn handle_save(_frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
     let state_ref = state.borrow();
+    let embedded = state_ref.scientific_state.embedded_metadata();
     match &state_ref.path {
         Some(path) => {
-            if let Some(img) = &state_ref.image {
-                if save_image(img, path) {
+            if let Some(stack) = state_ref.scientific_state.image_stack.as_ref().filter(|_| is_tiff(path)) {
+                let pages = match stack_pages_to_save(stack, state_ref.image.as_ref(), state_ref.raw_image.as_ref()) {
+                    Some(pages) => pages,
+                    None => {
+                        alert(200, 200, STACK_PAGE_RESIZED);
+                        return;
+                    }
+                };
+                if save_stack(&pages, path, &embedded, &state_ref.export_options) {
+                    write_sidecar_if_enabled(&state_ref, path, &embedded);
+                    message(200, 200, "Stack saved successfully!");
+                } else {
+                    alert(200, 200, "Failed to save stack!");
+                }
+            } else if let Some(img) = &state_ref.image {
+                if save_image(img, state_ref.raw_image.as_ref(), path, Some(&embedded), &state_ref.export_options) {
+                    write_sidecar_if_enabled(&state_ref, path, &embedded);
                     message(200, 200, "Image saved successfully!");
                 } else {
                     alert(200, 200, "Failed to save image!");
@@ -29,18 +51,62 @@ pub fn handle_save(_frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>)
     }
 }
 
-pub fn handle_save_as(_frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+pub fn handle_save_as(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
     let mut dialog = FileDialog::new(FileDialogType::BrowseSaveFile);
-    dialog.set_filter("Image Files\t*.{jpg,jpeg,png,tif,tiff}");
+    dialog.set_filter(ExportFormat::FILE_FILTER);
     dialog.show();
 
     if let Some(filename) = dialog.filename().to_str() {
+        if filename.is_empty() {
+            return;
+        }
         let path = PathBuf::from(filename);
-        let mut state = state.borrow_mut();
+        match ExportFormat::from_path(&path) {
+            Some(format) if format.has_options() => {
+                if !show_export_options_dialog(state, Some(format)) {
+                    return;
+                }
+            }
+            Some(_) => {}
+            None => {
+                alert(200, 200, "Unsupported file type.\nUse jpg, png, tif, bmp, webp, gif or pnm.");
+                return;
+            }
+        }
+
+        let mut state_ref = state.borrow_mut();
+        let options = state_ref.export_options.clone();
+        let embedded = state_ref.scientific_state.embedded_metadata();
+
+        let stack_pages = state_ref.scientific_state.image_stack.as_ref()
+            .filter(|_| is_tiff(&path))
+            .map(|stack| stack_pages_to_save(stack, state_ref.image.as_ref(), state_ref.raw_image.as_ref()));
+        if let Some(pages) = stack_pages {
+            let pages = match pages {
+                Some(pages) => pages,
+                None => {
+                    alert(200, 200, STACK_PAGE_RESIZED);
+                    return;
+                }
+            };
+            if save_stack(&pages, &path, &embedded, &options) {
+                write_sidecar_if_enabled(&state_ref, &path, &embedded);
+                state_ref.path = Some(path.clone());
+                drop(state_ref);
+                remember_recent(frame, state, &path, RecentKind::Image);
+                message(200, 200, "Stack saved successfully!");
+            } else {
+                alert(200, 200, "Failed to save stack!");
+            }
+            return;
+        }
         
-        if let Some(img) = &state.image {
-            if save_image(img, &path) {
-                state.path = Some(path);
+        if let Some(img) = &state_ref.image {
+            if save_image(img, state_ref.raw_image.as_ref(), &path, Some(&embedded), &options) {
+                write_sidecar_if_enabled(&state_ref, &path, &embedded);
+                state_ref.path = Some(path.clone());
+                drop(state_ref);
+                remember_recent(frame, state, &path, RecentKind::Image);
                 message(200, 200, "Image saved successfully!");
             } else {
                 alert(200, 200, "Failed to save image!");
@@ -49,38 +115,78 @@ pub fn handle_save_as(_frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState
     }
 }
 
-fn save_image(image: &fltk::image::RgbImage, path: &PathBuf) -> bool {
-    // Use data dimensions instead of display dimensions
-    let width = image.data_w() as u32;     // Changed from width()
-    let height = image.data_h() as u32;    // Changed from height()
-    let data = image.to_rgb_data();
+fn save_image(
+    image: &fltk::image::RgbImage,
+    raw: Option<&RawImage>,
+    path: &PathBuf,
+    embedded: Option<&EmbeddedMetadata>,
+    options: &ExportOptions,
+) -> bool {
+    let format = match ExportFormat::from_path(path) {
+        Some(format) => format,
+        None => {
+            println!("Unsupported file type: {:?}", path);
+            return false;
+        }
+    };
 
-    println!("Saving image with dimensions: {}x{}", width, height);
+    let source = pixels_to_save(image, raw, format);
+    match write_image(path, &source, embedded, options) {
+        Ok(()) => {
+            println!("Saved {}x{} {}-bit image", source.width, source.height, source.bit_depth());
+            true
+        },
+        Err(e) => {
+            println!("Failed to save image: {}", e);
+            false
+        }
+    }
+}
 
-    if let Some(img_buffer) = image::RgbImage::from_raw(width, height, data) {
-        let format = match path.extension().and_then(|ext| ext.to_str()) {
-            Some("jpg") | Some("jpeg") => Some(ImageFormat::Jpeg),
-            Some("png") => Some(ImageFormat::Png),
-            Some("tif") | Some("tiff") => Some(ImageFormat::Tiff),
-            _ => None,
-        };
+/// keeps 16-bit/float data when nothing has been edited since loading and
+/// the format can store it, otherwise saves what is on screen
+fn pixels_to_save(image: &fltk::image::RgbImage, raw: Option<&RawImage>, format: ExportFormat) -> RawImage {
+    raw.filter(|r| r.is_high_bit_depth() && format.keeps_high_bit_depth())
+        .filter(|r| r.width as i32 == image.data_w() && r.height as i32 == image.data_h())
+        .filter(|r| !(format == ExportFormat::Png && matches!(r.pixels, RawPixels::F32(_))))
+        .cloned()
+        .unwrap_or_else(|| RawImage::from_rgb_image(image))
+}
 
-        if let Some(format) = format {
-            // Add quality settings for JPEG
-            if format == ImageFormat::Jpeg {
-                if let Err(e) = img_buffer.save_with_format(path, format) {
-                    println!("Failed to save image: {}", e);
-                    return false;
-                }
-            } else {
-                if let Err(e) = img_buffer.save_with_format(path, format) {
-                    println!("Failed to save image: {}", e);
-                    return false;
-                }
-            }
-            println!("Successfully saved image with dimensions: {}x{}", width, height);
-            return true;
+fn write_sidecar_if_enabled(state: &ImageState, path: &PathBuf, embedded: &EmbeddedMetadata) {
+    if state.write_metadata_sidecar {
+        if let Err(e) = write_sidecar(path, embedded) {
+            println!("Failed to write metadata sidecar: {}", e);
         }
     }
-    false
-}
\ No newline at end of file
+}
+
+const STACK_PAGE_RESIZED: &str =
+    "The current page no longer has the size of the other pages.\nUndo the crop or resize, or save it to a single-image format.";
+
+/// the stack with the page on screen written back into it, so edits to that page are
+/// saved. None when the page was cropped or resized on its own
+fn stack_pages_to_save(
+    stack: &ImageStack,
+    image: Option<&fltk::image::RgbImage>,
+    raw: Option<&RawImage>,
+) -> Option<Vec<RawImage>> {
+    // edits drop the raw data, so whatever is on screen is the edited page
+    let current = match (raw, image) {
+        (Some(raw), _) => raw.clone(),
+        (None, Some(image)) => RawImage::from_rgb_image(image),
+        (None, None) => return Some(stack.pages.clone()),
+    };
+    stack.pages_with_current(current)
+}
+
+/// stacks saved as TIFF keep every page, at each page's original bit depth
+fn save_stack(pages: &[RawImage], path: &PathBuf, embedded: &EmbeddedMetadata, options: &ExportOptions) -> bool {
+    match write_tiff_pages(path, pages, Some(embedded), options.tiff_compression) {
+        Ok(()) => true,
+        Err(e) => {
+            println!("Failed to save stack: {}", e);
+            false
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// src/menu/image/display_range.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    window::Window,
+    button::Button,
+    input::FloatInput,
+    frame::Frame,
+    group::{Pack, PackType},
+    dialog::alert,
+    prelude::*,
+};
+
+// Internal state imports
+use crate::state::ImageState;
+
+/// min/max windowing for the on-screen rendering of 16-bit and float images.
+/// the raw values aren't touched, only what gets drawn
+pub fn show_display_range_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let info = match state.try_borrow() {
+        Ok(state_ref) => state_ref.raw_image.as_ref().map(|raw| {
+            (raw.bit_depth(), raw.data_range(), raw.display_min, raw.display_max)
+        }),
+        Err(_) => return,
+    };
+
+    let (bit_depth, (data_min, data_max), display_min, display_max) = match info {
+        Some(info) => info,
+        None => {
+            alert(300, 300, "No raw image data available.\nOpen an image first (edits applied to the 8-bit image discard it).");
+            return;
+        }
+    };
+
+    let mut dialog = Window::default()
+        .with_size(320, 250)
+        .with_label("Display Range");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 300, 230, "");
+    pack.set_spacing(8);
+
+    Frame::default()
+        .with_size(300, 25)
+        .with_label(&format!("{}-bit data, range {:.2} - {:.2}", bit_depth, data_min, data_max));
+
+    Frame::default().with_size(300, 20).with_label("Display minimum:");
+    let mut min_input = FloatInput::default().with_size(300, 25);
+    min_input.set_value(&format!("{:.2}", display_min));
+
+    Frame::default().with_size(300, 20).with_label("Display maximum:");
+    let mut max_input = FloatInput::default().with_size(300, 25);
+    max_input.set_value(&format!("{:.2}", display_max));
+
+    let mut button_pack = Pack::default().with_size(300, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut auto_btn = Button::default().with_size(70, 30).with_label("Auto");
+    let mut apply_btn = Button::default().with_size(70, 30).with_label("Apply");
+    let mut close_btn = Button::default().with_size(70, 30).with_label("Close");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+    dialog.show();
+
+    let mut min_auto = min_input.clone();
+    let mut max_auto = max_input.clone();
+    let state_auto = state.clone();
+    let frame_auto = frame.clone();
+    auto_btn.set_callback(move |_| {
+        if let Some((min, max)) = auto_window(&state_auto) {
+            min_auto.set_value(&format!("{:.2}", min));
+            max_auto.set_value(&format!("{:.2}", max));
+            refresh_display(&frame_auto, &state_auto);
+        }
+    });
+
+    let state_apply = state.clone();
+    let frame_apply = frame.clone();
+    apply_btn.set_callback(move |_| {
+        let min = min_input.value().parse::<f64>();
+        let max = max_input.value().parse::<f64>();
+        if let (Ok(min), Ok(max)) = (min, max) {
+            set_display_range(&state_apply, min, max);
+            refresh_display(&frame_apply, &state_apply);
+        }
+    });
+
+    let mut dialog_close = dialog.clone();
+    close_btn.set_callback(move |_| {
+        dialog_close.hide();
+    });
+
+    while dialog.shown() {
+        fltk::app::wait();
+    }
+}
+
+fn auto_window(state: &Rc<RefCell<ImageState>>) -> Option<(f64, f64)> {
+    let mut state_ref = state.try_borrow_mut().ok()?;
+    let raw = state_ref.raw_image.as_mut()?;
+    raw.auto_window();
+    Some((raw.display_min, raw.display_max))
+}
+
+fn set_display_range(state: &Rc<RefCell<ImageState>>, min: f64, max: f64) {
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        if let Some(raw) = state_ref.raw_image.as_mut() {
+            raw.set_display_range(min, max);
+            println!("Display range set to {:.2} - {:.2}", raw.display_min, raw.display_max);
+        }
+    }
+}
+
+// re-renders the 8-bit display image from the raw data; not an edit, so no history entry
+fn refresh_display(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        let rendered = state_ref.raw_image.as_ref().and_then(|raw| raw.to_rgb_image());
+        if let Some(image) = rendered {
+            state_ref.image = Some(image.clone());
+            frame.borrow_mut().set_image(Some(image));
+            frame.borrow_mut().redraw();
+        }
+    }
+}
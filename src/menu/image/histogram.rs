@@ -0,0 +1,125 @@
+// src/menu/image/histogram.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    window::Window,
+    button::{Button, CheckButton},
+    frame::Frame,
+    group::{Pack, PackType},
+    enums::Align,
+    dialog::alert,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::utils::histogram::{Histogram, HistogramChannel};
+
+const PLOT_WIDTH: u32 = 440;
+const PLOT_HEIGHT: u32 = 220;
+
+fn current_histogram(state: &Rc<RefCell<ImageState>>) -> Option<Histogram> {
+    let state_ref = state.try_borrow().ok()?;
+    state_ref.image.as_ref().map(Histogram::of_image)
+}
+
+/// RGB and luminance histogram of the open image, with min/max/mean per channel.
+/// stays open next to the image, Refresh picks up later edits
+pub fn show_histogram_window(state: &Rc<RefCell<ImageState>>) {
+    let histogram = match current_histogram(state) {
+        Some(histogram) => Rc::new(RefCell::new(histogram)),
+        None => {
+            alert(300, 300, "Please open an image first");
+            return;
+        }
+    };
+
+    let mut window = Window::default()
+        .with_size(PLOT_WIDTH as i32 + 20, 410)
+        .with_label("Histogram");
+
+    let mut pack = Pack::new(10, 10, PLOT_WIDTH as i32, 390, "");
+    pack.set_spacing(6);
+
+    let plot = Frame::default().with_size(PLOT_WIDTH as i32, PLOT_HEIGHT as i32);
+
+    let mut channel_pack = Pack::default().with_size(PLOT_WIDTH as i32, 25);
+    channel_pack.set_type(PackType::Horizontal);
+    channel_pack.set_spacing(5);
+    let checks: Vec<CheckButton> = HistogramChannel::ALL
+        .iter()
+        .map(|channel| {
+            let mut check = CheckButton::default().with_size(90, 25).with_label(channel.label());
+            check.set_checked(*channel != HistogramChannel::Luminance);
+            check
+        })
+        .collect();
+    let log_check = CheckButton::default().with_size(80, 25).with_label("Log scale");
+    channel_pack.end();
+
+    let mut stats = Frame::default().with_size(PLOT_WIDTH as i32, 70);
+    stats.set_align(Align::Left | Align::Inside | Align::Top);
+
+    let mut button_pack = Pack::default().with_size(PLOT_WIDTH as i32, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut refresh_btn = Button::default().with_size(90, 30).with_label("Refresh");
+    let mut close_btn = Button::default().with_size(90, 30).with_label("Close");
+    button_pack.end();
+
+    pack.end();
+    window.end();
+
+    let redraw = {
+        let histogram = histogram.clone();
+        let checks = checks.clone();
+        let log_check = log_check.clone();
+        Rc::new(move || {
+            let histogram = histogram.borrow();
+            let channels: Vec<HistogramChannel> = HistogramChannel::ALL
+                .iter()
+                .zip(checks.iter())
+                .filter(|(_, check)| check.is_checked())
+                .map(|(&channel, _)| channel)
+                .collect();
+            match histogram.render(&channels, PLOT_WIDTH, PLOT_HEIGHT, log_check.is_checked(), true) {
+                Ok(image) => plot.clone().set_image(Some(image)),
+                Err(e) => eprintln!("Failed to draw histogram: {}", e),
+            }
+            plot.clone().redraw();
+
+            let mut lines = vec![format!("{} pixels", histogram.pixel_count())];
+            for channel in HistogramChannel::ALL {
+                if let Some((min, max, mean)) = histogram.stats(channel) {
+                    lines.push(format!("{:<10} min {:>3}   max {:>3}   mean {:>6.1}", channel.label(), min, max, mean));
+                }
+            }
+            stats.clone().set_label(&lines.join("\n"));
+        })
+    };
+    redraw();
+
+    for mut check in checks.into_iter().chain(std::iter::once(log_check)) {
+        let redraw = redraw.clone();
+        check.set_callback(move |_| redraw());
+    }
+
+    let state_refresh = state.clone();
+    let redraw_refresh = redraw.clone();
+    refresh_btn.set_callback(move |_| {
+        if let Some(updated) = current_histogram(&state_refresh) {
+            *histogram.borrow_mut() = updated;
+            redraw_refresh();
+        }
+    });
+
+    let mut window_close = window.clone();
+    close_btn.set_callback(move |_| {
+        window_close.hide();
+    });
+
+    window.show();
+}
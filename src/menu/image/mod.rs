@@ -0,0 +1,10 @@
+// src/menu/image/mod.rs
+pub mod display_range;
+pub mod transform;
+pub mod resize;
+pub mod histogram;
+
+pub use display_range::show_display_range_dialog;
+pub use transform::{handle_transform, show_rotate_dialog};
+pub use resize::show_resize_dialog;
+pub use histogram::show_histogram_window;
@@ -0,0 +1,248 @@
+// src/menu/image/resize.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::{Button, CheckButton},
+    input::FloatInput,
+    frame::Frame,
+    group::{Pack, PackType},
+    menu::Choice,
+    dialog::alert,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::utils::transform::{ResampleFilter, Transform};
+use super::transform::handle_transform;
+
+/// largest side the dialog will produce, to keep a typo from eating all memory
+const MAX_SIDE: f64 = 32768.0;
+
+/// what the two numbers in the resize dialog mean
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResizeMode {
+    /// new width and height in pixels
+    Pixels,
+    /// new width and height as a percentage of the current size
+    Percent,
+    /// new pixel width and height in the calibrated unit, e.g. 0.5 µm per pixel
+    PhysicalSize,
+}
+
+impl ResizeMode {
+    pub const ALL: [ResizeMode; 3] = [ResizeMode::Pixels, ResizeMode::Percent, ResizeMode::PhysicalSize];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResizeMode::Pixels => "Pixels",
+            ResizeMode::Percent => "Percent",
+            ResizeMode::PhysicalSize => "Physical pixel size",
+        }
+    }
+
+    fn field_labels(&self, unit: &str) -> (String, String) {
+        match self {
+            ResizeMode::Pixels => ("Width (pixels):".to_string(), "Height (pixels):".to_string()),
+            ResizeMode::Percent => ("Width (%):".to_string(), "Height (%):".to_string()),
+            ResizeMode::PhysicalSize => (format!("Pixel width ({}):", unit), format!("Pixel height ({}):", unit)),
+        }
+    }
+
+    // what the fields show for the image as it is
+    fn current_values(&self, size: (u32, u32), pixels_per_unit: f64) -> (f64, f64) {
+        match self {
+            ResizeMode::Pixels => (size.0 as f64, size.1 as f64),
+            ResizeMode::Percent => (100.0, 100.0),
+            ResizeMode::PhysicalSize => (1.0 / pixels_per_unit, 1.0 / pixels_per_unit),
+        }
+    }
+}
+
+/// the new size in pixels for a `size` image, None for values that don't make one.
+/// with `keep_aspect` only the first value is used
+pub fn target_size(mode: ResizeMode, values: (f64, f64), keep_aspect: bool, size: (u32, u32), pixels_per_unit: f64) -> Option<(u32, u32)> {
+    let (w, h) = (size.0 as f64, size.1 as f64);
+    let (first, second) = if keep_aspect { (values.0, values.0) } else { values };
+    if !(first > 0.0 && second > 0.0 && first.is_finite() && second.is_finite()) || w == 0.0 || h == 0.0 {
+        return None;
+    }
+
+    let (new_w, new_h) = match mode {
+        ResizeMode::Pixels if keep_aspect => (first, first * h / w),
+        ResizeMode::Pixels => (first, second),
+        ResizeMode::Percent => (w * first / 100.0, h * second / 100.0),
+        ResizeMode::PhysicalSize => {
+            if pixels_per_unit <= 0.0 {
+                return None;
+            }
+            // the image keeps its real extent, w / pixels_per_unit units wide
+            (w / pixels_per_unit / first, h / pixels_per_unit / second)
+        }
+    };
+    let (new_w, new_h) = (new_w.round(), new_h.round());
+    if new_w < 1.0 || new_h < 1.0 || new_w > MAX_SIDE || new_h > MAX_SIDE {
+        return None;
+    }
+    Some((new_w as u32, new_h as u32))
+}
+
+pub fn show_resize_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let (size, pixels_per_unit, unit, calibrated) = match state.try_borrow() {
+        Ok(state_ref) => match &state_ref.image {
+            Some(image) => {
+                let calibration = &state_ref.scientific_state.calibration;
+                (
+                    (image.data_w() as u32, image.data_h() as u32),
+                    calibration.pixels_per_unit as f64,
+                    calibration.unit.clone(),
+                    calibration.is_calibrated(),
+                )
+            }
+            None => {
+                alert(300, 300, "Please open an image first");
+                return;
+            }
+        },
+        Err(_) => return,
+    };
+
+    let mut dialog = Window::default()
+        .with_size(320, 330)
+        .with_label("Resize");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 300, 310, "");
+    pack.set_spacing(6);
+
+    Frame::default()
+        .with_size(300, 20)
+        .with_label(&format!("Current size: {} x {} pixels", size.0, size.1));
+
+    let mut mode_choice = Choice::default().with_size(300, 25);
+    for mode in ResizeMode::ALL {
+        mode_choice.add_choice(mode.label());
+    }
+    mode_choice.set_value(0);
+
+    let (first_label, second_label) = ResizeMode::Pixels.field_labels(&unit);
+    let mut first_frame = Frame::default().with_size(300, 20).with_label(&first_label);
+    let mut first_input = FloatInput::default().with_size(300, 25);
+    let mut second_frame = Frame::default().with_size(300, 20).with_label(&second_label);
+    let mut second_input = FloatInput::default().with_size(300, 25);
+    first_input.set_value(&size.0.to_string());
+    second_input.set_value(&size.1.to_string());
+
+    let mut aspect_check = CheckButton::default().with_size(300, 25).with_label("Keep aspect ratio");
+    aspect_check.set_checked(true);
+    second_input.deactivate();
+
+    Frame::default().with_size(300, 20).with_label("Interpolation:");
+    let mut filter_choice = Choice::default().with_size(300, 25);
+    for filter in ResampleFilter::ALL {
+        filter_choice.add_choice(filter.label());
+    }
+    filter_choice.set_value(2);
+
+    let mut button_pack = Pack::default().with_size(300, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut ok_btn = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel_btn = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+    dialog.show();
+
+    let mut first_mode = first_input.clone();
+    let mut second_mode = second_input.clone();
+    let mut dialog_mode = dialog.clone();
+    mode_choice.set_callback(move |choice| {
+        let mode = ResizeMode::ALL[(choice.value().max(0) as usize).min(ResizeMode::ALL.len() - 1)];
+        if mode == ResizeMode::PhysicalSize && !calibrated {
+            alert(300, 300, "The image has no spatial calibration.\nSet one under Scientific → Measurements first.");
+            choice.set_value(0);
+            return;
+        }
+        let (first_label, second_label) = mode.field_labels(&unit);
+        first_frame.set_label(&first_label);
+        second_frame.set_label(&second_label);
+        let (first, second) = mode.current_values(size, pixels_per_unit);
+        first_mode.set_value(&format!("{}", first));
+        second_mode.set_value(&format!("{}", second));
+        dialog_mode.redraw();
+    });
+
+    let mut second_aspect = second_input.clone();
+    aspect_check.set_callback(move |check| {
+        if check.is_checked() {
+            second_aspect.deactivate();
+        } else {
+            second_aspect.activate();
+        }
+    });
+
+    let frame_ok = frame.clone();
+    let state_ok = state.clone();
+    let mut dialog_ok = dialog.clone();
+    ok_btn.set_callback(move |_| {
+        let mode = ResizeMode::ALL[(mode_choice.value().max(0) as usize).min(ResizeMode::ALL.len() - 1)];
+        let values = (
+            first_input.value().trim().parse::<f64>().unwrap_or(0.0),
+            second_input.value().trim().parse::<f64>().unwrap_or(0.0),
+        );
+        let (width, height) = match target_size(mode, values, aspect_check.is_checked(), size, pixels_per_unit) {
+            Some(target) => target,
+            None => {
+                alert(300, 300, "Please enter a size that gives at least 1 pixel and at most 32768");
+                return;
+            }
+        };
+        let filter = ResampleFilter::ALL[(filter_choice.value().max(0) as usize).min(ResampleFilter::ALL.len() - 1)];
+        dialog_ok.hide();
+        if (width, height) != size {
+            handle_transform(&frame_ok, &state_ok, Transform::Resize { width, height, filter });
+        }
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel_btn.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    while dialog.shown() {
+        app::wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_sizes_keep_the_aspect_ratio_when_asked() {
+        assert_eq!(target_size(ResizeMode::Pixels, (400.0, 0.0), true, (800, 600), 1.0), Some((400, 300)));
+        assert_eq!(target_size(ResizeMode::Pixels, (400.0, 100.0), false, (800, 600), 1.0), Some((400, 100)));
+        assert_eq!(target_size(ResizeMode::Pixels, (0.0, 100.0), false, (800, 600), 1.0), None);
+    }
+
+    #[test]
+    fn percentages_scale_each_side() {
+        assert_eq!(target_size(ResizeMode::Percent, (50.0, 0.0), true, (800, 600), 1.0), Some((400, 300)));
+        assert_eq!(target_size(ResizeMode::Percent, (200.0, 50.0), false, (800, 600), 1.0), Some((1600, 300)));
+    }
+
+    #[test]
+    fn physical_pixel_size_keeps_the_real_extent() {
+        // 2 pixels per µm: 800 px is 400 µm, which at 0.5 µm per pixel is still 800 px
+        assert_eq!(target_size(ResizeMode::PhysicalSize, (0.5, 0.0), true, (800, 600), 2.0), Some((800, 600)));
+        assert_eq!(target_size(ResizeMode::PhysicalSize, (1.0, 0.0), true, (800, 600), 2.0), Some((400, 300)));
+        assert_eq!(target_size(ResizeMode::PhysicalSize, (1.0, 0.0), true, (800, 600), 0.0), None);
+    }
+}
@@ -0,0 +1,118 @@
+// src/menu/image/transform.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::{Button, CheckButton},
+    input::FloatInput,
+    frame::Frame,
+    group::{Pack, PackType},
+    menu::Choice,
+    dialog::alert,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::utils::transform::{ResampleFilter, Transform};
+
+/// applies `transform` to the open image and everything measured on it, as one undo step
+pub fn handle_transform(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, transform: Transform) {
+    let image = match state.try_borrow_mut() {
+        Ok(mut state_ref) => {
+            if state_ref.image.is_none() {
+                alert(300, 300, "Please open an image first");
+                return;
+            }
+            if !state_ref.apply_transform(&transform) {
+                alert(300, 300, "Failed to transform the image");
+                return;
+            }
+            println!("Applied {}", transform.label());
+            state_ref.image.clone()
+        }
+        Err(_) => return,
+    };
+
+    let mut frame_ref = frame.borrow_mut();
+    frame_ref.set_image(image);
+    frame_ref.redraw();
+}
+
+pub fn show_rotate_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    match state.try_borrow() {
+        Ok(state_ref) if state_ref.image.is_some() => {}
+        Ok(_) => {
+            alert(300, 300, "Please open an image first");
+            return;
+        }
+        Err(_) => return,
+    }
+
+    let mut dialog = Window::default()
+        .with_size(320, 210)
+        .with_label("Rotate");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 300, 190, "");
+    pack.set_spacing(8);
+
+    Frame::default().with_size(300, 20).with_label("Angle (degrees, clockwise):");
+    let mut angle_input = FloatInput::default().with_size(300, 25);
+    angle_input.set_value("0");
+
+    let mut expand_check = CheckButton::default()
+        .with_size(300, 25)
+        .with_label("Expand canvas to fit (otherwise corners are cropped)");
+    expand_check.set_checked(true);
+
+    Frame::default().with_size(300, 20).with_label("Interpolation:");
+    let mut filter_choice = Choice::default().with_size(300, 25);
+    for filter in ResampleFilter::ALL {
+        filter_choice.add_choice(filter.label());
+    }
+    filter_choice.set_value(1);
+
+    let mut button_pack = Pack::default().with_size(300, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut ok_btn = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel_btn = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+    dialog.show();
+
+    let frame_ok = frame.clone();
+    let state_ok = state.clone();
+    let mut dialog_ok = dialog.clone();
+    ok_btn.set_callback(move |_| {
+        let degrees = match angle_input.value().trim().parse::<f64>() {
+            Ok(degrees) if degrees.is_finite() => degrees,
+            _ => {
+                alert(300, 300, "Please enter the angle in degrees");
+                return;
+            }
+        };
+        let filter = ResampleFilter::ALL[(filter_choice.value().max(0) as usize).min(ResampleFilter::ALL.len() - 1)];
+        dialog_ok.hide();
+        if degrees.rem_euclid(360.0) != 0.0 {
+            let transform = Transform::Rotate { degrees, expand: expand_check.is_checked(), filter };
+            handle_transform(&frame_ok, &state_ok, transform);
+        }
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel_btn.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    while dialog.shown() {
+        app::wait();
+    }
+}
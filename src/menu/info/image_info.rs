@@ -0,0 +1,210 @@
+// src/menu/info/image_info.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell, path::PathBuf};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::Button,
+    frame::Frame,
+    group::{Pack, PackType},
+    enums::{Align, Font},
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::scientific::io::read_exif_summary;
+use crate::utils::image_info::{channel_stats, format_file_size, FileInfo};
+use crate::utils::raw_image::RawImage;
+
+const WINDOW_WIDTH: i32 = 460;
+/// how often the window checks whether the image changed
+const REFRESH_INTERVAL: f64 = 0.5;
+
+/// everything that changes what the window shows. the image pointer changes with every
+/// edit, undo or newly opened file since the state always gets a new RgbImage, the
+/// history size guards against a freed image's address being reused
+#[derive(Clone, PartialEq)]
+struct Snapshot {
+    image: Option<usize>,
+    history: usize,
+    has_raw: bool,
+    path: Option<PathBuf>,
+    calibration: (u32, String),
+}
+
+impl Snapshot {
+    fn of(state: &ImageState) -> Snapshot {
+        let calibration = &state.scientific_state.calibration;
+        Snapshot {
+            image: state.image.as_ref().map(|image| image.as_image_ptr() as usize),
+            history: state.history.memory_usage(),
+            has_raw: state.raw_image.is_some(),
+            path: state.path.clone(),
+            calibration: (calibration.pixels_per_unit.to_bits(), calibration.unit.clone()),
+        }
+    }
+}
+
+/// the file section, read from disk once per path since it doesn't change with edits
+fn file_text(path: &Option<PathBuf>) -> String {
+    let path = match path {
+        Some(path) => path,
+        None => return "Not saved to a file".to_string(),
+    };
+    let info = FileInfo::read(path);
+
+    let mut lines = vec![
+        format!("Path: {}", info.path.display()),
+        format!("Format: {}", info.format.as_deref().unwrap_or("unknown")),
+        format!("File size: {}", format_file_size(info.file_size)),
+    ];
+    if let Some((w, h)) = info.dimensions {
+        lines.push(format!("Stored size: {} x {} pixels", w, h));
+    }
+    if let Some(color) = &info.color_type {
+        lines.push(format!("Colour type: {}", color));
+    }
+    if let (Some(channels), Some(bits)) = (info.channels, info.bits_per_sample) {
+        lines.push(format!("Original bit depth: {}-bit, {} channel(s)", bits, channels));
+    }
+
+    let exif = read_exif_summary(path);
+    if exif.is_empty() {
+        lines.push("EXIF: none".to_string());
+    } else {
+        lines.push("EXIF:".to_string());
+        lines.extend(exif.into_iter().map(|(label, value)| format!("    {}: {}", label, value)));
+    }
+    lines.join("\n")
+}
+
+/// the current (possibly edited) image: size, channels, calibration and statistics
+fn image_text(state: &ImageState) -> String {
+    let image = match &state.image {
+        Some(image) => image,
+        None => return "No image open".to_string(),
+    };
+    let (width, height) = (image.data_w(), image.data_h());
+
+    // statistics at full bit depth while there is one, of the 8-bit display otherwise
+    let converted;
+    let raw = match &state.raw_image {
+        Some(raw) => raw,
+        None => {
+            converted = RawImage::from_rgb_image(image);
+            &converted
+        }
+    };
+
+    let mut lines = vec![
+        format!("Dimensions: {} x {} pixels", width, height),
+        format!("Channels: {}", raw.channels),
+        format!(
+            "Current bit depth: {}-bit{}",
+            raw.bit_depth(),
+            if state.raw_image.is_none() { " (after 8-bit edits)" } else { "" }
+        ),
+    ];
+
+    let calibration = &state.scientific_state.calibration;
+    if calibration.is_calibrated() && calibration.pixels_per_unit > 0.0 {
+        let ppu = calibration.pixels_per_unit as f64;
+        lines.push(format!("Calibration: {:.4} pixels/{}", ppu, calibration.unit));
+        lines.push(format!(
+            "Physical size: {:.2} x {:.2} {}",
+            width as f64 / ppu,
+            height as f64 / ppu,
+            calibration.unit
+        ));
+    } else {
+        lines.push("Calibration: none (1 pixel = 1 pixel)".to_string());
+    }
+
+    for correction in &state.scientific_state.image_metadata.corrections {
+        lines.push(correction.clone());
+    }
+
+    let names: &[&str] = if raw.channels == 1 { &["Gray"] } else { &["Red", "Green", "Blue"] };
+    lines.push(String::new());
+    lines.push(format!("{:<8}{:>10}{:>10}{:>10}{:>10}", "", "min", "max", "mean", "std"));
+    for (name, stats) in names.iter().zip(channel_stats(raw)) {
+        lines.push(format!(
+            "{:<8}{:>10.1}{:>10.1}{:>10.2}{:>10.2}",
+            name, stats.min, stats.max, stats.mean, stats.std_dev
+        ));
+    }
+    lines.join("\n")
+}
+
+/// file, calibration and per channel statistics of the open image.
+/// stays open and follows edits, undo and newly opened files
+pub fn show_image_info_window(state: &Rc<RefCell<ImageState>>) {
+    let mut window = Window::default()
+        .with_size(WINDOW_WIDTH, 560)
+        .with_label("Image Info");
+
+    let mut pack = Pack::new(10, 10, WINDOW_WIDTH - 20, 540, "");
+    pack.set_spacing(8);
+
+    let mut file_frame = Frame::default().with_size(WINDOW_WIDTH - 20, 260);
+    file_frame.set_align(Align::Left | Align::Inside | Align::Top | Align::Clip);
+
+    let mut image_frame = Frame::default().with_size(WINDOW_WIDTH - 20, 220);
+    image_frame.set_align(Align::Left | Align::Inside | Align::Top | Align::Clip);
+    // keeps the statistics columns lined up
+    image_frame.set_label_font(Font::Courier);
+
+    let mut button_pack = Pack::default().with_size(WINDOW_WIDTH - 20, 30);
+    button_pack.set_type(PackType::Horizontal);
+    let mut close_btn = Button::default().with_size(90, 30).with_label("Close");
+    button_pack.end();
+
+    pack.end();
+    window.end();
+
+    let mut last: Option<Snapshot> = None;
+
+    let mut update = {
+        let state = state.clone();
+        let window = window.clone();
+        move || {
+            let state_ref = match state.try_borrow() {
+                Ok(state_ref) => state_ref,
+                // an edit is in progress, look again next time
+                Err(_) => return,
+            };
+            let snapshot = Snapshot::of(&state_ref);
+            let previous = last.replace(snapshot.clone());
+            if previous.as_ref() == Some(&snapshot) {
+                return;
+            }
+            if previous.map(|p| p.path) != Some(snapshot.path.clone()) {
+                file_frame.set_label(&file_text(&snapshot.path));
+            }
+            image_frame.set_label(&image_text(&state_ref));
+            window.clone().redraw();
+        }
+    };
+    update();
+
+    app::add_timeout3(REFRESH_INTERVAL, {
+        let window = window.clone();
+        move |handle| {
+            if window.shown() {
+                update();
+                app::repeat_timeout3(REFRESH_INTERVAL, handle);
+            }
+        }
+    });
+
+    let mut window_close = window.clone();
+    close_btn.set_callback(move |_| {
+        window_close.hide();
+    });
+
+    window.show();
+}
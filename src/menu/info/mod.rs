@@ -0,0 +1,4 @@
+// src/menu/info/mod.rs
+pub mod image_info;
+
+pub use image_info::show_image_info_window;
@@ -1,4 +1,14 @@
 // src/menu/mod.rs
+#[cfg(feature = "gui")]
 pub mod file;
 pub mod edit;
+#[cfg(feature = "gui")]
 pub mod scientific;
+#[cfg(feature = "gui")]
+pub mod image;
+#[cfg(feature = "gui")]
+pub mod view;
+#[cfg(feature = "gui")]
+pub mod info;
+#[cfg(feature = "gui")]
+pub mod process;
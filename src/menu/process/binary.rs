@@ -0,0 +1,288 @@
+// src/menu/process/binary.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::Button,
+    frame::Frame,
+    group::{Pack, PackType},
+    input::FloatInput,
+    menu::Choice,
+    dialog::{alert, choice2},
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::scientific::analysis::morphology::{Mask, StructuringElement};
+use crate::scientific::tools::interactive::cell_analysis_tool::CellAnalysisState;
+use crate::scientific::types::ROIShape;
+use crate::scientific::ui::cell_analysis::show_statistics_dialog;
+use crate::utils::image::display_image_with_zoom;
+use crate::utils::raw_image::RawImage;
+
+/// the Process→Binary commands, all working on the white objects of a thresholded image
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOperation {
+    Erode,
+    Dilate,
+    Open,
+    Close,
+    FillHoles,
+    RemoveSmall,
+    Outline,
+    DistanceMap,
+}
+
+impl BinaryOperation {
+    pub const ALL: [BinaryOperation; 8] = [
+        BinaryOperation::Erode,
+        BinaryOperation::Dilate,
+        BinaryOperation::Open,
+        BinaryOperation::Close,
+        BinaryOperation::FillHoles,
+        BinaryOperation::RemoveSmall,
+        BinaryOperation::Outline,
+        BinaryOperation::DistanceMap,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BinaryOperation::Erode => "Erode",
+            BinaryOperation::Dilate => "Dilate",
+            BinaryOperation::Open => "Open",
+            BinaryOperation::Close => "Close",
+            BinaryOperation::FillHoles => "Fill Holes",
+            BinaryOperation::RemoveSmall => "Remove Small Objects",
+            BinaryOperation::Outline => "Outline",
+            BinaryOperation::DistanceMap => "Distance Map",
+        }
+    }
+
+    /// the menu entry under Process→Binary
+    pub fn menu_path(&self) -> &'static str {
+        match self {
+            BinaryOperation::Erode => "&Process/Bi&nary/&Erode...",
+            BinaryOperation::Dilate => "&Process/Bi&nary/&Dilate...",
+            BinaryOperation::Open => "&Process/Bi&nary/&Open...",
+            BinaryOperation::Close => "&Process/Bi&nary/&Close...",
+            BinaryOperation::FillHoles => "&Process/Bi&nary/&Fill Holes",
+            BinaryOperation::RemoveSmall => "&Process/Bi&nary/&Remove Small Objects...",
+            BinaryOperation::Outline => "&Process/Bi&nary/Out&line",
+            BinaryOperation::DistanceMap => "&Process/Bi&nary/Distance &Map",
+        }
+    }
+
+    fn uses_element(&self) -> bool {
+        matches!(self, BinaryOperation::Erode | BinaryOperation::Dilate | BinaryOperation::Open | BinaryOperation::Close)
+    }
+}
+
+/// Process→Binary: asks for the structuring element and size, or the smallest object
+/// to keep, then runs `operation` on the open image
+pub fn show_binary_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, operation: BinaryOperation) {
+    if state.borrow().image.is_none() {
+        alert(300, 300, "Please open an image first");
+        return;
+    }
+
+    if !operation.uses_element() && operation != BinaryOperation::RemoveSmall {
+        apply_binary_operation(frame, state, operation, StructuringElement::Square, 1);
+        return;
+    }
+
+    let mut dialog = Window::default()
+        .with_size(320, if operation.uses_element() { 170 } else { 115 })
+        .with_label(operation.label());
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 300, if operation.uses_element() { 150 } else { 95 }, "");
+    pack.set_spacing(5);
+
+    let mut element = None;
+    if operation.uses_element() {
+        Frame::default().with_size(300, 20).with_label("Structuring element:");
+        let mut choice = Choice::default().with_size(300, 25);
+        for item in StructuringElement::ALL.iter() {
+            choice.add_choice(item.label());
+        }
+        choice.set_value(0);
+        element = Some(choice);
+    }
+
+    let label = if operation.uses_element() { "Radius (1 - 25 pixels):" } else { "Smallest object to keep (pixels):" };
+    Frame::default().with_size(300, 20).with_label(label);
+    let mut size = FloatInput::default().with_size(300, 25);
+    size.set_value(if operation.uses_element() { "1" } else { "10" });
+
+    let mut button_pack = Pack::default().with_size(300, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut ok = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+
+    let result: Rc<RefCell<Option<(StructuringElement, u32)>>> = Rc::new(RefCell::new(None));
+
+    let result_ok = result.clone();
+    let mut dialog_ok = dialog.clone();
+    ok.set_callback(move |_| {
+        let value = match size.value().parse::<f32>() {
+            Ok(value) if value >= 1.0 => value.round() as u32,
+            _ => {
+                alert(300, 300, "Please enter a size of at least 1");
+                return;
+            }
+        };
+        let chosen = element.as_ref()
+            .map(|choice| StructuringElement::ALL[(choice.value().max(0) as usize).min(StructuringElement::ALL.len() - 1)])
+            .unwrap_or(StructuringElement::Square);
+        let value = if operation.uses_element() { value.min(25) } else { value };
+        *result_ok.borrow_mut() = Some((chosen, value));
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    let chosen = result.take();
+    if let Some((element, size)) = chosen {
+        apply_binary_operation(frame, state, operation, element, size);
+    }
+}
+
+/// runs `operation` on the mask of the open image as one undo step. `size` is the element
+/// radius, or the smallest object kept by Remove Small Objects
+pub fn apply_binary_operation(
+    frame: &Rc<RefCell<Frame>>,
+    state: &Rc<RefCell<ImageState>>,
+    operation: BinaryOperation,
+    element: StructuringElement,
+    size: u32,
+) {
+    let mut state_ref = match state.try_borrow_mut() {
+        Ok(state_ref) => state_ref,
+        Err(_) => return,
+    };
+    let image = match &state_ref.image {
+        Some(image) => image.clone(),
+        None => return,
+    };
+
+    let mask = match binary_mask(&image) {
+        Some(mask) => mask,
+        None => return,
+    };
+    let (result, distances) = match operation {
+        BinaryOperation::Erode => (mask.erode(element, size), None),
+        BinaryOperation::Dilate => (mask.dilate(element, size), None),
+        BinaryOperation::Open => (mask.open(element, size), None),
+        BinaryOperation::Close => (mask.close(element, size), None),
+        BinaryOperation::FillHoles => (mask.fill_holes(), None),
+        BinaryOperation::RemoveSmall => (mask.remove_small(size as usize), None),
+        BinaryOperation::Outline => (mask.outline(), None),
+        BinaryOperation::DistanceMap => {
+            let distances = mask.distance_map();
+            (mask, Some(distances))
+        }
+    };
+    println!("Binary {}: {} object pixels", operation.label(), result.area());
+
+    let mut new_image = match distances.as_ref().map_or_else(|| result.to_rgb_image(), |d| d.to_rgb_image()) {
+        Some(image) => image,
+        None => return,
+    };
+    state_ref.record_image_edit(operation.label());
+    // the distances are kept at full precision for measurements
+    state_ref.raw_image = distances;
+    state_ref.image = Some(new_image.clone());
+    let zoom = state_ref.zoom;
+    drop(state_ref);
+    display_image_with_zoom(frame, &mut new_image, zoom, state);
+}
+
+/// Process→Binary→Analyze Particles: measures every object of the thresholded image, only
+/// those inside the drawn ROI when there is one, and shows their statistics
+pub fn analyze_particles(state: &Rc<RefCell<ImageState>>) {
+    let measurements = {
+        let mut state_ref = match state.try_borrow_mut() {
+            Ok(state_ref) => state_ref,
+            Err(_) => return,
+        };
+        let image = match &state_ref.image {
+            Some(image) => image.clone(),
+            None => {
+                alert(300, 300, "Please open an image first");
+                return;
+            }
+        };
+        let mut mask = match binary_mask(&image) {
+            Some(mask) => mask,
+            None => return,
+        };
+
+        let roi = &state_ref.scientific_state.current_roi_points;
+        if roi.len() >= 3 {
+            let inside = Mask::from_roi(&ROIShape::Polygon { points: roi.clone() }, mask.width, mask.height);
+            mask = Mask::from_fn(mask.width, mask.height, |x, y| {
+                mask.get(x as i32, y as i32) && inside.get(x as i32, y as i32)
+            });
+        }
+
+        let intensities = particle_intensities(&state_ref, &image, &mask);
+        let scientific = &mut state_ref.scientific_state;
+        let calibration_scale = scientific.calibration.pixels_per_unit as f64;
+        let unit = scientific.calibration.unit.clone();
+        scientific.init_cell_analysis(calibration_scale, unit);
+        let tool = match scientific.cell_analysis_tool.as_mut() {
+            Some(tool) => tool,
+            None => return,
+        };
+        let count = tool.process_mask(&mask, &intensities);
+        println!("Analyze Particles: {} objects", count);
+        tool.get_measurements().to_vec()
+    };
+
+    if measurements.is_empty() {
+        alert(300, 300, "No objects found. Threshold the image so the objects are white first.");
+        return;
+    }
+    show_statistics_dialog(&measurements);
+}
+
+// the objects of the open image. anything but pure black and white was not thresholded
+// yet, so the user is asked before brightness alone decides
+fn binary_mask(image: &fltk::image::RgbImage) -> Option<Mask> {
+    let is_binary = image.to_rgb_data().iter().all(|&v| v == 0 || v == 255);
+    if !is_binary
+        && choice2(300, 300, "The image is not binary. Treat pixels brighter than half as objects?", "Yes", "No", "") != Some(0)
+    {
+        return None;
+    }
+    Some(Mask::from_rgb_image(image))
+}
+
+// the thresholded image only says where the objects are, their intensities come from the
+// active channel when it still holds the data the mask was made from
+fn particle_intensities(state_ref: &ImageState, image: &fltk::image::RgbImage, mask: &Mask) -> RawImage {
+    let scientific = &state_ref.scientific_state;
+    scientific.active_channel
+        .and_then(|i| scientific.channels.get(i))
+        .map(|channel| channel.raw.clone().unwrap_or_else(|| RawImage::from_rgb_image(&channel.image)))
+        .filter(|raw| raw.width == mask.width && raw.height == mask.height)
+        .unwrap_or_else(|| RawImage::from_rgb_image(image))
+}
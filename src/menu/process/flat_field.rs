@@ -0,0 +1,204 @@
+// src/menu/process/flat_field.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell, path::PathBuf};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::{Button, CheckButton},
+    frame::Frame,
+    group::{Pack, PackType},
+    input::Input,
+    dialog::{alert, choice2, FileDialog, FileDialogType},
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::scientific::calibration::{flat_field, FlatFieldCalibration};
+use crate::utils::image::display_image_with_zoom;
+use crate::utils::raw_image::RawImage;
+
+/// a path input with a Browse... button next to it
+fn path_row(label: &str, value: Option<&PathBuf>) -> Input {
+    Frame::default().with_size(360, 20).with_label(label);
+    let mut row = Pack::default().with_size(360, 25);
+    row.set_type(PackType::Horizontal);
+    row.set_spacing(5);
+    let mut input = Input::default().with_size(275, 25);
+    if let Some(path) = value {
+        input.set_value(&path.to_string_lossy());
+    }
+    let mut browse = Button::default().with_size(80, 25).with_label("Browse...");
+    row.end();
+
+    let mut target = input.clone();
+    browse.set_callback(move |_| {
+        let mut dialog = FileDialog::new(FileDialogType::BrowseFile);
+        dialog.set_filter("Image Files\t*.{jpg,jpeg,png,gif,bmp,tif,tiff,webp,pnm,pgm,ppm,pam}");
+        dialog.show();
+        let filename = dialog.filename();
+        if !filename.as_os_str().is_empty() {
+            target.set_value(&filename.to_string_lossy());
+        }
+    });
+    input
+}
+
+/// Process→Flat-Field Correction: registers a flat-field and dark frame for an objective
+/// and optionally corrects the open image with them
+pub fn show_flat_field_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let (objective, existing, registered, has_image) = match state.try_borrow() {
+        Ok(state_ref) => {
+            let sci = &state_ref.scientific_state;
+            let objective = sci.image_metadata.objective.clone()
+                .filter(|o| !o.trim().is_empty())
+                .or_else(|| sci.calibrations.last().map(|c| c.objective.clone()))
+                .unwrap_or_default();
+            let existing = sci.flat_fields.iter().find(|f| f.matches(&objective)).cloned();
+            let registered: Vec<String> = sci.flat_fields.iter().map(|f| f.objective.clone()).collect();
+            (objective, existing, registered, state_ref.image.is_some())
+        }
+        Err(_) => return,
+    };
+
+    let mut dialog = Window::default()
+        .with_size(380, 360)
+        .with_label("Flat-Field Correction");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 360, 340, "");
+    pack.set_spacing(5);
+
+    Frame::default().with_size(360, 20).with_label("Objective:");
+    let mut objective_input = Input::default().with_size(360, 25);
+    objective_input.set_value(&objective);
+
+    let flat_input = path_row("Flat-field image:", existing.as_ref().map(|f| &f.flat_path));
+    let dark_input = path_row("Dark-frame image (optional):", existing.as_ref().and_then(|f| f.dark_path.as_ref()));
+
+    let mut on_open = CheckButton::default()
+        .with_size(360, 25)
+        .with_label("Correct images of this objective when opened");
+    on_open.set_checked(existing.as_ref().map_or(true, |f| f.apply_on_open));
+
+    let mut correct_now = CheckButton::default()
+        .with_size(360, 25)
+        .with_label("Correct the open image now");
+    correct_now.set_checked(has_image);
+    if !has_image {
+        correct_now.deactivate();
+    }
+
+    let registered = if registered.is_empty() { "none".to_string() } else { registered.join(", ") };
+    Frame::default()
+        .with_size(360, 20)
+        .with_label(&format!("Registered: {}", registered.replace('@', "@@")));
+
+    let mut button_pack = Pack::default().with_size(360, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut ok = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+
+    let result: Rc<RefCell<Option<(FlatFieldCalibration, bool)>>> = Rc::new(RefCell::new(None));
+
+    let result_ok = result.clone();
+    let mut dialog_ok = dialog.clone();
+    ok.set_callback(move |_| {
+        let objective = objective_input.value().trim().to_string();
+        let flat_path = PathBuf::from(flat_input.value().trim());
+        let dark = dark_input.value().trim().to_string();
+        if objective.is_empty() {
+            alert(300, 300, "Please name the objective");
+            return;
+        }
+        if !flat_path.is_file() {
+            alert(300, 300, "Please choose a flat-field image");
+            return;
+        }
+        let dark_path = if dark.is_empty() { None } else { Some(PathBuf::from(dark)) };
+        if dark_path.as_ref().is_some_and(|path| !path.is_file()) {
+            alert(300, 300, "The dark-frame image does not exist");
+            return;
+        }
+        let calibration = FlatFieldCalibration::new(objective, flat_path, dark_path, on_open.is_checked());
+        *result_ok.borrow_mut() = Some((calibration, correct_now.is_checked()));
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    let chosen = result.take();
+    if let Some((calibration, correct_now)) = chosen {
+        if let Ok(mut state_ref) = state.try_borrow_mut() {
+            println!("Registered flat-field for {}", calibration.objective);
+            state_ref.scientific_state.add_flat_field(calibration.clone());
+            flat_field::save_registry(&state_ref.scientific_state.flat_fields);
+        }
+        if correct_now {
+            apply_flat_field_correction(frame, state, &calibration);
+        }
+    }
+}
+
+/// corrects the open image with `calibration` as one undo step
+pub fn apply_flat_field_correction(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, calibration: &FlatFieldCalibration) {
+    let mut state_ref = match state.try_borrow_mut() {
+        Ok(state_ref) => state_ref,
+        Err(_) => return,
+    };
+    let source = match (&state_ref.raw_image, &state_ref.image) {
+        (Some(raw), _) => raw.clone(),
+        (None, Some(image)) => RawImage::from_rgb_image(image),
+        (None, None) => return,
+    };
+
+    if flat_field::was_applied(&state_ref.scientific_state.image_metadata.corrections)
+        && choice2(300, 300, "This image was already flat-field corrected. Correct it again?", "Yes", "No", "") != Some(0)
+    {
+        return;
+    }
+
+    let corrected = match calibration.correct(&source) {
+        Ok(corrected) => corrected,
+        Err(e) => {
+            alert(300, 300, &e.to_string());
+            return;
+        }
+    };
+    let mut image = match corrected.to_rgb_image() {
+        Some(image) => image,
+        None => return,
+    };
+
+    let high_bit_depth = state_ref.raw_image.is_some();
+    // the image snapshot carries the metadata, so undo also drops the record
+    state_ref.record_image_edit("Flat-Field Correction");
+    state_ref.scientific_state.record_flat_field(calibration);
+    let metadata = &mut state_ref.scientific_state.image_metadata;
+    if metadata.objective.as_deref().map_or(true, |o| o.trim().is_empty()) {
+        metadata.objective = Some(calibration.objective.clone());
+    }
+    if high_bit_depth {
+        state_ref.raw_image = Some(corrected);
+    }
+    state_ref.image = Some(image.clone());
+    let zoom = state_ref.zoom;
+    drop(state_ref);
+    display_image_with_zoom(frame, &mut image, zoom, state);
+}
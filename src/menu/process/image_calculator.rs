@@ -0,0 +1,254 @@
+// src/menu/process/image_calculator.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell, path::Path};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::Button,
+    frame::Frame,
+    group::{Pack, PackType},
+    menu::Choice,
+    dialog::{alert, FileDialog, FileDialogType},
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::menu::file::open::{load_image_file, open_untitled_image};
+use crate::scientific::layers::Channel;
+use crate::utils::image::display_image_with_zoom;
+use crate::utils::image_math::{calculate, ImageOperation, ResultDepth};
+use crate::utils::raw_image::RawImage;
+
+/// where an operand comes from
+#[derive(Clone, Debug, PartialEq)]
+enum Operand {
+    CurrentImage,
+    Clipboard,
+    Channel(usize),
+    /// asked for once OK is pressed
+    File,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Output {
+    ReplaceImage,
+    NewChannel,
+    NewImage,
+}
+
+impl Output {
+    const ALL: [Output; 3] = [Output::ReplaceImage, Output::NewChannel, Output::NewImage];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Output::ReplaceImage => "Replace current image",
+            Output::NewChannel => "New channel",
+            Output::NewImage => "New untitled image",
+        }
+    }
+}
+
+/// the operands on offer right now with their menu labels
+fn available_operands(state: &ImageState) -> Vec<(Operand, String)> {
+    let mut operands = Vec::new();
+    if state.image.is_some() {
+        let name = state.path.as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        operands.push((Operand::CurrentImage, format!("Current image ({})", name)));
+    }
+    if state.clipboard.is_some() {
+        operands.push((Operand::Clipboard, "Clipboard".to_string()));
+    }
+    for (i, channel) in state.scientific_state.channels.iter().enumerate() {
+        operands.push((Operand::Channel(i), format!("Channel: {}", channel.name)));
+    }
+    operands.push((Operand::File, "Image file...".to_string()));
+    operands
+}
+
+/// the full bit-depth pixels of `operand` and a short name for it
+fn operand_image(state: &ImageState, operand: &Operand) -> Option<(RawImage, String)> {
+    match operand {
+        Operand::CurrentImage => {
+            let raw = match (&state.raw_image, &state.image) {
+                (Some(raw), _) => raw.clone(),
+                (None, Some(image)) => RawImage::from_rgb_image(image),
+                (None, None) => return None,
+            };
+            Some((raw, "image".to_string()))
+        }
+        Operand::Clipboard => state.clipboard.clone().map(|raw| (raw, "clipboard".to_string())),
+        Operand::Channel(i) => state.scientific_state.channels.get(*i).map(|channel| {
+            let raw = channel.raw.clone().unwrap_or_else(|| RawImage::from_rgb_image(&channel.image));
+            (raw, channel.name.clone())
+        }),
+        Operand::File => {
+            let mut dialog = FileDialog::new(FileDialogType::BrowseFile);
+            dialog.set_filter("Image Files\t*.{jpg,jpeg,png,gif,bmp,tif,tiff,webp,pnm,pgm,ppm,pam}");
+            dialog.show();
+            let filename = dialog.filename();
+            if filename.as_os_str().is_empty() {
+                return None;
+            }
+            let (_, raw) = load_image_file(&filename)?;
+            let name = Path::new(&filename)
+                .file_stem()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "file".to_string());
+            Some((raw, name))
+        }
+    }
+}
+
+/// Process→Image Calculator: `first <operation> second` for two of the open image,
+/// the clipboard, the channels or a file, at full bit depth
+pub fn show_image_calculator(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let operands = match state.try_borrow() {
+        Ok(state_ref) => available_operands(&state_ref),
+        Err(_) => return,
+    };
+
+    let mut dialog = Window::default()
+        .with_size(340, 330)
+        .with_label("Image Calculator");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 320, 310, "");
+    pack.set_spacing(5);
+
+    let choice_with = |label: &str, items: &[String], selected: usize| {
+        Frame::default().with_size(320, 20).with_label(label);
+        let mut choice = Choice::default().with_size(320, 25);
+        for item in items {
+            // '/' would open a submenu and '&' mark a shortcut
+            choice.add_choice(&item.replace('/', "\\/").replace('&', "&&"));
+        }
+        choice.set_value(selected.min(items.len().saturating_sub(1)) as i32);
+        choice
+    };
+
+    let labels: Vec<String> = operands.iter().map(|(_, label)| label.clone()).collect();
+    let first = choice_with("Image 1:", &labels, 0);
+    let operations: Vec<String> = ImageOperation::ALL.iter().map(|o| o.label().to_string()).collect();
+    let operation = choice_with("Operation:", &operations, 1);
+    let second = choice_with("Image 2:", &labels, 1);
+    let depth = choice_with("Result:", &["32-bit float".to_string(), "8-bit".to_string()], 0);
+    let outputs: Vec<String> = Output::ALL.iter().map(|o| o.label().to_string()).collect();
+    let output = choice_with("Output:", &outputs, 0);
+
+    let mut button_pack = Pack::default().with_size(320, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut ok = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+
+    type Request = (Operand, ImageOperation, Operand, ResultDepth, Output);
+    let result: Rc<RefCell<Option<Request>>> = Rc::new(RefCell::new(None));
+
+    let result_ok = result.clone();
+    let mut dialog_ok = dialog.clone();
+    ok.set_callback(move |_| {
+        let pick = |choice: &Choice| operands[(choice.value().max(0) as usize).min(operands.len() - 1)].0.clone();
+        let index = |choice: &Choice, len: usize| (choice.value().max(0) as usize).min(len - 1);
+        *result_ok.borrow_mut() = Some((
+            pick(&first),
+            ImageOperation::ALL[index(&operation, ImageOperation::ALL.len())],
+            pick(&second),
+            if depth.value() == 1 { ResultDepth::Eight } else { ResultDepth::Float },
+            Output::ALL[index(&output, Output::ALL.len())],
+        ));
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    let request = result.take();
+    if let Some((first, operation, second, depth, output)) = request {
+        run_calculation(frame, state, (&first, operation, &second), depth, output);
+    }
+}
+
+fn run_calculation(
+    frame: &Rc<RefCell<Frame>>,
+    state: &Rc<RefCell<ImageState>>,
+    (first, operation, second): (&Operand, ImageOperation, &Operand),
+    depth: ResultDepth,
+    output: Output,
+) {
+    let operands = match state.try_borrow() {
+        Ok(state_ref) => operand_image(&state_ref, first).zip(operand_image(&state_ref, second)),
+        Err(_) => return,
+    };
+    let ((a, a_name), (b, b_name)) = match operands {
+        Some(operands) => operands,
+        None => return,
+    };
+
+    let result = match calculate(&a, &b, operation, depth) {
+        Ok(result) => result,
+        Err(e) => {
+            alert(300, 300, &e.to_string());
+            return;
+        }
+    };
+    let title = format!("{} {} {}", a_name, operation.label(), b_name);
+    println!("Image calculator: {} ({}-bit)", title, result.bit_depth());
+
+    match output {
+        Output::NewImage => {
+            open_untitled_image(frame, state, result);
+        }
+        Output::ReplaceImage | Output::NewChannel => {
+            let mut image = match result.to_rgb_image() {
+                Some(image) => image,
+                None => return,
+            };
+            let mut state_ref = match state.try_borrow_mut() {
+                Ok(state_ref) => state_ref,
+                Err(_) => return,
+            };
+            let same_size = state_ref.image.as_ref().map(|i| (i.data_w(), i.data_h())) == Some((result.width as i32, result.height as i32));
+            if output == Output::NewChannel {
+                if !same_size {
+                    alert(300, 300, "A new channel has to be the size of the open image");
+                    return;
+                }
+                let channel = Channel::new(title, image, 550.0, (255, 255, 255)).with_raw(Some(result));
+                state_ref.scientific_state.add_channel(channel);
+                drop(state_ref);
+                frame.borrow_mut().redraw();
+                return;
+            }
+            if !same_size {
+                // a new size can't keep the ROIs and annotations of the old image
+                drop(state_ref);
+                open_untitled_image(frame, state, result);
+                return;
+            }
+            state_ref.record_image_edit(&format!("Image Calculator: {}", operation.label()));
+            state_ref.raw_image = Some(result);
+            state_ref.image = Some(image.clone());
+            let zoom = state_ref.zoom;
+            drop(state_ref);
+            display_image_with_zoom(frame, &mut image, zoom, state);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+// src/menu/process/mod.rs
+pub mod image_calculator;
+pub mod subtract_background;
+pub mod flat_field;
+pub mod binary;
+
+pub use image_calculator::show_image_calculator;
+pub use flat_field::show_flat_field_dialog;
+pub use subtract_background::show_subtract_background_dialog;
+pub use binary::{analyze_particles, show_binary_dialog, BinaryOperation};
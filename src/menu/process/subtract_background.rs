@@ -0,0 +1,289 @@
+// src/menu/process/subtract_background.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    app,
+    window::Window,
+    button::Button,
+    frame::Frame,
+    group::{Pack, PackType},
+    input::FloatInput,
+    menu::Choice,
+    dialog::{alert, choice2},
+    enums::{Color, Event},
+    draw,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::menu::edit::crop::CropSelection;
+use crate::menu::view::zoom::is_pan_button;
+use crate::scientific::analysis::background::{background_surface, subtract_background, Background, BackgroundMethod};
+use crate::scientific::layers::Channel;
+use crate::utils::image::display_image_with_zoom;
+use crate::utils::raw_image::RawImage;
+use crate::utils::{draw_current_image, Viewport};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Output {
+    Subtract,
+    SubtractAndChannel,
+    /// leaves the image alone, CTCF measurements subtract the background instead
+    ChannelOnly,
+}
+
+impl Output {
+    const ALL: [Output; 3] = [Output::Subtract, Output::SubtractAndChannel, Output::ChannelOnly];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Output::Subtract => "Subtract from image",
+            Output::SubtractAndChannel => "Subtract and add background channel",
+            Output::ChannelOnly => "Background channel only (for CTCF)",
+        }
+    }
+}
+
+/// Process→Subtract Background: removes uneven illumination with a rolling ball, sliding
+/// paraboloid, polynomial surface or the mean of a dragged background region
+pub fn show_subtract_background_dialog(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    if state.borrow().image.is_none() {
+        alert(300, 300, "Please open an image first");
+        return;
+    }
+
+    let mut dialog = Window::default()
+        .with_size(320, 310)
+        .with_label("Subtract Background");
+    dialog.make_modal(true);
+
+    let mut pack = Pack::new(10, 10, 300, 290, "");
+    pack.set_spacing(5);
+
+    Frame::default().with_size(300, 20).with_label("Method:");
+    let mut method = Choice::default().with_size(300, 25);
+    method.add_choice("Rolling ball|Sliding paraboloid|Polynomial surface|Background ROI mean");
+    method.set_value(0);
+
+    Frame::default().with_size(300, 20).with_label("Radius (pixels, larger than the objects):");
+    let mut radius = FloatInput::default().with_size(300, 25);
+    radius.set_value("50");
+
+    Frame::default().with_size(300, 20).with_label("Polynomial degree (1 - 4):");
+    let mut degree = FloatInput::default().with_size(300, 25);
+    degree.set_value("2");
+
+    Frame::default().with_size(300, 20).with_label("Output:");
+    let mut output = Choice::default().with_size(300, 25);
+    for item in Output::ALL.iter() {
+        output.add_choice(item.label());
+    }
+    output.set_value(0);
+
+    let mut button_pack = Pack::default().with_size(300, 30);
+    button_pack.set_type(PackType::Horizontal);
+    button_pack.set_spacing(10);
+    let mut ok = Button::default().with_size(90, 30).with_label("OK");
+    let mut cancel = Button::default().with_size(90, 30).with_label("Cancel");
+    button_pack.end();
+
+    pack.end();
+    dialog.end();
+
+    let result: Rc<RefCell<Option<(BackgroundMethod, Output)>>> = Rc::new(RefCell::new(None));
+
+    let result_ok = result.clone();
+    let mut dialog_ok = dialog.clone();
+    ok.set_callback(move |_| {
+        let (radius, degree) = match (radius.value().parse::<f32>(), degree.value().parse::<f32>()) {
+            (Ok(radius), Ok(degree)) if radius >= 1.0 && degree >= 1.0 => (radius, degree.round().min(4.0) as u32),
+            _ => {
+                alert(300, 300, "Please enter a radius of at least 1 and a degree from 1 to 4");
+                return;
+            }
+        };
+        let chosen = match method.value() {
+            1 => BackgroundMethod::SlidingParaboloid { radius },
+            2 => BackgroundMethod::Polynomial { degree },
+            // the region is dragged once the dialog is closed
+            3 => BackgroundMethod::RegionMean { rect: (0, 0, 0, 0) },
+            _ => BackgroundMethod::RollingBall { radius },
+        };
+        let index = (output.value().max(0) as usize).min(Output::ALL.len() - 1);
+        *result_ok.borrow_mut() = Some((chosen, Output::ALL[index]));
+        dialog_ok.hide();
+    });
+
+    let mut dialog_cancel = dialog.clone();
+    cancel.set_callback(move |_| {
+        dialog_cancel.hide();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    let chosen = result.take();
+    match chosen {
+        Some((BackgroundMethod::RegionMean { .. }, output)) => start_background_selection(frame, state, output),
+        Some((method, output)) => apply_background_subtraction(frame, state, &method, output),
+        None => {}
+    }
+}
+
+/// subtracts the background at the image's own bit depth and/or adds it as a channel
+fn apply_background_subtraction(
+    frame: &Rc<RefCell<Frame>>,
+    state: &Rc<RefCell<ImageState>>,
+    method: &BackgroundMethod,
+    output: Output,
+) {
+    let mut state_ref = match state.try_borrow_mut() {
+        Ok(state_ref) => state_ref,
+        Err(_) => return,
+    };
+    let source = match (&state_ref.raw_image, &state_ref.image) {
+        (Some(raw), _) => raw.clone(),
+        (None, Some(image)) => RawImage::from_rgb_image(image),
+        (None, None) => return,
+    };
+
+    let (corrected, mut background) = subtract_background(&source, method);
+    // shown on the same scale as the image it was estimated from
+    background.set_display_range(source.display_min, source.display_max);
+    println!("Subtract background: {}", method.label());
+
+    if output != Output::Subtract {
+        if let Some(image) = background.to_rgb_image() {
+            let name = format!("Background ({})", method.label());
+            let channel = Channel::new(name, image, 550.0, (255, 255, 255)).with_raw(Some(background.clone()));
+            state_ref.scientific_state.add_channel(channel);
+        }
+    }
+
+    if output == Output::ChannelOnly {
+        state_ref.scientific_state.measurement_background = Some(match method {
+            BackgroundMethod::RegionMean { .. } => Background::Level(background.intensity(0, 0)),
+            _ => background_surface(&background),
+        });
+        drop(state_ref);
+        frame.borrow_mut().redraw();
+        return;
+    }
+
+    let mut image = match corrected.to_rgb_image() {
+        Some(image) => image,
+        None => return,
+    };
+    let high_bit_depth = state_ref.raw_image.is_some();
+    state_ref.record_image_edit("Subtract Background");
+    if high_bit_depth {
+        state_ref.raw_image = Some(corrected);
+    }
+    state_ref.image = Some(image.clone());
+    // the background is gone from the image now, there is nothing left to correct for
+    state_ref.scientific_state.measurement_background = None;
+    let zoom = state_ref.zoom;
+    drop(state_ref);
+    display_image_with_zoom(frame, &mut image, zoom, state);
+}
+
+/// lets the user drag a region that contains background only, its mean is subtracted
+fn start_background_selection(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, output: Output) {
+    let mut state_ref = state.borrow_mut();
+    if let Some(img) = &state_ref.image {
+        let frame_ref = frame.borrow();
+        let selection = CropSelection::new(img.data_w(), img.data_h(), frame_ref.w(), frame_ref.h());
+        state_ref.crop_selection = Some(selection);
+    }
+    drop(state_ref);
+
+    let frame_clone = frame.clone();
+    let state_clone = state.clone();
+    let mut frame = frame.borrow_mut();
+
+    let draw_callback = {
+        let state_clone = state_clone.clone();
+        move |f: &mut Frame| {
+            let dimensions = match state_clone.try_borrow() {
+                Ok(state_ref) => {
+                    draw_current_image(f, &state_ref);
+                    state_ref.crop_selection.as_ref().map(|s| (s.is_selecting, s.get_dimensions()))
+                }
+                Err(_) => None,
+            };
+
+            if let Some((true, (x, y, w, h))) = dimensions {
+                draw::set_draw_color(Color::Cyan);
+                draw::set_line_style(draw::LineStyle::Dash, 2);
+                draw::draw_rect(x, y, w, h);
+                draw::set_line_style(draw::LineStyle::Solid, 0);
+            }
+        }
+    };
+
+    frame.draw(draw_callback);
+
+    let handle_callback = move |f: &mut Frame, ev: Event| -> bool {
+        match ev {
+            Event::Push | Event::Drag | Event::Released if is_pan_button() => false,
+            Event::Push => {
+                if let Ok(mut state) = state_clone.try_borrow_mut() {
+                    let viewport = Viewport::current(f, &state);
+                    if let Some(selection) = &mut state.crop_selection {
+                        selection.reset();
+                        selection.set_viewport(viewport);
+                        selection.start_x = app::event_x();
+                        selection.start_y = app::event_y();
+                        selection.is_selecting = true;
+                        f.redraw();
+                    }
+                }
+                true
+            },
+            Event::Drag => {
+                if let Ok(mut state) = state_clone.try_borrow_mut() {
+                    if let Some(selection) = &mut state.crop_selection {
+                        selection.end_x = app::event_x();
+                        selection.end_y = app::event_y();
+                        f.redraw();
+                    }
+                }
+                true
+            },
+            Event::Released => {
+                let mut rect = None;
+                if let Ok(mut state) = state_clone.try_borrow_mut() {
+                    if let Some(selection) = &mut state.crop_selection {
+                        selection.is_selecting = false;
+                        selection.end_x = app::event_x();
+                        selection.end_y = app::event_y();
+                        let (_, _, w, h) = selection.get_dimensions();
+                        if w > 5 && h > 5 {
+                            rect = Some(selection.get_image_dimensions());
+                        }
+                    }
+                    state.crop_selection = None;
+                }
+
+                let confirmed = rect.filter(|_| {
+                    choice2(300, 300, "Use the selected area as background?", "Yes", "No", "") == Some(0)
+                });
+                if let Some(rect) = confirmed {
+                    apply_background_subtraction(&frame_clone, &state_clone, &BackgroundMethod::RegionMean { rect }, output);
+                }
+
+                f.redraw();
+                true
+            },
+            _ => false,
+        }
+    };
+
+    frame.handle(handle_callback);
+}
@@ -48,7 +48,8 @@ pub fn setup_cell_analysis_menu(menu: &mut MenuBar, frame: &Rc<RefCell<Frame>>,
                     
                     println!("Storing base image and creating channel");
                     // Store base image - this will also create the channel
-                    state_ref.scientific_state.store_base_image(img);
+                    let raw = state_ref.raw_image.clone();
+                    state_ref.scientific_state.store_base_image(img, raw);
                     
                     println!("Initializing cell analysis");
                     // Initialize cell analysis using the trait method
@@ -80,7 +81,8 @@ pub fn setup_cell_analysis_menu(menu: &mut MenuBar, frame: &Rc<RefCell<Frame>>,
                     let calibration_scale = state_ref.scientific_state.calibration.pixels_per_unit as f64;
                     let unit = state_ref.scientific_state.calibration.unit.clone();
                     
-                    state_ref.scientific_state.store_base_image(img);
+                    let raw = state_ref.raw_image.clone();
+                    state_ref.scientific_state.store_base_image(img, raw);
                     state_ref.scientific_state.init_cell_analysis(calibration_scale, unit);
                     state_ref.scientific_state.start_cell_analysis(CellMeasurementMode::Batch);
                 }
@@ -227,6 +229,7 @@ pub fn setup_cell_analysis_menu(menu: &mut MenuBar, frame: &Rc<RefCell<Frame>>,
                                         // Re-borrow to update state
                                         if let Ok(mut state_ref) = state_events.try_borrow_mut() {
                                             println!("Adding measurement and annotation to state");
+                                            state_ref.record_annotation_edit("Add Cell Measurement");
                                             state_ref.scientific_state.add_cell_measurement(measurement);
                                             state_ref.scientific_state.add_annotation(annotation);
                                         }
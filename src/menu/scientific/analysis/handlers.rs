@@ -39,7 +39,8 @@ pub fn handle_cell_analysis(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Image
             
             println!("Storing base image and creating channel");
             // Store base image - this will also create the channel
-            state_ref.scientific_state.store_base_image(img);
+            let raw = state_ref.raw_image.clone();
+            state_ref.scientific_state.store_base_image(img, raw);
             
             println!("Initializing cell analysis");
             // Initialize cell analysis using the trait method
@@ -63,7 +64,8 @@ pub fn handle_batch_analysis(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Imag
             let calibration_scale = state_ref.scientific_state.calibration.pixels_per_unit as f64;
             let unit = state_ref.scientific_state.calibration.unit.clone();
             
-            state_ref.scientific_state.store_base_image(img);
+            let raw = state_ref.raw_image.clone();
+            state_ref.scientific_state.store_base_image(img, raw);
             state_ref.scientific_state.init_cell_analysis(calibration_scale, unit);
             state_ref.scientific_state.start_cell_analysis(CellMeasurementMode::Batch);
 
@@ -0,0 +1,209 @@
+// src/menu/view/filmstrip.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell, path::PathBuf, sync::{atomic::{AtomicUsize, Ordering}, mpsc::TryRecvError, Arc}};
+
+// FLTK imports
+use fltk::{
+    app,
+    button::Button,
+    enums::{Color, ColorDepth, FrameType},
+    frame::Frame,
+    group::{Scroll, ScrollType},
+    image::RgbImage,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::menu::file::open::open_image_path;
+use crate::utils::image::display_image_with_zoom;
+use crate::utils::thumbnails::{spawn_thumbnail_job, THUMBNAIL_SIZE};
+use crate::utils::{MENU_HEIGHT, STATUS_BAR_HEIGHT};
+
+/// id the strip is registered under so open/navigation code can reach it
+pub const FILMSTRIP_ID: &str = "filmstrip";
+/// room for a thumbnail button plus the horizontal scrollbar
+pub const FILMSTRIP_HEIGHT: i32 = THUMBNAIL_SIZE as i32 + 28;
+const BUTTON_SIZE: i32 = THUMBNAIL_SIZE as i32 + 8;
+const BUTTON_SPACING: i32 = 4;
+
+/// creates the (hidden) strip along the bottom of the main window.
+/// call while the window is still taking children
+pub fn create_filmstrip(x: i32, y: i32, w: i32) -> Scroll {
+    let mut scroll = Scroll::new(x, y, w, FILMSTRIP_HEIGHT, "").with_id(FILMSTRIP_ID);
+    scroll.set_type(ScrollType::Horizontal);
+    scroll.end();
+    scroll.hide();
+    scroll
+}
+
+pub fn handle_toggle_filmstrip(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, visible: bool) {
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        state_ref.filmstrip.visible = visible;
+    }
+    update_filmstrip(frame, state);
+}
+
+/// syncs the strip with the folder listing: shows or hides it, rebuilds it
+/// when the folder changed and highlights the open file
+pub fn update_filmstrip(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let mut scroll = match app::widget_from_id::<Scroll>(FILMSTRIP_ID) {
+        Some(scroll) => scroll,
+        None => return,
+    };
+
+    let (visible, files, current, job) = {
+        let mut state_ref = match state.try_borrow_mut() {
+            Ok(state_ref) => state_ref,
+            Err(_) => return,
+        };
+        let visible = state_ref.filmstrip.visible;
+        let files = state_ref.folder.files();
+        // a new job id also tells the previous thumbnail thread to stop
+        let job = if visible && files != state_ref.filmstrip.files {
+            state_ref.filmstrip.files = files.clone();
+            Some(state_ref.filmstrip.generation.fetch_add(1, Ordering::Relaxed) + 1)
+        } else {
+            None
+        };
+        (visible, files, state_ref.folder.current_index(), job)
+    };
+
+    layout(frame, state, &mut scroll, visible);
+    if !visible {
+        return;
+    }
+    if let Some(job) = job {
+        let generation = match state.try_borrow() {
+            Ok(state_ref) => state_ref.filmstrip.generation.clone(),
+            Err(_) => return,
+        };
+        rebuild(frame, state, &mut scroll, files, generation, job);
+    }
+    highlight(&mut scroll, current);
+}
+
+// gives the frame the space the strip doesn't use and redraws the image at the new size
+fn layout(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, scroll: &mut Scroll, visible: bool) {
+    let window_h = match scroll.parent() {
+        Some(parent) => parent.h(),
+        None => return,
+    };
+    let frame_h = window_h - MENU_HEIGHT - STATUS_BAR_HEIGHT - if visible { FILMSTRIP_HEIGHT } else { 0 };
+
+    if visible {
+        scroll.show();
+    } else {
+        scroll.hide();
+    }
+
+    let resized = {
+        let mut frame_ref = frame.borrow_mut();
+        if frame_ref.h() == frame_h {
+            false
+        } else {
+            let (x, y, w) = (frame_ref.x(), frame_ref.y(), frame_ref.w());
+            frame_ref.resize(x, y, w, frame_h);
+            true
+        }
+    };
+    if resized {
+        let current = state.try_borrow().ok().and_then(|state_ref| state_ref.image.clone().map(|img| (img, state_ref.zoom)));
+        if let Some((mut image, zoom)) = current {
+            display_image_with_zoom(frame, &mut image, zoom, state);
+        }
+        if let Some(mut parent) = scroll.parent() {
+            parent.redraw();
+        }
+    }
+}
+
+fn rebuild(
+    frame: &Rc<RefCell<Frame>>,
+    state: &Rc<RefCell<ImageState>>,
+    scroll: &mut Scroll,
+    files: Vec<PathBuf>,
+    generation: Arc<AtomicUsize>,
+    job: usize,
+) {
+    scroll.clear();
+    scroll.begin();
+    let mut buttons = Vec::with_capacity(files.len());
+    for (i, path) in files.iter().enumerate() {
+        let mut button = Button::new(
+            scroll.x() + BUTTON_SPACING + i as i32 * (BUTTON_SIZE + BUTTON_SPACING),
+            scroll.y() + BUTTON_SPACING,
+            BUTTON_SIZE,
+            BUTTON_SIZE,
+            "",
+        );
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        button.set_tooltip(&name);
+
+        let frame = frame.clone();
+        let state = state.clone();
+        let path = path.clone();
+        button.set_callback(move |_| {
+            // opening can rebuild the strip, which must not happen inside a button's own callback
+            let frame = frame.clone();
+            let state = state.clone();
+            let path = path.clone();
+            app::add_timeout3(0.0, move |_| {
+                open_image_path(&frame, &state, &path);
+            });
+        });
+        buttons.push(button);
+    }
+    scroll.end();
+    scroll.scroll_to(0, 0);
+    scroll.redraw();
+
+    let receiver = spawn_thumbnail_job(files, generation.clone(), job);
+    app::add_timeout3(0.05, move |handle| {
+        loop {
+            // a newer rebuild owns the strip now and these buttons are gone
+            if generation.load(Ordering::Relaxed) != job {
+                return;
+            }
+            match receiver.try_recv() {
+                Ok((index, thumbnail)) => {
+                    let (w, h) = thumbnail.dimensions();
+                    if let (Some(button), Ok(image)) =
+                        (buttons.get_mut(index), RgbImage::new(&thumbnail, w as i32, h as i32, ColorDepth::Rgb8))
+                    {
+                        button.set_image(Some(image));
+                        button.redraw();
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+        app::repeat_timeout3(0.05, handle);
+    });
+}
+
+fn highlight(scroll: &mut Scroll, current: Option<usize>) {
+    // the scrollbars are the last children, the buttons come first in file order
+    let count = (scroll.children() - 2).max(0);
+    for i in 0..count {
+        if let Some(mut button) = scroll.child(i) {
+            if Some(i as usize) == current {
+                button.set_frame(FrameType::DownBox);
+                button.set_color(Color::Selection);
+            } else {
+                button.set_frame(FrameType::UpBox);
+                button.set_color(Color::BackGround);
+            }
+            button.redraw();
+        }
+    }
+
+    // keep the open file roughly centred
+    if let Some(index) = current {
+        let offset = index as i32 * (BUTTON_SIZE + BUTTON_SPACING) - (scroll.w() - BUTTON_SIZE) / 2;
+        let max_offset = (count * (BUTTON_SIZE + BUTTON_SPACING) + BUTTON_SPACING - scroll.w()).max(0);
+        scroll.scroll_to(offset.clamp(0, max_offset), 0);
+    }
+}
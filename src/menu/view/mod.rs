@@ -0,0 +1,5 @@
+// src/menu/view/mod.rs
+pub mod navigation;
+pub mod filmstrip;
+pub mod zoom;
+pub mod status_bar;
@@ -0,0 +1,71 @@
+// src/menu/view/navigation.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{frame::Frame, dialog::alert};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::state::folder_listing::FolderSort;
+use crate::menu::file::open::{open_image_path, OpenOutcome};
+use super::filmstrip::update_filmstrip;
+
+pub fn handle_next_image(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    step_through_folder(frame, state, 1);
+}
+
+pub fn handle_previous_image(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    step_through_folder(frame, state, -1);
+}
+
+/// opens the next (`step` 1) or previous (-1) image, moving past files that fail to
+/// open until one does, the user cancels, or the folder runs out
+fn step_through_folder(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, step: isize) {
+    let mut skipped = Vec::new();
+    let mut offset = step;
+    loop {
+        let path = match state.try_borrow() {
+            Ok(state_ref) => state_ref.folder.neighbour(offset),
+            Err(_) => return,
+        };
+        let path = match path {
+            Some(path) => path,
+            None => {
+                if skipped.is_empty() {
+                    println!("No {} image in this folder", if step > 0 { "next" } else { "previous" });
+                }
+                break;
+            }
+        };
+        match open_image_path(frame, state, &path) {
+            OpenOutcome::Opened | OpenOutcome::Cancelled => break,
+            OpenOutcome::Failed => skipped.push(path),
+        }
+        offset += step;
+    }
+
+    if skipped.is_empty() {
+        return;
+    }
+
+    // the files were probably moved or deleted since the folder was read
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        state_ref.folder.refresh();
+    }
+    update_filmstrip(frame, state);
+
+    let names: Vec<String> = skipped.iter()
+        .map(|path| path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default())
+        .collect();
+    println!("Skipped files that could not be opened: {:?}", skipped);
+    alert(300, 300, &format!("Skipped {} file(s) that could not be opened:\n{}", names.len(), names.join("\n")));
+}
+
+pub fn handle_sort_folder(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, sort: FolderSort) {
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        state_ref.folder.set_sort(sort);
+    }
+    update_filmstrip(frame, state);
+}
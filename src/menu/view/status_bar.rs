@@ -0,0 +1,169 @@
+// src/menu/view/status_bar.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    app,
+    enums::{Align, Event, FrameType},
+    frame::Frame,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::utils::raw_image::RawImage;
+use crate::utils::{Viewport, STATUS_BAR_HEIGHT};
+
+/// id the bar is registered under so the inspector can reach it
+pub const STATUS_BAR_ID: &str = "status_bar";
+
+/// creates the bar along the bottom of the main window.
+/// call while the window is still taking children
+pub fn create_status_bar(x: i32, y: i32, w: i32) -> Frame {
+    let mut bar = Frame::new(x, y, w, STATUS_BAR_HEIGHT, "").with_id(STATUS_BAR_ID);
+    bar.set_frame(FrameType::ThinDownBox);
+    bar.set_align(Align::Left | Align::Inside | Align::Clip);
+    bar.set_label_size(12);
+    bar
+}
+
+/// puts a one-off message in the bar, it stays until the pointer moves over the image
+pub fn show_status(text: &str) {
+    if let Some(mut bar) = app::widget_from_id::<Frame>(STATUS_BAR_ID) {
+        bar.set_label(text);
+        bar.redraw();
+    }
+}
+
+/// 8-bit images have no raw copy, reading their RgbImage means copying all of it.
+/// keeps that copy until the image (identified by its pointer) is replaced
+#[derive(Default)]
+struct DisplayCopy {
+    source: Option<usize>,
+    raw: Option<RawImage>,
+}
+
+impl DisplayCopy {
+    fn get(&mut self, image: &fltk::image::RgbImage) -> &RawImage {
+        let source = image.as_image_ptr() as usize;
+        if self.source != Some(source) {
+            self.source = Some(source);
+            self.raw = None;
+        }
+        self.raw.get_or_insert_with(|| RawImage::from_rgb_image(image))
+    }
+}
+
+/// shows position, calibrated position, pixel values and zoom under the mouse.
+/// fed from the main window's handler so it keeps reporting whichever tool owns the frame
+#[derive(Default)]
+pub struct PixelInspector {
+    image: DisplayCopy,
+    channels: Vec<DisplayCopy>,
+}
+
+impl PixelInspector {
+    /// updates the bar for `ev`, never consumes the event
+    pub fn handle_event(&mut self, frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, ev: Event) {
+        let text = match ev {
+            Event::Move | Event::Drag | Event::MouseWheel | Event::Released => {
+                let (x, y) = app::event_coords();
+                match state.try_borrow() {
+                    Ok(state_ref) => self.describe(&frame.borrow(), &state_ref, x, y),
+                    // a tool is busy with the state, keep the last readout
+                    Err(_) => return,
+                }
+            }
+            Event::Leave => String::new(),
+            _ => return,
+        };
+        if let Some(mut bar) = app::widget_from_id::<Frame>(STATUS_BAR_ID) {
+            if bar.label() != text {
+                bar.set_label(&text);
+            }
+        }
+    }
+
+    fn describe(&mut self, frame: &Frame, state: &ImageState, x: i32, y: i32) -> String {
+        let image = match &state.image {
+            Some(image) => image,
+            None => return String::new(),
+        };
+        let viewport = match Viewport::current(frame, state) {
+            Some(viewport) => viewport,
+            None => return String::new(),
+        };
+        let zoom = format!("zoom {:.0}%", viewport.scale() * 100.0);
+        if !viewport.contains(x, y) {
+            return zoom;
+        }
+
+        let (px, py) = viewport.to_pixel(x, y);
+        let mut parts = vec![format!("x {}  y {}", px, py)];
+
+        let calibration = &state.scientific_state.calibration;
+        if calibration.is_calibrated() {
+            let (rx, ry) = calibration.pixel_to_real((px, py));
+            parts.push(format!("{:.2}, {:.2} {}", rx, ry, calibration.unit));
+        }
+
+        let raw = match &state.raw_image {
+            Some(raw) => raw,
+            None => self.image.get(image),
+        };
+        parts.push(format_values(&raw.pixel(px, py)));
+
+        // channels are the same size as the image, each one read at full depth when it has it
+        let channels = &state.scientific_state.channels;
+        self.channels.resize_with(channels.len(), DisplayCopy::default);
+        for (channel, copy) in channels.iter().zip(self.channels.iter_mut()) {
+            if !channel.visible {
+                continue;
+            }
+            let raw = match &channel.raw {
+                Some(raw) => raw,
+                None => copy.get(&channel.image),
+            };
+            parts.push(format!("{} {}", channel.name, format_values(&raw.pixel(px, py))));
+        }
+
+        parts.push(zoom);
+        parts.join("   |   ")
+    }
+}
+
+/// "I 1023" for one channel, "R 12 G 40 B 255" for three; floats keep 3 decimals
+pub fn format_values(values: &[f64]) -> String {
+    let format = |value: f64| {
+        if value.fract() == 0.0 {
+            format!("{}", value)
+        } else {
+            format!("{:.3}", value)
+        }
+    };
+    match values {
+        [] => String::new(),
+        [intensity] => format!("I {}", format(*intensity)),
+        _ => ["R", "G", "B"]
+            .iter()
+            .zip(values.iter())
+            .map(|(label, &value)| format!("{} {}", label, format(value)))
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_labelled_by_channel_count() {
+        assert_eq!(format_values(&[1023.0]), "I 1023");
+        assert_eq!(format_values(&[12.0, 40.0, 255.0]), "R 12 G 40 B 255");
+        assert_eq!(format_values(&[0.25, 0.5, 1.0]), "R 0.250 G 0.500 B 1");
+        assert_eq!(format_values(&[]), "");
+    }
+}
@@ -0,0 +1,118 @@
+// src/menu/view/zoom.rs
+
+// Standard library imports
+use std::{rc::Rc, cell::RefCell};
+
+// FLTK imports
+use fltk::{
+    app::{self, MouseButton},
+    enums::Event,
+    frame::Frame,
+    prelude::*,
+};
+
+// Internal imports
+use crate::state::ImageState;
+use crate::utils::Viewport;
+
+/// zoom factor for one wheel notch or one Zoom In/Out
+const ZOOM_STEP: f32 = 1.25;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZoomPreset {
+    /// whole image visible
+    Fit,
+    /// frame covered, the image may be cut off
+    Fill,
+    /// one image pixel per screen pixel
+    Actual,
+}
+
+pub fn handle_zoom_preset(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, preset: ZoomPreset) {
+    update_viewport(frame, state, |viewport| {
+        let zoom = match preset {
+            ZoomPreset::Fit => 1.0,
+            ZoomPreset::Fill => viewport.fill_zoom(),
+            ZoomPreset::Actual => viewport.actual_size_zoom(),
+        };
+        let centred = Viewport { pan: (0.0, 0.0), ..viewport };
+        centred.zoomed_at(zoom, centred.centre())
+    });
+}
+
+pub fn handle_zoom_in(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    zoom_by(frame, state, ZOOM_STEP, None);
+}
+
+pub fn handle_zoom_out(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    zoom_by(frame, state, 1.0 / ZOOM_STEP, None);
+}
+
+/// true while the middle button, which always pans, is the one held down.
+/// tools check this so a pan doesn't also start a selection
+pub fn is_pan_button() -> bool {
+    app::event_mouse_button() == MouseButton::Middle
+}
+
+/// wheel zoom around the cursor and middle-button drag panning. installed on the
+/// window so it keeps working whichever tool owns the frame's handler.
+/// `drag` holds the last mouse position while a pan is in progress
+pub fn handle_viewport_event(
+    frame: &Rc<RefCell<Frame>>,
+    state: &Rc<RefCell<ImageState>>,
+    ev: Event,
+    drag: &mut Option<(i32, i32)>,
+) -> bool {
+    match ev {
+        Event::MouseWheel => {
+            let steps = app::event_dy_value();
+            if steps == 0 || !app::event_inside_widget(&*frame.borrow()) {
+                return false;
+            }
+            zoom_by(frame, state, ZOOM_STEP.powi(-steps), Some(app::event_coords()));
+            true
+        }
+        Event::Push if is_pan_button() && app::event_inside_widget(&*frame.borrow()) => {
+            *drag = Some(app::event_coords());
+            true
+        }
+        Event::Drag => match *drag {
+            Some((last_x, last_y)) => {
+                let (x, y) = app::event_coords();
+                pan_by(frame, state, (x - last_x) as f64, (y - last_y) as f64);
+                *drag = Some((x, y));
+                true
+            }
+            None => false,
+        },
+        Event::Released => drag.take().is_some(),
+        _ => false,
+    }
+}
+
+fn zoom_by(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, factor: f32, anchor: Option<(i32, i32)>) {
+    update_viewport(frame, state, |viewport| {
+        viewport.zoomed_at(viewport.zoom * factor, anchor.unwrap_or_else(|| viewport.centre()))
+    });
+}
+
+fn pan_by(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, dx: f64, dy: f64) {
+    update_viewport(frame, state, |viewport| viewport.panned(dx, dy));
+}
+
+// applies `change` to the open image's viewport and stores the resulting zoom and pan
+fn update_viewport(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, change: impl FnOnce(Viewport) -> Viewport) {
+    {
+        let mut state_ref = match state.try_borrow_mut() {
+            Ok(state_ref) => state_ref,
+            Err(_) => return,
+        };
+        let viewport = match Viewport::current(&frame.borrow(), &state_ref) {
+            Some(viewport) => change(viewport),
+            None => return,
+        };
+        state_ref.zoom = viewport.zoom;
+        state_ref.pan = viewport.pan;
+    }
+    frame.borrow_mut().redraw();
+}
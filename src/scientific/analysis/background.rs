@@ -0,0 +1,392 @@
+// src/scientific/analysis/background.rs
+
+use crate::utils::raw_image::{RawImage, RawPixels};
+
+/// how the uneven background under the objects is estimated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackgroundMethod {
+    /// ImageJ's rolling ball: the surface a ball of `radius` reaches rolling under the
+    /// intensity landscape. objects should be smaller than the ball
+    RollingBall { radius: f32 },
+    /// like the rolling ball with a paraboloid of the same curvature; no shrinking so it
+    /// is exact and fast for large radii
+    SlidingParaboloid { radius: f32 },
+    /// least-squares polynomial surface of `degree` 1 - 4 in x and y, refitted with the
+    /// bright (foreground) points left out
+    Polynomial { degree: u32 },
+    /// the mean of an (x, y, w, h) region that contains background only
+    RegionMean { rect: (i32, i32, i32, i32) },
+}
+
+impl BackgroundMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackgroundMethod::RollingBall { .. } => "Rolling ball",
+            BackgroundMethod::SlidingParaboloid { .. } => "Sliding paraboloid",
+            BackgroundMethod::Polynomial { .. } => "Polynomial surface",
+            BackgroundMethod::RegionMean { .. } => "Background ROI mean",
+        }
+    }
+}
+
+/// the background estimated for one plane of `width` x `height` values, row-major
+pub fn estimate_background(values: &[f32], width: usize, height: usize, method: &BackgroundMethod) -> Vec<f32> {
+    if values.is_empty() || width == 0 || height == 0 {
+        return Vec::new();
+    }
+    match *method {
+        BackgroundMethod::RollingBall { radius } => rolling_ball(values, width, height, radius.max(1.0)),
+        BackgroundMethod::SlidingParaboloid { radius } => sliding_paraboloid(values, width, height, radius.max(1.0)),
+        BackgroundMethod::Polynomial { degree } => polynomial_surface(values, width, height, degree.clamp(1, 4)),
+        BackgroundMethod::RegionMean { rect } => vec![region_mean(values, width, height, rect); values.len()],
+    }
+}
+
+/// what the CTCF measurement subtracts from the integrated density of an ROI
+#[derive(Clone, Debug, PartialEq)]
+pub enum Background {
+    /// one level everywhere, the classic "mean of a background ROI"
+    Level(f64),
+    /// a per pixel estimate, e.g. from a rolling ball, row-major
+    Surface { width: u32, height: u32, values: Vec<f32> },
+}
+
+impl Background {
+    /// the background intensity at (x, y), 0 outside a surface
+    pub fn at(&self, x: i32, y: i32) -> f64 {
+        match self {
+            Background::Level(level) => *level,
+            Background::Surface { width, height, values } => {
+                if x < 0 || y < 0 || x as u32 >= *width || y as u32 >= *height {
+                    return 0.0;
+                }
+                values.get(y as usize * *width as usize + x as usize).map(|&v| v as f64).unwrap_or(0.0)
+            }
+        }
+    }
+}
+
+/// subtracts the background from every channel of `raw` at its own bit depth.
+/// returns the corrected image and the (32-bit) background estimate
+pub fn subtract_background(raw: &RawImage, method: &BackgroundMethod) -> (RawImage, RawImage) {
+    let (width, height) = (raw.width as usize, raw.height as usize);
+    let channels = raw.channels.max(1);
+    let mut corrected = vec![0f32; width * height * channels];
+    let mut background = vec![0f32; width * height * channels];
+
+    for channel in 0..channels {
+        let plane: Vec<f32> = (0..width * height)
+            .map(|i| raw.value((i % width) as i32, (i / width) as i32, channel).unwrap_or(0.0) as f32)
+            .collect();
+        let estimate = estimate_background(&plane, width, height, method);
+        for (i, (&value, &bg)) in plane.iter().zip(estimate.iter()).enumerate() {
+            corrected[i * channels + channel] = value - bg;
+            background[i * channels + channel] = bg;
+        }
+    }
+
+    let pixels = match raw.pixels {
+        RawPixels::U8(_) => RawPixels::U8(corrected.iter().map(|v| v.round().clamp(0.0, 255.0) as u8).collect()),
+        RawPixels::U16(_) => RawPixels::U16(corrected.iter().map(|v| v.round().clamp(0.0, 65535.0) as u16).collect()),
+        RawPixels::F32(_) => RawPixels::F32(corrected),
+    };
+    (
+        RawImage::new(raw.width, raw.height, channels, pixels),
+        RawImage::new(raw.width, raw.height, channels, RawPixels::F32(background)),
+    )
+}
+
+/// the per pixel background intensity (channel average) of an estimate, for CTCF
+pub fn background_surface(background: &RawImage) -> Background {
+    Background::Surface {
+        width: background.width,
+        height: background.height,
+        values: background.intensities().into_iter().map(|v| v as f32).collect(),
+    }
+}
+
+fn region_mean(values: &[f32], width: usize, height: usize, (x, y, w, h): (i32, i32, i32, i32)) -> f32 {
+    let (left, top) = (x.clamp(0, width as i32) as usize, y.clamp(0, height as i32) as usize);
+    let (right, bottom) = ((x + w).clamp(0, width as i32) as usize, (y + h).clamp(0, height as i32) as usize);
+    let (mut sum, mut count) = (0f64, 0usize);
+    for row in top..bottom {
+        for &value in &values[row * width + left..row * width + right] {
+            sum += value as f64;
+            count += 1;
+        }
+    }
+    if count == 0 { 0.0 } else { (sum / count as f64) as f32 }
+}
+
+/// d[p] = min over q of f[q] + curvature * (p - q)², the lower envelope of parabolas
+/// (Felzenszwalb & Huttenlocher), linear in the length of `f`
+pub(crate) fn lower_envelope(f: &[f32], curvature: f32) -> Vec<f32> {
+    let n = f.len();
+    let mut out = vec![0f32; n];
+    if n == 0 {
+        return out;
+    }
+    // apexes of the parabolas on the envelope and where each one takes over
+    let mut apex = vec![0usize; n];
+    let mut start = vec![0f32; n + 1];
+    let mut k = 0;
+    start[0] = f32::NEG_INFINITY;
+    start[1] = f32::INFINITY;
+    let intersection = |q: usize, v: usize| {
+        let (q_f, v_f) = (q as f32, v as f32);
+        ((f[q] + curvature * q_f * q_f) - (f[v] + curvature * v_f * v_f)) / (2.0 * curvature * (q_f - v_f))
+    };
+    for q in 1..n {
+        let mut s = intersection(q, apex[k]);
+        while s <= start[k] {
+            k -= 1;
+            s = intersection(q, apex[k]);
+        }
+        k += 1;
+        apex[k] = q;
+        start[k] = s;
+        start[k + 1] = f32::INFINITY;
+    }
+    k = 0;
+    for (p, value) in out.iter_mut().enumerate() {
+        while start[k + 1] < p as f32 {
+            k += 1;
+        }
+        let d = p as f32 - apex[k] as f32;
+        *value = f[apex[k]] + curvature * d * d;
+    }
+    out
+}
+
+// the envelope along every row, then every column
+fn envelope_2d(values: &[f32], width: usize, height: usize, curvature: f32) -> Vec<f32> {
+    let mut out = values.to_vec();
+    for row in out.chunks_mut(width) {
+        let enveloped = lower_envelope(row, curvature);
+        row.copy_from_slice(&enveloped);
+    }
+    let mut column = vec![0f32; height];
+    for x in 0..width {
+        for y in 0..height {
+            column[y] = out[y * width + x];
+        }
+        for (y, value) in lower_envelope(&column, curvature).into_iter().enumerate() {
+            out[y * width + x] = value;
+        }
+    }
+    out
+}
+
+/// grayscale opening with the paraboloid z = -d² / (2 radius): the erosion and the
+/// dilation are both separable lower envelopes
+fn sliding_paraboloid(values: &[f32], width: usize, height: usize, radius: f32) -> Vec<f32> {
+    let curvature = 0.5 / radius;
+    let eroded = envelope_2d(values, width, height, curvature);
+    let negated: Vec<f32> = eroded.iter().map(|v| -v).collect();
+    envelope_2d(&negated, width, height, curvature).into_iter().map(|v| -v).collect()
+}
+
+/// grayscale opening with a ball. like ImageJ, large balls roll over a copy shrunk
+/// by taking block minima and the result is interpolated back up
+fn rolling_ball(values: &[f32], width: usize, height: usize, radius: f32) -> Vec<f32> {
+    let shrink = match radius {
+        r if r <= 10.0 => 1,
+        r if r <= 30.0 => 2,
+        r if r <= 100.0 => 4,
+        _ => 8,
+    };
+    let (small_w, small_h) = (width.div_ceil(shrink), height.div_ceil(shrink));
+    let mut small = vec![f32::INFINITY; small_w * small_h];
+    for y in 0..height {
+        for x in 0..width {
+            let cell = &mut small[(y / shrink) * small_w + x / shrink];
+            *cell = cell.min(values[y * width + x]);
+        }
+    }
+
+    // ball heights below its top, in shrunken pixels
+    let reach = (radius / shrink as f32).floor().max(1.0) as i32;
+    let mut ball = Vec::new();
+    for dy in -reach..=reach {
+        for dx in -reach..=reach {
+            let d2 = ((dx * dx + dy * dy) as usize * shrink * shrink) as f32;
+            if d2 <= radius * radius {
+                ball.push((dx, dy, (radius * radius - d2).sqrt() - radius));
+            }
+        }
+    }
+
+    let pass = |source: &[f32], erode: bool| -> Vec<f32> {
+        let mut out = vec![0f32; source.len()];
+        for y in 0..small_h as i32 {
+            for x in 0..small_w as i32 {
+                let mut best = if erode { f32::INFINITY } else { f32::NEG_INFINITY };
+                for &(dx, dy, z) in &ball {
+                    let (sx, sy) = (x + dx, y + dy);
+                    if sx < 0 || sy < 0 || sx >= small_w as i32 || sy >= small_h as i32 {
+                        continue;
+                    }
+                    let value = source[sy as usize * small_w + sx as usize];
+                    best = if erode { best.min(value - z) } else { best.max(value + z) };
+                }
+                out[y as usize * small_w + x as usize] = best;
+            }
+        }
+        out
+    };
+    let opened = pass(&pass(&small, true), false);
+
+    if shrink == 1 {
+        return opened;
+    }
+    // bilinear back to full size between block centres, never above the image itself
+    let sample = |x: usize, y: usize| opened[y.min(small_h - 1) * small_w + x.min(small_w - 1)];
+    let mut out = vec![0f32; width * height];
+    for y in 0..height {
+        let fy = ((y as f32 + 0.5) / shrink as f32 - 0.5).max(0.0);
+        let (y0, wy) = (fy.floor() as usize, fy.fract());
+        for x in 0..width {
+            let fx = ((x as f32 + 0.5) / shrink as f32 - 0.5).max(0.0);
+            let (x0, wx) = (fx.floor() as usize, fx.fract());
+            let top = sample(x0, y0) * (1.0 - wx) + sample(x0 + 1, y0) * wx;
+            let bottom = sample(x0, y0 + 1) * (1.0 - wx) + sample(x0 + 1, y0 + 1) * wx;
+            out[y * width + x] = (top * (1.0 - wy) + bottom * wy).min(values[y * width + x]);
+        }
+    }
+    out
+}
+
+/// fits sum c_ij x^i y^j (i + j <= degree) to a grid of samples, three times, each
+/// time leaving out the samples more than one standard deviation above the surface
+fn polynomial_surface(values: &[f32], width: usize, height: usize, degree: u32) -> Vec<f32> {
+    let terms: Vec<(i32, i32)> = (0..=degree as i32)
+        .flat_map(|i| (0..=degree as i32 - i).map(move |j| (i, j)))
+        .collect();
+    // coordinates scaled to -1..1 keep the normal equations well conditioned
+    let scale = |p: usize, len: usize| if len > 1 { 2.0 * p as f64 / (len - 1) as f64 - 1.0 } else { 0.0 };
+    let basis = |x: usize, y: usize| -> Vec<f64> {
+        let (u, v) = (scale(x, width), scale(y, height));
+        terms.iter().map(|&(i, j)| u.powi(i) * v.powi(j)).collect()
+    };
+
+    let step = ((width * height) as f64 / 4096.0).sqrt().max(1.0) as usize;
+    let mut samples: Vec<(Vec<f64>, f64)> = (0..height)
+        .step_by(step)
+        .flat_map(|y| (0..width).step_by(step).map(move |x| (x, y)))
+        .map(|(x, y)| (basis(x, y), values[y * width + x] as f64))
+        .collect();
+
+    let mut coefficients = vec![0f64; terms.len()];
+    for _ in 0..3 {
+        if samples.len() < terms.len() {
+            break;
+        }
+        coefficients = match least_squares(&samples, terms.len()) {
+            Some(coefficients) => coefficients,
+            None => break,
+        };
+        let evaluate = |b: &[f64]| b.iter().zip(coefficients.iter()).map(|(b, c)| b * c).sum::<f64>();
+        let residuals: Vec<f64> = samples.iter().map(|(b, v)| v - evaluate(b)).collect();
+        let sigma = (residuals.iter().map(|r| r * r).sum::<f64>() / residuals.len() as f64).sqrt();
+        samples = samples.into_iter().zip(residuals).filter(|(_, r)| *r <= sigma).map(|(s, _)| s).collect();
+    }
+
+    let mut out = vec![0f32; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            out[y * width + x] = basis(x, y).iter().zip(coefficients.iter()).map(|(b, c)| b * c).sum::<f64>() as f32;
+        }
+    }
+    out
+}
+
+/// solves the normal equations by Gaussian elimination, None when they are singular
+fn least_squares(samples: &[(Vec<f64>, f64)], n: usize) -> Option<Vec<f64>> {
+    let mut matrix = vec![vec![0f64; n + 1]; n];
+    for (basis, value) in samples {
+        for row in 0..n {
+            for col in 0..n {
+                matrix[row][col] += basis[row] * basis[col];
+            }
+            matrix[row][n] += basis[row] * value;
+        }
+    }
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for row in lower.iter_mut() {
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot_value) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+    let mut solution = vec![0f64; n];
+    for row in (0..n).rev() {
+        let known: f64 = (row + 1..n).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (matrix[row][n] - known) / matrix[row][row];
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a tilted background with a small bright square on it
+    fn tilted_with_spot(width: usize, height: usize) -> Vec<f32> {
+        let mut values: Vec<f32> = (0..width * height).map(|i| 20.0 + (i % width) as f32 * 0.5).collect();
+        for y in 10..14 {
+            for x in 10..14 {
+                values[y * width + x] += 100.0;
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn envelope_matches_brute_force() {
+        let f = [5.0, 1.0, 8.0, 0.0, 3.0, 9.0];
+        let envelope = lower_envelope(&f, 0.5);
+        for (p, &value) in envelope.iter().enumerate() {
+            let expected = f.iter().enumerate()
+                .map(|(q, &fq)| fq + 0.5 * (p as f32 - q as f32).powi(2))
+                .fold(f32::INFINITY, f32::min);
+            assert!((value - expected).abs() < 1e-4, "{} at {}", value, p);
+        }
+    }
+
+    #[test]
+    fn ball_and_paraboloid_remove_the_gradient_but_keep_the_spot() {
+        let (width, height) = (40, 30);
+        let values = tilted_with_spot(width, height);
+        for method in [
+            BackgroundMethod::RollingBall { radius: 8.0 },
+            BackgroundMethod::SlidingParaboloid { radius: 8.0 },
+            BackgroundMethod::Polynomial { degree: 1 },
+        ] {
+            let background = estimate_background(&values, width, height, &method);
+            // flat areas come out near zero, the spot keeps most of its height
+            let flat = values[25 * width + 30] - background[25 * width + 30];
+            let spot = values[12 * width + 12] - background[12 * width + 12];
+            assert!(flat.abs() < 2.0, "{:?} left {} on the background", method, flat);
+            assert!(spot > 90.0, "{:?} left {} of the spot", method, spot);
+        }
+    }
+
+    #[test]
+    fn region_mean_and_ctcf_level() {
+        let values = vec![10.0, 20.0, 30.0, 40.0];
+        let background = estimate_background(&values, 2, 2, &BackgroundMethod::RegionMean { rect: (0, 0, 2, 1) });
+        assert_eq!(background, vec![15.0; 4]);
+        assert_eq!(Background::Level(15.0).at(5, 5), 15.0);
+        let surface = Background::Surface { width: 2, height: 2, values };
+        assert_eq!(surface.at(1, 1), 40.0);
+        assert_eq!(surface.at(2, 0), 0.0);
+    }
+}
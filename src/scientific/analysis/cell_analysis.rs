@@ -1,12 +1,20 @@
+#[cfg(feature = "gui")]
 use fltk::image::RgbImage;
 use crate::scientific::{
-    types::{ROIShape, MeasurementTool},
+    types::MeasurementTool,
+    analysis::Mask,
+};
+#[cfg(feature = "gui")]
+use crate::scientific::{
+    types::ROIShape,
     layers::{Annotation, AnnotationType},
     analysis::IntensityProfile,
 };
+use crate::utils::raw_image::RawImage;
 use chrono::Utc;
+use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CellMeasurement {
     pub timestamp: chrono::DateTime<Utc>,
     pub area: f64,
@@ -81,6 +89,7 @@ impl CellAnalyzer {
         }
     }
 
+    #[cfg(feature = "gui")]
     pub fn analyze_roi(&mut self, roi: &ROIShape, intensity_profile: &IntensityProfile) -> Option<CellMeasurement> {
         let (area_pixels, perimeter_pixels) = match roi {
             ROIShape::Polygon { points } => self.calculate_polygon_metrics(points),
@@ -116,6 +125,34 @@ impl CellAnalyzer {
         Some(measurement)
     }
 
+    /// measures every object of a binary mask, intensities taken from `intensities`.
+    /// the perimeter counts the pixel sides between object and background
+    pub fn analyze_mask(&mut self, mask: &Mask, intensities: &RawImage) -> Vec<CellMeasurement> {
+        let mut measurements = Vec::new();
+        for object in mask.objects() {
+            let perimeter_pixels: usize = object.iter()
+                .map(|&(x, y)| [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().filter(|&&(dx, dy)| !mask.get(x + dx, y + dy)).count())
+                .sum();
+            let values: Vec<f64> = object.iter().map(|&(x, y)| intensities.intensity(x, y)).collect();
+            let mean_intensity = values.iter().sum::<f64>() / values.len() as f64;
+            let min_intensity = values.iter().cloned().fold(f64::MAX, f64::min);
+            let max_intensity = values.iter().cloned().fold(f64::MIN, f64::max);
+
+            let measurement = CellMeasurement::new(
+                object.len() as f64 / (self.calibration_scale * self.calibration_scale),
+                perimeter_pixels as f64 / self.calibration_scale,
+                mean_intensity,
+                min_intensity,
+                max_intensity,
+                self.calibration_unit.clone(),
+            );
+            self.measurements.push(measurement.clone());
+            measurements.push(measurement);
+        }
+        measurements
+    }
+
+    #[cfg(feature = "gui")]
     fn calculate_polygon_metrics(&self, points: &[(i32, i32)]) -> (f64, f64) {
         if points.len() < 3 {
             return (0.0, 0.0);
@@ -140,6 +177,7 @@ impl CellAnalyzer {
         (area, perimeter)
     }
 
+    #[cfg(feature = "gui")]
     fn calculate_ellipse_metrics(&self, width: i32, height: i32) -> (f64, f64) {
         let a = width as f64 / 2.0;
         let b = height as f64 / 2.0;
@@ -154,6 +192,7 @@ impl CellAnalyzer {
         (area, perimeter)
     }
 
+    #[cfg(feature = "gui")]
     fn calculate_rectangle_metrics(&self, width: i32, height: i32) -> (f64, f64) {
         let w = width as f64;
         let h = height as f64;
@@ -170,6 +209,7 @@ impl CellAnalyzer {
         self.measurements.clear();
     }
 
+    #[cfg(feature = "gui")]
     pub fn create_measurement_annotation(&self, measurement: &CellMeasurement, roi: &ROIShape) -> Annotation {
         let name = format!("Cell Measurement {}", Utc::now().format("%H:%M:%S"));
         
@@ -189,4 +229,42 @@ impl CellAnalyzer {
             },
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::raw_image::RawPixels;
+
+    #[test]
+    fn mask_objects_are_measured_separately() {
+        let rows = [
+            "##....",
+            "##....",
+            "......",
+            "...###",
+        ];
+        let mask = Mask::from_fn(6, 4, |x, y| rows[y as usize].as_bytes()[x as usize] == b'#');
+        let values: Vec<u16> = (0..24).map(|i| i * 100).collect();
+        let intensities = RawImage::new(6, 4, 1, RawPixels::U16(values));
+
+        // 2 pixels per µm
+        let mut analyzer = CellAnalyzer::new(2.0, "µm".to_string());
+        let mut measurements = analyzer.analyze_mask(&mask, &intensities);
+        assert_eq!(measurements.len(), 2);
+        measurements.sort_by(|a, b| a.area.partial_cmp(&b.area).unwrap());
+
+        let bar = &measurements[0];
+        assert!((bar.area - 0.75).abs() < 1e-9);
+        assert!((bar.perimeter - 4.0).abs() < 1e-9);
+        assert_eq!((bar.min_intensity, bar.max_intensity), (2100.0, 2300.0));
+        assert!((bar.mean_intensity - 2200.0).abs() < 1e-9);
+
+        let square = &measurements[1];
+        assert!((square.area - 1.0).abs() < 1e-9);
+        assert!((square.perimeter - 4.0).abs() < 1e-9);
+        assert!((square.mean_intensity - 350.0).abs() < 1e-9);
+        assert_eq!(square.calibration_unit, "µm");
+
+        assert_eq!(analyzer.get_measurements().len(), 2);
+    }
+}
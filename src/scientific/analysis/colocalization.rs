@@ -1,5 +1,3 @@
-use fltk::image::RgbImage;
-use fltk::prelude::*;  
 use crate::scientific::layers::Channel;
 use std::collections::HashMap;
 
@@ -23,17 +21,17 @@ impl ColocalizationAnalysis {
         }
     }
 
+    // raw channel values; all the coefficients here are scale invariant so
+    // 16-bit and float data don't need normalising first
     fn get_intensity_vectors(channel1: &Channel, channel2: &Channel) -> (Vec<f32>, Vec<f32>) {
-        let data1 = channel1.image.to_rgb_data();
-        let data2 = channel2.image.to_rgb_data();
+        let values1 = channel1.intensities();
+        let values2 = channel2.intensities();
         let mut intensities1 = Vec::new();
         let mut intensities2 = Vec::new();
 
-        for (chunk1, chunk2) in data1.chunks(3).zip(data2.chunks(3)) {
-            let intensity1 = (chunk1[0] as f32 + chunk1[1] as f32 + chunk1[2] as f32) / (3.0 * 255.0);
-            let intensity2 = (chunk2[0] as f32 + chunk2[1] as f32 + chunk2[2] as f32) / (3.0 * 255.0);
-            intensities1.push(intensity1);
-            intensities2.push(intensity2);
+        for (intensity1, intensity2) in values1.iter().zip(values2.iter()) {
+            intensities1.push(*intensity1 as f32);
+            intensities2.push(*intensity2 as f32);
         }
 
         (intensities1, intensities2)
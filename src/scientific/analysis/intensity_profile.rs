@@ -1,5 +1,3 @@
-use fltk::image::RgbImage;
-use fltk::prelude::*;  
 use crate::scientific::layers::Channel;
 pub struct IntensityProfile {
     pub x_values: Vec<f32>,
@@ -43,24 +41,14 @@ impl IntensityProfile {
             self.x_values.push(i as f32);
             
             for (idx, channel) in channels.iter().enumerate() {
-                let intensity = self.sample_intensity(&channel.image, x as i32, y as i32);
+                let intensity = self.sample_intensity(channel, x as i32, y as i32);
                 self.intensities[idx].push(intensity);
             }
         }
     }
 
-    fn sample_intensity(&self, image: &RgbImage, x: i32, y: i32) -> f32 {
-        if x < 0 || y < 0 || x >= image.data_w() as i32 || y >= image.data_h() as i32 {
-            return 0.0;
-        }
-
-        let idx = (y * image.data_w() as i32 + x) as usize * 3;
-        let data = image.to_rgb_data();
-        
-        if idx + 2 < data.len() {
-            (data[idx] as f32 + data[idx + 1] as f32 + data[idx + 2] as f32) / (3.0 * 255.0)
-        } else {
-            0.0
-        }
+    // raw units (0-255 for 8-bit, 0-65535 for 16-bit, as stored for float)
+    fn sample_intensity(&self, channel: &Channel, x: i32, y: i32) -> f32 {
+        channel.intensity_at(x, y) as f32
     }
 }
\ No newline at end of file
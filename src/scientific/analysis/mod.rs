@@ -1,9 +1,16 @@
+#[cfg(feature = "gui")]
 pub mod intensity_profile;
+#[cfg(feature = "gui")]
 pub mod colocalization;
 pub mod cell_statistics;
 pub mod cell_analysis;
+pub mod background;
+pub mod morphology;
 
+#[cfg(feature = "gui")]
 pub use intensity_profile::*;
+#[cfg(feature = "gui")]
 pub use colocalization::*;
 pub use cell_analysis::*;
-pub use cell_statistics::*;
\ No newline at end of file
+pub use cell_statistics::*;
+pub use morphology::{Mask, StructuringElement};
\ No newline at end of file
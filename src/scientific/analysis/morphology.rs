@@ -0,0 +1,369 @@
+// src/scientific/analysis/morphology.rs
+
+#[cfg(feature = "gui")]
+use fltk::{enums::ColorDepth, image::RgbImage, prelude::ImageExt};
+use crate::scientific::analysis::background::lower_envelope;
+use crate::scientific::types::ROIShape;
+use crate::utils::raw_image::{RawImage, RawPixels};
+
+/// the neighbourhood an erosion or dilation looks at
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StructuringElement {
+    Square,
+    Disk,
+    Cross,
+}
+
+impl StructuringElement {
+    pub const ALL: [StructuringElement; 3] = [
+        StructuringElement::Square,
+        StructuringElement::Disk,
+        StructuringElement::Cross,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StructuringElement::Square => "Square",
+            StructuringElement::Disk => "Disk",
+            StructuringElement::Cross => "Cross",
+        }
+    }
+
+    /// (dx, dy) of every pixel in the element of `radius`, a radius of 1 is 3 x 3
+    pub fn offsets(&self, radius: u32) -> Vec<(i32, i32)> {
+        let r = radius as i32;
+        let mut offsets = Vec::new();
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let inside = match self {
+                    StructuringElement::Square => true,
+                    // the half pixel keeps a radius 1 disk from being a cross
+                    StructuringElement::Disk => ((dx * dx + dy * dy) as f32) <= (r as f32 + 0.5).powi(2),
+                    StructuringElement::Cross => dx == 0 || dy == 0,
+                };
+                if inside {
+                    offsets.push((dx, dy));
+                }
+            }
+        }
+        offsets
+    }
+}
+
+/// a binary image: true for object (white) pixels, row-major
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mask {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<bool>,
+}
+
+impl Mask {
+    /// an empty mask
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, pixels: vec![false; width as usize * height as usize] }
+    }
+
+    pub fn from_fn(width: u32, height: u32, f: impl Fn(u32, u32) -> bool) -> Self {
+        let pixels = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| f(x, y)).collect();
+        Self { width, height, pixels }
+    }
+
+    /// objects are the bright pixels, e.g. the white ones after Threshold
+    #[cfg(feature = "gui")]
+    pub fn from_rgb_image(image: &RgbImage) -> Self {
+        Self::from_intensities(&RawImage::from_rgb_image(image), 127.0)
+    }
+
+    /// objects are the pixels brighter than `threshold`
+    pub fn from_intensities(raw: &RawImage, threshold: f64) -> Self {
+        Self::from_fn(raw.width, raw.height, |x, y| raw.intensity(x as i32, y as i32) > threshold)
+    }
+
+    /// the pixels inside an ROI, placed the way `MeasurementCalculator` measures it.
+    /// a line has no inside
+    pub fn from_roi(shape: &ROIShape, width: u32, height: u32) -> Self {
+        match shape {
+            ROIShape::Polygon { points } => {
+                Self::from_fn(width, height, |x, y| point_in_polygon(x as f64 + 0.5, y as f64 + 0.5, points))
+            }
+            ROIShape::Rectangle { width: w, height: h } => {
+                Self::from_fn(width, height, |x, y| (x as i32) < *w && (y as i32) < *h)
+            }
+            ROIShape::Ellipse { width: w, height: h } => {
+                let (a, b) = (*w as f64 / 2.0, *h as f64 / 2.0);
+                Self::from_fn(width, height, |x, y| {
+                    let (dx, dy) = ((x as f64 + 0.5 - a) / a, (y as f64 + 0.5 - b) / b);
+                    a > 0.0 && b > 0.0 && dx * dx + dy * dy <= 1.0
+                })
+            }
+            ROIShape::Line { .. } => Self::new(width, height),
+        }
+    }
+
+    /// white objects on black
+    #[cfg(feature = "gui")]
+    pub fn to_rgb_image(&self) -> Option<RgbImage> {
+        let data: Vec<u8> = self.pixels.iter().flat_map(|&on| [if on { 255 } else { 0 }; 3]).collect();
+        RgbImage::new(&data, self.width as i32, self.height as i32, ColorDepth::Rgb8).ok()
+    }
+
+    /// false outside the mask
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        self.index(x, y).map(|i| self.pixels[i]).unwrap_or(false)
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, value: bool) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = value;
+        }
+    }
+
+    /// number of object pixels
+    pub fn area(&self) -> usize {
+        self.pixels.iter().filter(|&&on| on).count()
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width as usize + x as usize)
+    }
+
+    /// keeps the pixels whose whole neighbourhood is object. pixels beyond the image
+    /// edge are ignored, so objects touching it are not eaten away from that side
+    pub fn erode(&self, element: StructuringElement, radius: u32) -> Mask {
+        let offsets = element.offsets(radius);
+        let (width, height) = (self.width as i32, self.height as i32);
+        Mask::from_fn(self.width, self.height, |x, y| {
+            let (x, y) = (x as i32, y as i32);
+            self.get(x, y) && offsets.iter().all(|&(dx, dy)| {
+                let (nx, ny) = (x + dx, y + dy);
+                nx < 0 || ny < 0 || nx >= width || ny >= height || self.get(nx, ny)
+            })
+        })
+    }
+
+    /// grows the objects by the neighbourhood
+    pub fn dilate(&self, element: StructuringElement, radius: u32) -> Mask {
+        let offsets = element.offsets(radius);
+        Mask::from_fn(self.width, self.height, |x, y| {
+            offsets.iter().any(|&(dx, dy)| self.get(x as i32 + dx, y as i32 + dy))
+        })
+    }
+
+    /// erode then dilate: removes specks and thin bridges smaller than the element
+    pub fn open(&self, element: StructuringElement, radius: u32) -> Mask {
+        self.erode(element, radius).dilate(element, radius)
+    }
+
+    /// dilate then erode: closes gaps and small holes smaller than the element
+    pub fn close(&self, element: StructuringElement, radius: u32) -> Mask {
+        self.dilate(element, radius).erode(element, radius)
+    }
+
+    /// turns every background region that does not reach the image edge into object
+    pub fn fill_holes(&self) -> Mask {
+        let (width, height) = (self.width as i32, self.height as i32);
+        let mut outside = vec![false; self.pixels.len()];
+        let mut stack: Vec<(i32, i32)> = Vec::new();
+        for x in 0..width {
+            stack.push((x, 0));
+            stack.push((x, height - 1));
+        }
+        for y in 0..height {
+            stack.push((0, y));
+            stack.push((width - 1, y));
+        }
+        while let Some((x, y)) = stack.pop() {
+            match self.index(x, y) {
+                Some(i) if !self.pixels[i] && !outside[i] => {
+                    outside[i] = true;
+                    stack.extend([(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]);
+                }
+                _ => {}
+            }
+        }
+        Mask { width: self.width, height: self.height, pixels: outside.iter().map(|&o| !o).collect() }
+    }
+
+    /// the 8-connected objects, each as its pixel coordinates
+    pub fn objects(&self) -> Vec<Vec<(i32, i32)>> {
+        let mut seen = vec![false; self.pixels.len()];
+        let mut objects = Vec::new();
+        for start in 0..self.pixels.len() {
+            if !self.pixels[start] || seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let width = self.width as usize;
+            let mut stack = vec![((start % width) as i32, (start / width) as i32)];
+            let mut object = Vec::new();
+            while let Some((x, y)) = stack.pop() {
+                object.push((x, y));
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if let Some(i) = self.index(x + dx, y + dy) {
+                            if self.pixels[i] && !seen[i] {
+                                seen[i] = true;
+                                stack.push((x + dx, y + dy));
+                            }
+                        }
+                    }
+                }
+            }
+            objects.push(object);
+        }
+        objects
+    }
+
+    /// drops the objects made of fewer than `min_pixels` pixels
+    pub fn remove_small(&self, min_pixels: usize) -> Mask {
+        let mut mask = Mask::new(self.width, self.height);
+        for object in self.objects().iter().filter(|o| o.len() >= min_pixels) {
+            for &(x, y) in object {
+                mask.set(x, y, true);
+            }
+        }
+        mask
+    }
+
+    /// whether an object pixel has background (or the image edge) above, below or beside it
+    pub fn is_edge(&self, x: i32, y: i32) -> bool {
+        self.get(x, y) && [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().any(|&(dx, dy)| !self.get(x + dx, y + dy))
+    }
+
+    /// the one pixel wide border of every object
+    pub fn outline(&self) -> Mask {
+        Mask::from_fn(self.width, self.height, |x, y| self.is_edge(x as i32, y as i32))
+    }
+
+    /// Euclidean distance of every object pixel to the nearest background pixel, 0 on the
+    /// background, row-major. exact, by the lower envelope of parabolas along columns then rows
+    pub fn distance_transform(&self) -> Vec<f32> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        // farther than any two pixels of the image can be, finite so the envelope stays exact
+        let far = (width * width + height * height) as f32 + 1.0;
+        let mut squared: Vec<f32> = self.pixels.iter().map(|&on| if on { far } else { 0.0 }).collect();
+
+        for x in 0..width {
+            let column: Vec<f32> = (0..height).map(|y| squared[y * width + x]).collect();
+            for (y, value) in lower_envelope(&column, 1.0).into_iter().enumerate() {
+                squared[y * width + x] = value;
+            }
+        }
+        for row in squared.chunks_mut(width.max(1)) {
+            let envelope = lower_envelope(row, 1.0);
+            row.copy_from_slice(&envelope);
+        }
+        squared.iter().map(|&d| d.min(far).sqrt()).collect()
+    }
+
+    /// the distance transform as a 32-bit image, windowed to its range
+    pub fn distance_map(&self) -> RawImage {
+        RawImage::new(self.width, self.height, 1, RawPixels::F32(self.distance_transform()))
+    }
+}
+
+fn point_in_polygon(x: f64, y: f64, vertices: &[(i32, i32)]) -> bool {
+    if vertices.len() < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = vertices.len() - 1;
+    for i in 0..vertices.len() {
+        let (xi, yi) = (vertices[i].0 as f64, vertices[i].1 as f64);
+        let (xj, yj) = (vertices[j].0 as f64, vertices[j].1 as f64);
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_rows(rows: &[&str]) -> Mask {
+        Mask::from_fn(rows[0].len() as u32, rows.len() as u32, |x, y| rows[y as usize].as_bytes()[x as usize] == b'#')
+    }
+
+    #[test]
+    fn opening_removes_specks_and_closing_fills_gaps() {
+        let mask = from_rows(&[
+            "#.......",
+            "..###...",
+            "..###...",
+            "..###...",
+            "........",
+        ]);
+        let opened = mask.open(StructuringElement::Square, 1);
+        assert!(!opened.get(0, 0));
+        assert_eq!(opened.area(), 9);
+
+        let gap = from_rows(&[
+            "........",
+            ".###.##.",
+            ".###.##.",
+            ".###.##.",
+            "........",
+        ]);
+        assert_eq!(gap.close(StructuringElement::Cross, 1).objects().len(), 1);
+        assert_eq!(StructuringElement::Cross.offsets(1).len(), 5);
+        assert_eq!(StructuringElement::Disk.offsets(1).len(), 9);
+    }
+
+    #[test]
+    fn holes_are_filled_and_small_objects_removed() {
+        let mask = from_rows(&[
+            ".....#",
+            ".###..",
+            ".#.#..",
+            ".###..",
+            "......",
+        ]);
+        let filled = mask.fill_holes();
+        assert!(filled.get(2, 2));
+        assert!(!filled.get(0, 0));
+        assert_eq!(filled.area(), 10);
+
+        let cleaned = mask.remove_small(2);
+        assert!(!cleaned.get(5, 0));
+        assert_eq!(cleaned.area(), 8);
+        // the ring has no inside pixel, so its outline is all of it
+        assert_eq!(cleaned.outline(), cleaned);
+    }
+
+    #[test]
+    fn distance_transform_is_euclidean() {
+        let mask = from_rows(&[
+            ".......",
+            ".#####.",
+            ".#####.",
+            ".#####.",
+            ".......",
+        ]);
+        let distances = mask.distance_transform();
+        let at = |x: usize, y: usize| distances[y * 7 + x];
+        assert_eq!(at(0, 0), 0.0);
+        assert_eq!(at(1, 1), 1.0);
+        assert_eq!(at(3, 2), 2.0);
+        assert!((at(2, 2) - 2.0).abs() < 1e-6);
+        assert_eq!(distances.iter().filter(|&&d| d > 0.0).count(), mask.area());
+    }
+
+    #[test]
+    fn roi_masks_cover_the_measured_pixels() {
+        let square = vec![(1, 1), (4, 1), (4, 4), (1, 4)];
+        let inside = Mask::from_roi(&ROIShape::Polygon { points: square }, 6, 6);
+        assert_eq!(inside.area(), 9);
+        assert!(inside.get(1, 1) && inside.get(3, 3));
+        assert!(!inside.get(4, 4) && !inside.get(0, 2));
+
+        assert_eq!(Mask::from_roi(&ROIShape::Rectangle { width: 3, height: 2 }, 6, 6).area(), 6);
+        assert_eq!(Mask::from_roi(&ROIShape::Line { points: vec![(0, 0), (5, 5)] }, 6, 6).area(), 0);
+    }
+}
@@ -0,0 +1,210 @@
+//src/scientific/calibration/flat_field.rs
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use crate::utils::raw_image::{RawImage, RawPixels};
+use crate::utils::config::config_dir;
+
+/// start of the line `describe` records in the metadata
+const RECORD_PREFIX: &str = "Flat-field correction";
+const REGISTRY_FILE_NAME: &str = "flat_fields.json";
+
+/// flat-field and dark-frame images registered for one objective, kept next to
+/// the objective's spatial `Calibration`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FlatFieldCalibration {
+    pub objective: String,
+    pub flat_path: PathBuf,
+    pub dark_path: Option<PathBuf>,
+    /// corrects images taken with this objective as they are opened
+    pub apply_on_open: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FlatFieldError {
+    Load(PathBuf, String),
+    SizeMismatch((u32, u32), (u32, u32)),
+}
+
+impl std::fmt::Display for FlatFieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlatFieldError::Load(path, e) => write!(f, "Could not load {:?}: {}", path, e),
+            FlatFieldError::SizeMismatch(image, reference) => write!(
+                f,
+                "The image is {} x {} but the flat-field/dark frame is {} x {}",
+                image.0, image.1, reference.0, reference.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FlatFieldError {}
+
+impl FlatFieldCalibration {
+    pub fn new(objective: String, flat_path: PathBuf, dark_path: Option<PathBuf>, apply_on_open: bool) -> Self {
+        Self {
+            objective,
+            flat_path,
+            dark_path,
+            apply_on_open,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// whether this entry belongs to the objective named in an image's metadata
+    pub fn matches(&self, objective: &str) -> bool {
+        self.objective.trim().eq_ignore_ascii_case(objective.trim())
+    }
+
+    /// decodes the flat-field and, when registered, the dark frame
+    pub fn load(&self) -> Result<(RawImage, Option<RawImage>), FlatFieldError> {
+        let flat = load_reference(&self.flat_path)?;
+        let dark = self.dark_path.as_deref().map(load_reference).transpose()?;
+        Ok((flat, dark))
+    }
+
+    /// corrects `raw` with the registered images
+    pub fn correct(&self, raw: &RawImage) -> Result<RawImage, FlatFieldError> {
+        let (flat, dark) = self.load()?;
+        flat_field_correct(raw, &flat, dark.as_ref())
+    }
+
+    /// the line written into the image metadata once the correction is applied
+    pub fn describe(&self) -> String {
+        let name = |path: &Path| {
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string())
+        };
+        format!(
+            "{} ({}): flat {}, dark {}",
+            RECORD_PREFIX,
+            self.objective,
+            name(&self.flat_path),
+            self.dark_path.as_deref().map(name).unwrap_or_else(|| "none".to_string())
+        )
+    }
+}
+
+/// the flat-fields registered per objective, kept as JSON in the user's config
+/// directory so they carry over between sessions
+pub fn load_registry() -> Vec<FlatFieldCalibration> {
+    registry_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_registry(flat_fields: &[FlatFieldCalibration]) {
+    let path = match registry_path() {
+        Some(path) => path,
+        None => return,
+    };
+    match serde_json::to_string_pretty(flat_fields) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                println!("Failed to save flat-fields to {:?}: {}", path, e);
+            }
+        }
+        Err(e) => println!("Failed to serialize flat-fields: {}", e),
+    }
+}
+
+fn registry_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(REGISTRY_FILE_NAME))
+}
+
+/// whether the metadata corrections of an image already include a flat-field,
+/// e.g. a corrected image that was saved and opened again
+pub fn was_applied(corrections: &[String]) -> bool {
+    corrections.iter().any(|line| line.starts_with(RECORD_PREFIX))
+}
+
+fn load_reference(path: &Path) -> Result<RawImage, FlatFieldError> {
+    image::open(path)
+        .map(|img| RawImage::from_dynamic(&img))
+        .map_err(|e| FlatFieldError::Load(path.to_path_buf(), e.to_string()))
+}
+
+/// `(raw - dark) / (flat - dark) * mean(flat - dark)` for each channel, at the bit depth
+/// of `raw`. a grayscale flat or dark frame is used for every colour channel. where the
+/// flat is not brighter than the dark frame only the dark frame is removed
+pub fn flat_field_correct(raw: &RawImage, flat: &RawImage, dark: Option<&RawImage>) -> Result<RawImage, FlatFieldError> {
+    for reference in std::iter::once(flat).chain(dark) {
+        if (reference.width, reference.height) != (raw.width, raw.height) {
+            return Err(FlatFieldError::SizeMismatch((raw.width, raw.height), (reference.width, reference.height)));
+        }
+    }
+
+    let (width, height) = (raw.width as i32, raw.height as i32);
+    let channels = raw.channels.max(1);
+    let sample = |image: &RawImage, x: i32, y: i32, channel: usize| {
+        image.value(x, y, channel.min(image.channels.saturating_sub(1))).unwrap_or(0.0)
+    };
+    let dark_at = |x: i32, y: i32, channel: usize| dark.map_or(0.0, |dark| sample(dark, x, y, channel));
+
+    // mean(flat - dark) keeps the corrected image at the brightness of the original
+    let mut means = vec![0.0; channels];
+    for (channel, mean) in means.iter_mut().enumerate() {
+        let mut sum = 0.0;
+        for y in 0..height {
+            for x in 0..width {
+                sum += sample(flat, x, y, channel) - dark_at(x, y, channel);
+            }
+        }
+        *mean = sum / (width as f64 * height as f64).max(1.0);
+    }
+
+    let mut values = Vec::with_capacity(width as usize * height as usize * channels);
+    for y in 0..height {
+        for x in 0..width {
+            for (channel, &mean) in means.iter().enumerate() {
+                let dark_value = dark_at(x, y, channel);
+                let signal = sample(raw, x, y, channel) - dark_value;
+                let gain = sample(flat, x, y, channel) - dark_value;
+                values.push(if gain > 0.0 { signal / gain * mean } else { signal });
+            }
+        }
+    }
+
+    let pixels = match raw.pixels {
+        RawPixels::U8(_) => RawPixels::U8(values.iter().map(|v| v.round().clamp(0.0, 255.0) as u8).collect()),
+        RawPixels::U16(_) => RawPixels::U16(values.iter().map(|v| v.round().clamp(0.0, 65535.0) as u16).collect()),
+        RawPixels::F32(_) => RawPixels::F32(values.iter().map(|&v| v as f32).collect()),
+    };
+    Ok(RawImage::new(raw.width, raw.height, channels, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(values: Vec<u16>) -> RawImage {
+        RawImage::new(values.len() as u32, 1, 1, RawPixels::U16(values))
+    }
+
+    #[test]
+    fn vignetting_is_flattened_at_the_original_brightness() {
+        // a uniform 1000 sample seen through optics that lose half the light at the edge
+        let dark = gray(vec![100, 100, 100, 100]);
+        let flat = gray(vec![1100, 2100, 2100, 1100]);
+        let raw = gray(vec![600, 1100, 1100, 600]);
+        let corrected = flat_field_correct(&raw, &flat, Some(&dark)).unwrap();
+        // mean(flat - dark) = 1500
+        assert_eq!(corrected.intensities(), vec![750.0; 4]);
+    }
+
+    #[test]
+    fn grayscale_flat_corrects_every_colour_channel() {
+        let raw = RawImage::new(2, 1, 3, RawPixels::U8(vec![50, 60, 70, 100, 120, 140]));
+        let flat = RawImage::new(2, 1, 1, RawPixels::U8(vec![100, 200]));
+        let corrected = flat_field_correct(&raw, &flat, None).unwrap();
+        assert_eq!(corrected.pixel(0, 0), vec![75.0, 90.0, 105.0]);
+        assert_eq!(corrected.pixel(1, 0), vec![75.0, 90.0, 105.0]);
+
+        let small = gray(vec![1]);
+        assert!(flat_field_correct(&raw, &small, None).is_err());
+    }
+}
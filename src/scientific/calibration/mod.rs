@@ -1,2 +1,4 @@
 mod spatial_calibration;
-pub use spatial_calibration::SpatialCalibration;
\ No newline at end of file
+pub mod flat_field;
+pub use spatial_calibration::SpatialCalibration;
+pub use flat_field::FlatFieldCalibration;
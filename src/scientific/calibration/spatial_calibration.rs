@@ -1,13 +1,14 @@
 //src/scientific/calibration/spatial_calibration.rs
-use fltk::image::RgbImage;
+use serde::{Serialize, Deserialize};
+use crate::utils::transform::Transform;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CalibrationPoint {
     pub pixel_coord: (i32, i32),
     pub real_coord: (f32, f32),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SpatialCalibration {
     pub points: Vec<CalibrationPoint>,
     pub pixels_per_unit: f32,
@@ -25,6 +26,13 @@ impl SpatialCalibration {
         }
     }
 
+    /// false for the default 1 pixel = 1 unit scale nobody has set
+    pub fn is_calibrated(&self) -> bool {
+        !self.points.is_empty()
+            || self.transformation_matrix.is_some()
+            || (self.pixels_per_unit - 1.0).abs() > f32::EPSILON
+    }
+
     pub fn add_point(&mut self, pixel_coord: (i32, i32), real_coord: (f32, f32)) {
         self.points.push(CalibrationPoint {
             pixel_coord,
@@ -60,6 +68,42 @@ impl SpatialCalibration {
         }
     }
 
+    /// follows the image through a rotation, flip or resize so real positions and
+    /// distances stay where they were. `width` x `height` is the size before
+    pub fn transform(&mut self, transform: &Transform, width: u32, height: u32) {
+        let (scale_x, scale_y) = transform.scale(width, height);
+        // a single pixels-per-unit can't hold a non-uniform stretch, the mean keeps areas right
+        self.pixels_per_unit *= (scale_x * scale_y).sqrt() as f32;
+        for point in &mut self.points {
+            point.pixel_coord = transform.map_pixel(width, height, point.pixel_coord.0, point.pixel_coord.1);
+        }
+
+        if let Some(matrix) = self.transformation_matrix {
+            // the transform is affine: origin plus where the unit steps go
+            let origin = transform.map_point(width, height, 0.0, 0.0);
+            let step_x = transform.map_point(width, height, 1.0, 0.0);
+            let step_y = transform.map_point(width, height, 0.0, 1.0);
+            let (a, c) = (step_x.0 - origin.0, step_x.1 - origin.1);
+            let (b, d) = (step_y.0 - origin.0, step_y.1 - origin.1);
+            let det = a * d - b * c;
+            if det.abs() < f64::EPSILON {
+                return;
+            }
+            // new pixel -> old pixel is the inverse, then the old matrix takes it to real units
+            let inverse = [[d / det, -b / det], [-c / det, a / det]];
+            let mut updated = matrix;
+            for (updated_row, row) in updated.iter_mut().zip(matrix.iter()).take(2) {
+                let (m0, m1) = (row[0] as f64, row[1] as f64);
+                let new_0 = m0 * inverse[0][0] + m1 * inverse[1][0];
+                let new_1 = m0 * inverse[0][1] + m1 * inverse[1][1];
+                updated_row[0] = new_0 as f32;
+                updated_row[1] = new_1 as f32;
+                updated_row[2] = (row[2] as f64 - new_0 * origin.0 - new_1 * origin.1) as f32;
+            }
+            self.transformation_matrix = Some(updated);
+        }
+    }
+
     pub fn pixel_to_real(&self, pixel_coord: (i32, i32)) -> (f32, f32) {
         if let Some(matrix) = self.transformation_matrix {
             let x = matrix[0][0] * pixel_coord.0 as f32 + matrix[0][1] * pixel_coord.1 as f32 + matrix[0][2];
@@ -0,0 +1,265 @@
+// src/scientific/io/embedded_metadata.rs
+
+use std::{fs::File, io::BufWriter, path::{Path, PathBuf}};
+use serde::{Serialize, Deserialize};
+use crate::scientific::{calibration::SpatialCalibration, layers::Metadata};
+use crate::utils::raw_image::{RawImage, RawPixels};
+use crate::state::export_options::PngCompression;
+use super::{read_exif_metadata, tiff_stack, ImageIoError};
+
+/// keyword of the PNG iTXt chunk holding our JSON
+pub const METADATA_KEYWORD: &str = "ScientificMetadata";
+
+/// what gets written alongside the pixels so a reopened file keeps its
+/// metadata and calibration
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EmbeddedMetadata {
+    pub metadata: Metadata,
+    pub calibration: Option<SpatialCalibration>,
+}
+
+impl EmbeddedMetadata {
+    pub fn new(metadata: &Metadata, calibration: &SpatialCalibration) -> Self {
+        Self {
+            metadata: metadata.clone(),
+            calibration: if calibration.is_calibrated() {
+                Some(calibration.clone())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// metadata holding nothing but the pixel size, e.g. from resolution tags
+    pub fn from_pixel_size(pixel_size_um: f64) -> Self {
+        let metadata = Metadata {
+            pixel_size: Some(pixel_size_um as f32),
+            scale_calibration: Some(((1.0 / pixel_size_um) as f32, "µm".to_string())),
+            ..Metadata::default()
+        };
+        Self {
+            metadata,
+            calibration: None,
+        }
+    }
+
+    pub fn to_json(&self) -> Option<String> {
+        serde_json::to_string(self).ok()
+    }
+
+    pub fn from_json(text: &str) -> Option<Self> {
+        serde_json::from_str(text).ok()
+    }
+
+    /// pixel size in µm, from the metadata or else from a µm/nm/mm calibration
+    pub fn pixel_size_microns(&self) -> Option<f64> {
+        if let Some(size) = self.metadata.pixel_size.filter(|s| *s > 0.0) {
+            return Some(size as f64);
+        }
+        let calibration = self.calibration.as_ref()?;
+        let per_unit = 1.0 / calibration.pixels_per_unit as f64;
+        match calibration.unit.as_str() {
+            "µm" | "μm" | "um" => Some(per_unit),
+            "nm" => Some(per_unit / 1000.0),
+            "mm" => Some(per_unit * 1000.0),
+            _ => None,
+        }
+    }
+}
+
+/// `image.png` -> `image.png.json`
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+pub fn write_sidecar(path: &Path, embedded: &EmbeddedMetadata) -> Result<(), ImageIoError> {
+    let json = serde_json::to_string_pretty(embedded)
+        .map_err(|e| ImageIoError::Encode(e.to_string()))?;
+    std::fs::write(sidecar_path(path), json)?;
+    println!("Wrote metadata sidecar for {:?}", path);
+    Ok(())
+}
+
+/// embedded metadata first, then a sidecar file next to the image
+pub fn read_embedded_metadata(path: &Path) -> Option<EmbeddedMetadata> {
+    let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+    let embedded = match ext.as_deref() {
+        Some("png") => read_png_metadata(path),
+        Some("tif") | Some("tiff") => tiff_stack::read_tiff_metadata(path),
+        _ => None,
+    };
+
+    embedded.or_else(|| {
+        let text = std::fs::read_to_string(sidecar_path(path)).ok()?;
+        println!("Reading metadata sidecar for {:?}", path);
+        EmbeddedMetadata::from_json(&text)
+    })
+}
+
+/// metadata of a file this app did not write: its EXIF tags, plus the pixel size from
+/// the TIFF resolution or PNG pHYs tags when EXIF has none
+pub fn read_foreign_metadata(path: &Path) -> Metadata {
+    let mut metadata = read_exif_metadata(path).unwrap_or_default();
+    if metadata.pixel_size.is_some() {
+        return metadata;
+    }
+
+    let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+    let pixel_size = match ext.as_deref() {
+        Some("png") => read_png_pixel_size(path),
+        Some("tif") | Some("tiff") => tiff_stack::read_tiff_pixel_size(path),
+        _ => None,
+    };
+    if let Some(pixel_size) = pixel_size {
+        let sized = EmbeddedMetadata::from_pixel_size(pixel_size).metadata;
+        metadata.pixel_size = sized.pixel_size;
+        metadata.scale_calibration = sized.scale_calibration;
+    }
+    metadata
+}
+
+/// 8 or 16-bit PNG with the metadata in an iTXt chunk and the pixel size in pHYs
+pub fn write_png(
+    path: &Path,
+    raw: &RawImage,
+    embedded: Option<&EmbeddedMetadata>,
+    compression: PngCompression,
+) -> Result<(), ImageIoError> {
+    let (depth, bytes) = match &raw.pixels {
+        RawPixels::U8(data) => (png::BitDepth::Eight, data.clone()),
+        // PNG stores 16-bit samples big-endian
+        RawPixels::U16(data) => (png::BitDepth::Sixteen, data.iter().flat_map(|v| v.to_be_bytes()).collect()),
+        RawPixels::F32(_) => return Err(ImageIoError::Unsupported("PNG cannot store float data".to_string())),
+    };
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, raw.width, raw.height);
+    encoder.set_color(if raw.channels == 1 { png::ColorType::Grayscale } else { png::ColorType::Rgb });
+    encoder.set_depth(depth);
+    encoder.set_compression(match compression {
+        PngCompression::Fast => png::Compression::Fast,
+        PngCompression::Default => png::Compression::Default,
+        PngCompression::Best => png::Compression::Best,
+    });
+
+    if let Some(embedded) = embedded {
+        if let Some(json) = embedded.to_json() {
+            encoder
+                .add_itxt_chunk(METADATA_KEYWORD.to_string(), json)
+                .map_err(|e| ImageIoError::Encode(e.to_string()))?;
+        }
+        // pHYs is pixels per metre
+        if let Some(um) = embedded.pixel_size_microns() {
+            let ppm = (1_000_000.0 / um).round() as u32;
+            encoder.set_pixel_dims(Some(png::PixelDimensions {
+                xppu: ppm,
+                yppu: ppm,
+                unit: png::Unit::Meter,
+            }));
+        }
+    }
+
+    let mut writer = encoder.write_header().map_err(|e| ImageIoError::Encode(e.to_string()))?;
+    writer.write_image_data(&bytes).map_err(|e| ImageIoError::Encode(e.to_string()))?;
+    println!("Wrote {}-bit PNG to {:?}", raw.bit_depth(), path);
+    Ok(())
+}
+
+fn read_png_metadata(path: &Path) -> Option<EmbeddedMetadata> {
+    let decoder = png::Decoder::new(File::open(path).ok()?);
+    let reader = decoder.read_info().ok()?;
+    let info = reader.info();
+
+    let from_itxt = info.utf8_text.iter()
+        .filter(|chunk| chunk.keyword == METADATA_KEYWORD)
+        .find_map(|chunk| chunk.get_text().ok());
+    let from_text = info.uncompressed_latin1_text.iter()
+        .find(|chunk| chunk.keyword == METADATA_KEYWORD)
+        .map(|chunk| chunk.text.clone());
+
+    from_itxt.or(from_text).and_then(|text| EmbeddedMetadata::from_json(&text))
+}
+
+/// pixel size in µm from the pHYs chunk, which is in pixels per metre
+fn read_png_pixel_size(path: &Path) -> Option<f64> {
+    let reader = png::Decoder::new(File::open(path).ok()?).read_info().ok()?;
+    match reader.info().pixel_dims {
+        Some(dims) if dims.unit == png::Unit::Meter && dims.xppu > 0 => Some(1_000_000.0 / dims.xppu as f64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use tiff::{
+        encoder::{colortype::Gray16, Rational, TiffEncoder},
+        tags::{ResolutionUnit, Tag},
+    };
+
+    fn temp_path(name: &str, ext: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("foreign-{}-{}.{}", name, std::process::id(), ext))
+    }
+
+    #[test]
+    fn camera_tiff_keeps_its_exif_and_its_resolution() {
+        let path = temp_path("camera", "tif");
+        {
+            let mut encoder = TiffEncoder::new(BufWriter::new(File::create(&path).unwrap())).unwrap();
+            let mut image = encoder.new_image::<Gray16>(4, 2).unwrap();
+            image.encoder().write_tag(Tag::DateTime, "2024:03:05 14:30:15").unwrap();
+            // 20000 pixels per cm is 0.5 µm per pixel
+            image.resolution(ResolutionUnit::Centimeter, Rational { n: 20_000, d: 1 });
+            image.write_data(&[0u16, 100, 200, 300, 400, 500, 600, 700]).unwrap();
+        }
+
+        let embedded = read_embedded_metadata(&path);
+        let metadata = read_foreign_metadata(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(embedded.is_none());
+        assert_eq!(metadata.acquisition_time, Utc.with_ymd_and_hms(2024, 3, 5, 14, 30, 15).single());
+        assert!((metadata.pixel_size.unwrap() - 0.5).abs() < 1e-6);
+        let (pixels_per_unit, unit) = metadata.scale_calibration.unwrap();
+        assert!((pixels_per_unit - 2.0).abs() < 1e-6);
+        assert_eq!(unit, "µm");
+    }
+
+    #[test]
+    fn png_pixel_size_comes_from_phys() {
+        let path = temp_path("phys", "png");
+        {
+            let mut encoder = png::Encoder::new(BufWriter::new(File::create(&path).unwrap()), 2, 2);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_pixel_dims(Some(png::PixelDimensions { xppu: 4_000_000, yppu: 4_000_000, unit: png::Unit::Meter }));
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&[0, 64, 128, 255]).unwrap();
+        }
+
+        let embedded = read_embedded_metadata(&path);
+        let metadata = read_foreign_metadata(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(embedded.is_none());
+        assert!((metadata.pixel_size.unwrap() - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn our_own_png_metadata_is_embedded() {
+        let path = temp_path("own", "png");
+        let raw = RawImage::new(2, 1, 1, RawPixels::U16(vec![1, 60_000]));
+        let mut written = EmbeddedMetadata::from_pixel_size(0.1);
+        written.metadata.objective = Some("60x".to_string());
+        write_png(&path, &raw, Some(&written), PngCompression::Default).unwrap();
+
+        let embedded = read_embedded_metadata(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let embedded = embedded.unwrap();
+        assert_eq!(embedded.metadata.objective.as_deref(), Some("60x"));
+        assert!((embedded.pixel_size_microns().unwrap() - 0.1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,249 @@
+// src/scientific/io/exif_reader.rs
+
+use std::{fs::File, io::BufReader, path::Path};
+use chrono::{TimeZone, Utc};
+use exif::{Exif, In, Reader, Tag, Value};
+use crate::scientific::layers::Metadata;
+
+// EXIF ResolutionUnit values
+const RESOLUTION_UNIT_INCH: u32 = 2;
+const RESOLUTION_UNIT_CM: u32 = 3;
+const MICRONS_PER_INCH: f64 = 25_400.0;
+const MICRONS_PER_CM: f64 = 10_000.0;
+
+/// reads the EXIF/TIFF tags we care about into a Metadata.
+/// returns None when the file has no EXIF block at all
+pub fn read_exif_metadata(path: &Path) -> Option<Metadata> {
+    let file = File::open(path).ok()?;
+    let exif = match Reader::new().read_from_container(&mut BufReader::new(file)) {
+        Ok(exif) => exif,
+        Err(e) => {
+            println!("No EXIF data in {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    let mut metadata = Metadata::default();
+
+    // EXIF times carry no timezone, they are stored as if they were UTC
+    if let Some(dt) = ascii_field(&exif, Tag::DateTimeOriginal)
+        .or_else(|| ascii_field(&exif, Tag::DateTime))
+        .and_then(|s| exif::DateTime::from_ascii(s.as_bytes()).ok())
+    {
+        metadata.acquisition_time = Utc
+            .with_ymd_and_hms(
+                dt.year as i32,
+                dt.month as u32,
+                dt.day as u32,
+                dt.hour as u32,
+                dt.minute as u32,
+                dt.second as u32,
+            )
+            .single();
+    }
+
+    // seconds in EXIF, milliseconds in Metadata
+    if let Some(seconds) = rational_field(&exif, Tag::ExposureTime) {
+        metadata.exposure_time = Some((seconds * 1000.0) as f32);
+    }
+
+    if let Some(iso) = exif
+        .get_field(Tag::PhotographicSensitivity, In::PRIMARY)
+        .and_then(|f| f.value.get_uint(0))
+    {
+        metadata.gain = Some(iso as f32);
+    }
+
+    metadata.objective = ascii_field(&exif, Tag::LensModel);
+
+    if let Some(pixel_size) = pixel_size_microns(&exif) {
+        metadata.pixel_size = Some(pixel_size as f32);
+        metadata.scale_calibration = Some(((1.0 / pixel_size) as f32, "µm".to_string()));
+    }
+
+    println!(
+        "EXIF: time={:?} exposure={:?}ms gain={:?} lens={:?} pixel_size={:?}µm",
+        metadata.acquisition_time,
+        metadata.exposure_time,
+        metadata.gain,
+        metadata.objective,
+        metadata.pixel_size
+    );
+
+    Some(metadata)
+}
+
+// the tags worth a line in the image info window, in display order
+const SUMMARY_TAGS: [(Tag, &str); 10] = [
+    (Tag::Make, "Camera make"),
+    (Tag::Model, "Camera model"),
+    (Tag::LensModel, "Lens"),
+    (Tag::DateTimeOriginal, "Taken"),
+    (Tag::ExposureTime, "Exposure"),
+    (Tag::FNumber, "Aperture"),
+    (Tag::PhotographicSensitivity, "ISO"),
+    (Tag::FocalLength, "Focal length"),
+    (Tag::XResolution, "Resolution"),
+    (Tag::Software, "Software"),
+];
+
+/// (label, value) pairs for the common EXIF tags present in the file, empty without EXIF
+pub fn read_exif_summary(path: &Path) -> Vec<(&'static str, String)> {
+    let exif = match File::open(path)
+        .ok()
+        .and_then(|file| Reader::new().read_from_container(&mut BufReader::new(file)).ok())
+    {
+        Some(exif) => exif,
+        None => return Vec::new(),
+    };
+
+    SUMMARY_TAGS
+        .iter()
+        .filter_map(|&(tag, label)| {
+            let field = exif.get_field(tag, In::PRIMARY)?;
+            let value = field.display_value().with_unit(&exif).to_string();
+            let value = value.trim_matches('"').trim().to_string();
+            (!value.is_empty()).then_some((label, value))
+        })
+        .collect()
+}
+
+/// XResolution/ResolutionUnit converted to the size of one pixel in µm
+fn pixel_size_microns(exif: &Exif) -> Option<f64> {
+    let resolution = rational_field(exif, Tag::XResolution)?;
+    if resolution <= 0.0 {
+        return None;
+    }
+
+    let unit = exif
+        .get_field(Tag::ResolutionUnit, In::PRIMARY)
+        .and_then(|f| f.value.get_uint(0))
+        .unwrap_or(RESOLUTION_UNIT_INCH);
+
+    match unit {
+        // 72/96 dpi are screen defaults most cameras write, not a real scale
+        RESOLUTION_UNIT_INCH if resolution == 72.0 || resolution == 96.0 => None,
+        RESOLUTION_UNIT_INCH => Some(MICRONS_PER_INCH / resolution),
+        RESOLUTION_UNIT_CM => Some(MICRONS_PER_CM / resolution),
+        _ => None,
+    }
+}
+
+fn ascii_field(exif: &Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => values
+            .first()
+            .map(|v| String::from_utf8_lossy(v).trim_end_matches('\0').trim().to_string())
+            .filter(|s| !s.is_empty()),
+        _ => None,
+    }
+}
+
+fn rational_field(exif: &Exif, tag: Tag) -> Option<f64> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(values) => values.first().map(|r| r.to_f64()),
+        Value::SRational(values) => values.first().map(|r| r.to_f64()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::experimental::Writer;
+    use exif::{Field, Rational};
+    use std::path::PathBuf;
+
+    fn field(tag: Tag, value: Value) -> Field {
+        Field { tag, ifd_num: In::PRIMARY, value }
+    }
+
+    fn rational(num: u32, denom: u32) -> Value {
+        Value::Rational(vec![Rational { num, denom }])
+    }
+
+    fn write_exif(name: &str, fields: &[Field]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.tif", name, std::process::id()));
+        let mut writer = Writer::new();
+        for field in fields {
+            writer.push_field(field);
+        }
+        let mut buf = std::io::Cursor::new(Vec::new());
+        writer.write(&mut buf, true).unwrap();
+        std::fs::write(&path, buf.into_inner()).unwrap();
+        path
+    }
+
+    #[test]
+    fn exif_tags_map_onto_metadata() {
+        let path = write_exif(
+            "exif-mapping",
+            &[
+                field(Tag::DateTimeOriginal, Value::Ascii(vec![b"2024:03:05 14:30:15".to_vec()])),
+                field(Tag::ExposureTime, rational(1, 50)),
+                field(Tag::PhotographicSensitivity, Value::Short(vec![400])),
+                field(Tag::LensModel, Value::Ascii(vec![b"Plan Apo 40x".to_vec()])),
+                field(Tag::XResolution, rational(2000, 1)),
+                field(Tag::ResolutionUnit, Value::Short(vec![RESOLUTION_UNIT_CM as u16])),
+            ],
+        );
+        let metadata = read_exif_metadata(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(
+            metadata.acquisition_time,
+            Utc.with_ymd_and_hms(2024, 3, 5, 14, 30, 15).single()
+        );
+        assert!((metadata.exposure_time.unwrap() - 20.0).abs() < 1e-4);
+        assert_eq!(metadata.gain, Some(400.0));
+        assert_eq!(metadata.objective.as_deref(), Some("Plan Apo 40x"));
+
+        // 2000 pixels per cm is 5 µm per pixel
+        assert!((metadata.pixel_size.unwrap() - 5.0).abs() < 1e-4);
+        let (pixels_per_unit, unit) = metadata.scale_calibration.unwrap();
+        assert!((pixels_per_unit - 0.2).abs() < 1e-6);
+        assert_eq!(unit, "µm");
+    }
+
+    #[test]
+    fn inch_resolution_is_converted_to_microns() {
+        let path = write_exif(
+            "exif-inch",
+            &[
+                field(Tag::XResolution, rational(25_400, 1)),
+                field(Tag::ResolutionUnit, Value::Short(vec![RESOLUTION_UNIT_INCH as u16])),
+            ],
+        );
+        let metadata = read_exif_metadata(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!((metadata.pixel_size.unwrap() - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn screen_dpi_is_not_a_calibration() {
+        let path = write_exif(
+            "exif-screen-dpi",
+            &[
+                field(Tag::XResolution, rational(72, 1)),
+                field(Tag::ResolutionUnit, Value::Short(vec![RESOLUTION_UNIT_INCH as u16])),
+                field(Tag::Model, Value::Ascii(vec![b"Camera".to_vec()])),
+            ],
+        );
+        let metadata = read_exif_metadata(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(metadata.pixel_size.is_none());
+        assert!(metadata.scale_calibration.is_none());
+    }
+
+    #[test]
+    fn file_without_exif_has_no_metadata() {
+        let path = std::env::temp_dir().join(format!("exif-none-{}.txt", std::process::id()));
+        std::fs::write(&path, b"not an image").unwrap();
+        let metadata = read_exif_metadata(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert!(metadata.is_none());
+    }
+}
@@ -0,0 +1,326 @@
+// src/scientific/io/export.rs
+
+use std::{fs::File, io::BufWriter, path::Path};
+use image::{
+    codecs::{
+        bmp::BmpEncoder,
+        gif::GifEncoder,
+        pnm::{PnmEncoder, PnmSubtype, SampleEncoding},
+        webp::WebPEncoder,
+    },
+    ExtendedColorType, ImageEncoder,
+};
+use jpeg_encoder::{ColorType as JpegColorType, Encoder as JpegEncoder, SamplingFactor};
+use crate::state::export_options::{ChromaSubsampling, ExportOptions};
+use crate::utils::raw_image::{RawImage, RawPixels};
+use super::{write_png, write_tiff_pages, EmbeddedMetadata, ImageIoError};
+
+/// every format Save As can write, picked from the file extension
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Jpeg,
+    Png,
+    Tiff,
+    Bmp,
+    WebP,
+    Gif,
+    Pnm,
+}
+
+impl ExportFormat {
+    /// filter string for the save dialog
+    pub const FILE_FILTER: &'static str =
+        "Image Files\t*.{jpg,jpeg,png,tif,tiff,bmp,webp,gif,pnm,pgm,ppm,pam}";
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_extension(path.extension()?.to_str()?)
+    }
+
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            "tif" | "tiff" => Some(Self::Tiff),
+            "bmp" => Some(Self::Bmp),
+            "webp" => Some(Self::WebP),
+            "gif" => Some(Self::Gif),
+            "pnm" | "pgm" | "ppm" | "pam" => Some(Self::Pnm),
+            _ => None,
+        }
+    }
+
+    /// PNG and TIFF keep 16-bit data (TIFF also float), everything else is 8-bit
+    pub fn keeps_high_bit_depth(&self) -> bool {
+        matches!(self, Self::Png | Self::Tiff)
+    }
+
+    /// formats with settings in the export options dialog
+    pub fn has_options(&self) -> bool {
+        matches!(self, Self::Jpeg | Self::Png | Self::Tiff)
+    }
+}
+
+/// writes `raw` to `path` in the format implied by the extension.
+/// metadata is only embedded by PNG and TIFF
+pub fn write_image(
+    path: &Path,
+    raw: &RawImage,
+    embedded: Option<&EmbeddedMetadata>,
+    options: &ExportOptions,
+) -> Result<(), ImageIoError> {
+    let format = ExportFormat::from_path(path)
+        .ok_or_else(|| ImageIoError::Unsupported(format!("unknown file type {:?}", path)))?;
+
+    match format {
+        ExportFormat::Png => {
+            // PNG has no float samples
+            let raw = if matches!(raw.pixels, RawPixels::F32(_)) { to_8bit(raw) } else { raw.clone() };
+            write_png(path, &raw, embedded, options.png_compression)
+        }
+        ExportFormat::Tiff => write_tiff_pages(path, std::slice::from_ref(raw), embedded, options.tiff_compression),
+        ExportFormat::Jpeg => write_jpeg(path, &to_8bit(raw), options),
+        _ => write_8bit(path, format, &to_8bit(raw)),
+    }
+}
+
+/// 8-bit copy, windowed through the display range for 16-bit/float data
+fn to_8bit(raw: &RawImage) -> RawImage {
+    match &raw.pixels {
+        RawPixels::U8(_) => raw.clone(),
+        _ if raw.channels == 1 => {
+            let gray = raw.to_display_rgb().chunks(3).map(|px| px[0]).collect();
+            RawImage::new(raw.width, raw.height, 1, RawPixels::U8(gray))
+        }
+        _ => RawImage::new(raw.width, raw.height, 3, RawPixels::U8(raw.to_display_rgb())),
+    }
+}
+
+fn write_jpeg(path: &Path, raw: &RawImage, options: &ExportOptions) -> Result<(), ImageIoError> {
+    let data = match &raw.pixels {
+        RawPixels::U8(data) => data,
+        _ => return Err(ImageIoError::Unsupported("JPEG needs 8-bit data".to_string())),
+    };
+    // the JPEG header stores dimensions as u16
+    let (width, height) = match (u16::try_from(raw.width), u16::try_from(raw.height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => return Err(ImageIoError::Unsupported("image is too large for JPEG".to_string())),
+    };
+
+    let mut encoder = JpegEncoder::new_file(path, options.jpeg_quality.clamp(1, 100))
+        .map_err(|e| ImageIoError::Encode(e.to_string()))?;
+    encoder.set_sampling_factor(match options.jpeg_subsampling {
+        ChromaSubsampling::Full => SamplingFactor::R_4_4_4,
+        ChromaSubsampling::Half => SamplingFactor::R_4_2_2,
+        ChromaSubsampling::Quarter => SamplingFactor::R_4_2_0,
+    });
+    let color = if raw.channels == 1 { JpegColorType::Luma } else { JpegColorType::Rgb };
+    encoder
+        .encode(data, width, height, color)
+        .map_err(|e| ImageIoError::Encode(e.to_string()))?;
+
+    println!(
+        "Wrote JPEG to {:?} (quality {}, {})",
+        path, options.jpeg_quality, options.jpeg_subsampling.label()
+    );
+    Ok(())
+}
+
+fn write_8bit(path: &Path, format: ExportFormat, raw: &RawImage) -> Result<(), ImageIoError> {
+    let data = match &raw.pixels {
+        RawPixels::U8(data) => data,
+        _ => return Err(ImageIoError::Unsupported("expected 8-bit data".to_string())),
+    };
+    let color = if raw.channels == 1 { ExtendedColorType::L8 } else { ExtendedColorType::Rgb8 };
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    let result = match format {
+        ExportFormat::Bmp => BmpEncoder::new(&mut writer).write_image(data, raw.width, raw.height, color),
+        ExportFormat::WebP => WebPEncoder::new_lossless(writer).write_image(data, raw.width, raw.height, color),
+        ExportFormat::Gif => {
+            // GIF only takes RGB(A), grayscale gets expanded
+            let rgb: Vec<u8> = if raw.channels == 1 {
+                data.iter().flat_map(|v| [*v, *v, *v]).collect()
+            } else {
+                data.clone()
+            };
+            GifEncoder::new(writer).encode(&rgb, raw.width, raw.height, ExtendedColorType::Rgb8)
+        }
+        ExportFormat::Pnm => {
+            let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+            let encoder = PnmEncoder::new(writer);
+            // .pam is the arbitrary-map variant, the others get a plain binary PGM/PPM
+            let encoder = match ext.as_deref() {
+                Some("pam") => encoder,
+                _ if raw.channels == 1 => encoder.with_subtype(PnmSubtype::Graymap(SampleEncoding::Binary)),
+                _ => encoder.with_subtype(PnmSubtype::Pixmap(SampleEncoding::Binary)),
+            };
+            encoder.write_image(data, raw.width, raw.height, color)
+        }
+        _ => return Err(ImageIoError::Unsupported(format!("{:?} is not written as plain 8-bit", format))),
+    };
+    result.map_err(|e| ImageIoError::Encode(e.to_string()))?;
+
+    println!("Wrote {:?} to {:?}", format, path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scientific::io::read_tiff_pages;
+    use crate::state::export_options::{PngCompression, TiffCompression};
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str, ext: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("export-{}-{}.{}", name, std::process::id(), ext))
+    }
+
+    // smooth RGB gradient with a few hard edges, the kind of content JPEG settings show on
+    fn test_image(width: u32, height: u32) -> RawImage {
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                let edge = if (x / 8 + y / 8) % 2 == 0 { 40 } else { 0 };
+                [(x * 4 % 200) as u8 + edge, (y * 4 % 200) as u8, ((x + y) * 2 % 200) as u8 + edge]
+            })
+            .collect();
+        RawImage::new(width, height, 3, RawPixels::U8(data))
+    }
+
+    fn u8_data(raw: &RawImage) -> &[u8] {
+        match &raw.pixels {
+            RawPixels::U8(data) => data,
+            other => panic!("expected 8-bit data, got {:?}", other),
+        }
+    }
+
+    fn decode(path: &Path) -> RawImage {
+        RawImage::from_dynamic(&image::open(path).unwrap())
+    }
+
+    fn mean_error(a: &[u8], b: &[u8]) -> f64 {
+        a.iter().zip(b).map(|(a, b)| (*a as f64 - *b as f64).abs()).sum::<f64>() / a.len() as f64
+    }
+
+    // the luma sampling factors from the JPEG frame header, 0x11 is no subsampling
+    fn luma_sampling(bytes: &[u8]) -> u8 {
+        let sof = bytes.windows(2).position(|w| w == [0xFF, 0xC0]).expect("no baseline frame header");
+        // marker, length, precision, height, width, component count, then id and sampling
+        bytes[sof + 11]
+    }
+
+    #[test]
+    fn jpeg_quality_and_subsampling_are_applied() {
+        let raw = test_image(64, 48);
+        let mut encoded = Vec::new();
+        for (quality, subsampling) in [
+            (95, ChromaSubsampling::Full),
+            (60, ChromaSubsampling::Half),
+            (20, ChromaSubsampling::Quarter),
+        ] {
+            let path = temp_path(&format!("jpeg-{}", quality), "jpg");
+            let options = ExportOptions { jpeg_quality: quality, jpeg_subsampling: subsampling, ..Default::default() };
+            write_image(&path, &raw, None, &options).unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            let decoded = decode(&path);
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!((decoded.width, decoded.height, decoded.channels), (64, 48, 3));
+            encoded.push((bytes.len(), mean_error(u8_data(&raw), u8_data(&decoded)), luma_sampling(&bytes)));
+        }
+
+        let sampling: Vec<u8> = encoded.iter().map(|e| e.2).collect();
+        assert_eq!(sampling, vec![0x11, 0x21, 0x22]);
+        assert!(encoded[0].0 > encoded[1].0 && encoded[1].0 > encoded[2].0);
+        assert!(encoded[0].1 < encoded[2].1);
+        assert!(encoded[0].1 < 3.0);
+    }
+
+    #[test]
+    fn png_keeps_sixteen_bit_data_at_every_level() {
+        let raw = RawImage::new(32, 16, 1, RawPixels::U16((0..512).map(|v| (v * 127) as u16).collect()));
+        let mut sizes = Vec::new();
+        for compression in PngCompression::ALL {
+            let path = temp_path(&format!("png-{:?}", compression), "png");
+            let options = ExportOptions { png_compression: compression, ..Default::default() };
+            write_image(&path, &raw, None, &options).unwrap();
+            sizes.push(std::fs::metadata(&path).unwrap().len());
+            let decoded = decode(&path);
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!(decoded.channels, 1);
+            assert_eq!(format!("{:?}", decoded.pixels), format!("{:?}", raw.pixels));
+        }
+        // Fast, Default, Best
+        assert!(sizes[2] <= sizes[0]);
+    }
+
+    #[test]
+    fn tiff_records_the_chosen_compression() {
+        let raw = RawImage::new(16, 8, 1, RawPixels::U16((0..128).map(|v| v * 500).collect()));
+        for (compression, tag) in [(TiffCompression::None, 1), (TiffCompression::Lzw, 5), (TiffCompression::Deflate, 8)] {
+            let path = temp_path(&format!("tiff-{:?}", compression), "tif");
+            let options = ExportOptions { tiff_compression: compression, ..Default::default() };
+            write_image(&path, &raw, None, &options).unwrap();
+            let mut decoder = tiff::decoder::Decoder::new(File::open(&path).unwrap()).unwrap();
+            let written = decoder.get_tag_u32(tiff::tags::Tag::Compression).unwrap();
+            let read = read_tiff_pages(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!(written, tag);
+            assert_eq!(format!("{:?}", read[0].pixels), format!("{:?}", raw.pixels));
+        }
+    }
+
+    #[test]
+    fn lossless_eight_bit_formats_round_trip() {
+        let raw = test_image(20, 10);
+        for ext in ["bmp", "webp", "ppm", "pam"] {
+            let path = temp_path("lossless", ext);
+            write_image(&path, &raw, None, &ExportOptions::default()).unwrap();
+            let decoded = decode(&path);
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!((decoded.width, decoded.height), (20, 10), "{}", ext);
+            assert_eq!(u8_data(&decoded), u8_data(&raw), "{}", ext);
+        }
+
+        let gray = RawImage::new(8, 4, 1, RawPixels::U8((0..32).map(|v| v * 8).collect()));
+        let path = temp_path("gray", "pgm");
+        write_image(&path, &gray, None, &ExportOptions::default()).unwrap();
+        let decoded = decode(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(decoded.channels, 1);
+        assert_eq!(u8_data(&decoded), u8_data(&gray));
+    }
+
+    #[test]
+    fn gif_is_close_and_sixteen_bit_data_is_windowed() {
+        // GIF quantizes to a palette, so only a handful of colours survive exactly
+        let colors = [[255u8, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255]];
+        let data = (0..64).flat_map(|i| colors[i / 16]).collect();
+        let raw = RawImage::new(8, 8, 3, RawPixels::U8(data));
+        let path = temp_path("palette", "gif");
+        write_image(&path, &raw, None, &ExportOptions::default()).unwrap();
+        let decoded = decode(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(mean_error(u8_data(&decoded), u8_data(&raw)) < 4.0);
+
+        // 8-bit only formats get the display window of deeper data
+        let deep = RawImage::new(4, 1, 1, RawPixels::U16(vec![0, 1000, 2000, 4000]));
+        let path = temp_path("deep", "bmp");
+        write_image(&path, &deep, None, &ExportOptions::default()).unwrap();
+        let decoded = decode(&path);
+        let _ = std::fs::remove_file(&path);
+        let data = u8_data(&decoded);
+        assert_eq!((data[0], data[9]), (0, 255));
+    }
+
+    #[test]
+    fn formats_with_their_own_writer_are_refused_as_plain_eight_bit() {
+        let path = temp_path("refused", "tif");
+        let result = write_8bit(&path, ExportFormat::Tiff, &test_image(2, 2));
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(result, Err(ImageIoError::Unsupported(_))));
+    }
+}
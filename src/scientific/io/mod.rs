@@ -0,0 +1,37 @@
+// src/scientific/io/mod.rs
+pub mod tiff_stack;
+pub mod exif_reader;
+pub mod embedded_metadata;
+pub mod export;
+
+pub use tiff_stack::{read_tiff_pages, write_tiff_pages, count_tiff_pages, is_tiff};
+pub use exif_reader::{read_exif_metadata, read_exif_summary};
+pub use embedded_metadata::{EmbeddedMetadata, read_embedded_metadata, read_foreign_metadata, write_png, write_sidecar};
+pub use export::{write_image, ExportFormat};
+
+#[derive(Debug)]
+pub enum ImageIoError {
+    Io(String),
+    Decode(String),
+    Encode(String),
+    Unsupported(String),
+}
+
+impl std::fmt::Display for ImageIoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageIoError::Io(msg) => write!(f, "IO error: {}", msg),
+            ImageIoError::Decode(msg) => write!(f, "Decode error: {}", msg),
+            ImageIoError::Encode(msg) => write!(f, "Encode error: {}", msg),
+            ImageIoError::Unsupported(msg) => write!(f, "Unsupported: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ImageIoError {}
+
+impl From<std::io::Error> for ImageIoError {
+    fn from(error: std::io::Error) -> Self {
+        ImageIoError::Io(error.to_string())
+    }
+}
@@ -0,0 +1,284 @@
+// src/scientific/io/tiff_stack.rs
+
+use std::{fs::File, io::{BufReader, BufWriter, Seek, Write}, path::Path};
+use tiff::{
+    decoder::{ifd::Value, Decoder, DecodingResult, Limits},
+    encoder::{
+        colortype::{self, ColorType},
+        compression::{Compression, Deflate, Lzw, Uncompressed},
+        Rational, TiffEncoder, TiffValue,
+    },
+    tags::{ResolutionUnit, Tag},
+    TiffResult,
+};
+use crate::utils::raw_image::{RawImage, RawPixels};
+use crate::state::export_options::TiffCompression;
+use super::{EmbeddedMetadata, ImageIoError};
+
+pub fn is_tiff(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref(),
+        Some("tif") | Some("tiff")
+    )
+}
+
+/// number of pages (IFDs) in the file, without decoding pixel data
+pub fn count_tiff_pages(path: &Path) -> Result<usize, ImageIoError> {
+    let mut decoder = open_decoder(path)?;
+    let mut count = 1;
+    while decoder.more_images() {
+        decoder.next_image().map_err(|e| ImageIoError::Decode(e.to_string()))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// decodes every page at its stored bit depth
+pub fn read_tiff_pages(path: &Path) -> Result<Vec<RawImage>, ImageIoError> {
+    let mut decoder = open_decoder(path)?;
+    let mut pages = Vec::new();
+
+    loop {
+        let (width, height) = decoder.dimensions().map_err(|e| ImageIoError::Decode(e.to_string()))?;
+        let data = decoder.read_image().map_err(|e| ImageIoError::Decode(e.to_string()))?;
+        pages.push(page_to_raw(width, height, data)?);
+
+        if !decoder.more_images() {
+            break;
+        }
+        decoder.next_image().map_err(|e| ImageIoError::Decode(e.to_string()))?;
+    }
+
+    println!("Read {} TIFF page(s) from {:?}", pages.len(), path);
+    Ok(pages)
+}
+
+/// writes the pages as one multi-page TIFF, keeping each page's bit depth.
+/// metadata goes into the first page's ImageDescription and resolution tags
+pub fn write_tiff_pages(
+    path: &Path,
+    pages: &[RawImage],
+    embedded: Option<&EmbeddedMetadata>,
+    compression: TiffCompression,
+) -> Result<(), ImageIoError> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = TiffEncoder::new(file).map_err(|e| ImageIoError::Encode(e.to_string()))?;
+
+    let description = embedded.and_then(|m| m.to_json()).map(|json| ascii_json(&json));
+    // TIFF resolution is pixels per centimetre
+    let resolution = embedded
+        .and_then(|m| m.pixel_size_microns())
+        .map(|um| Rational { n: (10_000_000.0 / um).round() as u32, d: 1000 });
+    let tags = (description.as_deref(), resolution);
+
+    let result = match compression {
+        TiffCompression::None => write_all_pages(&mut encoder, pages, tags, || Uncompressed),
+        TiffCompression::Lzw => write_all_pages(&mut encoder, pages, tags, || Lzw),
+        TiffCompression::Deflate => write_all_pages(&mut encoder, pages, tags, Deflate::default),
+    };
+    result.map_err(|e| ImageIoError::Encode(e.to_string()))?;
+
+    println!("Wrote {} TIFF page(s) to {:?} ({:?} compression)", pages.len(), path, compression);
+    Ok(())
+}
+
+/// reads our metadata JSON back from ImageDescription, None for files written by
+/// something else
+pub fn read_tiff_metadata(path: &Path) -> Option<EmbeddedMetadata> {
+    let mut decoder = open_decoder(path).ok()?;
+    let description = decoder.get_tag_ascii_string(Tag::ImageDescription).ok()?;
+    EmbeddedMetadata::from_json(&description)
+}
+
+/// pixel size in µm from the XResolution/ResolutionUnit tags
+pub fn read_tiff_pixel_size(path: &Path) -> Option<f64> {
+    let mut decoder = open_decoder(path).ok()?;
+    let resolution = match decoder.find_tag(Tag::XResolution).ok()?? {
+        Value::Rational(n, d) if n > 0 && d > 0 => n as f64 / d as f64,
+        _ => return None,
+    };
+    let unit = decoder.find_tag_unsigned::<u16>(Tag::ResolutionUnit).ok().flatten().unwrap_or(2);
+    match unit {
+        3 => Some(10_000.0 / resolution),
+        // 72/96 dpi are screen defaults, not a real scale
+        2 if resolution != 72.0 && resolution != 96.0 => Some(25_400.0 / resolution),
+        _ => None,
+    }
+}
+
+/// ImageDescription is an ASCII tag and the tiff crate refuses anything else, so
+/// units like "µm" are written as JSON \uXXXX escapes, which from_json reads back
+fn ascii_json(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                escaped.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    escaped
+}
+
+// the tiff encoder takes the compression as a type parameter, hence the factory
+fn write_all_pages<W: Write + Seek, D: Compression>(
+    encoder: &mut TiffEncoder<W>,
+    pages: &[RawImage],
+    (description, resolution): (Option<&str>, Option<Rational>),
+    compression: impl Fn() -> D,
+) -> TiffResult<()> {
+    for (i, page) in pages.iter().enumerate() {
+        let (w, h) = (page.width, page.height);
+        let tags = if i == 0 { (description, resolution.clone()) } else { (None, None) };
+        let c = compression();
+        match (&page.pixels, page.channels) {
+            (RawPixels::U8(data), 1) => write_page::<_, colortype::Gray8, _>(encoder, w, h, data, tags, c)?,
+            (RawPixels::U8(data), _) => write_page::<_, colortype::RGB8, _>(encoder, w, h, data, tags, c)?,
+            (RawPixels::U16(data), 1) => write_page::<_, colortype::Gray16, _>(encoder, w, h, data, tags, c)?,
+            (RawPixels::U16(data), _) => write_page::<_, colortype::RGB16, _>(encoder, w, h, data, tags, c)?,
+            (RawPixels::F32(data), 1) => write_page::<_, colortype::Gray32Float, _>(encoder, w, h, data, tags, c)?,
+            (RawPixels::F32(data), _) => write_page::<_, colortype::RGB32Float, _>(encoder, w, h, data, tags, c)?,
+        }
+    }
+    Ok(())
+}
+
+fn write_page<W: Write + Seek, C: ColorType, D: Compression>(
+    encoder: &mut TiffEncoder<W>,
+    width: u32,
+    height: u32,
+    data: &[C::Inner],
+    (description, resolution): (Option<&str>, Option<Rational>),
+    compression: D,
+) -> TiffResult<()>
+where
+    [C::Inner]: TiffValue,
+{
+    let mut image = encoder.new_image_with_compression::<C, D>(width, height, compression)?;
+    if let Some(description) = description {
+        image.encoder().write_tag(Tag::ImageDescription, description)?;
+    }
+    if let Some(resolution) = resolution {
+        image.resolution(ResolutionUnit::Centimeter, resolution);
+    }
+    image.write_data(data)
+}
+
+fn open_decoder(path: &Path) -> Result<Decoder<BufReader<File>>, ImageIoError> {
+    let file = BufReader::new(File::open(path)?);
+    Decoder::new(file)
+        .map(|d| d.with_limits(Limits::unlimited()))
+        .map_err(|e| ImageIoError::Decode(e.to_string()))
+}
+
+// alpha and extra samples are dropped; anything that isn't 8/16-bit or float becomes float
+fn page_to_raw(width: u32, height: u32, data: DecodingResult) -> Result<RawImage, ImageIoError> {
+    let pixel_count = (width * height) as usize;
+    if pixel_count == 0 {
+        return Err(ImageIoError::Unsupported("empty page".to_string()));
+    }
+
+    let pixels = match data {
+        DecodingResult::U8(v) => RawPixels::U8(v),
+        DecodingResult::U16(v) => RawPixels::U16(v),
+        DecodingResult::F32(v) => RawPixels::F32(v),
+        DecodingResult::U32(v) => RawPixels::F32(v.into_iter().map(|x| x as f32).collect()),
+        DecodingResult::U64(v) => RawPixels::F32(v.into_iter().map(|x| x as f32).collect()),
+        DecodingResult::F64(v) => RawPixels::F32(v.into_iter().map(|x| x as f32).collect()),
+        DecodingResult::I8(v) => RawPixels::F32(v.into_iter().map(|x| x as f32).collect()),
+        DecodingResult::I16(v) => RawPixels::F32(v.into_iter().map(|x| x as f32).collect()),
+        DecodingResult::I32(v) => RawPixels::F32(v.into_iter().map(|x| x as f32).collect()),
+        DecodingResult::I64(v) => RawPixels::F32(v.into_iter().map(|x| x as f32).collect()),
+    };
+
+    let samples = match &pixels {
+        RawPixels::U8(v) => v.len(),
+        RawPixels::U16(v) => v.len(),
+        RawPixels::F32(v) => v.len(),
+    } / pixel_count;
+
+    let channels = match samples {
+        1 | 2 => 1,
+        3 | 4 => 3,
+        n => return Err(ImageIoError::Unsupported(format!("{} samples per pixel", n))),
+    };
+
+    let pixels = if samples == channels {
+        pixels
+    } else {
+        strip_extra_samples(pixels, samples, channels)
+    };
+
+    Ok(RawImage::new(width, height, channels, pixels))
+}
+
+fn strip_extra_samples(pixels: RawPixels, samples: usize, keep: usize) -> RawPixels {
+    fn strip<T: Copy>(data: Vec<T>, samples: usize, keep: usize) -> Vec<T> {
+        data.chunks(samples).flat_map(|px| px[..keep].to_vec()).collect()
+    }
+    match pixels {
+        RawPixels::U8(v) => RawPixels::U8(strip(v, samples, keep)),
+        RawPixels::U16(v) => RawPixels::U16(strip(v, samples, keep)),
+        RawPixels::F32(v) => RawPixels::F32(strip(v, samples, keep)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_ascii_units_are_escaped() {
+        assert_eq!(ascii_json(r#"{"unit":"µm"}"#), r#"{"unit":"\u00b5m"}"#);
+        assert_eq!(ascii_json("𝜇"), "\\ud835\\udf07");
+    }
+
+    #[test]
+    fn micrometre_calibration_survives_a_tiff_round_trip() {
+        let path = std::env::temp_dir().join(format!("tiff-stack-test-{}.tif", std::process::id()));
+        let pages = vec![
+            RawImage::new(4, 3, 1, RawPixels::U16((0..12).map(|v| v * 1000).collect())),
+            RawImage::new(4, 3, 1, RawPixels::U16(vec![7; 12])),
+        ];
+        let embedded = EmbeddedMetadata::from_pixel_size(0.25);
+
+        write_tiff_pages(&path, &pages, Some(&embedded), TiffCompression::Lzw).unwrap();
+        let read = read_tiff_pages(&path).unwrap();
+        let metadata = read_tiff_metadata(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(read.len(), 2);
+        assert!(matches!(&read[0].pixels, RawPixels::U16(v) if v[11] == 11_000));
+        assert!(matches!(&read[1].pixels, RawPixels::U16(v) if v == &vec![7; 12]));
+        let (_, unit) = metadata.metadata.scale_calibration.clone().unwrap();
+        assert_eq!(unit, "µm");
+        assert!((metadata.pixel_size_microns().unwrap() - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pages_keep_their_own_bit_depth() {
+        let pages = vec![
+            RawImage::new(2, 2, 1, RawPixels::U16(vec![0, 1000, 40_000, 65_535])),
+            RawImage::new(2, 2, 3, RawPixels::U8((0..12).collect())),
+            RawImage::new(2, 2, 1, RawPixels::F32(vec![0.0, 0.5, -1.0, 1.5])),
+        ];
+
+        for compression in [TiffCompression::None, TiffCompression::Deflate] {
+            let path = std::env::temp_dir().join(format!("tiff-pages-test-{}-{:?}.tif", std::process::id(), compression));
+            write_tiff_pages(&path, &pages, None, compression).unwrap();
+            let count = count_tiff_pages(&path).unwrap();
+            let read = read_tiff_pages(&path).unwrap();
+            let metadata = read_tiff_metadata(&path);
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!(count, 3);
+            assert!(metadata.is_none());
+            for (written, read) in pages.iter().zip(&read) {
+                assert_eq!((read.width, read.height, read.channels), (written.width, written.height, written.channels));
+                assert_eq!(format!("{:?}", read.pixels), format!("{:?}", written.pixels));
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 // src/scientific/layers/annotation.rs
 use fltk::image::RgbImage;
+use serde::{Serialize, Deserialize};
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum AnnotationType {
     ROI {
         color: (u8, u8, u8),
@@ -1,6 +1,7 @@
 // src/scientific/layers/channel.rs
-use fltk::image::RgbImage;
+use fltk::{image::RgbImage, prelude::ImageExt};
 use crate::scientific::layers::metadata::Metadata;
+use crate::utils::raw_image::RawImage;
 
 #[derive(Clone)]
 pub struct Channel {
@@ -11,6 +12,8 @@ pub struct Channel {
     pub opacity: f32,
     pub visible: bool,
     pub metadata: Metadata,
+    /// full bit-depth data behind `image`, when the source had more than 8 bits
+    pub raw: Option<RawImage>,
 }
 
 impl Channel {
@@ -23,6 +26,40 @@ impl Channel {
             opacity: 1.0,
             visible: true,
             metadata: Metadata::default(),
+            raw: None,
         }
     }
-}
\ No newline at end of file
+
+    pub fn with_raw(mut self, raw: Option<RawImage>) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// intensity at (x, y) in raw units, falling back to the 8-bit display data
+    pub fn intensity_at(&self, x: i32, y: i32) -> f64 {
+        if let Some(raw) = &self.raw {
+            return raw.intensity(x, y);
+        }
+        if x < 0 || y < 0 || x >= self.image.data_w() || y >= self.image.data_h() {
+            return 0.0;
+        }
+        let idx = (y * self.image.data_w() + x) as usize * 3;
+        let data = self.image.to_rgb_data();
+        if idx + 2 < data.len() {
+            (data[idx] as f64 + data[idx + 1] as f64 + data[idx + 2] as f64) / 3.0
+        } else {
+            0.0
+        }
+    }
+
+    /// per-pixel intensities in raw units, row-major
+    pub fn intensities(&self) -> Vec<f64> {
+        if let Some(raw) = &self.raw {
+            return raw.intensities();
+        }
+        self.image.to_rgb_data()
+            .chunks(3)
+            .map(|c| (c[0] as f64 + c[1] as f64 + c[2] as f64) / 3.0)
+            .collect()
+    }
+}
@@ -12,6 +12,9 @@ pub struct Metadata {
     pub pixel_size: Option<f32>,
     pub comments: Option<String>,
     pub scale_calibration: Option<(f32, String)>,
+    /// corrections applied to the pixels since acquisition, e.g. flat-field
+    #[serde(default)]
+    pub corrections: Vec<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
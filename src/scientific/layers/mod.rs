@@ -1,7 +1,11 @@
+#[cfg(feature = "gui")]
 mod channel;
+#[cfg(feature = "gui")]
 mod annotation;
 pub mod metadata;
 
+#[cfg(feature = "gui")]
 pub use channel::Channel;
+#[cfg(feature = "gui")]
 pub use annotation::{Annotation, AnnotationType};
 pub use metadata::{Metadata, Calibration}; 
\ No newline at end of file
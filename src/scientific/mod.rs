@@ -2,17 +2,27 @@ pub mod analysis;
 pub mod calibration;
 pub mod layers;
 pub mod state;
+#[cfg(feature = "gui")]
 pub mod tools;
+#[cfg(feature = "gui")]
 pub mod ui;
 pub mod types;
+#[cfg(feature = "gui")]
 pub mod rendering;
 pub mod reporting;
+#[cfg(feature = "gui")]
 pub mod visualization;
+pub mod io;
 
-pub use layers::{Channel, Annotation, AnnotationType, Metadata};
+#[cfg(feature = "gui")]
+pub use layers::{Channel, Annotation, AnnotationType};
+pub use layers::Metadata;
+#[cfg(feature = "gui")]
 pub use analysis::{IntensityProfile, ColocalizationAnalysis};
 pub use calibration::SpatialCalibration;
+#[cfg(feature = "gui")]
 pub use state::ScientificState;
 pub use types::{LegendPosition, ROIShape, ROITool, MeasurementTool};
 pub use reporting::CalibrationReport;
-pub use visualization::CellVisualizer;
\ No newline at end of file
+#[cfg(feature = "gui")]
+pub use visualization::CellVisualizer;
@@ -8,7 +8,7 @@ use fltk::{
 use std::{rc::Rc, cell::RefCell};
 use crate::state::ImageState;
 use crate::scientific::rendering::ScaleRenderer;
-use crate::utils::{scale_image_dimensions, MENU_HEIGHT};
+use crate::utils::draw_current_image;
 
 pub struct FrameRenderer;
 
@@ -17,21 +17,8 @@ impl FrameRenderer {
         let state = state.clone();
         
         frame.borrow_mut().draw(move |f| {
-            // draw the base image if available
-            if let Some(base_img) = state.borrow().image.as_ref() {
-                let mut img = base_img.clone();
-                let zoom = state.borrow().zoom;
-                
-                // scale image according to frame size and zoom
-                let (new_w, new_h) = scale_image_dimensions(
-                    img.data_w(),
-                    img.data_h(),
-                    f.width(),
-                    f.height() - MENU_HEIGHT,
-                    zoom as f64
-                );
-                img.scale(new_w, new_h, true, true);
-                img.draw(f.x(), f.y(), f.width(), f.height());
+            if let Ok(state_ref) = state.try_borrow() {
+                draw_current_image(f, &state_ref);
             }
         });
     }
@@ -44,21 +31,9 @@ impl FrameRenderer {
             let state_ref = state_clone.borrow();
             
             // Draw base image first
-            if let Some(base_img) = state_ref.image.as_ref() {
-                let mut img = base_img.clone();
-                let zoom = state_ref.zoom;
-                
-                // Scale image according to frame size and zoom
-                let (new_w, new_h) = scale_image_dimensions(
-                    img.data_w(),
-                    img.data_h(),
-                    f.width(),
-                    f.height() - MENU_HEIGHT,
-                    zoom as f64
-                );
-                img.scale(new_w, new_h, true, true);
-                img.draw(f.x(), f.y(), f.width(), f.height());
-                
+            if state_ref.image.is_some() {
+                draw_current_image(f, &state_ref);
+
                 // Draw the scale if enabled
                 if state_ref.scientific_state.show_legend {
                     ScaleRenderer::draw_legend(
@@ -0,0 +1,107 @@
+//src/scientific/state/image_stack.rs
+use serde::{Serialize, Deserialize};
+use crate::utils::raw_image::RawImage;
+
+/// how the pages of a multi-page file are interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StackKind {
+    Channels,
+    ZSlices,
+    TimeFrames,
+}
+
+impl StackKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StackKind::Channels => "Channel",
+            StackKind::ZSlices => "Z",
+            StackKind::TimeFrames => "T",
+        }
+    }
+}
+
+/// pages of a multi-page TIFF kept at full bit depth
+#[derive(Clone)]
+pub struct ImageStack {
+    pub pages: Vec<RawImage>,
+    pub kind: StackKind,
+    pub current: usize,
+}
+
+impl ImageStack {
+    pub fn new(pages: Vec<RawImage>, kind: StackKind) -> Self {
+        Self {
+            pages,
+            kind,
+            current: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    pub fn current_page(&self) -> Option<&RawImage> {
+        self.pages.get(self.current)
+    }
+
+    pub fn current_page_mut(&mut self) -> Option<&mut RawImage> {
+        self.pages.get_mut(self.current)
+    }
+
+    pub fn set_current(&mut self, index: usize) -> Option<&RawImage> {
+        if index < self.pages.len() {
+            self.current = index;
+        }
+        self.current_page()
+    }
+
+    /// the pages with the current one replaced by its edited version, or None when
+    /// the edit changed its size and the stack no longer fits together
+    pub fn pages_with_current(&self, edited: RawImage) -> Option<Vec<RawImage>> {
+        let current = self.current_page()?;
+        if (edited.width, edited.height) != (current.width, current.height) {
+            return None;
+        }
+        let mut pages = self.pages.clone();
+        pages[self.current] = edited;
+        Some(pages)
+    }
+
+    pub fn position_label(&self) -> String {
+        format!("{} {}/{}", self.kind.label(), self.current + 1, self.pages.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::raw_image::RawPixels;
+
+    fn page(width: u32, value: u16) -> RawImage {
+        RawImage::new(width, 2, 1, RawPixels::U16(vec![value; width as usize * 2]))
+    }
+
+    #[test]
+    fn edited_page_replaces_only_the_current_one() {
+        let mut stack = ImageStack::new(vec![page(3, 1), page(3, 2), page(3, 3)], StackKind::ZSlices);
+        stack.set_current(1);
+
+        let edited = RawImage::new(3, 2, 3, RawPixels::U8(vec![9; 18]));
+        let pages = stack.pages_with_current(edited).unwrap();
+        assert_eq!(pages.len(), 3);
+        assert!(matches!(&pages[0].pixels, RawPixels::U16(v) if v[0] == 1));
+        assert!(matches!(&pages[1].pixels, RawPixels::U8(v) if v[0] == 9));
+        assert!(matches!(&pages[2].pixels, RawPixels::U16(v) if v[0] == 3));
+    }
+
+    #[test]
+    fn resized_page_does_not_fit_the_stack() {
+        let stack = ImageStack::new(vec![page(3, 1), page(3, 2)], StackKind::TimeFrames);
+        assert!(stack.pages_with_current(page(2, 5)).is_none());
+    }
+}
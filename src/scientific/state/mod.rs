@@ -1,4 +1,8 @@
+#[cfg(feature = "gui")]
 pub mod scientific_state;
+pub mod image_stack;
 
 
+#[cfg(feature = "gui")]
 pub use scientific_state::*;
+pub use image_stack::{ImageStack, StackKind};
@@ -5,12 +5,16 @@ use chrono::Utc;
 use crate::{
 scientific::{
     layers::{Channel, Annotation, AnnotationType,Metadata, Calibration},
-    analysis::{IntensityProfile, CellStatistics, CellMeasurement},
-    calibration::SpatialCalibration,    
+    analysis::{IntensityProfile, CellStatistics, CellMeasurement, background::Background},
+    calibration::{SpatialCalibration, FlatFieldCalibration, flat_field},
     types::{ROIShape, ROITool, MeasurementTool, LegendPosition, CellMeasurementMode},
     tools::interactive::cell_analysis_tool::{CellAnalysisTool, CellAnalysisState},
+    tools::interactive::roi::measurements::MeasurementCalculator,
+    state::image_stack::{ImageStack, StackKind},
+    io::EmbeddedMetadata,
 },
 state::ImageState,
+utils::{raw_image::RawImage, transform::Transform, transform_rgb_image},
 };
 
 
@@ -27,6 +31,8 @@ pub struct ScientificState {
     pub current_measurement_points: Vec<(i32, i32)>,
     pub show_overlay: bool,
     pub calibrations: Vec<Calibration>,
+    /// flat-field/dark-frame pairs, at most one per objective
+    pub flat_fields: Vec<FlatFieldCalibration>,
     pub show_legend: bool,
     pub legend_position: LegendPosition,
     pub cell_analysis_tool: Option<CellAnalysisTool>,
@@ -39,8 +45,65 @@ pub struct ScientificState {
     pub preview_layer: Option<RgbImage>,
     pub show_base_image: bool, 
     pub show_drawing_layer: bool,
+    /// set when a multi-page TIFF was opened
+    pub image_stack: Option<ImageStack>,
+    /// metadata read from the opened file, copied onto channels created from it
+    pub image_metadata: Metadata,
+    /// background of the open image from Process→Subtract Background, used for CTCF
+    pub measurement_background: Option<Background>,
 
 }
+
+/// the parts of the scientific state tied to the image's pixel grid,
+/// kept in the history so a rotate or resize undoes in one step
+#[derive(Clone)]
+pub struct GeometrySnapshot {
+    annotations: Vec<Annotation>,
+    calibration: SpatialCalibration,
+    image_metadata: Metadata,
+    channels: Vec<Channel>,
+    base_image: Option<RgbImage>,
+    image_stack: Option<ImageStack>,
+}
+
+impl GeometrySnapshot {
+    /// rough size in bytes, for the history budget
+    pub fn size_bytes(&self) -> usize {
+        let image_bytes = |image: &RgbImage| (image.data_w() * image.data_h() * 3).max(0) as usize;
+        self.annotations.iter().map(|a| image_bytes(&a.image)).sum::<usize>()
+            + self.channels.iter()
+                .map(|c| image_bytes(&c.image) + c.raw.as_ref().map_or(0, |r| r.size_bytes()))
+                .sum::<usize>()
+            + self.base_image.as_ref().map_or(0, image_bytes)
+            + self.image_stack.as_ref().map_or(0, |s| s.pages.iter().map(|p| p.size_bytes()).sum())
+    }
+}
+
+/// annotations, ROIs and what was measured or calibrated with them, kept in the
+/// history for edits that leave the pixels alone (adding an ROI, setting the scale)
+#[derive(Clone)]
+pub struct AnnotationSnapshot {
+    annotations: Vec<Annotation>,
+    calibration: SpatialCalibration,
+    calibrations: Vec<Calibration>,
+    measurements: Vec<CellMeasurement>,
+    channel_metadata: Vec<Metadata>,
+}
+
+impl AnnotationSnapshot {
+    /// rough size in bytes, for the history budget
+    pub fn size_bytes(&self) -> usize {
+        self.annotations
+            .iter()
+            .map(|a| {
+                (a.image.data_w() * a.image.data_h() * 3).max(0) as usize
+                    + a.coordinates.len() * std::mem::size_of::<(i32, i32)>()
+            })
+            .sum::<usize>()
+            + self.measurements.len() * std::mem::size_of::<CellMeasurement>()
+    }
+}
+
 // implementation block for ScientificState
 impl ScientificState {
     pub fn new() -> Self {
@@ -57,6 +120,7 @@ impl ScientificState {
             current_measurement_points: Vec::new(),
             show_overlay: true,
             calibrations: Vec::new(),
+            flat_fields: Vec::new(),
             show_legend: false,  // adding a default value that will be removed later
             legend_position: LegendPosition::BottomRight,
             cell_analysis_tool: None,
@@ -69,6 +133,9 @@ impl ScientificState {
             preview_layer: None,
             show_base_image: true,
             show_drawing_layer: true,
+            image_stack: None,
+            image_metadata: Metadata::default(),
+            measurement_background: None,
 
         }
     }
@@ -94,10 +161,15 @@ impl ScientificState {
             Some(self.measurements.clone())
         }
     }
+    /// replaces the stored cell measurements, used when loading a project
+    pub fn set_measurements(&mut self, measurements: Vec<CellMeasurement>) {
+        self.measurements = measurements;
+    }
     pub fn get_measurement_mode(&self) -> CellMeasurementMode {
         self.measurement_mode
     }
-    pub fn store_base_image(&mut self, image: RgbImage) {
+    /// `raw` is the full bit-depth data for `image`, if the loader kept one
+    pub fn store_base_image(&mut self, image: RgbImage, raw: Option<RawImage>) {
         println!("Storing base image in scientific state");
         
         // Get the image dimensions
@@ -116,21 +188,205 @@ impl ScientificState {
         
         // Create channel with same dimensions
         println!("Creating default channel for base image");
-        let channel = Channel::new(
+        let mut channel = Channel::new(
             "Base".to_string(),
             image,
             550.0,
             (255, 255, 255)
-        );
+        ).with_raw(raw);
+        channel.metadata = self.image_metadata.clone();
         
         self.channels.clear();
         self.channels.push(channel);
         println!("Channel created and stored. Total channels: {}", self.channels.len());
     }
+    /// replaces the file metadata and, when it carries a pixel size, the spatial calibration
+    pub fn set_image_metadata(&mut self, metadata: Metadata) {
+        if let Some((pixels_per_unit, unit)) = &metadata.scale_calibration {
+            if *pixels_per_unit > 0.0 {
+                println!("Calibration from file metadata: {:.4} pixels/{}", pixels_per_unit, unit);
+                self.calibration = SpatialCalibration::new(unit.clone());
+                self.calibration.pixels_per_unit = *pixels_per_unit;
+            }
+        }
+        for channel in &mut self.channels {
+            channel.metadata = metadata.clone();
+        }
+        self.image_metadata = metadata;
+    }
+
+    /// drops the scale of the previous image, for when a new one is opened
+    pub fn reset_calibration(&mut self) {
+        self.calibration = SpatialCalibration::new("µm".to_string());
+    }
+
+    /// restores metadata and calibration that were saved with the file
+    pub fn apply_embedded_metadata(&mut self, embedded: EmbeddedMetadata) {
+        self.set_image_metadata(embedded.metadata);
+        if let Some(calibration) = embedded.calibration {
+            println!("Restored calibration: {:.4} pixels/{}", calibration.pixels_per_unit, calibration.unit);
+            self.calibration = calibration;
+        }
+    }
+
+    /// metadata and calibration in the form they get written into saved files
+    pub fn embedded_metadata(&self) -> EmbeddedMetadata {
+        EmbeddedMetadata::new(&self.image_metadata, &self.calibration)
+    }
+
+    /// forgets the stack and the channels made from it, for when a single image is opened
+    pub fn clear_stack(&mut self) {
+        self.image_stack = None;
+        self.channels.clear();
+        self.active_channel = None;
+    }
+
+    /// stores a page stack; in channel mode each page also becomes a Channel
+    pub fn load_stack(&mut self, stack: ImageStack) {
+        const PSEUDO_COLORS: [(u8, u8, u8); 6] = [
+            (255, 0, 0), (0, 255, 0), (0, 0, 255),
+            (0, 255, 255), (255, 0, 255), (255, 255, 0),
+        ];
+
+        if stack.kind == StackKind::Channels {
+            self.channels.clear();
+            for (i, page) in stack.pages.iter().enumerate() {
+                if let Some(image) = page.to_rgb_image() {
+                    let mut channel = Channel::new(
+                        format!("Channel {}", i + 1),
+                        image,
+                        0.0,
+                        PSEUDO_COLORS[i % PSEUDO_COLORS.len()],
+                    ).with_raw(Some(page.clone()));
+                    channel.metadata = self.image_metadata.clone();
+                    self.channels.push(channel);
+                }
+            }
+            self.active_channel = if self.channels.is_empty() { None } else { Some(0) };
+            println!("Loaded {} stack pages as channels", self.channels.len());
+        }
+
+        self.image_stack = Some(stack);
+    }
+
     pub fn get_base_image(&self) -> Option<RgbImage> {
         self.base_image.clone()
     }
 
+    pub fn geometry_snapshot(&self) -> GeometrySnapshot {
+        GeometrySnapshot {
+            annotations: self.annotations.clone(),
+            calibration: self.calibration.clone(),
+            image_metadata: self.image_metadata.clone(),
+            channels: self.channels.clone(),
+            base_image: self.base_image.clone(),
+            image_stack: self.image_stack.clone(),
+        }
+    }
+
+    pub fn restore_geometry(&mut self, snapshot: GeometrySnapshot) {
+        self.annotations = snapshot.annotations;
+        self.calibration = snapshot.calibration;
+        self.image_metadata = snapshot.image_metadata;
+        self.channels = snapshot.channels;
+        self.base_image = snapshot.base_image;
+        self.image_stack = snapshot.image_stack;
+        self.clear_points();
+    }
+
+    pub fn annotation_snapshot(&self) -> AnnotationSnapshot {
+        AnnotationSnapshot {
+            annotations: self.annotations.clone(),
+            calibration: self.calibration.clone(),
+            calibrations: self.calibrations.clone(),
+            measurements: self.measurements.clone(),
+            channel_metadata: self.channels.iter().map(|c| c.metadata.clone()).collect(),
+        }
+    }
+
+    pub fn restore_annotations(&mut self, snapshot: AnnotationSnapshot) {
+        self.annotations = snapshot.annotations;
+        self.calibration = snapshot.calibration;
+        self.calibrations = snapshot.calibrations;
+        self.measurements = snapshot.measurements;
+        // channels only change with the image, so they still line up with the metadata
+        for (channel, metadata) in self.channels.iter_mut().zip(snapshot.channel_metadata) {
+            channel.metadata = metadata;
+        }
+    }
+
+    /// moves everything measured on a `width` x `height` image along with a rotation,
+    /// flip or resize of it: channels, ROIs, annotations, points and the calibration
+    pub fn transform_geometry(&mut self, transform: &Transform, width: u32, height: u32) {
+        let (scale_x, scale_y) = transform.scale(width, height);
+        let length_scale = (scale_x * scale_y).sqrt();
+        let map = |points: &mut Vec<(i32, i32)>| {
+            for point in points.iter_mut() {
+                *point = transform.map_pixel(width, height, point.0, point.1);
+            }
+        };
+        // annotation and channel images that cover the whole image move with it
+        let same_size = |image: &RgbImage| image.data_w() as u32 == width && image.data_h() as u32 == height;
+
+        for annotation in &mut self.annotations {
+            map(&mut annotation.coordinates);
+            if same_size(&annotation.image) {
+                if let Some(image) = transform_rgb_image(&annotation.image, transform) {
+                    annotation.image = image;
+                }
+            }
+            if let AnnotationType::Scale { pixels_per_unit, .. } = &mut annotation.annotation_type {
+                *pixels_per_unit *= length_scale as f32;
+                // scale bars stay horizontal, they're put back in the legend corner below
+                if annotation.coordinates.len() >= 2 {
+                    let start = annotation.coordinates[0];
+                    let length = (*pixels_per_unit * 100.0).round() as i32;
+                    annotation.coordinates = vec![start, (start.0 + length, start.1)];
+                }
+            }
+        }
+        map(&mut self.current_roi_points);
+        map(&mut self.current_measurement_points);
+        // a background surface no longer lines up, measure it again
+        if let Some(Background::Surface { .. }) = self.measurement_background {
+            self.measurement_background = None;
+        }
+        if let Some(roi) = &mut self.roi_tool {
+            roi.shape = match &roi.shape {
+                ROIShape::Polygon { points } => {
+                    let mut points = points.clone();
+                    map(&mut points);
+                    ROIShape::Polygon { points }
+                }
+                ROIShape::Line { points } => {
+                    let mut points = points.clone();
+                    map(&mut points);
+                    ROIShape::Line { points }
+                }
+                shape => shape.clone(),
+            };
+        }
+
+        for channel in &mut self.channels {
+            if let Some(image) = transform_rgb_image(&channel.image, transform) {
+                channel.image = image;
+            }
+            channel.raw = channel.raw.as_ref().map(|raw| raw.transformed(transform));
+            transform_metadata(&mut channel.metadata, length_scale);
+        }
+        if let Some(base) = &self.base_image {
+            self.base_image = transform_rgb_image(base, transform);
+        }
+        if let Some(stack) = &mut self.image_stack {
+            stack.pages = stack.pages.iter().map(|page| page.transformed(transform)).collect();
+        }
+        transform_metadata(&mut self.image_metadata, length_scale);
+        self.calibration.transform(transform, width, height);
+
+        let position = self.legend_position;
+        self.set_legend_position(position);
+    }
+
     // Add this method to store measurements
     pub fn add_cell_measurement(&mut self, measurement: CellMeasurement) {
         self.measurements.push(measurement);
@@ -164,6 +420,12 @@ impl ScientificState {
         self.clear_points();
     }
 
+    /// ROI measurements in the current calibration, with CTCF when a background is set
+    pub fn measurement_calculator(&self) -> MeasurementCalculator {
+        let calibration = self.calibration.is_calibrated().then(|| self.calibration.clone());
+        MeasurementCalculator::new(calibration).with_background(self.measurement_background.clone())
+    }
+
     // Add ROI intensity profile method
     pub fn get_roi_intensity_profile(&mut self, points: &[(i32, i32)]) -> Option<IntensityProfile> {
         println!("Attempting to get ROI intensity profile");
@@ -719,6 +981,51 @@ impl ScientificState {
         self.calibrations.push(calibration);
     }
 
+    /// the flat-fields registered in earlier sessions, read once when the viewer starts
+    pub fn load_flat_field_registry(&mut self) {
+        self.flat_fields = flat_field::load_registry();
+    }
+
+    /// registers `flat_field`, replacing the one its objective had. the caller saves
+    /// the registry, so a state that never loaded it doesn't overwrite it
+    pub fn add_flat_field(&mut self, flat_field: FlatFieldCalibration) {
+        self.flat_fields.retain(|existing| !existing.matches(&flat_field.objective));
+        self.flat_fields.push(flat_field);
+    }
+
+    /// the flat-field registered for the objective the open image was taken with
+    pub fn flat_field_for_image(&self) -> Option<&FlatFieldCalibration> {
+        let objective = self.image_metadata.objective.as_deref()?;
+        self.flat_fields.iter().find(|flat_field| flat_field.matches(objective))
+    }
+
+    /// notes a flat-field correction of the open image in its metadata. channels keep
+    /// theirs, since only the image itself was corrected
+    pub fn record_flat_field(&mut self, flat_field: &FlatFieldCalibration) {
+        let record = flat_field.describe();
+        println!("{}", record);
+        self.image_metadata.corrections.push(record);
+    }
+
+    /// the automatic correction of a freshly opened image, when its objective has a
+    /// flat-field registered to be applied on open and the file wasn't corrected before
+    pub fn flat_field_on_open(&mut self, raw: &RawImage) -> Option<RawImage> {
+        if flat_field::was_applied(&self.image_metadata.corrections) {
+            return None;
+        }
+        let flat_field = self.flat_field_for_image().filter(|f| f.apply_on_open)?.clone();
+        match flat_field.correct(raw) {
+            Ok(corrected) => {
+                self.record_flat_field(&flat_field);
+                Some(corrected)
+            }
+            Err(e) => {
+                println!("Flat-field correction skipped: {}", e);
+                None
+            }
+        }
+    }
+
 
 }
 // Implement CellAnalysisState trait for ScientificState
@@ -759,4 +1066,14 @@ impl CellAnalysisState for ScientificState {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+// pixel size and pixels-per-unit after lengths in pixels were stretched by `length_scale`
+fn transform_metadata(metadata: &mut Metadata, length_scale: f64) {
+    if let Some(pixel_size) = &mut metadata.pixel_size {
+        *pixel_size /= length_scale as f32;
+    }
+    if let Some((pixels_per_unit, _)) = &mut metadata.scale_calibration {
+        *pixels_per_unit *= length_scale as f32;
+    }
+}
@@ -1,11 +1,12 @@
 use fltk::{image::RgbImage, prelude::*, enums::Event};
 use crate::scientific::{
-    analysis::{CellMeasurement, CellAnalyzer, CellStatistics},
+    analysis::{CellMeasurement, CellAnalyzer, CellStatistics, Mask},
     types::{ROIShape, CellMeasurementMode},
     layers::{Annotation, AnnotationType},
     analysis::cell_statistics::StatisticalAnalysis,
 };
 use crate::scientific::IntensityProfile;
+use crate::utils::raw_image::RawImage;
 
 pub struct CellAnalysisTool {
     active: bool,
@@ -155,6 +156,14 @@ impl CellAnalysisTool {
         }
     }
 
+    /// measures every object of a binary mask, e.g. one made with Process→Binary
+    pub fn process_mask(&mut self, mask: &Mask, intensities: &RawImage) -> usize {
+        let measurements = self.analyzer.analyze_mask(mask, intensities);
+        let count = measurements.len();
+        self.current_measurements.extend(measurements);
+        count
+    }
+
     pub fn set_mode(&mut self, mode: CellMeasurementMode) {
         self.measurement_mode = mode;
     }
@@ -8,6 +8,8 @@ use fltk::{
 };
 use std::{rc::Rc, cell::RefCell};
 use crate::state::ImageState;
+use crate::utils::{draw_current_image, Viewport};
+use crate::menu::view::zoom::is_pan_button;
 
 pub struct InteractiveProfile {
     points: Vec<(i32, i32)>,
@@ -32,15 +34,18 @@ pub fn start_interactive_profile(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<
         let frame = frame.clone();
         
         frame.borrow_mut().draw(move |f| {
-            // Draw base image
-            if let Some(mut img) = state.borrow().image.clone() {
-                img.draw(f.x(), f.y(), f.width(), f.height());
-            }
-            
-            // Draw profile line
-            let points = interactive.borrow().points.clone();
-            if !points.is_empty() {
-                draw_profile(&points);
+            if let Ok(state_ref) = state.try_borrow() {
+                // Draw base image
+                draw_current_image(f, &state_ref);
+
+                // Draw profile line, collected in image pixels
+                let points: Vec<(i32, i32)> = interactive.borrow().points
+                    .iter()
+                    .filter_map(|&(x, y)| Viewport::current(f, &state_ref).map(|viewport| viewport.pixel_centre(x, y)))
+                    .collect();
+                if !points.is_empty() {
+                    draw_profile(&points);
+                }
             }
         });
     }
@@ -49,18 +54,15 @@ pub fn start_interactive_profile(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<
         let interactive = interactive.clone();
         let state = state.clone();
         
-        move |_, ev| match ev {
-            Event::Push => {
-                let coords = fltk::app::event_coords();
-                println!("Push event at ({}, {})", coords.0, coords.1);
-                interactive.borrow_mut().points.push(coords);
-                frame_draw.borrow_mut().redraw();
-                true
-            },
-            Event::Drag => {
+        move |f, ev| match ev {
+            Event::Push | Event::Drag | Event::Released if is_pan_button() => false,
+            Event::Push | Event::Drag => {
                 let coords = fltk::app::event_coords();
-                println!("Drag event at ({}, {})", coords.0, coords.1);
-                interactive.borrow_mut().points.push(coords);
+                println!("{:?} event at ({}, {})", ev, coords.0, coords.1);
+                let point = state.try_borrow().ok().and_then(|state_ref| Viewport::current(f, &state_ref).map(|viewport| viewport.to_pixel(coords.0, coords.1)));
+                if let Some(point) = point {
+                    interactive.borrow_mut().points.push(point);
+                }
                 frame_draw.borrow_mut().redraw();
                 true
             },
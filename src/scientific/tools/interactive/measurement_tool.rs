@@ -7,6 +7,8 @@ use fltk::{
 };
 use std::{rc::Rc, cell::RefCell};
 use crate::state::ImageState;
+use crate::utils::{draw_current_image, Viewport};
+use crate::menu::view::zoom::is_pan_button;
 use crate::scientific::{
     layers::{Annotation, AnnotationType},
     calibration::SpatialCalibration,
@@ -86,6 +88,7 @@ impl MeasurementTool {
                     format!("Measurement {}", state_ref.scientific_state.get_annotation_count() + 1),
                     img
                 );
+                state_ref.record_annotation_edit("Add Measurement");
                 state_ref.scientific_state.add_annotation(annotation);
             }
         }
@@ -98,21 +101,20 @@ pub fn start_interactive_measurement(frame: &Rc<RefCell<Frame>>, state: &Rc<RefC
     let tool = Rc::new(tool);
     
     // Setup draw callback
-    let draw_frame = frame.clone();
     let draw_state = state.clone();
     let draw_tool = tool.clone();
     
     {
         let mut frame_ref = frame.borrow_mut();
-        frame_ref.draw(move |_| {
+        frame_ref.draw(move |f| {
             if let Ok(state_ref) = draw_state.try_borrow() {
-                if let Some(mut img) = state_ref.image.clone() {
-                    let frame_ref = draw_frame.borrow();
-                    img.draw(0, 0, frame_ref.width(), frame_ref.height());
-                }
+                draw_current_image(f, &state_ref);
 
-                // Draw current measurement line
-                let points = &state_ref.scientific_state.current_measurement_points;
+                // Draw current measurement line, the points are in image pixels
+                let points: Vec<(i32, i32)> = state_ref.scientific_state.current_measurement_points
+                    .iter()
+                    .filter_map(|&(x, y)| Viewport::current(f, &state_ref).map(|viewport| viewport.pixel_centre(x, y)))
+                    .collect();
                 if points.len() >= 2 {
                     draw::set_draw_color(Color::from_rgb(
                         draw_tool.line_color.0,
@@ -130,12 +132,12 @@ pub fn start_interactive_measurement(frame: &Rc<RefCell<Frame>>, state: &Rc<RefC
                     }
 
                     // Draw points at each vertex
-                    for &(x, y) in points {
+                    for &(x, y) in &points {
                         draw::draw_circle(x as f64, y as f64, 3.0);
                     }
 
                     // Draw distance if calibration is available
-                    let distance = draw_tool.calculate_distance(points);
+                    let distance = draw_tool.calculate_distance(&state_ref.scientific_state.current_measurement_points);
                     let last_point = points.last().unwrap();
                     draw::set_font(Font::Helvetica, 12);
                     draw::draw_text2(
@@ -155,12 +157,16 @@ pub fn start_interactive_measurement(frame: &Rc<RefCell<Frame>>, state: &Rc<RefC
     let handle_state = state;
     let handle_tool = tool;
     
-    frame.borrow_mut().handle(move |_, ev| match ev {
+    frame.borrow_mut().handle(move |f, ev| match ev {
+        Event::Push if is_pan_button() => false,
         Event::Push => {
-            let coords = fltk::app::event_coords();
+            let (x, y) = fltk::app::event_coords();
             if let Ok(mut state_ref) = handle_state.try_borrow_mut() {
-                state_ref.scientific_state.add_point(coords);
-                handle_frame.borrow_mut().redraw();
+                // clicks beside the image don't add a point
+                if let Some(viewport) = Viewport::current(f, &state_ref).filter(|viewport| viewport.contains(x, y)) {
+                    state_ref.scientific_state.add_point(viewport.to_pixel(x, y));
+                    handle_frame.borrow_mut().redraw();
+                }
             }
             true
         },
@@ -176,6 +182,7 @@ pub fn start_interactive_measurement(frame: &Rc<RefCell<Frame>>, state: &Rc<RefC
                                 format!("Measurement {}", state_ref.scientific_state.get_annotation_count() + 1),
                                 img
                             );
+                            state_ref.record_annotation_edit("Add Measurement");
                             state_ref.scientific_state.add_annotation(annotation);
                             state_ref.scientific_state.clear_points();
                             handle_frame.borrow_mut().redraw();
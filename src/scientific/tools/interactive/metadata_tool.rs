@@ -10,7 +10,7 @@ use fltk::{
 use std::{rc::Rc, cell::RefCell};
 use crate::state::ImageState;
 use crate::scientific::layers::Metadata;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
 pub struct MetadataEditor {
     window: Window,
@@ -21,15 +21,20 @@ pub struct MetadataEditor {
     pixel_size_input: FloatInput,
     comments_input: Input,
     scale_info_frame: Frame,
+    acquisition_frame: Frame,
+    apply_btn: Button,
+    acquisition_time: Option<DateTime<Utc>>,
+    /// not editable, carried over so applying the editor keeps them
+    corrections: Vec<String>,
 }
 
 impl MetadataEditor {
     pub fn new() -> Self {
         let mut window = Window::default()
-            .with_size(400, 350)
+            .with_size(400, 390)
             .with_label("Metadata Editor");
 
-        let mut pack = Pack::new(10, 10, 380, 330, "");
+        let mut pack = Pack::new(10, 10, 380, 370, "");
         pack.set_spacing(10);
 
         let mut exposure_label = Frame::new(10, 10, 110, 25, "Exposure (ms):");
@@ -68,10 +73,18 @@ impl MetadataEditor {
         comments_input.set_color(Color::Dark3);
         comments_input.set_text_color(Color::White);
 
-        let mut scale_info_frame = Frame::new(10, 220, 380, 60, "");
+        let mut acquisition_frame = Frame::new(10, 220, 380, 25, "");
+        acquisition_frame.set_label_color(Color::White);
+        acquisition_frame.set_align(Align::Left | Align::Inside);
+
+        let mut scale_info_frame = Frame::new(10, 255, 380, 60, "");
         scale_info_frame.set_label_color(Color::White);
         scale_info_frame.set_align(Align::Left | Align::Inside);
 
+        let mut apply_btn = Button::new(10, 325, 70, 25, "Apply");
+        apply_btn.set_color(Color::Dark3);
+        apply_btn.set_label_color(Color::White);
+
         pack.end();
         window.end();
 
@@ -84,6 +97,10 @@ impl MetadataEditor {
             pixel_size_input,
             comments_input,
             scale_info_frame,
+            acquisition_frame,
+            apply_btn,
+            acquisition_time: None,
+            corrections: Vec::new(),
         }
     }
 
@@ -107,6 +124,13 @@ impl MetadataEditor {
             self.comments_input.set_value(comments);
         }
 
+        self.acquisition_time = metadata.acquisition_time;
+        self.corrections = metadata.corrections.clone();
+        let acquired = metadata.acquisition_time
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "Unknown".to_string());
+        self.acquisition_frame.set_label(&format!("Acquired: {}", acquired));
+
         // Update scale information
         let scale_info = format!(
             "Scale: {:.2} pixels/{}\nCalibration: {}",
@@ -122,37 +146,43 @@ impl MetadataEditor {
     }
 
     pub fn get_metadata(&self) -> Metadata {
+        let pixel_size: Option<f32> = self.pixel_size_input.value().parse().ok();
         Metadata {
-            acquisition_time: Some(Utc::now()),
+            acquisition_time: self.acquisition_time,
             exposure_time: self.exposure_input.value().parse().ok(),
             gain: self.gain_input.value().parse().ok(),
             objective: Some(self.objective_input.value()),
             binning: self.binning_input.value().parse().ok(),
-            pixel_size: self.pixel_size_input.value().parse().ok(),
+            pixel_size,
             comments: Some(self.comments_input.value()),
-            scale_calibration: None,
+            scale_calibration: pixel_size
+                .filter(|size| *size > 0.0)
+                .map(|size| (1.0 / size, "µm".to_string())),
+            corrections: self.corrections.clone(),
         }
     }
 }
 
 pub fn start_metadata_editor(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
     let mut editor = MetadataEditor::new();
-    let state_ref = state.borrow();
-    let current_metadata = Metadata::default();
-    
-    editor.show(&current_metadata, &state_ref);
+    {
+        let state_ref = state.borrow();
+        let current_metadata = state_ref.scientific_state.image_metadata.clone();
+        editor.show(&current_metadata, &state_ref);
+    }
 
-    let mut apply_btn = Button::new(10, 290, 70, 25, "Apply");
-    apply_btn.set_color(Color::Dark3);
-    apply_btn.set_label_color(Color::White);
-    
+    let mut apply_btn = editor.apply_btn.clone();
+    let editor = Rc::new(RefCell::new(editor));
     let state_clone = state.clone();
     let frame_clone = frame.clone();
     
     apply_btn.set_callback(move |_| {
         if let Ok(mut state_ref) = state_clone.try_borrow_mut() {
-            let new_metadata = editor.get_metadata();
+            let new_metadata = editor.borrow().get_metadata();
+            state_ref.scientific_state.set_image_metadata(new_metadata);
+            println!("Metadata applied to {} channel(s)", state_ref.scientific_state.channels.len());
             frame_clone.borrow_mut().redraw();
         }
+        editor.borrow_mut().window.hide();
     });
-}
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
 // src/scientific/tools/interactive/roi/measurements.rs
 
+use crate::scientific::analysis::background::Background;
 use crate::scientific::calibration::SpatialCalibration;
 use crate::scientific::types::{ROIShape, ROIMeasurements};
 use crate::utils::image_data::ImageData;
@@ -12,15 +13,38 @@ struct IntensityStatistics {
     max: f64,
     integrated: f64,
     std_dev: f64,
+    /// summed background under the ROI, when one is set
+    background: Option<f64>,
+    count: usize,
+}
+
+impl IntensityStatistics {
+    /// mean background under the ROI and the corrected total cell fluorescence,
+    /// integrated density minus the background under the same pixels
+    fn corrected(&self) -> (Option<f64>, Option<f64>) {
+        match self.background {
+            Some(background) if self.count > 0 => {
+                (Some(background / self.count as f64), Some(self.integrated - background))
+            }
+            _ => (None, None),
+        }
+    }
 }
 
 pub struct MeasurementCalculator {
     calibration: Option<SpatialCalibration>,
+    background: Option<Background>,
 }
 
 impl MeasurementCalculator {
     pub fn new(calibration: Option<SpatialCalibration>) -> Self {
-        Self { calibration }
+        Self { calibration, background: None }
+    }
+
+    /// adds CTCF (corrected total cell fluorescence) to the measurements
+    pub fn with_background(mut self, background: Option<Background>) -> Self {
+        self.background = background;
+        self
     }
 
     fn generate_ellipse_points(&self, width: i32, height: i32) -> Vec<(i32, i32)> {
@@ -52,6 +76,7 @@ impl MeasurementCalculator {
         
         let points = self.generate_ellipse_points(width, height);
         let intensity_stats = self.calculate_intensity_statistics(&points, image_data);
+        let (background_mean, ctcf) = intensity_stats.corrected();
         let circularity = (4.0 * std::f64::consts::PI * area) / (perimeter * perimeter);
         let aspect_ratio = width as f64 / height as f64;
 
@@ -71,6 +96,8 @@ impl MeasurementCalculator {
             solidity: 1.0,  // Ellipse is its own convex hull
             is_calibrated: self.calibration.is_some(),
             units: self.get_units(),
+            background_mean,
+            ctcf,
             notes: None,
         }
     }
@@ -101,6 +128,7 @@ impl MeasurementCalculator {
         let perimeter = self.calculate_polygon_perimeter(points);
         
         let intensity_stats = self.calculate_intensity_statistics(points, image_data);
+        let (background_mean, ctcf) = intensity_stats.corrected();
         
         let circularity = if perimeter > 0.0 {
             (4.0 * std::f64::consts::PI * area) / (perimeter * perimeter)
@@ -126,6 +154,8 @@ impl MeasurementCalculator {
             solidity,
             is_calibrated: self.calibration.is_some(),
             units: self.get_units(),
+            background_mean,
+            ctcf,
             notes: None,
         }
     }
@@ -220,6 +250,7 @@ impl MeasurementCalculator {
             (0, 0),
         ];
         let intensity_stats = self.calculate_intensity_statistics(&points, image_data);
+        let (background_mean, ctcf) = intensity_stats.corrected();
         let aspect_ratio = width as f64 / height as f64;
 
         ROIMeasurements {
@@ -238,6 +269,8 @@ impl MeasurementCalculator {
             solidity: 1.0,
             is_calibrated: self.calibration.is_some(),
             units: self.get_units(),
+            background_mean,
+            ctcf,
             notes: None,
         }
     }
@@ -252,6 +285,7 @@ impl MeasurementCalculator {
         };
 
         let intensity_stats = self.calculate_intensity_statistics(points, image_data);
+        let (background_mean, ctcf) = intensity_stats.corrected();
 
         ROIMeasurements {
             id: 0,
@@ -269,6 +303,8 @@ impl MeasurementCalculator {
             solidity: 1.0,
             is_calibrated: self.calibration.is_some(),
             units: self.get_units(),
+            background_mean,
+            ctcf,
             notes: None,
         }
     }
@@ -297,16 +333,20 @@ impl MeasurementCalculator {
         let mut count = 0;
         let mut min_val = f64::MAX;
         let mut max_val = f64::MIN;
+        let mut background = 0.0;
 
         // Iterate through bounding box
         for y in min_y..=max_y {
             for x in min_x..=max_x {
                 if self.point_in_polygon((x, y), points) {
-                    let value = image.get_intensity(x, y) as f64;
+                    let value = image.get_intensity(x, y);
                     sum += value;
                     sum_sq += value * value;
                     min_val = min_val.min(value);
                     max_val = max_val.max(value);
+                    if let Some(level) = &self.background {
+                        background += level.at(x, y);
+                    }
                     count += 1;
                 }
             }
@@ -325,6 +365,8 @@ impl MeasurementCalculator {
             max: max_val,
             integrated: sum,
             std_dev: variance.sqrt(),
+            background: self.background.as_ref().map(|_| background),
+            count,
         }
     }
 
@@ -372,3 +414,74 @@ impl MeasurementCalculator {
             .unwrap_or_else(|| "pixels".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fltk::{enums::ColorDepth, image::RgbImage};
+
+    fn flat_image(width: i32, height: i32, value: u8) -> ImageData {
+        let data = vec![value; (width * height * 3) as usize];
+        ImageData::new(RgbImage::new(&data, width, height, ColorDepth::Rgb8).unwrap())
+    }
+
+    #[test]
+    fn ctcf_is_integrated_density_minus_the_summed_background() {
+        let stats = IntensityStatistics {
+            mean: 50.0,
+            min: 10.0,
+            max: 90.0,
+            integrated: 500.0,
+            std_dev: 1.0,
+            background: Some(120.0),
+            count: 10,
+        };
+        assert_eq!(stats.corrected(), (Some(12.0), Some(380.0)));
+
+        let without_background = IntensityStatistics { background: None, ..stats };
+        assert_eq!(without_background.corrected(), (None, None));
+    }
+
+    #[test]
+    fn background_level_is_subtracted_under_every_roi_pixel() {
+        let image = flat_image(20, 20, 100);
+        let square = ROIShape::Polygon { points: vec![(2, 2), (12, 2), (12, 12), (2, 12)] };
+
+        let plain = MeasurementCalculator::new(None).calculate_measurements(&square, &image);
+        assert_eq!(plain.ctcf, None);
+
+        let corrected = MeasurementCalculator::new(None)
+            .with_background(Some(Background::Level(30.0)))
+            .calculate_measurements(&square, &image);
+        let pixels = corrected.integrated_density / 100.0;
+        assert_eq!(corrected.background_mean, Some(30.0));
+        assert!((corrected.ctcf.unwrap() - 70.0 * pixels).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sixteen_bit_data_is_measured_at_full_range() {
+        use crate::utils::raw_image::{RawImage, RawPixels};
+
+        // left half 1000, right half 60000: both clip or collapse once shown at 8 bits
+        let raw = RawImage::new(20, 20, 1, RawPixels::U16(
+            (0..400).map(|i| if i % 20 < 10 { 1000 } else { 60_000 }).collect(),
+        ));
+        let display = flat_image(20, 20, 128);
+        let data = ImageData::with_raw(display.get_image().clone(), Some(raw));
+        let calculator = MeasurementCalculator::new(None);
+
+        let left = ROIShape::Polygon { points: vec![(1, 1), (8, 1), (8, 18), (1, 18)] };
+        let right = ROIShape::Polygon { points: vec![(11, 1), (18, 1), (18, 18), (11, 18)] };
+        let left = calculator.calculate_measurements(&left, &data);
+        let right = calculator.calculate_measurements(&right, &data);
+
+        assert_eq!((left.min_intensity, left.max_intensity, left.mean_intensity), (1000.0, 1000.0, 1000.0));
+        assert_eq!((right.min_intensity, right.max_intensity, right.mean_intensity), (60_000.0, 60_000.0, 60_000.0));
+
+        // raw data of another size (e.g. after a crop) is not used
+        let stale = RawImage::new(10, 10, 1, RawPixels::U16(vec![5000; 100]));
+        let data = ImageData::with_raw(display.get_image().clone(), Some(stale));
+        assert!(data.get_raw().is_none());
+        assert_eq!(data.get_intensity(3, 3), 128.0);
+    }
+}
@@ -210,6 +210,8 @@ impl ROIState {
                     solidity: 1.0,
                     is_calibrated: false,
                     units: "pixels".to_string(),
+                    background_mean: None,
+                    ctcf: None,
                     notes: None,
                 })
             },
@@ -11,14 +11,15 @@ use fltk::{
 
 use std::{rc::Rc, cell::RefCell};
 use crate::state::ImageState;
+use crate::utils::{draw_current_image, draw_image_in_frame, Viewport};
+use crate::menu::view::zoom::is_pan_button;
 use crate::scientific::{
     layers::{Annotation, AnnotationType},
     types::{ROIShape, ROITool, CellMeasurementMode},
     tools::interactive::cell_analysis_tool::CellAnalysisState,
     ui::{
         cell_analysis::dialog::show_cell_analysis_dialog,
-        roi::show_batch_analysis_dialog,
-        
+        roi::{show_batch_analysis_dialog, MeasurementDialog},
     },
 };
 pub use crate::scientific::ui::roi::show_roi_mode_dialog;
@@ -117,22 +118,12 @@ impl Default for ROIProperties {
     }
 }
 
-struct ScalingInfo {
-    scale: f32,
-    offset_x: i32,
-    offset_y: i32,
-    frame_x: i32,
-    frame_y: i32,
-    img_w: i32,
-    img_h: i32,
-}
-
 pub struct InteractiveROIState {
     start_pos: Option<(i32, i32)>,
     current_shape: Option<ROIShape>,
     points: Vec<(i32, i32)>,
     base_image: Option<RgbImage>,
-    scaling: Option<ScalingInfo>,
+    viewport: Option<Viewport>,
     properties: ROIProperties,
     active_shape_type: ROIShape,
 }
@@ -144,7 +135,7 @@ impl InteractiveROIState {
             current_shape: None,
             points: Vec::new(),
             base_image: None,
-            scaling: None,
+            viewport: None,
             properties: ROIProperties::default(),
             active_shape_type: ROIShape::Rectangle { width: 0, height: 0 },
         }
@@ -172,57 +163,19 @@ impl InteractiveROIState {
         self.points.clear();
     }
 
-    fn update_scaling(&mut self, frame: &Frame, img_w: i32, img_h: i32) {
-        let frame_w = frame.width() as f32;
-        let frame_h = frame.height() as f32;
-        let img_w = img_w as f32;
-        let img_h = img_h as f32;
-
-        let frame_aspect = frame_w / frame_h;
-        let img_aspect = img_w / img_h;
-
-        let (scale, offset_x, offset_y) = if frame_aspect > img_aspect {
-            let scale = frame_h / img_h;
-            let offset_x = ((frame_w - (img_w * scale)) / 2.0) as i32;
-            (scale, offset_x, 0)
-        } else {
-            let scale = frame_w / img_w;
-            let offset_y = ((frame_h - (img_h * scale)) / 2.0) as i32;
-            (scale, 0, offset_y)
-        };
-
-        self.scaling = Some(ScalingInfo {
-            scale,
-            offset_x,
-            offset_y,
-            frame_x: frame.x(),
-            frame_y: frame.y(),
-            img_w: img_w as i32,
-            img_h: img_h as i32,
-        });
+    fn update_scaling(&mut self, frame: &Frame, state: &ImageState) {
+        if let Some(viewport) = Viewport::current(frame, state) {
+            self.viewport = Some(viewport);
+        }
     }
 
     fn display_to_image_coords(&self, display_x: i32, display_y: i32) -> Option<(i32, i32)> {
-        self.scaling.as_ref().map(|scaling| {
-            let rel_x = (display_x - scaling.frame_x - scaling.offset_x) as f32;
-            let rel_y = (display_y - scaling.frame_y - scaling.offset_y) as f32;
-
-            let img_x = (rel_x / scaling.scale) as i32;
-            let img_y = (rel_y / scaling.scale) as i32;
-
-            (
-                img_x.clamp(0, scaling.img_w - 1),
-                img_y.clamp(0, scaling.img_h - 1)
-            )
-        })
+        self.viewport.map(|viewport| viewport.to_pixel(display_x, display_y))
     }
 
     fn image_to_display_coords(&self, image_x: i32, image_y: i32) -> Option<(i32, i32)> {
-        self.scaling.as_ref().map(|scaling| {
-            let display_x = (image_x as f32 * scaling.scale) as i32 + scaling.offset_x + scaling.frame_x;
-            let display_y = (image_y as f32 * scaling.scale) as i32 + scaling.offset_y + scaling.frame_y;
-            (display_x, display_y)
-        })
+        // the centre of the pixel, which matters once pixels are big
+        self.viewport.map(|viewport| viewport.pixel_centre(image_x, image_y))
     }
 }
 
@@ -231,14 +184,8 @@ pub fn start_interactive_roi(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Imag
     let interactive_state = Rc::new(RefCell::new(InteractiveROIState::new()));
     
     // Initialize scaling
-    {
-        if let Ok(mut state_ref) = state.try_borrow_mut() {
-            if let Some(img) = &state_ref.image {
-                println!("Calculating scaling with original image dimensions");
-                let mut interactive_ref = interactive_state.borrow_mut();
-                interactive_ref.update_scaling(&frame.borrow(), img.data_w(), img.data_h());
-            }
-        }
+    if let Ok(state_ref) = state.try_borrow() {
+        interactive_state.borrow_mut().update_scaling(&frame.borrow(), &state_ref);
     }
 
     // Create the shape selector first but don't show it yet
@@ -268,49 +215,21 @@ pub fn start_interactive_roi(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Imag
 
     frame.borrow_mut().draw(move |f| {
         if let Ok(state_ref) = state_draw.try_borrow() {
-            let (img_w, img_h) = if let Some(ref img) = state_ref.image {
-                (img.data_w() as f32, img.data_h() as f32)
-            } else {
+            if state_ref.image.is_none() {
                 return;
-            };
-
-            let frame_w = f.width() as f32;
-            let frame_h = f.height() as f32;
-            
-            let frame_aspect = frame_w / frame_h;
-            let img_aspect = img_w / img_h;
-            
-            let (scale, offset_x, offset_y) = if frame_aspect > img_aspect {
-                let scale = frame_h / img_h;
-                let offset_x = ((frame_w - (img_w * scale)) / 2.0) as i32;
-                (scale, offset_x, 0)
-            } else {
-                let scale = frame_w / img_w;
-                let offset_y = ((frame_h - (img_h * scale)) / 2.0) as i32;
-                (scale, 0, offset_y)
-            };
-    
-            if let Some(img) = &state_ref.image {
-                let mut img_copy = img.copy();
-                img_copy.draw(
-                    f.x() + offset_x,
-                    f.y() + offset_y,
-                    (img_w * scale) as i32,
-                    (img_h * scale) as i32
-                );
             }
+            draw_current_image(f, &state_ref);
 
             if state_ref.scientific_state.show_drawing_layer {
                 if let Some(composite_img) = state_ref.scientific_state.get_composite_image() {
-                    let mut composite_copy = composite_img.copy();
-                    composite_copy.draw(
-                        f.x() + offset_x,
-                        f.y() + offset_y,
-                        (img_w * scale) as i32,
-                        (img_h * scale) as i32
-                    );
+                    draw_image_in_frame(f, &composite_img, state_ref.zoom, state_ref.pan);
                 }
             }
+
+            // every zoom or pan ends in a redraw, so the mapping is refreshed here
+            if let Ok(mut interactive_ref) = interactive_state_draw.try_borrow_mut() {
+                interactive_ref.update_scaling(f, &state_ref);
+            }
         }
         
         if let Ok(interactive_ref) = interactive_state_draw.try_borrow() {
@@ -354,6 +273,7 @@ pub fn start_interactive_roi(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<Imag
                 true
             },
 
+Event::Push | Event::Drag | Event::Released if is_pan_button() => false,
 Event::Push => {
     let coords = fltk::app::event_coords();
     if let Ok(mut interactive_ref) = interactive_state_handle.try_borrow_mut() {
@@ -517,6 +437,7 @@ Event::Released => {
                         2
                     );
 
+                    state_ref.record_annotation_edit("Add ROI");
                     if let Some(cell_tool) = &mut state_ref.scientific_state.cell_analysis_tool {
                         let annotation = cell_tool.create_roi_annotation(&points, width, height);
                         state_ref.scientific_state.add_annotation(annotation);
@@ -524,6 +445,12 @@ Event::Released => {
 
                     state_ref.scientific_state.set_roi_tool(roi_tool);
 
+                    if !state_ref.scientific_state.is_analyzing_cells() {
+                        if let Some(measurements) = state_ref.measure_roi(current_shape, &points) {
+                            MeasurementDialog::new(measurements).show();
+                        }
+                    }
+
                     if let Some(profile) = state_ref.scientific_state.get_roi_intensity_profile(&points) {
                         crate::scientific::ui::show_profile_dialog(&profile);
                     }
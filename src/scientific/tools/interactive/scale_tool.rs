@@ -5,6 +5,8 @@ use fltk::{
  };
  use std::{rc::Rc, cell::RefCell};
  use crate::state::ImageState;
+ use crate::utils::{draw_current_image, Viewport};
+ use crate::menu::view::zoom::is_pan_button;
  use crate::scientific::ui::show_scale_input_dialog;
  use crate::scientific::rendering::{ScaleRenderer, frame_renderer::FrameRenderer};
  
@@ -34,8 +36,8 @@ use fltk::{
         let state_ref = state_draw.borrow();
         
         // Draw base image only once
-        if let Some(mut draw_img) = state_ref.image.clone() {
-            draw_img.draw(f.x(), f.y(), f.width(), f.height());
+        if state_ref.image.is_some() {
+            draw_current_image(f, &state_ref);
             
             // Draw existing scale if enabled
             if state_ref.scientific_state.show_legend {
@@ -51,7 +53,11 @@ use fltk::{
             }
             
             // Draw interactive preview
-            let points = interactive_draw.borrow().points.clone();
+            // the line is kept in image pixels so the calibration doesn't depend on the zoom
+            let points: Vec<(i32, i32)> = interactive_draw.borrow().points
+                .iter()
+                .filter_map(|&(x, y)| Viewport::current(f, &state_ref).map(|viewport| viewport.pixel_centre(x, y)))
+                .collect();
             if !points.is_empty() {
                 ScaleRenderer::draw_scale_preview(
                     points[0],
@@ -68,16 +74,23 @@ use fltk::{
         let state = state.clone();
         let frame = frame.clone();
         
-        move |_, ev| match ev {
+        move |f, ev| match ev {
+            Event::Push | Event::Drag | Event::Released if is_pan_button() => false,
             Event::Push => {
-                let coords = fltk::app::event_coords();
+                let (x, y) = fltk::app::event_coords();
                 interactive.borrow_mut().points.clear();
-                interactive.borrow_mut().points.push(coords);
+                if let Some(coords) = state.try_borrow().ok().and_then(|state_ref| Viewport::current(f, &state_ref).map(|viewport| viewport.to_pixel(x, y))) {
+                    interactive.borrow_mut().points.push(coords);
+                }
                 frame.borrow_mut().redraw();
                 true
             },
             Event::Drag => {
-                let coords = fltk::app::event_coords();
+                let (x, y) = fltk::app::event_coords();
+                let coords = match state.try_borrow().ok().and_then(|state_ref| Viewport::current(f, &state_ref).map(|viewport| viewport.to_pixel(x, y))) {
+                    Some(coords) => coords,
+                    None => return true,
+                };
                 if interactive.borrow().points.len() == 1 {
                     interactive.borrow_mut().points.push(coords);
                 } else if interactive.borrow().points.len() == 2 {
@@ -96,6 +109,7 @@ use fltk::{
                     
                     if let Some((real_distance, unit, objective)) = show_scale_input_dialog(pixel_distance, &state, &frame) {
                         if let Ok(mut state_ref) = state.try_borrow_mut() {
+                            state_ref.record_annotation_edit("Set Scale");
                             state_ref.scientific_state.set_scale(
                                 pixel_distance,
                                 real_distance,
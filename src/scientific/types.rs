@@ -1,5 +1,7 @@
 //src/scientific/types.rs
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum CellMeasurementMode {
     Single,
     Batch,
@@ -12,7 +14,7 @@ pub enum LineStyle {
     Dot,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ROIShape {
     Polygon {
         points: Vec<(i32, i32)>
@@ -39,14 +41,14 @@ impl std::fmt::Display for ROIShape {
         }
     }
 }
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ROITool {
     pub shape: ROIShape,
     pub color: (u8, u8, u8),
     pub line_width: i32,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum LegendPosition {
     TopLeft,
     TopRight,
@@ -81,6 +83,10 @@ pub struct ROIMeasurements {
     pub shape_type: ROIShape,     // Added to match usage
     pub is_calibrated: bool,
     pub units: String,
+    /// mean background under the ROI, when a background was set for measuring
+    pub background_mean: Option<f64>,
+    /// corrected total cell fluorescence: integrated density minus the background
+    pub ctcf: Option<f64>,
     pub notes: Option<String>,    // Added to match usage
 }
 pub struct MeasurementTool {
@@ -166,6 +166,7 @@ fn setup_add_button(
                     opacity: 1.0,
                     pseudo_color: color,
                     metadata: Default::default(),
+                    raw: state_ref.raw_image.clone(),
                 };
 
                 state_ref.scientific_state.add_channel(channel);
@@ -57,6 +57,7 @@ pub fn show_measurement_dialog(state: &Rc<RefCell<ImageState>>) -> bool {
                 1 => "mm",
                 _ => "px",
             }.to_string();
+            state_ref.record_annotation_edit("Add Scale Bar");
             add_scale_bar(&mut state_ref.scientific_state, value, unit);
         }
         window_ref_add.borrow_mut().hide();
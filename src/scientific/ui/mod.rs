@@ -7,6 +7,7 @@ pub mod scale_dialog;
 pub mod calibration_dialog;
 pub mod cell_analysis;
 pub mod roi;
+pub mod stack_dialog;
 
 pub use channel_dialog::*;
 //pub use measurement_dialog::*;
@@ -1,16 +1,12 @@
 use fltk::{
-    window::{Window, DoubleWindow},
+    window::DoubleWindow,
     group::{Pack, Scroll},
     button::Button,
     frame::Frame,
     enums::{Color},
     prelude::*,
 };
-use std::{rc::Rc, cell::RefCell};
-use crate::scientific::{
-    types::ROIMeasurements,
-    tools::interactive::roi::measurements::MeasurementCalculator,
-};
+use crate::scientific::types::ROIMeasurements;
 use super::components::measurement_table::MeasurementTable;
 
 pub struct MeasurementDialog {
@@ -22,20 +18,11 @@ pub struct MeasurementDialog {
 }
 
 impl MeasurementDialog {
-    pub fn new(
-        parent: Rc<RefCell<Window>>,
-        measurements: ROIMeasurements,
-    ) -> Self {
+    pub fn new(measurements: ROIMeasurements) -> Self {
         let mut window = DoubleWindow::new(
             0, 0, 400, 500,
             "ROI Measurements"
-        );
-
-        // Center dialog
-        let parent = parent.borrow();
-        let x = parent.x() + (parent.width() - window.width()) / 2;
-        let y = parent.y() + (parent.height() - window.height()) / 2;
-        window.set_pos(x, y);
+        ).center_screen();
 
         let mut pack = Pack::new(10, 10, 380, 480, "");
         pack.set_spacing(10);
@@ -55,12 +42,16 @@ impl MeasurementDialog {
 
         // Statistics summary
         let mut stats = Frame::new(0, 0, 380, 25, "");
-        stats.set_label(&format!(
+        let mut summary = format!(
             "Area: {:.2} {}², Mean Intensity: {:.2}",
             measurements.area,
             measurements.units,
             measurements.mean_intensity
-        ));
+        );
+        if let Some(ctcf) = measurements.ctcf {
+            summary.push_str(&format!(", CTCF: {:.1}", ctcf));
+        }
+        stats.set_label(&summary);
 
         // Create buttons
         let mut export_btn = Button::new(0, 0, 185, 25, "Export Data");
@@ -110,6 +101,10 @@ impl MeasurementDialog {
             writeln!(file, "Min Intensity,{:.2},", measurements.min_intensity).unwrap();
             writeln!(file, "Max Intensity,{:.2},", measurements.max_intensity).unwrap();
             writeln!(file, "Integrated Density,{:.2},", measurements.integrated_density).unwrap();
+            if let (Some(background), Some(ctcf)) = (measurements.background_mean, measurements.ctcf) {
+                writeln!(file, "Mean Background,{:.2},", background).unwrap();
+                writeln!(file, "CTCF,{:.2},", ctcf).unwrap();
+            }
             writeln!(file, "Circularity,{:.2},", measurements.circularity).unwrap();
             writeln!(file, "Aspect Ratio,{:.2},", measurements.aspect_ratio).unwrap();
         }
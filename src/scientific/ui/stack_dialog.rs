@@ -0,0 +1,87 @@
+//src/scientific/ui/stack_dialog.rs
+use fltk::{
+    app,
+    dialog::choice2,
+    enums::Align,
+    frame::Frame,
+    prelude::*,
+    valuator::HorNiceSlider,
+    window::Window,
+};
+use std::{rc::Rc, cell::RefCell};
+use crate::state::ImageState;
+use crate::scientific::state::StackKind;
+
+/// asks how the pages of a multi-page file should be interpreted
+pub fn choose_stack_kind(page_count: usize) -> Option<StackKind> {
+    let message = format!(
+        "This TIFF has {} pages.\nHow should the pages be loaded?",
+        page_count
+    );
+    match choice2(300, 300, &message, "Channels", "Z-slices", "Time frames") {
+        Some(0) => Some(StackKind::Channels),
+        Some(1) => Some(StackKind::ZSlices),
+        Some(2) => Some(StackKind::TimeFrames),
+        _ => None,
+    }
+}
+
+/// makes page `index` the displayed image (and the active channel in channel mode)
+pub fn show_stack_page(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>, index: usize) -> Option<String> {
+    let mut state_ref = state.try_borrow_mut().ok()?;
+    let (page, kind, label) = {
+        let stack = state_ref.scientific_state.image_stack.as_mut()?;
+        let page = stack.set_current(index)?.clone();
+        (page, stack.kind, stack.position_label())
+    };
+
+    let image = page.to_rgb_image()?;
+    if kind == StackKind::Channels {
+        state_ref.scientific_state.active_channel = Some(index);
+    }
+    state_ref.raw_image = Some(page);
+    state_ref.image = Some(image.clone());
+    drop(state_ref);
+
+    frame.borrow_mut().set_image(Some(image));
+    frame.borrow_mut().redraw();
+    Some(label)
+}
+
+/// small floating window with a slider for stepping through the stack
+pub fn show_stack_navigator(frame: &Rc<RefCell<Frame>>, state: &Rc<RefCell<ImageState>>) {
+    let (count, current, label) = match state.try_borrow() {
+        Ok(state_ref) => match &state_ref.scientific_state.image_stack {
+            Some(stack) if stack.len() > 1 => (stack.len(), stack.current, stack.position_label()),
+            _ => return,
+        },
+        Err(_) => return,
+    };
+
+    let mut win = Window::default()
+        .with_size(360, 70)
+        .with_label("Stack Navigator");
+
+    let mut position = Frame::new(10, 5, 340, 20, "");
+    position.set_label(&label);
+    position.set_align(Align::Left | Align::Inside);
+
+    let mut slider = HorNiceSlider::new(10, 30, 340, 25, "");
+    slider.set_minimum(0.0);
+    slider.set_maximum((count - 1) as f64);
+    slider.set_step(1.0, 1);
+    slider.set_value(current as f64);
+
+    win.end();
+    win.show();
+
+    let frame = frame.clone();
+    let state = state.clone();
+    slider.set_callback(move |s| {
+        let index = s.value().round() as usize;
+        if let Some(label) = show_stack_page(&frame, &state, index) {
+            position.set_label(&label);
+            app::redraw();
+        }
+    });
+}
@@ -0,0 +1,81 @@
+//src/state/export_options.rs
+use serde::{Serialize, Deserialize};
+
+/// JPEG chroma subsampling, named by the usual J:a:b ratio
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ChromaSubsampling {
+    Full,
+    Half,
+    Quarter,
+}
+
+impl ChromaSubsampling {
+    pub const ALL: [ChromaSubsampling; 3] = [Self::Full, Self::Half, Self::Quarter];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Full => "4:4:4 (none)",
+            Self::Half => "4:2:2",
+            Self::Quarter => "4:2:0",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PngCompression {
+    Fast,
+    Default,
+    Best,
+}
+
+impl PngCompression {
+    pub const ALL: [PngCompression; 3] = [Self::Fast, Self::Default, Self::Best];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Fast => "Fast",
+            Self::Default => "Default",
+            Self::Best => "Best",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TiffCompression {
+    None,
+    Lzw,
+    Deflate,
+}
+
+impl TiffCompression {
+    pub const ALL: [TiffCompression; 3] = [Self::None, Self::Lzw, Self::Deflate];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Lzw => "LZW",
+            Self::Deflate => "Deflate",
+        }
+    }
+}
+
+/// encoder settings used by Save / Save As
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// 1-100
+    pub jpeg_quality: u8,
+    pub jpeg_subsampling: ChromaSubsampling,
+    pub png_compression: PngCompression,
+    pub tiff_compression: TiffCompression,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            jpeg_quality: 90,
+            jpeg_subsampling: ChromaSubsampling::Quarter,
+            png_compression: PngCompression::Default,
+            tiff_compression: TiffCompression::None,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//src/state/filter_recipe.rs
+use std::path::Path;
+use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use crate::menu::edit::filters::FilterSpec;
+use crate::state::filter_state::FilterError;
+
+/// extension for saved filter recipes (JSON inside)
+pub const RECIPE_EXTENSION: &str = "ivfilters";
+
+/// one entry of the filter stack, disabled steps are kept but skipped when rendering
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FilterStep {
+    #[serde(flatten)]
+    pub spec: FilterSpec,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl FilterStep {
+    pub fn new(spec: FilterSpec) -> Self {
+        Self { spec, enabled: true }
+    }
+}
+
+/// an ordered, named list of filter steps
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilterRecipe {
+    #[serde(default)]
+    pub name: String,
+    pub steps: Vec<FilterStep>,
+}
+
+impl FilterRecipe {
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// swaps step `index` with its neighbour, returns the new index
+    pub fn move_step(&mut self, index: usize, up: bool) -> usize {
+        if up && index > 0 && index < self.steps.len() {
+            self.steps.swap(index, index - 1);
+            index - 1
+        } else if !up && index + 1 < self.steps.len() {
+            self.steps.swap(index, index + 1);
+            index + 1
+        } else {
+            index
+        }
+    }
+
+    /// runs the enabled steps in order
+    pub fn apply(&self, image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<(), FilterError> {
+        for step in self.steps.iter().filter(|s| s.enabled) {
+            step.spec.build().apply(image).map_err(|e| FilterError {
+                message: format!("{}: {}", step.spec.name(), e.message),
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FilterError> {
+        let text = std::fs::read_to_string(path).map_err(|e| FilterError { message: e.to_string() })?;
+        let mut recipe: FilterRecipe =
+            serde_json::from_str(&text).map_err(|e| FilterError { message: e.to_string() })?;
+        if recipe.name.is_empty() {
+            recipe.name = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+        }
+        Ok(recipe)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), FilterError> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| FilterError { message: e.to_string() })?;
+        std::fs::write(path, text).map_err(|e| FilterError { message: e.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_round_trip_with_flattened_parameters() {
+        let mut recipe = FilterRecipe { name: "test".to_string(), steps: Vec::new() };
+        recipe.steps.push(FilterStep::new(FilterSpec::Brightness { intensity: 0.2 }));
+        recipe.steps.push(FilterStep { spec: FilterSpec::GaussianBlur { radius: 2.0, sigma: 1.0 }, enabled: false });
+
+        let json = serde_json::to_string(&recipe).unwrap();
+        assert!(json.contains(r#""filter":"brightness","intensity":0.2"#));
+        assert_eq!(serde_json::from_str::<FilterRecipe>(&json).unwrap(), recipe);
+
+        // "enabled" may be left out of hand-written files
+        let parsed: FilterRecipe = serde_json::from_str(r#"{"steps":[{"filter":"hue","angle":90.0}]}"#).unwrap();
+        assert!(parsed.steps[0].enabled);
+    }
+
+    #[test]
+    fn move_step_stays_in_bounds() {
+        let mut recipe = FilterRecipe::default();
+        recipe.steps.push(FilterStep::new(FilterSpec::Grayscale { intensity: 1.0 }));
+        recipe.steps.push(FilterStep::new(FilterSpec::Sepia { intensity: 1.0 }));
+
+        assert_eq!(recipe.move_step(0, true), 0);
+        assert_eq!(recipe.move_step(0, false), 1);
+        assert_eq!(recipe.steps[1].spec, FilterSpec::Grayscale { intensity: 1.0 });
+        assert_eq!(recipe.move_step(1, false), 1);
+    }
+}
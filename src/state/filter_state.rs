@@ -1,10 +1,16 @@
 //! this is the filter state management module that handles image filtering operations
 //! and maintains the state of active filters and previews.
 
+#[cfg(feature = "gui")]
 use fltk::image::RgbImage;
+#[cfg(feature = "gui")]
 use fltk::prelude::*;  // in order to be able to import for ImageExt trait
+#[cfg(feature = "gui")]
 use image::{ImageBuffer, Rgba};
+#[cfg(feature = "gui")]
 use crate::menu::edit::filters::ImageFilter;
+#[cfg(feature = "gui")]
+use crate::state::filter_recipe::FilterRecipe;
 
 /// represents an error that can occur during the filtering process in human readable format
 #[derive(Clone, Debug)]
@@ -21,19 +27,29 @@ impl std::fmt::Display for FilterError {
 
 impl std::error::Error for FilterError {}
 
+#[cfg(feature = "gui")]
 #[derive(Clone)]
 // image conversin between fltk and image crate formats
 pub struct FilterState {
     is_preview_active: bool,
     current_filter: Option<String>,
+    /// the non-destructive stack, re-rendered from `stack_base` on every change
+    pub stack: FilterRecipe,
+    stack_base: Option<RgbImage>,
+    /// last image the stack produced, used to notice edits made outside the stack
+    stack_output: Option<Vec<u8>>,
 }
 // implement the FilterState struct
+#[cfg(feature = "gui")]
 impl FilterState {
     // create a new instance of FilterState
     pub fn new() -> Self {
         Self {
             is_preview_active: false,
             current_filter: None,
+            stack: FilterRecipe::default(),
+            stack_base: None,
+            stack_output: None,
         }
     }
 // apply a filter to an image and return the result
@@ -96,8 +112,58 @@ impl FilterState {
     pub fn set_current_filter(&mut self, filter: Option<String>) {
         self.current_filter = filter;
     }
+
+    /// makes `image` the stack's starting point unless it is still the stack's own output.
+    /// returns false when the stack had to start over
+    pub fn attach_stack(&mut self, image: &RgbImage) -> bool {
+        let current = self.stack_base.is_some() && self.stack_output.as_deref() == Some(&image.to_rgb_data()[..]);
+        if !current {
+            self.stack_base = Some(image.clone());
+            self.stack_output = None;
+        }
+        current
+    }
+
+    pub fn has_stack_base(&self) -> bool {
+        self.stack_base.is_some()
+    }
+
+    /// runs the enabled steps on a copy of the original image
+    pub fn render_stack(&mut self) -> Result<Option<RgbImage>, FilterError> {
+        let base = match &self.stack_base {
+            Some(base) => base,
+            None => return Ok(None),
+        };
+        let mut image_buffer = Self::fltk_to_image_buffer(base);
+        self.stack.apply(&mut image_buffer)?;
+        let rendered = Self::image_buffer_to_fltk(&image_buffer);
+        self.stack_output = rendered.as_ref().map(|img| img.to_rgb_data());
+        Ok(rendered)
+    }
+
+    /// what step `index` of the stack gets to work on, i.e. the enabled steps before it
+    pub fn render_stack_input(&self, index: usize) -> Result<Option<RgbImage>, FilterError> {
+        let base = match &self.stack_base {
+            Some(base) => base,
+            None => return Ok(None),
+        };
+        let before = FilterRecipe {
+            name: String::new(),
+            steps: self.stack.steps.iter().take(index).cloned().collect(),
+        };
+        let mut image_buffer = Self::fltk_to_image_buffer(base);
+        before.apply(&mut image_buffer)?;
+        Ok(Self::image_buffer_to_fltk(&image_buffer))
+    }
+
+    /// forgets the original image, the current result becomes a normal edit
+    pub fn detach_stack(&mut self) {
+        self.stack_base = None;
+        self.stack_output = None;
+    }
 }
 // implement Default trait and allow creating a new instance of FilterState with default values
+#[cfg(feature = "gui")]
 impl Default for FilterState {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,233 @@
+//src/state/folder_listing.rs
+use std::{
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicUsize, Arc},
+    time::SystemTime,
+};
+
+/// extensions the viewer opens, same list as the Open dialog filter
+pub const IMAGE_EXTENSIONS: [&str; 12] =
+    ["jpg", "jpeg", "png", "gif", "bmp", "tif", "tiff", "webp", "pnm", "pgm", "ppm", "pam"];
+
+pub fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FolderSort {
+    Name,
+    Date,
+    Size,
+}
+
+impl FolderSort {
+    pub const ALL: [FolderSort; 3] = [Self::Name, Self::Date, Self::Size];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Date => "Date",
+            Self::Size => "Size",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FolderEntry {
+    path: PathBuf,
+    modified: SystemTime,
+    size: u64,
+}
+
+/// the images next to the open file, for next/previous and the filmstrip
+#[derive(Clone, Debug)]
+pub struct FolderListing {
+    dir: Option<PathBuf>,
+    entries: Vec<FolderEntry>,
+    current: Option<usize>,
+    sort: FolderSort,
+}
+
+impl Default for FolderListing {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            entries: Vec::new(),
+            current: None,
+            sort: FolderSort::Name,
+        }
+    }
+}
+
+impl FolderListing {
+    /// points the listing at `path`, rescanning if it lives in another folder
+    pub fn open(&mut self, path: &Path) {
+        let dir = match path.parent() {
+            Some(dir) => dir.to_path_buf(),
+            None => return,
+        };
+        if self.dir.as_deref() != Some(dir.as_path()) || self.position(path).is_none() {
+            self.scan(&dir);
+        }
+        self.current = self.position(path);
+    }
+
+    /// re-reads the folder, keeping the current file selected
+    pub fn refresh(&mut self) {
+        let current = self.current_path().map(Path::to_path_buf);
+        if let Some(dir) = self.dir.clone() {
+            self.scan(&dir);
+        }
+        self.current = current.and_then(|path| self.position(&path));
+    }
+
+    fn scan(&mut self, dir: &Path) {
+        self.dir = Some(dir.to_path_buf());
+        self.entries = match std::fs::read_dir(dir) {
+            Ok(read_dir) => read_dir
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| is_image_file(&entry.path()))
+                .filter_map(|entry| {
+                    let metadata = entry.metadata().ok()?;
+                    if !metadata.is_file() {
+                        return None;
+                    }
+                    Some(FolderEntry {
+                        path: entry.path(),
+                        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                        size: metadata.len(),
+                    })
+                })
+                .collect(),
+            Err(e) => {
+                println!("Failed to read folder {:?}: {}", dir, e);
+                Vec::new()
+            }
+        };
+        sort_entries(&mut self.entries, self.sort);
+    }
+
+    fn position(&self, path: &Path) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.path == path)
+    }
+
+    pub fn sort(&self) -> FolderSort {
+        self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: FolderSort) {
+        let current = self.current_path().map(Path::to_path_buf);
+        self.sort = sort;
+        sort_entries(&mut self.entries, sort);
+        self.current = current.and_then(|path| self.position(&path));
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.entries.iter().map(|entry| entry.path.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current.and_then(|i| self.entries.get(i)).map(|entry| entry.path.as_path())
+    }
+
+    /// the open image no longer comes from this folder (e.g. after a paste)
+    pub fn clear_current(&mut self) {
+        self.current = None;
+    }
+
+    /// neighbour of the current file, `step` is +1 for next and -1 for previous
+    pub fn neighbour(&self, step: isize) -> Option<PathBuf> {
+        let index = self.current?.checked_add_signed(step)?;
+        self.entries.get(index).map(|entry| entry.path.clone())
+    }
+}
+
+fn sort_entries(entries: &mut [FolderEntry], sort: FolderSort) {
+    let name = |entry: &FolderEntry| {
+        entry.path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default()
+    };
+    match sort {
+        FolderSort::Name => entries.sort_by_cached_key(name),
+        FolderSort::Date => entries.sort_by_cached_key(|entry| (entry.modified, name(entry))),
+        FolderSort::Size => entries.sort_by_cached_key(|entry| (entry.size, name(entry))),
+    }
+}
+
+/// what the thumbnail strip is currently showing
+#[derive(Clone, Debug, Default)]
+pub struct FilmstripState {
+    pub visible: bool,
+    /// files the strip was last built for, it is rebuilt when the folder changes
+    pub files: Vec<PathBuf>,
+    /// bumped to cancel the running thumbnail job
+    pub generation: Arc<AtomicUsize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn listing(entries: &[(&str, u64, u64)]) -> FolderListing {
+        let entries = entries
+            .iter()
+            .map(|(name, secs, size)| FolderEntry {
+                path: PathBuf::from(format!("/nonexistent/{}", name)),
+                modified: SystemTime::UNIX_EPOCH + Duration::from_secs(*secs),
+                size: *size,
+            })
+            .collect();
+        FolderListing { dir: Some(PathBuf::from("/nonexistent")), entries, current: Some(0), sort: FolderSort::Name }
+    }
+
+    fn names(listing: &FolderListing) -> Vec<String> {
+        listing.files().iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect()
+    }
+
+    #[test]
+    fn sorting_keeps_current_file() {
+        let mut listing = listing(&[("b.png", 30, 1), ("A.png", 20, 3), ("c.png", 10, 2)]);
+        listing.current = Some(0);
+
+        listing.set_sort(FolderSort::Name);
+        assert_eq!(names(&listing), ["A.png", "b.png", "c.png"]);
+        assert_eq!(listing.current_index(), Some(1));
+
+        listing.set_sort(FolderSort::Date);
+        assert_eq!(names(&listing), ["c.png", "A.png", "b.png"]);
+
+        listing.set_sort(FolderSort::Size);
+        assert_eq!(names(&listing), ["b.png", "c.png", "A.png"]);
+        assert_eq!(listing.current_path(), Some(Path::new("/nonexistent/b.png")));
+    }
+
+    #[test]
+    fn neighbours_stop_at_the_ends() {
+        let listing = listing(&[("a.png", 0, 0), ("b.png", 0, 0)]);
+        assert_eq!(listing.neighbour(-1), None);
+        assert_eq!(listing.neighbour(1), Some(PathBuf::from("/nonexistent/b.png")));
+        assert_eq!(listing.neighbour(2), None);
+    }
+
+    #[test]
+    fn only_image_extensions_count() {
+        assert!(is_image_file(Path::new("scan.TIF")));
+        assert!(!is_image_file(Path::new("notes.txt")));
+        assert!(!is_image_file(Path::new("project.ivproj")));
+    }
+}
@@ -0,0 +1,299 @@
+//src/state/history_state.rs
+
+use fltk::{image::RgbImage, prelude::*, enums::ColorDepth};
+use std::collections::VecDeque;
+use crate::scientific::layers::Metadata;
+use crate::scientific::state::scientific_state::{AnnotationSnapshot, GeometrySnapshot};
+use crate::utils::raw_image::RawImage;
+
+pub const DEFAULT_HISTORY_BUDGET_MB: usize = 256;
+
+/// raw copy of an image so the history doesn't depend on fltk image handles
+#[derive(Clone)]
+struct ImageSnapshot {
+    data: Vec<u8>,
+    width: i32,
+    height: i32,
+}
+
+impl ImageSnapshot {
+    fn capture(image: &RgbImage) -> Self {
+        Self {
+            data: image.to_rgb_data(),
+            width: image.data_w(),
+            height: image.data_h(),
+        }
+    }
+
+    fn restore(&self) -> Option<RgbImage> {
+        RgbImage::new(&self.data, self.width, self.height, ColorDepth::Rgb8).ok()
+    }
+}
+
+#[derive(Clone)]
+enum Snapshot {
+    /// the metadata rides along for edits that record themselves in it (flat-field)
+    Image(Option<ImageSnapshot>, Option<RawImage>, Box<Metadata>),
+    Annotations(Box<AnnotationSnapshot>),
+    Geometry(Option<ImageSnapshot>, Option<RawImage>, Box<GeometrySnapshot>),
+}
+
+/// what kind of state an undo/redo step touched, so callers know what to refresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryKind {
+    Image,
+    /// annotations and ROIs, with the calibration and measurements made from them
+    Annotations,
+    /// the image together with everything positioned on it (rotate, flip, resize)
+    Geometry,
+}
+
+#[derive(Clone)]
+pub struct HistoryEntry {
+    pub label: String,
+    snapshot: Snapshot,
+}
+
+impl HistoryEntry {
+    pub fn image(label: &str, image: Option<&RgbImage>, raw: Option<&RawImage>, metadata: &Metadata) -> Self {
+        Self {
+            label: label.to_string(),
+            snapshot: Snapshot::Image(image.map(ImageSnapshot::capture), raw.cloned(), Box::new(metadata.clone())),
+        }
+    }
+
+    pub fn annotations(label: &str, annotations: AnnotationSnapshot) -> Self {
+        Self {
+            label: label.to_string(),
+            snapshot: Snapshot::Annotations(Box::new(annotations)),
+        }
+    }
+
+    pub fn geometry(label: &str, image: Option<&RgbImage>, raw: Option<&RawImage>, geometry: GeometrySnapshot) -> Self {
+        Self {
+            label: label.to_string(),
+            snapshot: Snapshot::Geometry(image.map(ImageSnapshot::capture), raw.cloned(), Box::new(geometry)),
+        }
+    }
+
+    pub fn kind(&self) -> HistoryKind {
+        match self.snapshot {
+            Snapshot::Image(..) => HistoryKind::Image,
+            Snapshot::Annotations(_) => HistoryKind::Annotations,
+            Snapshot::Geometry(..) => HistoryKind::Geometry,
+        }
+    }
+
+    /// rough size in bytes, used to keep the stacks inside the memory budget
+    pub fn size_bytes(&self) -> usize {
+        match &self.snapshot {
+            Snapshot::Image(snapshot, raw, _) => {
+                snapshot.as_ref().map_or(0, |s| s.data.len())
+                    + raw.as_ref().map_or(0, |r| r.size_bytes())
+            }
+            Snapshot::Annotations(annotations) => annotations.size_bytes(),
+            Snapshot::Geometry(snapshot, raw, geometry) => {
+                snapshot.as_ref().map_or(0, |s| s.data.len())
+                    + raw.as_ref().map_or(0, |r| r.size_bytes())
+                    + geometry.size_bytes()
+            }
+        }
+    }
+
+    pub fn restore_image(&self) -> Option<(Option<RgbImage>, Option<RawImage>)> {
+        match &self.snapshot {
+            Snapshot::Image(snapshot, raw, _) | Snapshot::Geometry(snapshot, raw, _) => {
+                Some((snapshot.as_ref().and_then(|s| s.restore()), raw.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn restore_metadata(&self) -> Option<Metadata> {
+        match &self.snapshot {
+            Snapshot::Image(_, _, metadata) => Some((**metadata).clone()),
+            _ => None,
+        }
+    }
+
+    pub fn restore_geometry(&self) -> Option<GeometrySnapshot> {
+        match &self.snapshot {
+            Snapshot::Geometry(_, _, geometry) => Some((**geometry).clone()),
+            _ => None,
+        }
+    }
+
+    pub fn restore_annotations(&self) -> Option<AnnotationSnapshot> {
+        match &self.snapshot {
+            Snapshot::Annotations(annotations) => Some((**annotations).clone()),
+            _ => None,
+        }
+    }
+}
+
+pub struct HistoryState {
+    undo_stack: VecDeque<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+    memory_budget: usize,
+}
+
+impl HistoryState {
+    pub fn new() -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            memory_budget: DEFAULT_HISTORY_BUDGET_MB * 1024 * 1024,
+        }
+    }
+
+    /// records a new edit; any redo steps are dropped since they no longer apply
+    pub fn push(&mut self, entry: HistoryEntry) {
+        println!("Recording history step: {}", entry.label);
+        self.redo_stack.clear();
+        self.undo_stack.push_back(entry);
+        self.enforce_budget();
+    }
+
+    pub fn pop_undo(&mut self) -> Option<HistoryEntry> {
+        self.undo_stack.pop_back()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<HistoryEntry> {
+        self.redo_stack.pop()
+    }
+
+    /// pushes onto the undo stack without touching redo (used while redoing)
+    pub fn push_undo(&mut self, entry: HistoryEntry) {
+        self.undo_stack.push_back(entry);
+        self.enforce_budget();
+    }
+
+    pub fn push_redo(&mut self, entry: HistoryEntry) {
+        self.redo_stack.push(entry);
+        self.enforce_budget();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo_stack.back().map(|e| e.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo_stack.last().map(|e| e.label.as_str())
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.undo_stack.iter().map(|e| e.size_bytes()).sum::<usize>()
+            + self.redo_stack.iter().map(|e| e.size_bytes()).sum::<usize>()
+    }
+
+    pub fn memory_budget(&self) -> usize {
+        self.memory_budget
+    }
+
+    pub fn set_memory_budget(&mut self, bytes: usize) {
+        self.memory_budget = bytes;
+        self.enforce_budget();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    // oldest undo steps go first, then the redo steps furthest from the current state.
+    // the most recent undo step is always kept so a single big edit can still be undone
+    fn enforce_budget(&mut self) {
+        while self.memory_usage() > self.memory_budget {
+            if self.undo_stack.len() > 1 {
+                let dropped = self.undo_stack.pop_front();
+                if let Some(entry) = dropped {
+                    println!("History budget exceeded, dropping: {}", entry.label);
+                }
+            } else if !self.redo_stack.is_empty() {
+                self.redo_stack.remove(0);
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl Default for HistoryState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(label: &str, bytes: usize) -> HistoryEntry {
+        HistoryEntry {
+            label: label.to_string(),
+            snapshot: Snapshot::Image(Some(ImageSnapshot {
+                data: vec![0; bytes],
+                width: 1,
+                height: 1,
+            }), None, Box::default()),
+        }
+    }
+
+    #[test]
+    fn test_push_clears_redo() {
+        let mut history = HistoryState::new();
+        history.push(entry("a", 3));
+        let undone = history.pop_undo().unwrap();
+        history.push_redo(undone);
+        assert!(history.can_redo());
+
+        history.push(entry("b", 3));
+        assert!(!history.can_redo());
+        assert_eq!(history.undo_label(), Some("b"));
+    }
+
+    #[test]
+    fn test_budget_drops_oldest() {
+        let mut history = HistoryState::new();
+        history.set_memory_budget(10);
+        history.push(entry("a", 4));
+        history.push(entry("b", 4));
+        history.push(entry("c", 4));
+
+        assert!(history.memory_usage() <= 10);
+        assert_eq!(history.undo_label(), Some("c"));
+        assert_eq!(history.pop_undo().unwrap().label, "c");
+        assert_eq!(history.pop_undo().unwrap().label, "b");
+        assert!(history.pop_undo().is_none());
+    }
+
+    #[test]
+    fn test_budget_keeps_latest_step() {
+        let mut history = HistoryState::new();
+        history.set_memory_budget(2);
+        history.push(entry("big", 8));
+        assert!(history.can_undo());
+    }
+
+    #[test]
+    fn test_annotation_step_restores_the_scale() {
+        use crate::scientific::state::ScientificState;
+
+        let mut scientific = ScientificState::new();
+        let entry = HistoryEntry::annotations("Set Scale", scientific.annotation_snapshot());
+        scientific.set_scale(200.0, 10.0, "µm".to_string(), None);
+        assert!(scientific.calibration.is_calibrated());
+
+        scientific.restore_annotations(entry.restore_annotations().unwrap());
+        assert!(!scientific.calibration.is_calibrated());
+        assert_eq!(entry.kind(), HistoryKind::Annotations);
+    }
+}
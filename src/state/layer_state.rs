@@ -1,5 +1,5 @@
 use fltk::{prelude::*, image::RgbImage, enums::ColorDepth};
-use crate::menu::edit::crop::crop_tool::CropSelection;
+use crate::menu::edit::crop::CropSelection;
 use std::collections::HashMap;
 
 #[derive(Clone)]
@@ -41,6 +41,24 @@ impl LayerState {
         }
     }
 
+    /// rebuilds a layer state exactly as it was saved in a project file
+    pub fn restore(
+        layers: Vec<Layer>,
+        groups: Vec<LayerGroup>,
+        active_layer: Option<usize>,
+        original_image: Option<RgbImage>,
+        is_preview_active: bool,
+    ) -> Self {
+        let active_layer = active_layer.filter(|&i| i < layers.len());
+        Self {
+            layers,
+            groups,
+            active_layer,
+            original_image,
+            is_preview_active,
+        }
+    }
+
     pub fn toggle_preview(&mut self) {
         self.is_preview_active = !self.is_preview_active;
         println!("Preview toggled: {}", self.is_preview_active);
@@ -1,17 +1,51 @@
 //src/state/mod.rs
 
-use fltk::image::RgbImage;
+#[cfg(feature = "gui")]
+use fltk::{image::RgbImage, prelude::ImageExt};
+#[cfg(feature = "gui")]
 use std::path::PathBuf;
-use crate::menu::edit::crop::crop_tool::CropSelection;
+#[cfg(feature = "gui")]
+use crate::menu::edit::crop::CropSelection;
+#[cfg(feature = "gui")]
 use crate::state::filter_state::FilterState;
+#[cfg(feature = "gui")]
 use crate::state::watermark_state::WatermarkState;
+#[cfg(feature = "gui")]
+use crate::state::history_state::{HistoryEntry, HistoryKind, HistoryState};
+#[cfg(feature = "gui")]
+use crate::state::recent_files::RecentFiles;
+#[cfg(feature = "gui")]
+use crate::state::export_options::ExportOptions;
+#[cfg(feature = "gui")]
+use crate::state::folder_listing::{FilmstripState, FolderListing};
 
+#[cfg(feature = "gui")]
 pub use layer_state::{Layer, LayerGroup, LayerState};
+#[cfg(feature = "gui")]
 use crate::menu::edit::watermark::WatermarkOptions;
+#[cfg(feature = "gui")]
 use crate::scientific::state::scientific_state::ScientificState;
+#[cfg(feature = "gui")]
+use crate::utils::raw_image::RawImage;
+#[cfg(feature = "gui")]
+use crate::utils::{transform::Transform, transform_rgb_image};
+#[cfg(feature = "gui")]
+use crate::utils::image_data::ImageData;
+#[cfg(feature = "gui")]
+use crate::scientific::types::{ROIMeasurements, ROIShape};
 
 pub mod filter_state;
+pub mod filter_recipe;
+#[cfg(feature = "gui")]
 pub mod watermark_state;
+#[cfg(feature = "gui")]
+pub mod history_state;
+#[cfg(feature = "gui")]
+pub mod project;
+pub mod recent_files;
+pub mod export_options;
+pub mod folder_listing;
+#[cfg(feature = "gui")]
 mod layer_state;
 
 #[derive(Debug)]
@@ -27,28 +61,54 @@ impl From<FilterError> for filter_state::FilterError {
    }
 }
 
+#[cfg(feature = "gui")]
 pub struct ImageState {
    pub image: Option<RgbImage>,
+   /// full bit-depth pixels behind `image`; None once an 8-bit edit has been applied
+   pub raw_image: Option<RawImage>,
    pub path: Option<PathBuf>,
    pub crop_selection: Option<CropSelection>,
    pub filter_state: FilterState,
    pub watermark_state: WatermarkState,
    pub layer_state: LayerState,
    pub scientific_state: ScientificState,
+   pub history: HistoryState,
+   /// also write `<file>.json` next to saved images
+   pub write_metadata_sidecar: bool,
+   pub recent_files: RecentFiles,
+   pub export_options: ExportOptions,
+   /// last Edit→Copy, kept at full bit depth
+   pub clipboard: Option<RawImage>,
+   /// images in the open file's folder, for next/previous
+   pub folder: FolderListing,
+   pub filmstrip: FilmstripState,
+   /// 1.0 fits the whole image in the frame
    pub zoom: f32,
+   /// offset of the image from the centre of the frame, in screen pixels
+   pub pan: (f64, f64),
 }
 
+#[cfg(feature = "gui")]
 impl ImageState {
    pub fn new() -> Self {
        Self {
            image: None,
+           raw_image: None,
            path: None,
            crop_selection: None,
            filter_state: FilterState::new(),
            watermark_state: WatermarkState::new(),
            layer_state: LayerState::new(),
            scientific_state: ScientificState::new(),
+           history: HistoryState::new(),
+           write_metadata_sidecar: false,
+           recent_files: RecentFiles::load(),
+           export_options: ExportOptions::default(),
+           clipboard: None,
+           folder: FolderListing::default(),
+           filmstrip: FilmstripState::default(),
            zoom: 1.0,
+           pan: (0.0, 0.0),
        }
    }
 
@@ -63,4 +123,125 @@ impl ImageState {
    pub fn get_scientific_state_mut(&mut self) -> &mut ScientificState {
        &mut self.scientific_state
    }
-}
\ No newline at end of file
+
+   /// measures the ROI outlined by `points` (image coordinates) on the full bit-depth
+   /// data when it is still valid, with CTCF once a measurement background is set.
+   /// rectangles and ellipses are measured through their outline, which carries the position
+   pub fn measure_roi(&self, shape: &ROIShape, points: &[(i32, i32)]) -> Option<ROIMeasurements> {
+       let image = self.image.clone()?;
+       let data = ImageData::with_raw(image, self.raw_image.clone());
+       let shape = match shape {
+           ROIShape::Line { .. } => shape.clone(),
+           _ => ROIShape::Polygon { points: points.to_vec() },
+       };
+       Some(self.scientific_state.measurement_calculator().calculate_measurements(&shape, &data))
+   }
+
+   /// call before replacing `image` with the result of a destructive edit.
+   /// edits work on the 8-bit display image, so the raw data stops matching
+   /// and is dropped here (it comes back on undo)
+   pub fn record_image_edit(&mut self, label: &str) {
+       let entry = HistoryEntry::image(
+           label,
+           self.image.as_ref(),
+           self.raw_image.as_ref(),
+           &self.scientific_state.image_metadata,
+       );
+       self.history.push(entry);
+       self.raw_image = None;
+   }
+
+   /// rotates, flips or resizes the image together with everything positioned on it
+   /// (ROIs, annotations, calibration), as a single undo step
+   pub fn apply_transform(&mut self, transform: &Transform) -> bool {
+       let (width, height, transformed) = match &self.image {
+           Some(image) => (image.data_w() as u32, image.data_h() as u32, transform_rgb_image(image, transform)),
+           None => return false,
+       };
+       let transformed = match transformed {
+           Some(transformed) => transformed,
+           None => return false,
+       };
+
+       let entry = HistoryEntry::geometry(
+           &transform.label(),
+           self.image.as_ref(),
+           self.raw_image.as_ref(),
+           self.scientific_state.geometry_snapshot(),
+       );
+       self.history.push(entry);
+
+       // the raw data goes through the same transform, so it stays valid
+       self.raw_image = self.raw_image.as_ref().map(|raw| raw.transformed(transform));
+       self.image = self.raw_image.as_ref().and_then(|raw| raw.to_rgb_image()).or(Some(transformed));
+       self.scientific_state.transform_geometry(transform, width, height);
+       self.crop_selection = None;
+       self.zoom = 1.0;
+       self.pan = (0.0, 0.0);
+       true
+   }
+
+   /// call before adding, removing or changing annotations/ROIs, the scale or measurements
+   pub fn record_annotation_edit(&mut self, label: &str) {
+       let entry = HistoryEntry::annotations(label, self.scientific_state.annotation_snapshot());
+       self.history.push(entry);
+   }
+
+   pub fn undo(&mut self) -> Option<HistoryKind> {
+       let entry = self.history.pop_undo()?;
+       let current = self.capture_current(&entry);
+       self.restore_entry(&entry);
+       self.history.push_redo(current);
+       println!("Undo: {}", entry.label);
+       Some(entry.kind())
+   }
+
+   pub fn redo(&mut self) -> Option<HistoryKind> {
+       let entry = self.history.pop_redo()?;
+       let current = self.capture_current(&entry);
+       self.restore_entry(&entry);
+       self.history.push_undo(current);
+       println!("Redo: {}", entry.label);
+       Some(entry.kind())
+   }
+
+   // snapshot of the part of the state the entry is about to overwrite
+   fn capture_current(&self, entry: &HistoryEntry) -> HistoryEntry {
+       match entry.kind() {
+           HistoryKind::Image => {
+               HistoryEntry::image(
+                   &entry.label,
+                   self.image.as_ref(),
+                   self.raw_image.as_ref(),
+                   &self.scientific_state.image_metadata,
+               )
+           }
+           HistoryKind::Annotations => {
+               HistoryEntry::annotations(&entry.label, self.scientific_state.annotation_snapshot())
+           }
+           HistoryKind::Geometry => HistoryEntry::geometry(
+               &entry.label,
+               self.image.as_ref(),
+               self.raw_image.as_ref(),
+               self.scientific_state.geometry_snapshot(),
+           ),
+       }
+   }
+
+   fn restore_entry(&mut self, entry: &HistoryEntry) {
+       if let Some((image, raw)) = entry.restore_image() {
+           self.image = image;
+           self.raw_image = raw;
+           if let Some(metadata) = entry.restore_metadata() {
+               self.scientific_state.image_metadata = metadata;
+           }
+           if let Some(geometry) = entry.restore_geometry() {
+               self.scientific_state.restore_geometry(geometry);
+               self.crop_selection = None;
+               self.pan = (0.0, 0.0);
+           }
+       } else if let Some(annotations) = entry.restore_annotations() {
+           self.scientific_state.restore_annotations(annotations);
+       }
+   }
+}
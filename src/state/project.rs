@@ -0,0 +1,805 @@
+//src/state/project.rs
+//! project files: a zip holding `manifest.json` plus the image payloads it
+//! points to. display images are stored as PNG, full bit-depth data as raw
+//! little-endian sample dumps so nothing is lost on a round trip
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Cursor, Read, Write},
+    path::{Path, PathBuf},
+};
+use chrono::{DateTime, Utc};
+use fltk::{enums::ColorDepth, image::RgbImage, prelude::*};
+use image::{DynamicImage, ImageFormat, Rgba};
+use serde::{Serialize, Deserialize};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+use crate::menu::edit::crop::CropSelection;
+use crate::menu::edit::watermark::{
+    image_watermark::ImageWatermark,
+    text_watermark::TextWatermark,
+    WatermarkOptions,
+};
+use crate::scientific::{
+    analysis::CellMeasurement,
+    calibration::{FlatFieldCalibration, SpatialCalibration},
+    layers::{Annotation, AnnotationType, Calibration, Channel, Metadata},
+    state::{ImageStack, StackKind},
+    types::{CellMeasurementMode, LegendPosition},
+};
+use crate::state::{ImageState, Layer, LayerGroup, LayerState};
+use crate::utils::raw_image::{RawImage, RawPixels};
+
+pub const PROJECT_EXTENSION: &str = "ivproj";
+const MANIFEST_NAME: &str = "manifest.json";
+const PROJECT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(String),
+    Format(String),
+    MissingPayload(String),
+    Unsupported(String),
+}
+
+impl std::fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProjectError::Io(msg) => write!(f, "I/O error: {}", msg),
+            ProjectError::Format(msg) => write!(f, "Invalid project file: {}", msg),
+            ProjectError::MissingPayload(name) => write!(f, "Project is missing {}", name),
+            ProjectError::Unsupported(msg) => write!(f, "Unsupported project: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl From<std::io::Error> for ProjectError {
+    fn from(error: std::io::Error) -> Self {
+        ProjectError::Io(error.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for ProjectError {
+    fn from(error: zip::result::ZipError) -> Self {
+        ProjectError::Format(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ProjectError {
+    fn from(error: serde_json::Error) -> Self {
+        ProjectError::Format(error.to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProjectManifest {
+    version: u32,
+    saved_at: DateTime<Utc>,
+    source_path: Option<PathBuf>,
+    zoom: f32,
+    image: Option<String>,
+    raw_image: Option<RawRecord>,
+    scientific: ScientificRecord,
+    layers: LayerStateRecord,
+    watermark: WatermarkRecord,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum SampleFormat {
+    U8,
+    U16,
+    F32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawRecord {
+    file: String,
+    width: u32,
+    height: u32,
+    channels: usize,
+    format: SampleFormat,
+    display_min: f64,
+    display_max: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChannelRecord {
+    name: String,
+    image: String,
+    wavelength: f32,
+    pseudo_color: (u8, u8, u8),
+    opacity: f32,
+    visible: bool,
+    metadata: Metadata,
+    raw: Option<RawRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AnnotationRecord {
+    name: String,
+    image: String,
+    annotation_type: AnnotationType,
+    visible: bool,
+    coordinates: Vec<(i32, i32)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StackRecord {
+    kind: StackKind,
+    current: usize,
+    pages: Vec<RawRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScientificRecord {
+    channels: Vec<ChannelRecord>,
+    annotations: Vec<AnnotationRecord>,
+    channel_groups: HashMap<String, Vec<usize>>,
+    active_channel: Option<usize>,
+    calibration: SpatialCalibration,
+    calibrations: Vec<Calibration>,
+    #[serde(default)]
+    flat_fields: Vec<FlatFieldCalibration>,
+    measurements: Vec<CellMeasurement>,
+    measurement_mode: CellMeasurementMode,
+    base_image: Option<String>,
+    image_metadata: Metadata,
+    stack: Option<StackRecord>,
+    show_overlay: bool,
+    show_legend: bool,
+    legend_position: LegendPosition,
+    show_preview_layer: bool,
+    show_base_image: bool,
+    show_drawing_layer: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayerRecord {
+    name: String,
+    image: String,
+    opacity: f32,
+    visible: bool,
+    color: (u8, u8, u8),
+    region: Option<CropSelection>,
+    group_id: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayerGroupRecord {
+    name: String,
+    color: (u8, u8, u8),
+    visible: bool,
+    layer_indices: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayerStateRecord {
+    layers: Vec<LayerRecord>,
+    groups: Vec<LayerGroupRecord>,
+    active_layer: Option<usize>,
+    original_image: Option<String>,
+    preview_active: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+enum WatermarkContentRecord {
+    Text { text: String, color: [u8; 4], font_size: f32 },
+    Image { file: String },
+}
+
+#[derive(Serialize, Deserialize)]
+struct WatermarkRecord {
+    options: WatermarkOptions,
+    content: Option<WatermarkContentRecord>,
+}
+
+/// collects payload files while the manifest is built. identical images
+/// (layers and annotations usually share the base image) are stored once
+struct PayloadWriter {
+    files: Vec<(String, Vec<u8>)>,
+    images: Vec<(i32, i32, Vec<u8>, String)>,
+}
+
+impl PayloadWriter {
+    fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    fn add_image(&mut self, image: &RgbImage) -> Result<String, ProjectError> {
+        let (width, height) = (image.data_w(), image.data_h());
+        let data = image.to_rgb_data();
+        if let Some((_, _, _, name)) = self.images.iter()
+            .find(|(w, h, d, _)| *w == width && *h == height && *d == data)
+        {
+            return Ok(name.clone());
+        }
+
+        let buffer = image::RgbImage::from_raw(width as u32, height as u32, data.clone())
+            .ok_or_else(|| ProjectError::Format("image data does not match its size".to_string()))?;
+        let name = format!("images/{}.png", self.images.len());
+        self.files.push((name.clone(), encode_png(&DynamicImage::ImageRgb8(buffer))?));
+        self.images.push((width, height, data, name.clone()));
+        Ok(name)
+    }
+
+    fn add_raw(&mut self, raw: &RawImage) -> RawRecord {
+        let (format, bytes): (SampleFormat, Vec<u8>) = match &raw.pixels {
+            RawPixels::U8(data) => (SampleFormat::U8, data.clone()),
+            RawPixels::U16(data) => (SampleFormat::U16, data.iter().flat_map(|v| v.to_le_bytes()).collect()),
+            RawPixels::F32(data) => (SampleFormat::F32, data.iter().flat_map(|v| v.to_le_bytes()).collect()),
+        };
+        let file = format!("raw/{}.bin", self.files.len());
+        self.files.push((file.clone(), bytes));
+        RawRecord {
+            file,
+            width: raw.width,
+            height: raw.height,
+            channels: raw.channels,
+            format,
+            display_min: raw.display_min,
+            display_max: raw.display_max,
+        }
+    }
+
+    fn add_png(&mut self, image: &DynamicImage) -> Result<String, ProjectError> {
+        let name = format!("images/watermark_{}.png", self.files.len());
+        self.files.push((name.clone(), encode_png(image)?));
+        Ok(name)
+    }
+}
+
+/// payloads are read up front so a broken project never leaves the
+/// viewer half-loaded
+struct PayloadReader {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl PayloadReader {
+    fn bytes(&self, name: &str) -> Result<&[u8], ProjectError> {
+        self.files
+            .get(name)
+            .map(|b| b.as_slice())
+            .ok_or_else(|| ProjectError::MissingPayload(name.to_string()))
+    }
+
+    fn image(&self, name: &str) -> Result<RgbImage, ProjectError> {
+        let rgb = self.dynamic_image(name)?.to_rgb8();
+        let (width, height) = rgb.dimensions();
+        RgbImage::new(rgb.as_raw(), width as i32, height as i32, ColorDepth::Rgb8)
+            .map_err(|e| ProjectError::Format(format!("{}: {}", name, e)))
+    }
+
+    fn dynamic_image(&self, name: &str) -> Result<DynamicImage, ProjectError> {
+        image::load_from_memory_with_format(self.bytes(name)?, ImageFormat::Png)
+            .map_err(|e| ProjectError::Format(format!("{}: {}", name, e)))
+    }
+
+    fn raw(&self, record: &RawRecord) -> Result<RawImage, ProjectError> {
+        let bytes = self.bytes(&record.file)?;
+        if !matches!(record.channels, 1 | 3 | 4) {
+            return Err(ProjectError::Format(format!("{} has {} channels", record.file, record.channels)));
+        }
+        let sample_size = match record.format {
+            SampleFormat::U8 => 1,
+            SampleFormat::U16 => 2,
+            SampleFormat::F32 => 4,
+        };
+        // the sizes come from the manifest, so they are not trusted to fit
+        let expected = (record.width as usize)
+            .checked_mul(record.height as usize)
+            .and_then(|n| n.checked_mul(record.channels))
+            .and_then(|n| n.checked_mul(sample_size));
+        if expected != Some(bytes.len()) {
+            return Err(ProjectError::Format(format!("{} has the wrong size", record.file)));
+        }
+
+        let pixels = match record.format {
+            SampleFormat::U8 => RawPixels::U8(bytes.to_vec()),
+            SampleFormat::U16 => RawPixels::U16(
+                bytes.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect(),
+            ),
+            SampleFormat::F32 => RawPixels::F32(
+                bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect(),
+            ),
+        };
+        let mut raw = RawImage::new(record.width, record.height, record.channels, pixels);
+        raw.display_min = record.display_min;
+        raw.display_max = record.display_max;
+        Ok(raw)
+    }
+}
+
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>, ProjectError> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| ProjectError::Format(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// writes the whole viewer session to `path`
+pub fn save_project(state: &ImageState, path: &Path) -> Result<(), ProjectError> {
+    let mut payloads = PayloadWriter::new();
+    let manifest = build_manifest(state, &mut payloads)?;
+
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    // PNGs are already compressed
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+    zip.start_file(MANIFEST_NAME, deflated)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+
+    for (name, bytes) in &payloads.files {
+        let options = if name.ends_with(".png") { stored } else { deflated };
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(bytes)?;
+    }
+    zip.finish()?;
+
+    println!("Saved project with {} payload(s) to {:?}", payloads.files.len(), path);
+    Ok(())
+}
+
+/// replaces the session in `state` with the one stored at `path`.
+/// undo history is cleared since it refers to the old session
+pub fn load_project(state: &mut ImageState, path: &Path) -> Result<(), ProjectError> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+
+    let manifest: ProjectManifest = {
+        let mut text = String::new();
+        archive.by_name(MANIFEST_NAME)?.read_to_string(&mut text)?;
+        serde_json::from_str(&text)?
+    };
+    if manifest.version > PROJECT_VERSION {
+        return Err(ProjectError::Unsupported(format!(
+            "version {} (this viewer reads up to {})",
+            manifest.version, PROJECT_VERSION
+        )));
+    }
+
+    let mut files = HashMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.name() == MANIFEST_NAME {
+            continue;
+        }
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut bytes)?;
+        files.insert(entry.name().to_string(), bytes);
+    }
+    let payloads = PayloadReader { files };
+
+    restore_manifest(state, manifest, &payloads)?;
+    println!("Loaded project from {:?}", path);
+    Ok(())
+}
+
+fn build_manifest(state: &ImageState, payloads: &mut PayloadWriter) -> Result<ProjectManifest, ProjectError> {
+    let image = state.image.as_ref().map(|img| payloads.add_image(img)).transpose()?;
+    let raw_image = state.raw_image.as_ref().map(|raw| payloads.add_raw(raw));
+
+    Ok(ProjectManifest {
+        version: PROJECT_VERSION,
+        saved_at: Utc::now(),
+        source_path: state.path.clone(),
+        zoom: state.zoom,
+        image,
+        raw_image,
+        scientific: build_scientific(state, payloads)?,
+        layers: build_layers(&state.layer_state, payloads)?,
+        watermark: build_watermark(state, payloads)?,
+    })
+}
+
+fn build_scientific(state: &ImageState, payloads: &mut PayloadWriter) -> Result<ScientificRecord, ProjectError> {
+    let sci = &state.scientific_state;
+
+    let mut channels = Vec::with_capacity(sci.channels.len());
+    for channel in &sci.channels {
+        channels.push(ChannelRecord {
+            name: channel.name.clone(),
+            image: payloads.add_image(&channel.image)?,
+            wavelength: channel.wavelength,
+            pseudo_color: channel.pseudo_color,
+            opacity: channel.opacity,
+            visible: channel.visible,
+            metadata: channel.metadata.clone(),
+            raw: channel.raw.as_ref().map(|raw| payloads.add_raw(raw)),
+        });
+    }
+
+    let mut annotations = Vec::with_capacity(sci.annotations.len());
+    for annotation in &sci.annotations {
+        annotations.push(AnnotationRecord {
+            name: annotation.name.clone(),
+            image: payloads.add_image(&annotation.image)?,
+            annotation_type: annotation.annotation_type.clone(),
+            visible: annotation.visible,
+            coordinates: annotation.coordinates.clone(),
+        });
+    }
+
+    let stack = sci.image_stack.as_ref().map(|stack| StackRecord {
+        kind: stack.kind,
+        current: stack.current,
+        pages: stack.pages.iter().map(|page| payloads.add_raw(page)).collect(),
+    });
+
+    Ok(ScientificRecord {
+        channels,
+        annotations,
+        channel_groups: sci.channel_groups.clone(),
+        active_channel: sci.active_channel,
+        calibration: sci.calibration.clone(),
+        calibrations: sci.calibrations.clone(),
+        flat_fields: sci.flat_fields.clone(),
+        measurements: sci.get_measurements().unwrap_or_default(),
+        measurement_mode: sci.measurement_mode,
+        base_image: sci.base_image.as_ref().map(|img| payloads.add_image(img)).transpose()?,
+        image_metadata: sci.image_metadata.clone(),
+        stack,
+        show_overlay: sci.show_overlay,
+        show_legend: sci.show_legend,
+        legend_position: sci.legend_position,
+        show_preview_layer: sci.show_preview_layer,
+        show_base_image: sci.show_base_image,
+        show_drawing_layer: sci.show_drawing_layer,
+    })
+}
+
+fn build_layers(layer_state: &LayerState, payloads: &mut PayloadWriter) -> Result<LayerStateRecord, ProjectError> {
+    let mut layers = Vec::with_capacity(layer_state.get_layer_count());
+    for i in 0..layer_state.get_layer_count() {
+        if let Some(layer) = layer_state.get_layer(i) {
+            layers.push(LayerRecord {
+                name: layer.name.clone(),
+                image: payloads.add_image(&layer.image)?,
+                opacity: layer.opacity,
+                visible: layer.visible,
+                color: layer.color,
+                region: layer.region.clone(),
+                group_id: layer.group_id,
+            });
+        }
+    }
+
+    let groups = layer_state.get_groups().iter().map(|group| LayerGroupRecord {
+        name: group.name.clone(),
+        color: group.color,
+        visible: group.visible,
+        layer_indices: group.layer_indices.clone(),
+    }).collect();
+
+    Ok(LayerStateRecord {
+        layers,
+        groups,
+        active_layer: layer_state.get_active_layer(),
+        original_image: layer_state.get_original_image().map(|img| payloads.add_image(img)).transpose()?,
+        preview_active: layer_state.is_preview_active(),
+    })
+}
+
+fn build_watermark(state: &ImageState, payloads: &mut PayloadWriter) -> Result<WatermarkRecord, ProjectError> {
+    let watermark = &state.watermark_state;
+    let content = if let Some(text) = watermark.text_watermark() {
+        Some(WatermarkContentRecord::Text {
+            text: text.text().to_string(),
+            color: text.color().0,
+            font_size: text.font_size(),
+        })
+    } else if let Some(image) = watermark.image_watermark() {
+        Some(WatermarkContentRecord::Image { file: payloads.add_png(image.image())? })
+    } else {
+        None
+    };
+
+    Ok(WatermarkRecord {
+        options: watermark.get_current_options(),
+        content,
+    })
+}
+
+fn restore_manifest(state: &mut ImageState, manifest: ProjectManifest, payloads: &PayloadReader) -> Result<(), ProjectError> {
+    // decode everything before touching the state
+    let image = manifest.image.as_deref().map(|name| payloads.image(name)).transpose()?;
+    let raw_image = manifest.raw_image.as_ref().map(|raw| payloads.raw(raw)).transpose()?;
+    let sci = manifest.scientific;
+
+    let mut channels = Vec::with_capacity(sci.channels.len());
+    for record in &sci.channels {
+        let mut channel = Channel::new(record.name.clone(), payloads.image(&record.image)?, record.wavelength, record.pseudo_color)
+            .with_raw(record.raw.as_ref().map(|raw| payloads.raw(raw)).transpose()?);
+        channel.opacity = record.opacity;
+        channel.visible = record.visible;
+        channel.metadata = record.metadata.clone();
+        channels.push(channel);
+    }
+
+    let mut annotations = Vec::with_capacity(sci.annotations.len());
+    for record in sci.annotations {
+        annotations.push(Annotation {
+            image: payloads.image(&record.image)?,
+            name: record.name,
+            annotation_type: record.annotation_type,
+            visible: record.visible,
+            coordinates: record.coordinates,
+        });
+    }
+
+    let base_image = sci.base_image.as_deref().map(|name| payloads.image(name)).transpose()?;
+    let image_stack = match &sci.stack {
+        Some(record) => {
+            let pages = record.pages.iter().map(|page| payloads.raw(page)).collect::<Result<Vec<_>, _>>()?;
+            let mut stack = ImageStack::new(pages, record.kind);
+            stack.set_current(record.current);
+            Some(stack)
+        }
+        None => None,
+    };
+
+    let layer_state = restore_layers(manifest.layers, payloads)?;
+    let watermark_content = manifest.watermark.content
+        .map(|content| match content {
+            WatermarkContentRecord::Text { text, color, font_size } => {
+                TextWatermark::new(text, Rgba(color), font_size)
+                    .map(WatermarkContent::Text)
+                    .map_err(|e| ProjectError::Format(e.to_string()))
+            }
+            WatermarkContentRecord::Image { file } => {
+                Ok(WatermarkContent::Image(ImageWatermark::new(payloads.dynamic_image(&file)?)))
+            }
+        })
+        .transpose()?;
+
+    state.image = image;
+    state.raw_image = raw_image;
+    state.path = manifest.source_path;
+    state.zoom = manifest.zoom;
+    state.pan = (0.0, 0.0);
+    state.crop_selection = None;
+    state.history.clear();
+    state.layer_state = layer_state;
+
+    state.watermark_state.clear_watermark();
+    state.watermark_state.set_options(manifest.watermark.options);
+    match watermark_content {
+        Some(WatermarkContent::Text(watermark)) => state.watermark_state.set_text_watermark(watermark),
+        Some(WatermarkContent::Image(watermark)) => state.watermark_state.set_watermark(watermark),
+        None => {}
+    }
+
+    let scientific = &mut state.scientific_state;
+    scientific.channels = channels;
+    scientific.annotations = annotations;
+    scientific.channel_groups = sci.channel_groups;
+    scientific.active_channel = sci.active_channel;
+    scientific.calibration = sci.calibration;
+    scientific.calibrations = sci.calibrations;
+    // the registry of this installation wins over the one saved with the project
+    for flat_field in sci.flat_fields {
+        if !scientific.flat_fields.iter().any(|f| f.matches(&flat_field.objective)) {
+            scientific.flat_fields.push(flat_field);
+        }
+    }
+    scientific.set_measurements(sci.measurements);
+    scientific.measurement_mode = sci.measurement_mode;
+    scientific.base_image = base_image;
+    scientific.image_metadata = sci.image_metadata;
+    scientific.image_stack = image_stack;
+    scientific.show_overlay = sci.show_overlay;
+    scientific.show_legend = sci.show_legend;
+    scientific.legend_position = sci.legend_position;
+    scientific.show_preview_layer = sci.show_preview_layer;
+    scientific.show_base_image = sci.show_base_image;
+    scientific.show_drawing_layer = sci.show_drawing_layer;
+    scientific.preview_layer = None;
+    scientific.clear_current_roi();
+    Ok(())
+}
+
+// local stand-in for the watermark state's private content enum
+enum WatermarkContent {
+    Text(TextWatermark),
+    Image(ImageWatermark),
+}
+
+fn restore_layers(record: LayerStateRecord, payloads: &PayloadReader) -> Result<LayerState, ProjectError> {
+    let mut layers = Vec::with_capacity(record.layers.len());
+    for layer in record.layers {
+        layers.push(Layer {
+            image: payloads.image(&layer.image)?,
+            name: layer.name,
+            opacity: layer.opacity,
+            visible: layer.visible,
+            color: layer.color,
+            region: layer.region,
+            group_id: layer.group_id,
+        });
+    }
+
+    let groups = record.groups.into_iter().map(|group| LayerGroup {
+        name: group.name,
+        color: group.color,
+        visible: group.visible,
+        layer_indices: group.layer_indices,
+    }).collect();
+
+    let original_image = record.original_image.as_deref().map(|name| payloads.image(name)).transpose()?;
+    Ok(LayerState::restore(layers, groups, record.active_layer, original_image, record.preview_active))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(width: i32, height: i32, value: u8) -> RgbImage {
+        RgbImage::new(&vec![value; (width * height * 3) as usize], width, height, ColorDepth::Rgb8).unwrap()
+    }
+
+    fn temp_project(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.{}", name, std::process::id(), PROJECT_EXTENSION))
+    }
+
+    fn session() -> ImageState {
+        let mut state = ImageState::new();
+        let raw = RawImage::new(4, 3, 1, RawPixels::U16((0..12).map(|v| v * 5000 + 7).collect()));
+        state.image = raw.to_rgb_image();
+        state.raw_image = Some(raw);
+        state.zoom = 2.0;
+
+        let sci = &mut state.scientific_state;
+        let channel_raw = RawImage::new(4, 3, 1, RawPixels::U16(vec![40_000; 12]));
+        sci.channels.push(Channel::new("DAPI".to_string(), rgb(4, 3, 156), 461.0, (0, 0, 255)).with_raw(Some(channel_raw)));
+        sci.active_channel = Some(0);
+        sci.annotations.push(Annotation {
+            name: "ROI 1".to_string(),
+            image: rgb(4, 3, 0),
+            annotation_type: AnnotationType::ROI { color: (255, 0, 0), line_width: 2 },
+            visible: true,
+            coordinates: vec![(0, 0), (3, 0), (3, 2)],
+        });
+        sci.calibrations.push(Calibration::new("40x".to_string(), 12.5, "µm".to_string(), 125.0, 10.0));
+        sci.calibration.pixels_per_unit = 12.5;
+        sci.calibration.unit = "µm".to_string();
+        sci.image_metadata.objective = Some("40x".to_string());
+
+        let layer = Layer {
+            name: "Layer 1".to_string(),
+            image: rgb(4, 3, 200),
+            opacity: 0.5,
+            visible: false,
+            color: (0, 255, 0),
+            region: None,
+            group_id: Some(0),
+        };
+        let group = LayerGroup { name: "Group 1".to_string(), color: (0, 255, 0), visible: true, layer_indices: vec![0] };
+        state.layer_state = LayerState::restore(vec![layer], vec![group], Some(0), Some(rgb(4, 3, 30)), false);
+        state
+    }
+
+    #[test]
+    fn session_survives_a_save_and_open() {
+        let path = temp_project("project-round-trip");
+        save_project(&session(), &path).unwrap();
+        let mut state = ImageState::new();
+        let loaded = load_project(&mut state, &path);
+        let _ = std::fs::remove_file(&path);
+        loaded.unwrap();
+
+        let raw = state.raw_image.as_ref().unwrap();
+        assert_eq!((raw.width, raw.height, raw.channels), (4, 3, 1));
+        assert!(matches!(&raw.pixels, RawPixels::U16(v) if v[11] == 55_007));
+        assert_eq!(state.image.as_ref().map(|i| (i.data_w(), i.data_h())), Some((4, 3)));
+        assert_eq!(state.zoom, 2.0);
+
+        let sci = &state.scientific_state;
+        assert_eq!(sci.channels.len(), 1);
+        assert_eq!(sci.channels[0].name, "DAPI");
+        assert_eq!(sci.channels[0].pseudo_color, (0, 0, 255));
+        assert!(matches!(sci.channels[0].raw.as_ref().map(|r| &r.pixels), Some(RawPixels::U16(v)) if v[0] == 40_000));
+        assert_eq!(sci.active_channel, Some(0));
+
+        assert_eq!(sci.annotations.len(), 1);
+        assert_eq!(sci.annotations[0].coordinates, vec![(0, 0), (3, 0), (3, 2)]);
+        assert!(sci.annotations[0].annotation_type == AnnotationType::ROI { color: (255, 0, 0), line_width: 2 });
+
+        assert_eq!(sci.calibrations.len(), 1);
+        assert_eq!(sci.calibrations[0].objective, "40x");
+        assert_eq!(sci.calibration.pixels_per_unit, 12.5);
+        assert_eq!(sci.calibration.unit, "µm");
+        assert_eq!(sci.image_metadata.objective.as_deref(), Some("40x"));
+
+        let layers = &state.layer_state;
+        assert_eq!(layers.get_layer_count(), 1);
+        let layer = layers.get_layer(0).unwrap();
+        assert_eq!((layer.name.as_str(), layer.opacity, layer.visible, layer.group_id), ("Layer 1", 0.5, false, Some(0)));
+        assert_eq!(layer.image.to_rgb_data()[0], 200);
+        assert_eq!(layers.get_groups().len(), 1);
+        assert_eq!(layers.get_active_layer(), Some(0));
+        assert_eq!(layers.get_original_image().map(|i| i.to_rgb_data()[0]), Some(30));
+    }
+
+    #[test]
+    fn manifest_without_newer_fields_still_loads() {
+        let path = temp_project("project-old-manifest");
+        save_project(&session(), &path).unwrap();
+
+        // rewrite the manifest the way a viewer from before flat-fields and corrections saved it
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut entries = Vec::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).unwrap();
+            entries.push((entry.name().to_string(), bytes));
+        }
+        drop(archive);
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        for (name, bytes) in entries {
+            let bytes = if name == MANIFEST_NAME {
+                let mut manifest: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                let scientific = manifest["scientific"].as_object_mut().unwrap();
+                assert!(scientific.remove("flat_fields").is_some());
+                assert!(scientific["image_metadata"].as_object_mut().unwrap().remove("corrections").is_some());
+                serde_json::to_vec(&manifest).unwrap()
+            } else {
+                bytes
+            };
+            zip.start_file(name.as_str(), FileOptions::default()).unwrap();
+            zip.write_all(&bytes).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let mut state = ImageState::new();
+        let loaded = load_project(&mut state, &path);
+        let _ = std::fs::remove_file(&path);
+        loaded.unwrap();
+        assert!(state.scientific_state.image_metadata.corrections.is_empty());
+        assert_eq!(state.scientific_state.calibrations.len(), 1);
+        assert!(state.raw_image.is_some());
+    }
+
+    #[test]
+    fn raw_records_with_bad_sizes_are_rejected() {
+        let reader = PayloadReader {
+            files: HashMap::from([("raw/0.bin".to_string(), vec![0u8; 13])]),
+        };
+        let record = |width: u32, height: u32, channels: usize| RawRecord {
+            file: "raw/0.bin".to_string(),
+            width,
+            height,
+            channels,
+            format: SampleFormat::U16,
+            display_min: 0.0,
+            display_max: 65_535.0,
+        };
+
+        // an odd byte count can't be 16-bit samples
+        assert!(matches!(reader.raw(&record(13, 1, 1)), Err(ProjectError::Format(_))));
+        assert!(matches!(reader.raw(&record(u32::MAX, u32::MAX, 4)), Err(ProjectError::Format(_))));
+        assert!(matches!(reader.raw(&record(0, 1, 0)), Err(ProjectError::Format(_))));
+
+        let reader = PayloadReader {
+            files: HashMap::from([("raw/0.bin".to_string(), vec![1u8; 12])]),
+        };
+        assert!(matches!(reader.raw(&record(2, 1, 3)), Ok(raw) if raw.len() == 6));
+    }
+
+    #[test]
+    fn flat_field_registry_stays_in_memory_until_loaded() {
+        let mut state = ImageState::new();
+        assert!(state.scientific_state.flat_fields.is_empty());
+
+        let flat = |path: &str| FlatFieldCalibration::new("40x".to_string(), PathBuf::from(path), None, false);
+        state.scientific_state.add_flat_field(flat("old.tif"));
+        state.scientific_state.add_flat_field(flat("new.tif"));
+        assert_eq!(state.scientific_state.flat_fields.len(), 1);
+        assert_eq!(state.scientific_state.flat_fields[0].flat_path, PathBuf::from("new.tif"));
+    }
+}
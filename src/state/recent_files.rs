@@ -0,0 +1,124 @@
+//src/state/recent_files.rs
+use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
+use crate::utils::config::config_dir;
+
+const RECENT_FILE_NAME: &str = "recent_files.json";
+pub const MAX_RECENT_FILES: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecentKind {
+    Image,
+    Project,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecentEntry {
+    pub path: PathBuf,
+    pub kind: RecentKind,
+}
+
+/// most recently used files, newest first. persisted as JSON in the user's
+/// config directory
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct RecentFiles {
+    entries: Vec<RecentEntry>,
+}
+
+impl RecentFiles {
+    /// reads the list from disk, dropping files that no longer exist
+    pub fn load() -> Self {
+        let mut recent = Self::storage_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str::<RecentFiles>(&text).ok())
+            .unwrap_or_default();
+        if recent.prune() {
+            recent.save();
+        }
+        recent
+    }
+
+    pub fn save(&self) {
+        let path = match Self::storage_path() {
+            Some(path) => path,
+            None => return,
+        };
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    println!("Failed to save recent files to {:?}: {}", path, e);
+                }
+            }
+            Err(e) => println!("Failed to serialize recent files: {}", e),
+        }
+    }
+
+    /// moves `path` to the top of the list
+    pub fn add(&mut self, path: &Path, kind: RecentKind) {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.entries.retain(|entry| entry.path != path);
+        self.entries.insert(0, RecentEntry { path, kind });
+        self.entries.truncate(MAX_RECENT_FILES);
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.entries.retain(|entry| entry.path != path);
+    }
+
+    /// drops entries whose file is gone, returns true if anything was removed
+    pub fn prune(&mut self) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.path.exists());
+        self.entries.len() != before
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn entries(&self) -> &[RecentEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn storage_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(RECENT_FILE_NAME))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_moves_existing_entry_to_front() {
+        let mut recent = RecentFiles::default();
+        recent.add(Path::new("/nonexistent/a.png"), RecentKind::Image);
+        recent.add(Path::new("/nonexistent/b.png"), RecentKind::Image);
+        recent.add(Path::new("/nonexistent/a.png"), RecentKind::Image);
+
+        let paths: Vec<_> = recent.entries().iter().map(|e| e.path.clone()).collect();
+        assert_eq!(paths, vec![PathBuf::from("/nonexistent/a.png"), PathBuf::from("/nonexistent/b.png")]);
+    }
+
+    #[test]
+    fn list_is_capped() {
+        let mut recent = RecentFiles::default();
+        for i in 0..MAX_RECENT_FILES + 5 {
+            recent.add(Path::new(&format!("/nonexistent/{}.png", i)), RecentKind::Image);
+        }
+        assert_eq!(recent.entries().len(), MAX_RECENT_FILES);
+        assert_eq!(recent.entries()[0].path, PathBuf::from(format!("/nonexistent/{}.png", MAX_RECENT_FILES + 4)));
+    }
+
+    #[test]
+    fn prune_drops_missing_files() {
+        let mut recent = RecentFiles::default();
+        recent.add(Path::new("/nonexistent/missing.png"), RecentKind::Project);
+        assert!(recent.prune());
+        assert!(recent.is_empty());
+    }
+}
@@ -234,6 +234,20 @@ impl WatermarkState {
         })
     }
 
+    pub fn text_watermark(&self) -> Option<&TextWatermark> {
+        match &self.watermark_content {
+            Some(WatermarkContent::Text(watermark)) => Some(watermark),
+            _ => None,
+        }
+    }
+
+    pub fn image_watermark(&self) -> Option<&ImageWatermark> {
+        match &self.watermark_content {
+            Some(WatermarkContent::Image(watermark)) => Some(watermark),
+            _ => None,
+        }
+    }
+
     pub fn has_watermark(&self) -> bool {
         self.watermark_content.is_some()
     }
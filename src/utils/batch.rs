@@ -0,0 +1,366 @@
+// src/utils/batch.rs
+use std::path::{Path, PathBuf};
+use image::{imageops, DynamicImage, ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use crate::menu::edit::filters::{FilterSpec, ImageFilter};
+use crate::menu::edit::watermark::{
+    image_watermark::ImageWatermark, text_watermark::TextWatermark, Watermark, WatermarkOptions,
+};
+use crate::scientific::io::{write_image, ExportFormat};
+use crate::state::export_options::ExportOptions;
+use crate::state::filter_recipe::FilterRecipe;
+use crate::utils::raw_image::{RawImage, RawPixels};
+
+/// a batch job: the steps run in order on every input, then the output settings
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchRecipe {
+    #[serde(default)]
+    pub steps: Vec<BatchStep>,
+    #[serde(default)]
+    pub output: BatchOutput,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchStep {
+    Filter(FilterSpec),
+    /// the enabled steps of a filter recipe saved from the filter stack
+    Recipe { path: PathBuf },
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    /// a missing side keeps the aspect ratio
+    Resize {
+        width: Option<u32>,
+        height: Option<u32>,
+        #[serde(default)]
+        filter: ResizeFilter,
+    },
+    Watermark(WatermarkStep),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeFilter {
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    #[default]
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    fn filter_type(self) -> imageops::FilterType {
+        match self {
+            ResizeFilter::Nearest => imageops::FilterType::Nearest,
+            ResizeFilter::Triangle => imageops::FilterType::Triangle,
+            ResizeFilter::CatmullRom => imageops::FilterType::CatmullRom,
+            ResizeFilter::Gaussian => imageops::FilterType::Gaussian,
+            ResizeFilter::Lanczos3 => imageops::FilterType::Lanczos3,
+        }
+    }
+}
+
+/// either `text` or `image` has to be set
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WatermarkStep {
+    pub text: Option<String>,
+    pub image: Option<PathBuf>,
+    #[serde(default = "default_watermark_color")]
+    pub color: [u8; 4],
+    #[serde(default = "default_font_size")]
+    pub font_size: f32,
+    #[serde(default)]
+    pub options: WatermarkOptions,
+}
+
+fn default_watermark_color() -> [u8; 4] {
+    [255, 255, 255, 255]
+}
+
+fn default_font_size() -> f32 {
+    32.0
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BatchOutput {
+    /// where results go, the command line can override it
+    pub directory: Option<PathBuf>,
+    /// target extension (e.g. "png"), None keeps the input's format
+    pub format: Option<String>,
+    /// appended to the file stem
+    #[serde(default)]
+    pub suffix: String,
+    #[serde(default)]
+    pub overwrite: bool,
+    #[serde(default)]
+    pub export: ExportOptions,
+}
+
+#[derive(Debug)]
+pub enum BatchError {
+    Recipe(String),
+    Load(String),
+    Step(String),
+    Write(String),
+}
+
+impl std::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::Recipe(msg) => write!(f, "Recipe error: {}", msg),
+            BatchError::Load(msg) => write!(f, "Load error: {}", msg),
+            BatchError::Step(msg) => write!(f, "Processing error: {}", msg),
+            BatchError::Write(msg) => write!(f, "Write error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl BatchRecipe {
+    /// reads a recipe, TOML for .toml files and JSON otherwise
+    pub fn load(path: &Path) -> Result<Self, BatchError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| BatchError::Recipe(format!("{}: {}", path.display(), e)))?;
+        let is_toml = path.extension().and_then(|e| e.to_str()).map(|e| e.eq_ignore_ascii_case("toml")) == Some(true);
+        if is_toml {
+            Self::from_toml(&text)
+        } else {
+            Self::from_json(&text)
+        }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, BatchError> {
+        serde_json::from_str(text).map_err(|e| BatchError::Recipe(e.to_string()))
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, BatchError> {
+        toml::from_str(text).map_err(|e| BatchError::Recipe(e.to_string()))
+    }
+}
+
+enum Stage {
+    Filter(&'static str, Box<dyn ImageFilter>),
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    Resize { width: Option<u32>, height: Option<u32>, filter: ResizeFilter },
+    Watermark(Box<dyn Watermark>, WatermarkOptions),
+}
+
+/// a recipe with its filters and watermarks built, ready to run over many files
+pub struct BatchPipeline {
+    stages: Vec<Stage>,
+    output: BatchOutput,
+}
+
+impl BatchPipeline {
+    pub fn new(recipe: &BatchRecipe) -> Result<Self, BatchError> {
+        if let Some(format) = &recipe.output.format {
+            if ExportFormat::from_extension(format).is_none() {
+                return Err(BatchError::Recipe(format!("unsupported output format '{}'", format)));
+            }
+        }
+
+        let mut stages = Vec::with_capacity(recipe.steps.len());
+        for step in &recipe.steps {
+            let stage = match step {
+                BatchStep::Filter(spec) => Stage::Filter(spec.name(), spec.build()),
+                BatchStep::Recipe { path } => {
+                    let recipe = FilterRecipe::load(path)
+                        .map_err(|e| BatchError::Recipe(format!("{}: {}", path.display(), e)))?;
+                    for step in recipe.steps.iter().filter(|s| s.enabled) {
+                        stages.push(Stage::Filter(step.spec.name(), step.spec.build()));
+                    }
+                    continue;
+                }
+                BatchStep::Crop { x, y, width, height } => {
+                    if *width == 0 || *height == 0 {
+                        return Err(BatchError::Recipe("crop size must be non-zero".to_string()));
+                    }
+                    if x.checked_add(*width).is_none() || y.checked_add(*height).is_none() {
+                        return Err(BatchError::Recipe("crop extends past the largest possible image".to_string()));
+                    }
+                    Stage::Crop { x: *x, y: *y, width: *width, height: *height }
+                }
+                BatchStep::Resize { width, height, filter } => {
+                    if width.is_none() && height.is_none() {
+                        return Err(BatchError::Recipe("resize needs a width or a height".to_string()));
+                    }
+                    Stage::Resize { width: *width, height: *height, filter: *filter }
+                }
+                BatchStep::Watermark(step) => Stage::Watermark(build_watermark(step)?, step.options.clone()),
+            };
+            stages.push(stage);
+        }
+
+        Ok(Self { stages, output: recipe.output.clone() })
+    }
+
+    pub fn output_directory(&self) -> Option<&Path> {
+        self.output.directory.as_deref()
+    }
+
+    /// where `input` ends up inside `out_dir`
+    pub fn output_path(&self, input: &Path, out_dir: &Path) -> PathBuf {
+        let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("image");
+        let extension = match &self.output.format {
+            Some(format) => format.clone(),
+            None => input.extension().and_then(|e| e.to_str()).unwrap_or("png").to_string(),
+        };
+        out_dir.join(format!("{}{}.{}", stem, self.output.suffix, extension.to_lowercase()))
+    }
+
+    /// runs every stage on `input` and writes the result into `out_dir`
+    pub fn process(&self, input: &Path, out_dir: &Path) -> Result<PathBuf, BatchError> {
+        let output = self.output_path(input, out_dir);
+        if output.exists() && !self.output.overwrite {
+            return Err(BatchError::Write(format!("{} already exists", output.display())));
+        }
+
+        let image = image::open(input).map_err(|e| BatchError::Load(format!("{}: {}", input.display(), e)))?;
+
+        // with no steps it's a plain conversion and the full bit depth is kept
+        let raw = if self.stages.is_empty() {
+            RawImage::from_dynamic(&image)
+        } else {
+            let mut buffer = image.to_rgba8();
+            for stage in &self.stages {
+                buffer = self.run_stage(stage, buffer)?;
+            }
+            let rgb = DynamicImage::ImageRgba8(buffer).to_rgb8();
+            RawImage::new(rgb.width(), rgb.height(), 3, RawPixels::U8(rgb.into_raw()))
+        };
+
+        write_image(&output, &raw, None, &self.output.export)
+            .map_err(|e| BatchError::Write(format!("{}: {}", output.display(), e)))?;
+        Ok(output)
+    }
+
+    fn run_stage(
+        &self,
+        stage: &Stage,
+        mut buffer: ImageBuffer<Rgba<u8>, Vec<u8>>,
+    ) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, BatchError> {
+        match stage {
+            Stage::Filter(name, filter) => {
+                filter
+                    .apply(&mut buffer)
+                    .map_err(|e| BatchError::Step(format!("{}: {}", name, e.message)))?;
+                Ok(buffer)
+            }
+            Stage::Crop { x, y, width, height } => {
+                let outside = |start: u32, len: u32, size: u32| start.checked_add(len).is_none_or(|end| end > size);
+                if outside(*x, *width, buffer.width()) || outside(*y, *height, buffer.height()) {
+                    return Err(BatchError::Step(format!(
+                        "crop {}x{}+{}+{} is outside the {}x{} image",
+                        width, height, x, y, buffer.width(), buffer.height()
+                    )));
+                }
+                Ok(imageops::crop_imm(&buffer, *x, *y, *width, *height).to_image())
+            }
+            Stage::Resize { width, height, filter } => {
+                let (w, h) = resize_dimensions(buffer.width(), buffer.height(), *width, *height);
+                Ok(imageops::resize(&buffer, w, h, filter.filter_type()))
+            }
+            Stage::Watermark(watermark, options) => {
+                watermark
+                    .apply(&mut buffer, options)
+                    .map_err(|e| BatchError::Step(format!("watermark: {}", e)))?;
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+fn build_watermark(step: &WatermarkStep) -> Result<Box<dyn Watermark>, BatchError> {
+    match (&step.text, &step.image) {
+        (Some(text), None) => {
+            let watermark = TextWatermark::new(text.clone(), Rgba(step.color), step.font_size)
+                .map_err(|e| BatchError::Recipe(e.to_string()))?;
+            Ok(Box::new(watermark))
+        }
+        (None, Some(path)) => {
+            let watermark = ImageWatermark::from_path(path).map_err(|e| BatchError::Recipe(e.to_string()))?;
+            Ok(Box::new(watermark))
+        }
+        _ => Err(BatchError::Recipe("a watermark needs exactly one of 'text' or 'image'".to_string())),
+    }
+}
+
+/// fills in a missing side from the aspect ratio
+fn resize_dimensions(src_w: u32, src_h: u32, width: Option<u32>, height: Option<u32>) -> (u32, u32) {
+    let scaled = |value: u32, num: u32, den: u32| ((value as f64 * num as f64 / den as f64).round() as u32).max(1);
+    match (width, height) {
+        (Some(w), Some(h)) => (w.max(1), h.max(1)),
+        (Some(w), None) => (w.max(1), scaled(src_h, w, src_w)),
+        (None, Some(h)) => (scaled(src_w, h, src_h), h.max(1)),
+        (None, None) => (src_w, src_h),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_and_toml_recipes_match() {
+        let json = r#"{
+            "steps": [
+                { "op": "filter", "filter": "brightness", "intensity": 0.2 },
+                { "op": "resize", "width": 640 }
+            ],
+            "output": { "format": "png", "suffix": "_small" }
+        }"#;
+        let toml = r#"
+            [[steps]]
+            op = "filter"
+            filter = "brightness"
+            intensity = 0.2
+
+            [[steps]]
+            op = "resize"
+            width = 640
+
+            [output]
+            format = "png"
+            suffix = "_small"
+        "#;
+
+        for recipe in [BatchRecipe::from_json(json).unwrap(), BatchRecipe::from_toml(toml).unwrap()] {
+            assert_eq!(recipe.steps.len(), 2);
+            assert!(matches!(&recipe.steps[0], BatchStep::Filter(FilterSpec::Brightness { .. })));
+            assert!(matches!(
+                recipe.steps[1],
+                BatchStep::Resize { width: Some(640), height: None, filter: ResizeFilter::Lanczos3 }
+            ));
+            assert_eq!(recipe.output.format.as_deref(), Some("png"));
+        }
+    }
+
+    #[test]
+    fn resize_keeps_aspect_ratio() {
+        assert_eq!(resize_dimensions(400, 200, Some(100), None), (100, 50));
+        assert_eq!(resize_dimensions(400, 200, None, Some(100)), (200, 100));
+        assert_eq!(resize_dimensions(400, 200, Some(10), Some(10)), (10, 10));
+    }
+
+    #[test]
+    fn crops_outside_the_image_are_errors_not_panics() {
+        let crop = |x: u32, width: u32| {
+            BatchRecipe::from_json(&format!(
+                r#"{{ "steps": [{{ "op": "crop", "x": {}, "y": 0, "width": {}, "height": 1 }}] }}"#,
+                x, width
+            ))
+            .unwrap()
+        };
+        assert!(matches!(BatchPipeline::new(&crop(u32::MAX, 10)), Err(BatchError::Recipe(_))));
+
+        let buffer = ImageBuffer::from_pixel(4, 4, Rgba([0u8, 0, 0, 255]));
+        let pipeline = BatchPipeline::new(&crop(u32::MAX - 10, 10)).unwrap();
+        let result = pipeline.run_stage(&pipeline.stages[0], buffer.clone());
+        assert!(matches!(result, Err(BatchError::Step(_))));
+
+        let pipeline = BatchPipeline::new(&crop(1, 3)).unwrap();
+        let cropped = pipeline.run_stage(&pipeline.stages[0], buffer).unwrap();
+        assert_eq!(cropped.dimensions(), (3, 1));
+    }
+}
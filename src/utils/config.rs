@@ -0,0 +1,45 @@
+// src/utils/config.rs
+
+use std::path::PathBuf;
+
+const APP_DIR: &str = "image-viewer";
+
+/// per-user config directory for the viewer, e.g. `~/.config/image-viewer`
+/// or `%APPDATA%\image-viewer`. created on first use
+pub fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }?;
+
+    let dir = base.join(APP_DIR);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        println!("Failed to create config directory {:?}: {}", dir, e);
+        return None;
+    }
+    Some(dir)
+}
+
+/// per-user cache directory, e.g. `~/.cache/image-viewer` or
+/// `%LOCALAPPDATA%\image-viewer`. anything in here can be regenerated
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    }?;
+
+    let dir = base.join(APP_DIR);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        println!("Failed to create cache directory {:?}: {}", dir, e);
+        return None;
+    }
+    Some(dir)
+}
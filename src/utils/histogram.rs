@@ -0,0 +1,195 @@
+// src/utils/histogram.rs
+use fltk::{enums::ColorDepth, image::RgbImage, prelude::*};
+use plotters::prelude::*;
+
+/// which counts of a histogram to show
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HistogramChannel {
+    Luminance,
+    Red,
+    Green,
+    Blue,
+}
+
+impl HistogramChannel {
+    pub const ALL: [HistogramChannel; 4] = [
+        HistogramChannel::Luminance,
+        HistogramChannel::Red,
+        HistogramChannel::Green,
+        HistogramChannel::Blue,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HistogramChannel::Luminance => "Luminance",
+            HistogramChannel::Red => "Red",
+            HistogramChannel::Green => "Green",
+            HistogramChannel::Blue => "Blue",
+        }
+    }
+
+    fn color(&self) -> RGBColor {
+        match self {
+            HistogramChannel::Luminance => RGBColor(90, 90, 90),
+            HistogramChannel::Red => RGBColor(220, 40, 40),
+            HistogramChannel::Green => RGBColor(40, 170, 40),
+            HistogramChannel::Blue => RGBColor(40, 70, 220),
+        }
+    }
+}
+
+/// 256-bin counts of each channel of an 8-bit image
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    pub red: [u32; 256],
+    pub green: [u32; 256],
+    pub blue: [u32; 256],
+    pub luminance: [u32; 256],
+}
+
+impl Histogram {
+    /// counts interleaved pixels with 1 (gray), 2 (gray + alpha), 3 or 4 channels.
+    /// gray pixels count towards all four channels
+    pub fn from_data(data: &[u8], channels: usize) -> Self {
+        let mut histogram = Histogram {
+            red: [0; 256],
+            green: [0; 256],
+            blue: [0; 256],
+            luminance: [0; 256],
+        };
+        if channels == 0 {
+            return histogram;
+        }
+        for pixel in data.chunks_exact(channels) {
+            let (r, g, b) = if channels < 3 {
+                (pixel[0], pixel[0], pixel[0])
+            } else {
+                (pixel[0], pixel[1], pixel[2])
+            };
+            histogram.red[r as usize] += 1;
+            histogram.green[g as usize] += 1;
+            histogram.blue[b as usize] += 1;
+            histogram.luminance[luminance(r, g, b) as usize] += 1;
+        }
+        histogram
+    }
+
+    pub fn of_image(image: &RgbImage) -> Self {
+        Self::from_data(&image.to_rgb_data(), image.depth() as usize)
+    }
+
+    pub fn channel(&self, channel: HistogramChannel) -> &[u32; 256] {
+        match channel {
+            HistogramChannel::Luminance => &self.luminance,
+            HistogramChannel::Red => &self.red,
+            HistogramChannel::Green => &self.green,
+            HistogramChannel::Blue => &self.blue,
+        }
+    }
+
+    pub fn pixel_count(&self) -> u64 {
+        self.luminance.iter().map(|&c| c as u64).sum()
+    }
+
+    /// (min, max, mean) of the values present in `channel`, None for an empty histogram
+    pub fn stats(&self, channel: HistogramChannel) -> Option<(u8, u8, f64)> {
+        let counts = self.channel(channel);
+        let min = counts.iter().position(|&c| c > 0)?;
+        let max = counts.iter().rposition(|&c| c > 0)?;
+        let total: u64 = counts.iter().map(|&c| c as u64).sum();
+        let sum: u64 = counts.iter().enumerate().map(|(v, &c)| v as u64 * c as u64).sum();
+        Some((min as u8, max as u8, sum as f64 / total as f64))
+    }
+
+    /// draws `channels` on top of each other into a `width` x `height` image. without
+    /// `axes` the bins span the whole image, so x = value * width / 256, which the
+    /// levels and curves editors rely on to draw over it
+    pub fn render(
+        &self,
+        channels: &[HistogramChannel],
+        width: u32,
+        height: u32,
+        log_scale: bool,
+        axes: bool,
+    ) -> Result<RgbImage, Box<dyn std::error::Error>> {
+        let scale = |count: u32| if log_scale { (count as f64 + 1.0).ln() } else { count as f64 };
+        let peak = channels
+            .iter()
+            .flat_map(|&channel| self.channel(channel).iter())
+            .map(|&count| scale(count))
+            .fold(1.0, f64::max);
+
+        let mut buffer = vec![0u8; (width * height * 3) as usize];
+        {
+            let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+            root.fill(&WHITE)?;
+
+            let mut builder = ChartBuilder::on(&root);
+            if axes {
+                builder
+                    .margin(5)
+                    .set_label_area_size(LabelAreaPosition::Left, 40)
+                    .set_label_area_size(LabelAreaPosition::Bottom, 25);
+            }
+            let mut chart = builder.build_cartesian_2d(0f64..256f64, 0f64..peak * 1.05)?;
+
+            if axes {
+                chart
+                    .configure_mesh()
+                    .disable_mesh()
+                    .x_labels(5)
+                    .y_labels(3)
+                    .y_label_formatter(&|_| String::new())
+                    .draw()?;
+            }
+
+            for &channel in channels {
+                let color = channel.color();
+                // step shaped, each bin covers [v, v + 1)
+                let points = self
+                    .channel(channel)
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(v, &count)| [(v as f64, scale(count)), (v as f64 + 1.0, scale(count))]);
+                chart.draw_series(AreaSeries::new(points, 0.0, color.mix(0.35)).border_style(color))?;
+            }
+
+            root.present()?;
+        }
+        Ok(RgbImage::new(&buffer, width as i32, height as i32, ColorDepth::Rgb8)?)
+    }
+}
+
+/// Rec. 601 luma, the weighting the grayscale filter uses
+pub fn luminance(r: u8, g: u8, b: u8) -> u8 {
+    (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32).round().min(255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_every_channel() {
+        let data = [255, 0, 0, 0, 255, 0, 10, 10, 10];
+        let histogram = Histogram::from_data(&data, 3);
+        assert_eq!(histogram.red[255], 1);
+        assert_eq!(histogram.red[0], 1);
+        assert_eq!(histogram.green[255], 1);
+        assert_eq!(histogram.blue[0], 2);
+        assert_eq!(histogram.luminance[10], 1);
+        assert_eq!(histogram.luminance[76], 1);
+        assert_eq!(histogram.pixel_count(), 3);
+    }
+
+    #[test]
+    fn gray_pixels_count_towards_all_channels() {
+        let histogram = Histogram::from_data(&[7, 7, 200], 1);
+        for channel in HistogramChannel::ALL {
+            assert_eq!(histogram.channel(channel)[7], 2);
+            assert_eq!(histogram.channel(channel)[200], 1);
+        }
+        assert_eq!(histogram.stats(HistogramChannel::Red), Some((7, 200, 214.0 / 3.0)));
+        assert_eq!(Histogram::from_data(&[], 3).stats(HistogramChannel::Luminance), None);
+    }
+}
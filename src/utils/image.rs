@@ -1,69 +1,95 @@
-use fltk::{frame::Frame, image::RgbImage, prelude::*};
+use fltk::{draw, frame::Frame, image::{RgbImage, RgbScaling}, prelude::*};
 use std::{rc::Rc, cell::RefCell};
 use crate::state::ImageState;
+use crate::utils::viewport::Viewport;
+use crate::utils::transform::Transform;
 use crate::scientific::rendering::frame_renderer::FrameRenderer;
 
-pub const MENU_HEIGHT: i32 = 25;
+/// from this many screen pixels per image pixel up, pixels are drawn as sharp blocks
+const NEAREST_NEIGHBOUR_SCALE: f64 = 2.0;
 
 pub fn display_image_with_zoom(frame: &Rc<RefCell<Frame>>, image: &mut RgbImage, zoom: f32, state: &Rc<RefCell<ImageState>>) {
-    let frame_ref = frame.borrow();
-    let frame_w = frame_ref.w();
-    let frame_h = frame_ref.h();
-                
-    let (new_w, new_h) = scale_image_dimensions(
-        image.data_w(),
-        image.data_h(),
-        frame_w,
-        frame_h - MENU_HEIGHT,
-        zoom as f64
-    );
+    if let Ok(mut state_ref) = state.try_borrow_mut() {
+        let viewport = Viewport::of_frame(&frame.borrow(), image.data_w(), image.data_h(), zoom, state_ref.pan).clamped();
+        state_ref.zoom = viewport.zoom;
+        state_ref.pan = viewport.pan;
+    }
 
-    image.scale(new_w, new_h, true, true);
-    drop(frame_ref);
-    
-    // Only set up the frame draw, don't set_image directly
+    // the renderer scales whatever it draws itself, the frame just keeps the pixels
+    frame.borrow_mut().set_image(Some(image.clone()));
     FrameRenderer::setup_frame_draw(frame, state);
     frame.borrow_mut().redraw();
 }
 
-pub fn display_image(frame: &Rc<RefCell<Frame>>, img: &RgbImage, zoom: f32) {
-    let mut frame = frame.borrow_mut();
-    let frame_w = frame.w();
-    let frame_h = frame.h();
-    
-    let (new_w, new_h) = scale_image_dimensions(
-        img.data_w(),
-        img.data_h(),
-        frame_w,
-        frame_h - MENU_HEIGHT,
-        zoom as f64
-    );
-    
-    let mut scaled_img = img.clone();
-    scaled_img.scale(new_w, new_h, true, true);
-    frame.set_image(Some(scaled_img));
-    frame.redraw();
+/// `image` rotated, flipped or resized; keeps its colour depth
+pub fn transform_rgb_image(image: &RgbImage, transform: &Transform) -> Option<RgbImage> {
+    let (width, height) = (image.data_w() as u32, image.data_h() as u32);
+    let depth = image.depth();
+    let data = transform.apply(&image.to_rgb_data(), width, height, depth as usize);
+    let (new_w, new_h) = transform.output_size(width, height);
+    RgbImage::new(&data, new_w as i32, new_h as i32, depth).ok()
 }
 
-pub fn scale_image_dimensions(
-    image_w: i32, 
-    image_h: i32, 
-    frame_w: i32, 
-    frame_h: i32, 
-    zoom: f64
-) -> (i32, i32) {
-    let aspect_ratio = image_w as f64 / image_h as f64;
-    let frame_aspect = frame_w as f64 / frame_h as f64;
+/// draws the frame's image, or the open image if it has none, at the current zoom and pan
+pub fn draw_current_image(f: &Frame, state: &ImageState) {
+    match (f.image(), &state.image) {
+        (Some(shown), _) => draw_image_in_frame(f, shown.as_ref(), state.zoom, state.pan),
+        (None, Some(image)) => draw_image_in_frame(f, image, state.zoom, state.pan),
+        (None, None) => {}
+    }
+}
 
-    let (base_w, base_h) = if aspect_ratio > frame_aspect {
-        let new_w = frame_w;
-        let new_h = (frame_w as f64 / aspect_ratio) as i32;
-        (new_w, new_h)
-    } else {
-        let new_h = frame_h;
-        let new_w = (frame_h as f64 * aspect_ratio) as i32;
-        (new_w, new_h)
+/// clears the frame and draws `image` placed by zoom and pan. only the visible
+/// part is scaled, so deep zooms into large images stay cheap
+pub fn draw_image_in_frame(f: &Frame, image: &dyn ImageExt, zoom: f32, pan: (f64, f64)) {
+    draw::draw_rect_fill(f.x(), f.y(), f.w(), f.h(), f.color());
+
+    let (image_w, image_h) = (image.data_w(), image.data_h());
+    if image_w <= 0 || image_h <= 0 {
+        return;
+    }
+    let viewport = Viewport::of_frame(f, image_w, image_h, zoom, pan);
+    let (x, y) = viewport.origin();
+    let scale = viewport.scale();
+
+    // the block of image pixels that lands inside the frame
+    let (left, top, visible_w, visible_h) = viewport.visible_rect();
+    if visible_w == 0 || visible_h == 0 {
+        return;
+    }
+    let (right, bottom) = (left + visible_w, top + visible_h);
+
+    let depth = image.depth();
+    let channels = depth as usize;
+    let data = image.to_rgb_data();
+    let stride = image_w as usize * channels;
+    if data.len() < stride * image_h as usize {
+        return;
+    }
+    let row_len = (right - left) as usize * channels;
+    let mut visible = Vec::with_capacity(row_len * (bottom - top) as usize);
+    for row in top..bottom {
+        let start = row as usize * stride + left as usize * channels;
+        visible.extend_from_slice(&data[start..start + row_len]);
+    }
+    let mut visible = match RgbImage::new(&visible, right - left, bottom - top, depth) {
+        Ok(visible) => visible,
+        Err(_) => return,
     };
 
-    ((base_w as f64 * zoom) as i32, (base_h as f64 * zoom) as i32)
-}
\ No newline at end of file
+    let draw_x = (x + left as f64 * scale).round() as i32;
+    let draw_y = (y + top as f64 * scale).round() as i32;
+    let draw_w = ((x + right as f64 * scale).round() as i32 - draw_x).max(1);
+    let draw_h = ((y + bottom as f64 * scale).round() as i32 - draw_y).max(1);
+
+    RgbImage::set_scaling_algorithm(if scale >= NEAREST_NEIGHBOUR_SCALE {
+        RgbScaling::Nearest
+    } else {
+        RgbScaling::Bilinear
+    });
+    visible.scale(draw_w, draw_h, false, true);
+
+    draw::push_clip(f.x(), f.y(), f.w(), f.h());
+    visible.draw(draw_x, draw_y, draw_w, draw_h);
+    draw::pop_clip();
+}
@@ -1,36 +1,54 @@
 // src/utils/image_data.rs
 
 use fltk::{image::RgbImage, prelude::ImageExt};
+use crate::utils::raw_image::RawImage;
 
 pub struct ImageData {
     image: RgbImage,
+    raw: Option<RawImage>,
 }
 
 impl ImageData {
     pub fn new(image: RgbImage) -> Self {
-        Self { image }
+        Self { image, raw: None }
     }
 
-    pub fn get_intensity(&self, x: i32, y: i32) -> u8 {
+    /// measurements use `raw` when it matches the displayed image size
+    pub fn with_raw(image: RgbImage, raw: Option<RawImage>) -> Self {
+        let raw = raw.filter(|r| {
+            r.width as i32 == image.data_w() && r.height as i32 == image.data_h()
+        });
+        Self { image, raw }
+    }
+
+    pub fn get_intensity(&self, x: i32, y: i32) -> f64 {
         if x < 0 || y < 0 || x >= self.image.data_w() || y >= self.image.data_h() {
-            return 0;
+            return 0.0;
+        }
+
+        if let Some(raw) = &self.raw {
+            return raw.intensity(x, y);
         }
         
         let data = self.image.to_rgb_data();
         let idx = (y * self.image.data_w() + x) as usize * 3;
         
         if idx + 2 >= data.len() {
-            return 0;
+            return 0.0;
         }
         
         // Calculate intensity as average of RGB
-        ((data[idx] as u16 + data[idx + 1] as u16 + data[idx + 2] as u16) / 3) as u8
+        ((data[idx] as u16 + data[idx + 1] as u16 + data[idx + 2] as u16) / 3) as f64
     }
 
     pub fn get_image(&self) -> &RgbImage {
         &self.image
     }
 
+    pub fn get_raw(&self) -> Option<&RawImage> {
+        self.raw.as_ref()
+    }
+
     pub fn width(&self) -> i32 {
         self.image.data_w()
     }
@@ -38,4 +56,4 @@ impl ImageData {
     pub fn height(&self) -> i32 {
         self.image.data_h()
     }
-}
\ No newline at end of file
+}
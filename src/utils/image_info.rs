@@ -0,0 +1,142 @@
+// src/utils/image_info.rs
+
+use std::{fs, path::{Path, PathBuf}};
+use image::{ImageDecoder, ImageReader};
+use crate::utils::raw_image::{RawImage, RawPixels};
+
+/// what the file on disk says about the image, independent of any edits since
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileInfo {
+    pub path: PathBuf,
+    /// e.g. "Tiff", None when the contents weren't recognised
+    pub format: Option<String>,
+    pub file_size: u64,
+    pub dimensions: Option<(u32, u32)>,
+    /// colour type as stored, e.g. "L16" or "Rgba8"
+    pub color_type: Option<String>,
+    pub channels: Option<u8>,
+    pub bits_per_sample: Option<u16>,
+}
+
+impl FileInfo {
+    /// reads the header only, the pixels aren't decoded
+    pub fn read(path: &Path) -> FileInfo {
+        let mut info = FileInfo {
+            path: path.to_path_buf(),
+            format: None,
+            file_size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            dimensions: None,
+            color_type: None,
+            channels: None,
+            bits_per_sample: None,
+        };
+
+        let reader = match ImageReader::open(path).and_then(|r| r.with_guessed_format()) {
+            Ok(reader) => reader,
+            Err(e) => {
+                println!("Could not read {:?}: {}", path, e);
+                return info;
+            }
+        };
+        info.format = reader.format().map(|f| format!("{:?}", f));
+
+        match reader.into_decoder() {
+            Ok(decoder) => {
+                let color = decoder.original_color_type();
+                info.dimensions = Some(decoder.dimensions());
+                info.color_type = Some(format!("{:?}", color));
+                info.channels = Some(color.channel_count());
+                if color.channel_count() > 0 {
+                    info.bits_per_sample = Some(color.bits_per_pixel() / color.channel_count() as u16);
+                }
+            }
+            Err(e) => println!("Could not decode the header of {:?}: {}", path, e),
+        }
+        info
+    }
+}
+
+/// min/max/mean/standard deviation of one channel, in the image's own units
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChannelStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl ChannelStats {
+    fn from_values(values: impl Iterator<Item = f64>) -> Option<ChannelStats> {
+        let (mut count, mut sum, mut sum_sq) = (0usize, 0.0, 0.0);
+        let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for value in values {
+            count += 1;
+            sum += value;
+            sum_sq += value * value;
+            min = min.min(value);
+            max = max.max(value);
+        }
+        if count == 0 {
+            return None;
+        }
+        let mean = sum / count as f64;
+        let variance = (sum_sq / count as f64 - mean * mean).max(0.0);
+        Some(ChannelStats { min, max, mean, std_dev: variance.sqrt() })
+    }
+}
+
+/// statistics of every channel of `raw`, one entry for grayscale, red/green/blue for colour
+pub fn channel_stats(raw: &RawImage) -> Vec<ChannelStats> {
+    let channels = raw.channels.max(1);
+    (0..channels)
+        .filter_map(|channel| match &raw.pixels {
+            RawPixels::U8(data) => {
+                ChannelStats::from_values(data.iter().skip(channel).step_by(channels).map(|&v| v as f64))
+            }
+            RawPixels::U16(data) => {
+                ChannelStats::from_values(data.iter().skip(channel).step_by(channels).map(|&v| v as f64))
+            }
+            RawPixels::F32(data) => {
+                ChannelStats::from_values(data.iter().skip(channel).step_by(channels).map(|&v| v as f64))
+            }
+        })
+        .collect()
+}
+
+/// 1536 -> "1.5 KB"
+pub fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["bytes", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_are_per_channel() {
+        let raw = RawImage::new(2, 1, 3, RawPixels::U16(vec![0, 10, 100, 4, 10, 300]));
+        let stats = channel_stats(&raw);
+        assert_eq!(stats.len(), 3);
+        assert_eq!((stats[0].min, stats[0].max, stats[0].mean, stats[0].std_dev), (0.0, 4.0, 2.0, 2.0));
+        assert_eq!(stats[1].std_dev, 0.0);
+        assert_eq!(stats[2].mean, 200.0);
+    }
+
+    #[test]
+    fn file_sizes_use_binary_units() {
+        assert_eq!(format_file_size(900), "900 bytes");
+        assert_eq!(format_file_size(1536), "1.5 KB");
+        assert_eq!(format_file_size(3 * 1024 * 1024), "3.0 MB");
+    }
+}
@@ -0,0 +1,165 @@
+// src/utils/image_math.rs
+
+use crate::utils::raw_image::{RawImage, RawPixels};
+
+/// pixel-by-pixel operations between two images of the same size
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageOperation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Min,
+    Max,
+    Difference,
+    And,
+    Or,
+    Xor,
+    Average,
+}
+
+impl ImageOperation {
+    pub const ALL: [ImageOperation; 11] = [
+        ImageOperation::Add,
+        ImageOperation::Subtract,
+        ImageOperation::Multiply,
+        ImageOperation::Divide,
+        ImageOperation::Min,
+        ImageOperation::Max,
+        ImageOperation::Difference,
+        ImageOperation::And,
+        ImageOperation::Or,
+        ImageOperation::Xor,
+        ImageOperation::Average,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImageOperation::Add => "Add",
+            ImageOperation::Subtract => "Subtract",
+            ImageOperation::Multiply => "Multiply",
+            ImageOperation::Divide => "Divide",
+            ImageOperation::Min => "Min",
+            ImageOperation::Max => "Max",
+            ImageOperation::Difference => "Difference",
+            ImageOperation::And => "AND",
+            ImageOperation::Or => "OR",
+            ImageOperation::Xor => "XOR",
+            ImageOperation::Average => "Average",
+        }
+    }
+
+    /// one output value. division by zero gives 0 rather than infinity so ratio images
+    /// stay displayable; the bitwise operations work on the integer part
+    pub fn apply(&self, a: f64, b: f64) -> f64 {
+        match self {
+            ImageOperation::Add => a + b,
+            ImageOperation::Subtract => a - b,
+            ImageOperation::Multiply => a * b,
+            ImageOperation::Divide => if b == 0.0 { 0.0 } else { a / b },
+            ImageOperation::Min => a.min(b),
+            ImageOperation::Max => a.max(b),
+            ImageOperation::Difference => (a - b).abs(),
+            ImageOperation::And => ((a as i64) & (b as i64)) as f64,
+            ImageOperation::Or => ((a as i64) | (b as i64)) as f64,
+            ImageOperation::Xor => ((a as i64) ^ (b as i64)) as f64,
+            ImageOperation::Average => (a + b) / 2.0,
+        }
+    }
+}
+
+/// how the result is stored
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResultDepth {
+    /// 32-bit float, nothing is clipped
+    Float,
+    /// 0-255, values outside are clipped
+    Eight,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImageMathError {
+    SizeMismatch((u32, u32), (u32, u32)),
+}
+
+impl std::fmt::Display for ImageMathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageMathError::SizeMismatch(a, b) => {
+                write!(f, "Images differ in size: {} x {} and {} x {}", a.0, a.1, b.0, b.1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImageMathError {}
+
+/// `a <op> b` at full bit depth. a grayscale image combined with a colour one is
+/// applied to each colour channel
+pub fn calculate(a: &RawImage, b: &RawImage, operation: ImageOperation, depth: ResultDepth) -> Result<RawImage, ImageMathError> {
+    if (a.width, a.height) != (b.width, b.height) {
+        return Err(ImageMathError::SizeMismatch((a.width, a.height), (b.width, b.height)));
+    }
+
+    let channels = a.channels.max(b.channels);
+    let (width, height) = (a.width as i32, a.height as i32);
+    let mut values = Vec::with_capacity(width as usize * height as usize * channels);
+    for y in 0..height {
+        for x in 0..width {
+            for channel in 0..channels {
+                let first = a.value(x, y, channel).unwrap_or(0.0);
+                let second = b.value(x, y, channel).unwrap_or(0.0);
+                values.push(operation.apply(first, second));
+            }
+        }
+    }
+
+    let pixels = match depth {
+        ResultDepth::Float => RawPixels::F32(values.into_iter().map(|v| v as f32).collect()),
+        ResultDepth::Eight => RawPixels::U8(values.into_iter().map(|v| v.round().clamp(0.0, 255.0) as u8).collect()),
+    };
+    Ok(RawImage::new(a.width, a.height, channels, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(values: Vec<u16>) -> RawImage {
+        RawImage::new(values.len() as u32, 1, 1, RawPixels::U16(values))
+    }
+
+    #[test]
+    fn operations_work_on_raw_values() {
+        let a = gray(vec![1000, 12, 300]);
+        let b = gray(vec![250, 10, 0]);
+        let ratio = calculate(&a, &b, ImageOperation::Divide, ResultDepth::Float).unwrap();
+        assert_eq!(ratio.pixel(0, 0), vec![4.0]);
+        assert_eq!(ratio.pixel(2, 0), vec![0.0]);
+
+        let difference = calculate(&b, &a, ImageOperation::Difference, ResultDepth::Float).unwrap();
+        assert_eq!(difference.pixel(0, 0), vec![750.0]);
+        assert_eq!(ImageOperation::Xor.apply(12.0, 10.0), 6.0);
+    }
+
+    #[test]
+    fn eight_bit_results_are_clipped() {
+        let a = gray(vec![1000, 12]);
+        let b = gray(vec![250, 20]);
+        let result = calculate(&a, &b, ImageOperation::Subtract, ResultDepth::Eight).unwrap();
+        assert_eq!(result.bit_depth(), 8);
+        assert_eq!(result.pixel(0, 0), vec![255.0]);
+        assert_eq!(result.pixel(1, 0), vec![0.0]);
+    }
+
+    #[test]
+    fn grayscale_is_applied_to_every_colour_channel() {
+        let color = RawImage::new(1, 1, 3, RawPixels::U8(vec![10, 20, 30]));
+        let result = calculate(&color, &gray(vec![5]), ImageOperation::Add, ResultDepth::Eight).unwrap();
+        assert_eq!(result.channels, 3);
+        assert_eq!(result.pixel(0, 0), vec![15.0, 25.0, 35.0]);
+
+        let small = gray(vec![1, 2]);
+        assert!(calculate(&color, &small, ImageOperation::Add, ResultDepth::Float).is_err());
+    }
+}
@@ -1,10 +1,25 @@
 // src/utils/mod.rs
 
+#[cfg(feature = "gui")]
 pub mod image; 
 pub mod template_utils;
+#[cfg(feature = "gui")]
 pub mod image_data;
+pub mod raw_image;
+pub mod config;
+pub mod batch;
+pub mod thumbnails;
+pub mod viewport;
+pub mod transform;
+#[cfg(feature = "gui")]
+pub mod histogram;
+pub mod image_info;
+pub mod image_math;
 
+#[cfg(feature = "gui")]
 pub use image::*;
+pub use viewport::Viewport;
 
 pub const MENU_HEIGHT: i32 = 25;
+pub const STATUS_BAR_HEIGHT: i32 = 22;
 
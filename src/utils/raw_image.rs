@@ -0,0 +1,339 @@
+// src/utils/raw_image.rs
+
+#[cfg(feature = "gui")]
+use fltk::{image::RgbImage, enums::ColorDepth, prelude::ImageExt};
+use image::DynamicImage;
+use crate::utils::transform::Transform;
+
+/// pixel storage at the bit depth the file was loaded with
+#[derive(Clone, Debug)]
+pub enum RawPixels {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    F32(Vec<f32>),
+}
+
+/// full-precision copy of the loaded image. measurements read from here,
+/// the 8-bit RgbImage in ImageState is only what gets drawn on screen
+#[derive(Clone, Debug)]
+pub struct RawImage {
+    pub width: u32,
+    pub height: u32,
+    /// 1 for grayscale, 3 for colour (alpha is dropped on load)
+    pub channels: usize,
+    pub pixels: RawPixels,
+    /// values at or below display_min render black, at or above display_max white
+    pub display_min: f64,
+    pub display_max: f64,
+}
+
+impl RawImage {
+    pub fn new(width: u32, height: u32, channels: usize, pixels: RawPixels) -> Self {
+        let mut raw = Self {
+            width,
+            height,
+            channels,
+            pixels,
+            display_min: 0.0,
+            display_max: 255.0,
+        };
+        raw.reset_window();
+        raw
+    }
+
+    pub fn from_dynamic(img: &DynamicImage) -> Self {
+        let (width, height) = (img.width(), img.height());
+        let (channels, pixels) = match img {
+            DynamicImage::ImageLuma8(buf) => (1, RawPixels::U8(buf.as_raw().clone())),
+            DynamicImage::ImageLumaA8(_) => (1, RawPixels::U8(img.to_luma8().into_raw())),
+            DynamicImage::ImageLuma16(buf) => (1, RawPixels::U16(buf.as_raw().clone())),
+            DynamicImage::ImageLumaA16(_) => (1, RawPixels::U16(img.to_luma16().into_raw())),
+            DynamicImage::ImageRgb16(buf) => (3, RawPixels::U16(buf.as_raw().clone())),
+            DynamicImage::ImageRgba16(_) => (3, RawPixels::U16(img.to_rgb16().into_raw())),
+            DynamicImage::ImageRgb32F(buf) => (3, RawPixels::F32(buf.as_raw().clone())),
+            DynamicImage::ImageRgba32F(_) => (3, RawPixels::F32(img.to_rgb32f().into_raw())),
+            _ => (3, RawPixels::U8(img.to_rgb8().into_raw())),
+        };
+
+        Self::new(width, height, channels, pixels)
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn from_rgb_image(image: &RgbImage) -> Self {
+        Self {
+            width: image.data_w() as u32,
+            height: image.data_h() as u32,
+            channels: 3,
+            pixels: RawPixels::U8(image.to_rgb_data()),
+            display_min: 0.0,
+            display_max: 255.0,
+        }
+    }
+
+    pub fn bit_depth(&self) -> u8 {
+        match self.pixels {
+            RawPixels::U8(_) => 8,
+            RawPixels::U16(_) => 16,
+            RawPixels::F32(_) => 32,
+        }
+    }
+
+    pub fn is_high_bit_depth(&self) -> bool {
+        !matches!(self.pixels, RawPixels::U8(_))
+    }
+
+    /// the range the pixel type can hold; floats use the data range
+    pub fn native_range(&self) -> (f64, f64) {
+        match self.pixels {
+            RawPixels::U8(_) => (0.0, u8::MAX as f64),
+            RawPixels::U16(_) => (0.0, u16::MAX as f64),
+            RawPixels::F32(_) => self.data_range(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.pixels {
+            RawPixels::U8(data) => data.len(),
+            RawPixels::U16(data) => data.len(),
+            RawPixels::F32(data) => data.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn size_bytes(&self) -> usize {
+        match &self.pixels {
+            RawPixels::U8(data) => data.len(),
+            RawPixels::U16(data) => data.len() * 2,
+            RawPixels::F32(data) => data.len() * 4,
+        }
+    }
+
+    fn sample(&self, idx: usize) -> f64 {
+        match &self.pixels {
+            RawPixels::U8(data) => data[idx] as f64,
+            RawPixels::U16(data) => data[idx] as f64,
+            RawPixels::F32(data) => data[idx] as f64,
+        }
+    }
+
+    /// raw value of one channel at (x, y); grayscale images ignore `channel`
+    pub fn value(&self, x: i32, y: i32, channel: usize) -> Option<f64> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return None;
+        }
+        let channel = channel.min(self.channels - 1);
+        let idx = (y as usize * self.width as usize + x as usize) * self.channels + channel;
+        if idx < self.len() {
+            Some(self.sample(idx))
+        } else {
+            None
+        }
+    }
+
+    /// all channel values at (x, y)
+    pub fn pixel(&self, x: i32, y: i32) -> Vec<f64> {
+        (0..self.channels)
+            .filter_map(|c| self.value(x, y, c))
+            .collect()
+    }
+
+    /// average of the channels, same as ImageData does for 8-bit images
+    pub fn intensity(&self, x: i32, y: i32) -> f64 {
+        let values = self.pixel(x, y);
+        if values.is_empty() {
+            0.0
+        } else {
+            values.iter().sum::<f64>() / values.len() as f64
+        }
+    }
+
+    /// per-pixel intensities in row-major order
+    pub fn intensities(&self) -> Vec<f64> {
+        let count = (self.width * self.height) as usize;
+        (0..count)
+            .map(|i| {
+                let base = i * self.channels;
+                (0..self.channels).map(|c| self.sample(base + c)).sum::<f64>() / self.channels as f64
+            })
+            .collect()
+    }
+
+    pub fn data_range(&self) -> (f64, f64) {
+        let mut min = f64::MAX;
+        let mut max = f64::MIN;
+        for i in 0..self.len() {
+            let v = self.sample(i);
+            if v.is_finite() {
+                min = min.min(v);
+                max = max.max(v);
+            }
+        }
+        if min > max {
+            (0.0, 1.0)
+        } else {
+            (min, max)
+        }
+    }
+
+    /// 8-bit keeps the identity mapping, deeper images start on the data range
+    pub fn reset_window(&mut self) {
+        let (min, max) = if self.is_high_bit_depth() {
+            self.data_range()
+        } else {
+            (0.0, 255.0)
+        };
+        self.set_display_range(min, max);
+    }
+
+    pub fn auto_window(&mut self) {
+        let (min, max) = self.data_range();
+        self.set_display_range(min, max);
+    }
+
+    pub fn set_display_range(&mut self, min: f64, max: f64) {
+        self.display_min = min.min(max);
+        self.display_max = if max > min { max } else { min + 1.0 };
+    }
+
+    fn window_value(&self, v: f64) -> u8 {
+        let span = self.display_max - self.display_min;
+        let scaled = (v - self.display_min) / span * 255.0;
+        if scaled.is_nan() {
+            0
+        } else {
+            scaled.round().clamp(0.0, 255.0) as u8
+        }
+    }
+
+    /// 8-bit RGB bytes for display, windowed by display_min/display_max
+    pub fn to_display_rgb(&self) -> Vec<u8> {
+        let count = (self.width * self.height) as usize;
+        let mut out = Vec::with_capacity(count * 3);
+        for i in 0..count {
+            let base = i * self.channels;
+            if self.channels == 1 {
+                let v = self.window_value(self.sample(base));
+                out.extend_from_slice(&[v, v, v]);
+            } else {
+                for c in 0..3 {
+                    out.push(self.window_value(self.sample(base + c)));
+                }
+            }
+        }
+        out
+    }
+
+    /// copy of a rectangle, clipped to the image. keeps the display range
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Option<RawImage> {
+        let width = width.min(self.width.saturating_sub(x));
+        let height = height.min(self.height.saturating_sub(y));
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        fn rows<T: Copy>(data: &[T], stride: usize, x: usize, y: usize, w: usize, h: usize) -> Vec<T> {
+            (y..y + h).flat_map(|row| data[row * stride + x..row * stride + x + w].iter().copied()).collect()
+        }
+        let (stride, xs, ws) = (self.width as usize * self.channels, x as usize * self.channels, width as usize * self.channels);
+        let pixels = match &self.pixels {
+            RawPixels::U8(data) => RawPixels::U8(rows(data, stride, xs, y as usize, ws, height as usize)),
+            RawPixels::U16(data) => RawPixels::U16(rows(data, stride, xs, y as usize, ws, height as usize)),
+            RawPixels::F32(data) => RawPixels::F32(rows(data, stride, xs, y as usize, ws, height as usize)),
+        };
+
+        Some(RawImage {
+            width,
+            height,
+            channels: self.channels,
+            pixels,
+            display_min: self.display_min,
+            display_max: self.display_max,
+        })
+    }
+
+    /// the image rotated, flipped or resized, at full precision. keeps the display range
+    pub fn transformed(&self, transform: &Transform) -> RawImage {
+        let (width, height) = transform.output_size(self.width, self.height);
+        let pixels = match &self.pixels {
+            RawPixels::U8(data) => RawPixels::U8(transform.apply(data, self.width, self.height, self.channels)),
+            RawPixels::U16(data) => RawPixels::U16(transform.apply(data, self.width, self.height, self.channels)),
+            RawPixels::F32(data) => RawPixels::F32(transform.apply(data, self.width, self.height, self.channels)),
+        };
+
+        RawImage {
+            width,
+            height,
+            channels: self.channels,
+            pixels,
+            display_min: self.display_min,
+            display_max: self.display_max,
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn to_rgb_image(&self) -> Option<RgbImage> {
+        RgbImage::new(
+            &self.to_display_rgb(),
+            self.width as i32,
+            self.height as i32,
+            ColorDepth::Rgb8,
+        ).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray16(values: Vec<u16>, width: u32) -> RawImage {
+        let height = values.len() as u32 / width;
+        RawImage::new(width, height, 1, RawPixels::U16(values))
+    }
+
+    #[test]
+    fn test_values_keep_full_precision() {
+        let raw = gray16(vec![0, 1000, 40000, 65535], 2);
+        assert_eq!(raw.value(1, 0, 0), Some(1000.0));
+        assert_eq!(raw.intensity(0, 1), 40000.0);
+        assert_eq!(raw.value(2, 0, 0), None);
+        assert_eq!(raw.bit_depth(), 16);
+    }
+
+    #[test]
+    fn test_display_window() {
+        let mut raw = gray16(vec![100, 200, 300, 400], 2);
+        assert_eq!((raw.display_min, raw.display_max), (100.0, 400.0));
+
+        let rgb = raw.to_display_rgb();
+        assert_eq!(&rgb[0..3], &[0, 0, 0]);
+        assert_eq!(&rgb[9..12], &[255, 255, 255]);
+
+        raw.set_display_range(200.0, 300.0);
+        let rgb = raw.to_display_rgb();
+        assert_eq!(rgb[0], 0);
+        assert_eq!(rgb[9], 255);
+    }
+
+    #[test]
+    fn test_crop_clips_to_image() {
+        let raw = gray16((0..12).collect(), 4);
+        let cropped = raw.crop(2, 1, 5, 5).unwrap();
+        assert_eq!((cropped.width, cropped.height), (2, 2));
+        assert_eq!(cropped.value(0, 0, 0), Some(6.0));
+        assert_eq!(cropped.value(1, 1, 0), Some(11.0));
+        assert!(raw.crop(4, 0, 1, 1).is_none());
+    }
+
+    #[test]
+    fn test_transform_keeps_precision() {
+        let raw = gray16(vec![1000, 40000, 65535, 7], 2);
+        let rotated = raw.transformed(&Transform::Rotate90);
+        assert_eq!((rotated.width, rotated.height), (2, 2));
+        assert_eq!(rotated.value(0, 0, 0), Some(65535.0));
+        assert_eq!(rotated.value(1, 0, 0), Some(1000.0));
+        assert_eq!((rotated.display_min, rotated.display_max), (raw.display_min, raw.display_max));
+    }
+}
@@ -0,0 +1,78 @@
+// src/utils/thumbnails.rs
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{atomic::{AtomicUsize, Ordering}, mpsc, Arc},
+    thread,
+    time::UNIX_EPOCH,
+};
+use image::RgbImage;
+use crate::utils::config::cache_dir;
+
+/// longest side of a thumbnail in pixels
+pub const THUMBNAIL_SIZE: u32 = 96;
+
+// one file per source image, named after its path, size and mtime so edits invalidate it
+fn cache_path(path: &Path) -> Option<PathBuf> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+
+    let mut hasher = DefaultHasher::new();
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf()).hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    modified.as_nanos().hash(&mut hasher);
+    THUMBNAIL_SIZE.hash(&mut hasher);
+
+    let dir = cache_dir()?.join("thumbnails");
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(format!("{:016x}.png", hasher.finish())))
+}
+
+/// the cached thumbnail, or a new one written to the cache
+pub fn load_or_create_thumbnail(path: &Path) -> Option<RgbImage> {
+    let cached = cache_path(path);
+    if let Some(cached) = &cached {
+        if let Ok(thumbnail) = image::open(cached) {
+            return Some(thumbnail.to_rgb8());
+        }
+    }
+
+    let thumbnail = match image::open(path) {
+        Ok(img) => img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8(),
+        Err(e) => {
+            println!("Failed to make thumbnail for {:?}: {}", path, e);
+            return None;
+        }
+    };
+    if let Some(cached) = &cached {
+        if let Err(e) = thumbnail.save(cached) {
+            println!("Failed to cache thumbnail {:?}: {}", cached, e);
+        }
+    }
+    Some(thumbnail)
+}
+
+/// makes thumbnails for `files` on a worker thread, sending (index, thumbnail) as each is ready.
+/// the job stops early once `generation` no longer equals `job`
+pub fn spawn_thumbnail_job(
+    files: Vec<PathBuf>,
+    generation: Arc<AtomicUsize>,
+    job: usize,
+) -> mpsc::Receiver<(usize, RgbImage)> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for (index, path) in files.iter().enumerate() {
+            if generation.load(Ordering::Relaxed) != job {
+                return;
+            }
+            if let Some(thumbnail) = load_or_create_thumbnail(path) {
+                if sender.send((index, thumbnail)).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    receiver
+}
@@ -0,0 +1,396 @@
+// src/utils/transform.rs
+
+use serde::{Deserialize, Serialize};
+
+/// how pixels are interpolated when an image is resampled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResampleFilter {
+    Nearest,
+    Bilinear,
+    Lanczos,
+}
+
+impl ResampleFilter {
+    pub const ALL: [ResampleFilter; 3] = [ResampleFilter::Nearest, ResampleFilter::Bilinear, ResampleFilter::Lanczos];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResampleFilter::Nearest => "Nearest Neighbour",
+            ResampleFilter::Bilinear => "Bilinear",
+            ResampleFilter::Lanczos => "Lanczos",
+        }
+    }
+
+    // kernel radius in source pixels at 1:1
+    fn support(&self) -> f64 {
+        match self {
+            ResampleFilter::Nearest => 0.5,
+            ResampleFilter::Bilinear => 1.0,
+            ResampleFilter::Lanczos => 3.0,
+        }
+    }
+
+    fn weight(&self, x: f64) -> f64 {
+        let x = x.abs();
+        match self {
+            ResampleFilter::Nearest => if x <= 0.5 { 1.0 } else { 0.0 },
+            ResampleFilter::Bilinear => (1.0 - x).max(0.0),
+            ResampleFilter::Lanczos => {
+                if x < 1e-9 {
+                    1.0
+                } else if x < 3.0 {
+                    let px = std::f64::consts::PI * x;
+                    3.0 * px.sin() * (px / 3.0).sin() / (px * px)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// a geometric change of the whole image. point coordinates are continuous,
+/// with pixel (x, y) covering [x, x + 1) × [y, y + 1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transform {
+    /// 90° clockwise
+    Rotate90,
+    Rotate180,
+    /// 90° counter-clockwise
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    /// clockwise by `degrees` about the centre. `expand` grows the canvas to hold
+    /// the whole rotated image, otherwise the size stays and the corners are cut off
+    Rotate { degrees: f64, expand: bool, filter: ResampleFilter },
+    Resize { width: u32, height: u32, filter: ResampleFilter },
+}
+
+impl Transform {
+    /// history label
+    pub fn label(&self) -> String {
+        match self {
+            Transform::Rotate90 => "Rotate 90° Right".to_string(),
+            Transform::Rotate180 => "Rotate 180°".to_string(),
+            Transform::Rotate270 => "Rotate 90° Left".to_string(),
+            Transform::FlipHorizontal => "Flip Horizontal".to_string(),
+            Transform::FlipVertical => "Flip Vertical".to_string(),
+            Transform::Rotate { degrees, .. } => format!("Rotate {:.1}°", degrees),
+            Transform::Resize { width, height, .. } => format!("Resize to {}x{}", width, height),
+        }
+    }
+
+    /// size of the result for a `width` x `height` image
+    pub fn output_size(&self, width: u32, height: u32) -> (u32, u32) {
+        match *self {
+            Transform::Rotate90 | Transform::Rotate270 => (height, width),
+            Transform::Rotate180 | Transform::FlipHorizontal | Transform::FlipVertical => (width, height),
+            Transform::Rotate { degrees, expand: true, .. } => {
+                let (sin, cos) = degrees.to_radians().sin_cos();
+                let (sin, cos) = (sin.abs(), cos.abs());
+                // rounded so that multiples of 90° don't pick up a pixel of float error
+                let w = (width as f64 * cos + height as f64 * sin).round().max(1.0) as u32;
+                let h = (width as f64 * sin + height as f64 * cos).round().max(1.0) as u32;
+                (w, h)
+            }
+            Transform::Rotate { expand: false, .. } => (width, height),
+            Transform::Resize { width, height, .. } => (width.max(1), height.max(1)),
+        }
+    }
+
+    /// where a point of a `width` x `height` image ends up
+    pub fn map_point(&self, width: u32, height: u32, x: f64, y: f64) -> (f64, f64) {
+        let (w, h) = (width as f64, height as f64);
+        match *self {
+            Transform::Rotate90 => (h - y, x),
+            Transform::Rotate180 => (w - x, h - y),
+            Transform::Rotate270 => (y, w - x),
+            Transform::FlipHorizontal => (w - x, y),
+            Transform::FlipVertical => (x, h - y),
+            Transform::Rotate { degrees, .. } => {
+                let (out_w, out_h) = self.output_size(width, height);
+                let (sin, cos) = degrees.to_radians().sin_cos();
+                let (dx, dy) = (x - w / 2.0, y - h / 2.0);
+                // y points down, so this turns clockwise on screen
+                (out_w as f64 / 2.0 + dx * cos - dy * sin, out_h as f64 / 2.0 + dx * sin + dy * cos)
+            }
+            Transform::Resize { .. } => {
+                let (sx, sy) = self.scale(width, height);
+                (x * sx, y * sy)
+            }
+        }
+    }
+
+    /// the pixel that pixel (x, y) lands in; can be outside the result when a
+    /// rotation cuts the corners off
+    pub fn map_pixel(&self, width: u32, height: u32, x: i32, y: i32) -> (i32, i32) {
+        let (x, y) = self.map_point(width, height, x as f64 + 0.5, y as f64 + 0.5);
+        (x.floor() as i32, y.floor() as i32)
+    }
+
+    /// how much horizontal and vertical lengths are stretched; rotations and flips keep them
+    pub fn scale(&self, width: u32, height: u32) -> (f64, f64) {
+        match *self {
+            Transform::Resize { .. } => {
+                let (out_w, out_h) = self.output_size(width, height);
+                (out_w as f64 / width.max(1) as f64, out_h as f64 / height.max(1) as f64)
+            }
+            _ => (1.0, 1.0),
+        }
+    }
+
+    /// applies the transform to interleaved pixels with `channels` samples each.
+    /// areas a rotation uncovers are filled with zeros
+    pub fn apply<T: Sample>(&self, data: &[T], width: u32, height: u32, channels: usize) -> Vec<T> {
+        let (out_w, out_h) = self.output_size(width, height);
+        if data.len() < width as usize * height as usize * channels || width == 0 || height == 0 {
+            return vec![T::default(); out_w as usize * out_h as usize * channels];
+        }
+        match *self {
+            Transform::Rotate { degrees, filter, .. } => rotate(data, width, height, channels, degrees, filter, (out_w, out_h)),
+            Transform::Resize { filter, .. } => {
+                let columns = resample_axis(data, width, height, channels, out_w, filter, true);
+                resample_axis(&columns, out_w, height, channels, out_h, filter, false)
+            }
+            _ => {
+                // lossless: every output pixel is a copy of exactly one source pixel
+                let (w, h) = (width as usize, height as usize);
+                let mut out = Vec::with_capacity(out_w as usize * out_h as usize * channels);
+                for oy in 0..out_h as usize {
+                    for ox in 0..out_w as usize {
+                        let (sx, sy) = match self {
+                            Transform::Rotate90 => (oy, h - 1 - ox),
+                            Transform::Rotate180 => (w - 1 - ox, h - 1 - oy),
+                            Transform::Rotate270 => (w - 1 - oy, ox),
+                            Transform::FlipHorizontal => (w - 1 - ox, oy),
+                            _ => (ox, h - 1 - oy),
+                        };
+                        let start = (sy * w + sx) * channels;
+                        out.extend_from_slice(&data[start..start + channels]);
+                    }
+                }
+                out
+            }
+        }
+    }
+}
+
+/// a pixel sample type that can be interpolated
+pub trait Sample: Copy + Default {
+    fn to_f64(self) -> f64;
+    /// rounds and clamps to the type's range
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Sample for u8 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(value: f64) -> Self {
+        value.round().clamp(0.0, u8::MAX as f64) as u8
+    }
+}
+
+impl Sample for u16 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(value: f64) -> Self {
+        value.round().clamp(0.0, u16::MAX as f64) as u16
+    }
+}
+
+impl Sample for f32 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+// normalised kernel weights for the source pixels around `centre` (in pixel-index space),
+// clipped to 0..len. `stretch` widens the kernel when shrinking so it averages instead of aliasing
+fn weights(filter: ResampleFilter, centre: f64, len: u32, stretch: f64) -> (usize, Vec<f64>) {
+    if filter == ResampleFilter::Nearest {
+        let index = (centre + 0.5).floor().clamp(0.0, len as f64 - 1.0) as usize;
+        return (index, vec![1.0]);
+    }
+    let support = filter.support() * stretch;
+    let first = ((centre - support).floor() as i64 + 1).max(0) as usize;
+    let last = ((centre + support).ceil() as i64 - 1).min(len as i64 - 1).max(first as i64) as usize;
+    let mut weights: Vec<f64> = (first..=last).map(|i| filter.weight((i as f64 - centre) / stretch)).collect();
+    let total: f64 = weights.iter().sum();
+    if total.abs() > 1e-12 {
+        weights.iter_mut().for_each(|w| *w /= total);
+    } else {
+        // centre between taps of a kernel with zeros there; fall back to the closest pixel
+        let index = (centre.round().clamp(0.0, len as f64 - 1.0) as usize).max(first).min(last);
+        weights = (first..=last).map(|i| if i == index { 1.0 } else { 0.0 }).collect();
+    }
+    (first, weights)
+}
+
+// resamples rows (`horizontal`) or columns to `out_len` pixels
+fn resample_axis<T: Sample>(
+    data: &[T],
+    width: u32,
+    height: u32,
+    channels: usize,
+    out_len: u32,
+    filter: ResampleFilter,
+    horizontal: bool,
+) -> Vec<T> {
+    let in_len = if horizontal { width } else { height };
+    let scale = out_len as f64 / in_len as f64;
+    let stretch = (1.0 / scale).max(1.0);
+    let taps: Vec<(usize, Vec<f64>)> = (0..out_len)
+        .map(|o| weights(filter, (o as f64 + 0.5) / scale - 0.5, in_len, stretch))
+        .collect();
+
+    let (out_w, out_h) = if horizontal { (out_len, height) } else { (width, out_len) };
+    let mut out = Vec::with_capacity(out_w as usize * out_h as usize * channels);
+    let mut sums = vec![0.0; channels];
+    for y in 0..out_h as usize {
+        for x in 0..out_w as usize {
+            let (first, kernel) = &taps[if horizontal { x } else { y }];
+            sums.iter_mut().for_each(|s| *s = 0.0);
+            for (k, weight) in kernel.iter().enumerate() {
+                let (sx, sy) = if horizontal { (first + k, y) } else { (x, first + k) };
+                let start = (sy * width as usize + sx) * channels;
+                for (c, sum) in sums.iter_mut().enumerate() {
+                    *sum += data[start + c].to_f64() * weight;
+                }
+            }
+            out.extend(sums.iter().map(|&s| T::from_f64(s)));
+        }
+    }
+    out
+}
+
+fn rotate<T: Sample>(
+    data: &[T],
+    width: u32,
+    height: u32,
+    channels: usize,
+    degrees: f64,
+    filter: ResampleFilter,
+    (out_w, out_h): (u32, u32),
+) -> Vec<T> {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (centre_x, centre_y) = (width as f64 / 2.0, height as f64 / 2.0);
+    let (out_centre_x, out_centre_y) = (out_w as f64 / 2.0, out_h as f64 / 2.0);
+
+    let mut out = vec![T::default(); out_w as usize * out_h as usize * channels];
+    let mut sums = vec![0.0; channels];
+    for oy in 0..out_h as usize {
+        for ox in 0..out_w as usize {
+            // inverse rotation of the output pixel's centre
+            let dx = ox as f64 + 0.5 - out_centre_x;
+            let dy = oy as f64 + 0.5 - out_centre_y;
+            let sx = centre_x + dx * cos + dy * sin;
+            let sy = centre_y - dx * sin + dy * cos;
+            if sx < 0.0 || sy < 0.0 || sx >= width as f64 || sy >= height as f64 {
+                continue;
+            }
+
+            let (first_x, kernel_x) = weights(filter, sx - 0.5, width, 1.0);
+            let (first_y, kernel_y) = weights(filter, sy - 0.5, height, 1.0);
+            sums.iter_mut().for_each(|s| *s = 0.0);
+            for (ky, wy) in kernel_y.iter().enumerate() {
+                for (kx, wx) in kernel_x.iter().enumerate() {
+                    let start = ((first_y + ky) * width as usize + first_x + kx) * channels;
+                    for (c, sum) in sums.iter_mut().enumerate() {
+                        *sum += data[start + c].to_f64() * wx * wy;
+                    }
+                }
+            }
+            let start = (oy * out_w as usize + ox) * channels;
+            for (value, sum) in out[start..start + channels].iter_mut().zip(&sums) {
+                *value = T::from_f64(*sum);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3x2 grayscale, values are the pixel index
+    const IMAGE: [u8; 6] = [0, 1, 2, 3, 4, 5];
+
+    #[test]
+    fn quarter_turns_and_flips_move_pixels() {
+        assert_eq!(Transform::Rotate90.apply(&IMAGE, 3, 2, 1), vec![3, 0, 4, 1, 5, 2]);
+        assert_eq!(Transform::Rotate270.apply(&IMAGE, 3, 2, 1), vec![2, 5, 1, 4, 0, 3]);
+        assert_eq!(Transform::Rotate180.apply(&IMAGE, 3, 2, 1), vec![5, 4, 3, 2, 1, 0]);
+        assert_eq!(Transform::FlipHorizontal.apply(&IMAGE, 3, 2, 1), vec![2, 1, 0, 5, 4, 3]);
+        assert_eq!(Transform::FlipVertical.apply(&IMAGE, 3, 2, 1), vec![3, 4, 5, 0, 1, 2]);
+    }
+
+    #[test]
+    fn points_follow_their_pixels() {
+        for transform in [
+            Transform::Rotate90,
+            Transform::Rotate180,
+            Transform::Rotate270,
+            Transform::FlipHorizontal,
+            Transform::FlipVertical,
+        ] {
+            let (out_w, _) = transform.output_size(3, 2);
+            let out = transform.apply(&IMAGE, 3, 2, 1);
+            for y in 0..2 {
+                for x in 0..3 {
+                    let (ox, oy) = transform.map_pixel(3, 2, x, y);
+                    assert_eq!(out[(oy * out_w as i32 + ox) as usize], IMAGE[(y * 3 + x) as usize], "{:?}", transform);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn arbitrary_rotation_by_a_quarter_turn_matches_rotate90() {
+        let rotation = Transform::Rotate { degrees: 90.0, expand: true, filter: ResampleFilter::Nearest };
+        assert_eq!(rotation.output_size(3, 2), (2, 3));
+        assert_eq!(rotation.apply(&IMAGE, 3, 2, 1), Transform::Rotate90.apply(&IMAGE, 3, 2, 1));
+        let (x, y) = rotation.map_point(3, 2, 0.5, 0.5);
+        let (x90, y90) = Transform::Rotate90.map_point(3, 2, 0.5, 0.5);
+        assert!((x - x90).abs() < 1e-9 && (y - y90).abs() < 1e-9);
+    }
+
+    #[test]
+    fn expanding_rotation_holds_the_whole_image() {
+        let expand = Transform::Rotate { degrees: 45.0, expand: true, filter: ResampleFilter::Bilinear };
+        let (w, h) = expand.output_size(100, 100);
+        assert_eq!((w, h), (141, 141));
+        let crop = Transform::Rotate { degrees: 45.0, expand: false, filter: ResampleFilter::Bilinear };
+        assert_eq!(crop.output_size(100, 50), (100, 50));
+        // the centre stays in the centre
+        assert_eq!(crop.map_point(100, 50, 50.0, 25.0), (50.0, 25.0));
+    }
+
+    #[test]
+    fn resizing_scales_points_and_keeps_flat_areas_flat() {
+        let resize = Transform::Resize { width: 6, height: 1, filter: ResampleFilter::Lanczos };
+        assert_eq!(resize.scale(3, 2), (2.0, 0.5));
+        assert_eq!(resize.map_point(3, 2, 1.5, 2.0), (3.0, 1.0));
+
+        let flat = [200u16; 12];
+        for filter in ResampleFilter::ALL {
+            let resize = Transform::Resize { width: 7, height: 5, filter };
+            assert!(resize.apply(&flat, 4, 3, 1).iter().all(|&v| v == 200), "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn halving_averages_neighbours() {
+        let row = [0u8, 100, 200, 100];
+        let resize = Transform::Resize { width: 2, height: 1, filter: ResampleFilter::Bilinear };
+        let out = resize.apply(&row, 4, 1, 1);
+        assert_eq!(out.len(), 2);
+        assert!(out[0] > 0 && out[0] < 200);
+        assert!(out[1] > 100 && out[1] < 200);
+    }
+}
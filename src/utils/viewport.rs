@@ -0,0 +1,247 @@
+// src/utils/viewport.rs
+
+#[cfg(feature = "gui")]
+use fltk::{frame::Frame, prelude::*};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use crate::state::ImageState;
+
+/// smallest zoom, relative to fitting the frame
+pub const MIN_ZOOM: f32 = 0.1;
+/// largest zoom in screen pixels per image pixel
+pub const MAX_SCALE: f64 = 32.0;
+
+/// where an image sits inside the viewer frame. zoom 1.0 fits the whole image
+/// and letterboxes the rest, pan moves it off centre in screen pixels.
+/// all window coordinates are the ones fltk reports (`app::event_coords()`)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Viewport {
+    pub frame_x: i32,
+    pub frame_y: i32,
+    pub frame_w: i32,
+    pub frame_h: i32,
+    pub image_w: i32,
+    pub image_h: i32,
+    pub zoom: f32,
+    pub pan: (f64, f64),
+}
+
+impl Viewport {
+    /// `frame` is (x, y, w, h) in window coordinates
+    pub fn new(frame: (i32, i32, i32, i32), image_w: i32, image_h: i32, zoom: f32, pan: (f64, f64)) -> Self {
+        Self {
+            frame_x: frame.0,
+            frame_y: frame.1,
+            frame_w: frame.2,
+            frame_h: frame.3,
+            image_w,
+            image_h,
+            zoom,
+            pan,
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn of_frame(frame: &Frame, image_w: i32, image_h: i32, zoom: f32, pan: (f64, f64)) -> Self {
+        Self::new((frame.x(), frame.y(), frame.w(), frame.h()), image_w, image_h, zoom, pan)
+    }
+
+    /// the open image in `frame`, None when nothing is open
+    #[cfg(feature = "gui")]
+    pub fn current(frame: &Frame, state: &ImageState) -> Option<Self> {
+        let image = state.image.as_ref()?;
+        Some(Self::of_frame(frame, image.data_w(), image.data_h(), state.zoom, state.pan))
+    }
+
+    /// screen pixels per image pixel at zoom 1.0
+    pub fn fit_scale(&self) -> f64 {
+        if self.image_w <= 0 || self.image_h <= 0 {
+            return 1.0;
+        }
+        (self.frame_w as f64 / self.image_w as f64).min(self.frame_h as f64 / self.image_h as f64)
+    }
+
+    /// zoom at which the image covers the whole frame
+    pub fn fill_zoom(&self) -> f32 {
+        if self.image_w <= 0 || self.image_h <= 0 {
+            return 1.0;
+        }
+        let fill = (self.frame_w as f64 / self.image_w as f64).max(self.frame_h as f64 / self.image_h as f64);
+        (fill / self.fit_scale()) as f32
+    }
+
+    /// zoom that shows one image pixel per screen pixel
+    pub fn actual_size_zoom(&self) -> f32 {
+        (1.0 / self.fit_scale()) as f32
+    }
+
+    /// screen pixels per image pixel
+    pub fn scale(&self) -> f64 {
+        self.fit_scale() * self.zoom as f64
+    }
+
+    /// window position of the image's top-left corner
+    pub fn origin(&self) -> (f64, f64) {
+        let scale = self.scale();
+        (
+            self.frame_x as f64 + (self.frame_w as f64 - self.image_w as f64 * scale) / 2.0 + self.pan.0,
+            self.frame_y as f64 + (self.frame_h as f64 - self.image_h as f64 * scale) / 2.0 + self.pan.1,
+        )
+    }
+
+    /// window coordinates to image coordinates, possibly outside the image
+    pub fn to_image(&self, x: i32, y: i32) -> (f64, f64) {
+        let (origin_x, origin_y) = self.origin();
+        let scale = self.scale();
+        ((x as f64 - origin_x) / scale, (y as f64 - origin_y) / scale)
+    }
+
+    /// the image pixel under window coordinates, clamped to the image
+    pub fn to_pixel(&self, x: i32, y: i32) -> (i32, i32) {
+        let (image_x, image_y) = self.to_image(x, y);
+        (
+            (image_x.floor() as i32).clamp(0, (self.image_w - 1).max(0)),
+            (image_y.floor() as i32).clamp(0, (self.image_h - 1).max(0)),
+        )
+    }
+
+    /// true when window coordinates are over the image rather than the letterbox
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        let (image_x, image_y) = self.to_image(x, y);
+        image_x >= 0.0 && image_y >= 0.0 && image_x < self.image_w as f64 && image_y < self.image_h as f64
+    }
+
+    /// image coordinates to window coordinates
+    pub fn to_window(&self, x: f64, y: f64) -> (i32, i32) {
+        let (origin_x, origin_y) = self.origin();
+        let scale = self.scale();
+        ((origin_x + x * scale).round() as i32, (origin_y + y * scale).round() as i32)
+    }
+
+    /// window position of an image pixel's centre, where markers on that pixel are drawn
+    pub fn pixel_centre(&self, x: i32, y: i32) -> (i32, i32) {
+        self.to_window(x as f64 + 0.5, y as f64 + 0.5)
+    }
+
+    /// a window rectangle to the image pixels it covers, clipped to the image.
+    /// returns (x, y, w, h); w or h is 0 when the rectangle misses the image
+    pub fn rect_to_image(&self, x: i32, y: i32, w: i32, h: i32) -> (i32, i32, i32, i32) {
+        let (left, top) = self.to_image(x, y);
+        let (right, bottom) = self.to_image(x + w, y + h);
+        let left = (left.floor() as i32).clamp(0, self.image_w);
+        let top = (top.floor() as i32).clamp(0, self.image_h);
+        let right = (right.ceil() as i32).clamp(0, self.image_w);
+        let bottom = (bottom.ceil() as i32).clamp(0, self.image_h);
+        (left, top, (right - left).max(0), (bottom - top).max(0))
+    }
+
+    /// the image pixels that show in the frame, as (x, y, w, h)
+    pub fn visible_rect(&self) -> (i32, i32, i32, i32) {
+        self.rect_to_image(self.frame_x, self.frame_y, self.frame_w, self.frame_h)
+    }
+
+    /// keeps an image larger than the frame covering it and centres one that is smaller
+    pub fn clamped(mut self) -> Self {
+        let scale = self.scale();
+        let limit = |image_size: i32, frame_size: i32, pan: f64| {
+            let slack = (image_size as f64 * scale - frame_size as f64) / 2.0;
+            if slack > 0.0 { pan.clamp(-slack, slack) } else { 0.0 }
+        };
+        self.pan = (limit(self.image_w, self.frame_w, self.pan.0), limit(self.image_h, self.frame_h, self.pan.1));
+        self
+    }
+
+    /// zooms to `zoom` keeping the image point under `anchor` (window coordinates) in place
+    pub fn zoomed_at(self, zoom: f32, anchor: (i32, i32)) -> Self {
+        let zoom = zoom.clamp(MIN_ZOOM, ((MAX_SCALE / self.fit_scale()) as f32).max(1.0));
+        let (image_x, image_y) = self.to_image(anchor.0, anchor.1);
+
+        // where the centred image lands at the new zoom, and how far off that the anchor needs it
+        let centred = Self { zoom, pan: (0.0, 0.0), ..self };
+        let (centred_x, centred_y) = centred.origin();
+        let scale = centred.scale();
+        Self {
+            pan: (
+                anchor.0 as f64 - image_x * scale - centred_x,
+                anchor.1 as f64 - image_y * scale - centred_y,
+            ),
+            ..centred
+        }
+        .clamped()
+    }
+
+    /// the frame's centre, the default zoom anchor
+    pub fn centre(&self) -> (i32, i32) {
+        (self.frame_x + self.frame_w / 2, self.frame_y + self.frame_h / 2)
+    }
+
+    pub fn panned(self, dx: f64, dy: f64) -> Self {
+        Self { pan: (self.pan.0 + dx, self.pan.1 + dy), ..self }.clamped()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 200x100 image in an 800x575 frame below the 25px menu bar, like the main window
+    fn viewport(zoom: f32, pan: (f64, f64)) -> Viewport {
+        Viewport::new((0, 25, 800, 575), 200, 100, zoom, pan)
+    }
+
+    #[test]
+    fn fitted_image_is_letterboxed_below_the_menu() {
+        let view = viewport(1.0, (0.0, 0.0));
+        assert_eq!(view.scale(), 4.0);
+        // 800x400 on screen, centred in the 575px high frame that starts at y = 25
+        assert_eq!(view.origin(), (0.0, 25.0 + 87.5));
+        assert_eq!(view.to_pixel(0, 113), (0, 0));
+        assert_eq!(view.to_pixel(799, 512), (199, 99));
+        assert!(!view.contains(400, 100));
+    }
+
+    #[test]
+    fn window_and_image_coordinates_round_trip() {
+        for view in [viewport(1.0, (0.0, 0.0)), viewport(3.0, (120.0, -40.0)), viewport(0.5, (0.0, 0.0))] {
+            for &(x, y) in &[(0.0, 0.0), (17.0, 42.0), (199.0, 99.0)] {
+                let (wx, wy) = view.to_window(x, y);
+                let (ix, iy) = view.to_image(wx, wy);
+                assert!((ix - x).abs() <= 1.0 / view.scale() && (iy - y).abs() <= 1.0 / view.scale());
+            }
+        }
+    }
+
+    #[test]
+    fn zooming_keeps_the_point_under_the_cursor() {
+        let view = viewport(1.0, (0.0, 0.0));
+        let anchor = (600, 300);
+        let before = view.to_image(anchor.0, anchor.1);
+        let zoomed = view.zoomed_at(4.0, anchor);
+        let after = zoomed.to_image(anchor.0, anchor.1);
+        assert_eq!(zoomed.zoom, 4.0);
+        assert!((before.0 - after.0).abs() < 1e-9 && (before.1 - after.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pan_is_limited_to_the_image() {
+        // smaller than the frame: always centred
+        assert_eq!(viewport(0.5, (0.0, 0.0)).panned(300.0, 300.0).pan, (0.0, 0.0));
+        // 1600x800 on screen: 400px of slack left and right, 112.5 up and down
+        assert_eq!(viewport(2.0, (0.0, 0.0)).panned(1000.0, -1000.0).pan, (400.0, -112.5));
+    }
+
+    #[test]
+    fn rectangles_are_clipped_to_the_image() {
+        let view = viewport(1.0, (0.0, 0.0));
+        assert_eq!(view.rect_to_image(-50, 0, 450, 212), (0, 0, 100, 25));
+        assert_eq!(view.rect_to_image(0, 0, 10, 10).3, 0);
+        assert_eq!(viewport(2.0, (0.0, 0.0)).visible_rect(), (50, 14, 100, 72));
+    }
+
+    #[test]
+    fn presets() {
+        let view = viewport(1.0, (0.0, 0.0));
+        assert_eq!(view.fill_zoom(), 1.4375);
+        assert_eq!(view.actual_size_zoom(), 0.25);
+    }
+}